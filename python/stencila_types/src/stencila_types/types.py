@@ -1292,6 +1292,9 @@ class CodeChunk(CodeExecutable):
     execution_pure: bool | None = None
     """Whether the code should be treated as side-effect free when executed."""
 
+    metadata: Object | None = None
+    """Metadata associated with the chunk that is not otherwise represented."""
+
 
 @dataclass(kw_only=True, repr=False)
 class CodeExpression(CodeExecutable):
@@ -1427,6 +1430,21 @@ class Config(Entity):
     theme: str | None = None
     """The styling theme to use for the document"""
 
+    secrets: list[str] | None = None
+    """The names of the secrets required by the document"""
+
+    memory_limit: UnsignedInteger | None = None
+    """The maximum memory, in megabytes, that each kernel for the document may use"""
+
+    cpu_limit: UnsignedInteger | None = None
+    """The maximum CPU time, in seconds, that each kernel for the document may use"""
+
+    timeout: UnsignedInteger | None = None
+    """The maximum time, in seconds, for each execution of code in the document"""
+
+    restricted: bool | None = None
+    """Whether kernels for the document should be run in restricted mode"""
+
 
 @dataclass(kw_only=True, repr=False)
 class ConstantValidator(Entity):
@@ -2528,6 +2546,9 @@ class RawBlock(Entity):
     provenance: list[ProvenanceCount] | None = None
     """A summary of the provenance of the content."""
 
+    metadata: Object | None = None
+    """Metadata associated with the block that is not otherwise represented."""
+
 
 @dataclass(kw_only=True, repr=False)
 class ReplaceBlock(SuggestionBlock):
@@ -2567,6 +2588,12 @@ class Review(CreativeWork):
     review_aspect: str | None = None
     """The part or facet of the item that is being reviewed."""
 
+    content: list[Block] | None = None
+    """The content of the review, e.g. a summary and major and minor issues."""
+
+    ratings: list[PropertyValue] | None = None
+    """Ratings of the item on various criteria."""
+
 
 @dataclass(kw_only=True, repr=False)
 class Section(Entity):
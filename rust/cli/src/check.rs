@@ -0,0 +1,513 @@
+use std::path::{Path, PathBuf};
+
+use codecs::{EncodeOptions, LossesResponse, PoshMap};
+use common::{
+    clap::{self, Args},
+    eyre::{bail, Result},
+    serde::Serialize,
+    serde_json,
+    tokio::fs::{read_to_string, write},
+    tracing,
+};
+use document::Document;
+use format::Format;
+use quick_xml::escape::escape;
+use schema::{
+    Block, ExecutionMessage, ExecutionStatus, IfBlockClause, Inline, MessageLevel, Node, NodeId,
+    NodeType, Visitor, WalkControl,
+};
+
+/// Command line arguments for checking the execution of a document
+///
+/// Used by the `execute` and `render` commands so that documents (e.g.
+/// documentation with code examples) can be used as tests in CI.
+#[derive(Debug, Clone, Args)]
+pub struct CheckOptions {
+    /// Check that all executable nodes executed without errors
+    ///
+    /// After execution, any code chunks, expressions and other executable nodes
+    /// which had errors or exceptions are listed, along with their messages and
+    /// location in the source document, and the command exits with a non-zero code.
+    #[arg(long)]
+    check: bool,
+
+    /// Also fail the check if any executable node has warnings
+    #[arg(long)]
+    strict: bool,
+
+    /// Write a report of the check to a file
+    ///
+    /// Written as JUnit XML if the file has a `.xml` extension, otherwise
+    /// as JSON. Implies `--check`.
+    #[arg(long, value_name = "FILE")]
+    check_report: Option<PathBuf>,
+}
+
+impl CheckOptions {
+    /// Whether a check was requested
+    pub(crate) fn enabled(&self) -> bool {
+        self.check || self.strict || self.check_report.is_some()
+    }
+
+    /// Replace placeholders in the path of the report (when rendering
+    /// a document with sets of parameters)
+    pub(crate) fn with_placeholders(&self, set: &[(String, String)]) -> Self {
+        let check_report = self.check_report.as_ref().map(|path| {
+            let mut path = path.to_string_lossy().to_string();
+            for (name, value) in set {
+                path = path.replace(&["{", name, "}"].concat(), value);
+            }
+            PathBuf::from(path)
+        });
+
+        Self {
+            check_report,
+            ..self.clone()
+        }
+    }
+
+    /// Check an executed document
+    ///
+    /// As for [`CheckOptions::failure`] but returns an error if the check failed.
+    pub(crate) async fn check(&self, doc: &Document, path: &Path) -> Result<()> {
+        if let Some(failure) = self.failure(doc, path).await? {
+            bail!(failure)
+        }
+
+        Ok(())
+    }
+
+    /// Check an executed document and get a description of the failure, if any
+    ///
+    /// Prints a summary of the nodes with problems and writes a report if requested.
+    /// Errors are only returned if the check could not be completed (e.g. the report
+    /// could not be written) so that callers can distinguish these from failed checks.
+    pub(crate) async fn failure(&self, doc: &Document, path: &Path) -> Result<Option<String>> {
+        if !self.enabled() {
+            return Ok(None);
+        }
+
+        let root = doc.root_read().await.clone();
+
+        let mut collector = Collector::default();
+        collector.visit(&root);
+        let mut results = collector.results;
+
+        locate(&root, path, &mut results).await;
+
+        let failures = results
+            .iter()
+            .filter(|result| result.failed(self.strict))
+            .count();
+        let warnings = results
+            .iter()
+            .filter(|result| result.has_warnings())
+            .count();
+
+        for result in &results {
+            if !(result.has_errors() || result.has_warnings()) {
+                continue;
+            }
+
+            let location = match (result.line, result.column) {
+                (Some(line), Some(column)) => format!("{}:{line}:{column}", path.display()),
+                _ => path.display().to_string(),
+            };
+            eprintln!("{location}: {} {}", result.node_type, result.node_id);
+            for message in &result.messages {
+                for line in message.formatted().lines() {
+                    eprintln!("    {line}");
+                }
+            }
+        }
+        eprintln!(
+            "Checked {} executable nodes: {failures} failed, {warnings} with warnings",
+            results.len()
+        );
+
+        if let Some(report) = &self.check_report {
+            let content = if report.extension().is_some_and(|ext| ext == "xml") {
+                junit(path, &results, self.strict)
+            } else {
+                serde_json::to_string_pretty(&Report {
+                    path: path.to_string_lossy().to_string(),
+                    nodes: results.len(),
+                    failures,
+                    warnings,
+                    results: &results,
+                })?
+            };
+            write(report, content).await?;
+        }
+
+        Ok((failures > 0).then(|| {
+            format!(
+                "{failures} of {} executable nodes in `{}` failed",
+                results.len(),
+                path.display()
+            )
+        }))
+    }
+}
+
+/// A report of a check
+#[derive(Serialize)]
+#[serde(crate = "common::serde")]
+struct Report<'results> {
+    path: String,
+    nodes: usize,
+    failures: usize,
+    warnings: usize,
+    results: &'results [CheckResult],
+}
+
+/// The result of checking an executable node
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase", crate = "common::serde")]
+struct CheckResult {
+    node_type: NodeType,
+
+    node_id: NodeId,
+
+    status: Option<ExecutionStatus>,
+
+    /// The 1-based line of the node in the source document
+    line: Option<usize>,
+
+    /// The 1-based column of the node in the source document
+    column: Option<usize>,
+
+    /// Warning, error, and exception messages
+    messages: Vec<ExecutionMessage>,
+}
+
+impl CheckResult {
+    fn has_errors(&self) -> bool {
+        matches!(
+            self.status,
+            Some(
+                ExecutionStatus::Errors
+                    | ExecutionStatus::Exceptions
+                    | ExecutionStatus::Interrupted
+            )
+        ) || self
+            .messages
+            .iter()
+            .any(|message| matches!(message.level, MessageLevel::Error | MessageLevel::Exception))
+    }
+
+    fn has_warnings(&self) -> bool {
+        matches!(self.status, Some(ExecutionStatus::Warnings))
+            || self
+                .messages
+                .iter()
+                .any(|message| message.level == MessageLevel::Warning)
+    }
+
+    fn failed(&self, strict: bool) -> bool {
+        self.has_errors() || (strict && self.has_warnings())
+    }
+}
+
+/// A visitor that collects check results for executable nodes
+#[derive(Default)]
+struct Collector {
+    results: Vec<CheckResult>,
+}
+
+impl Collector {
+    fn collect(
+        &mut self,
+        node_type: NodeType,
+        node_id: NodeId,
+        status: &Option<ExecutionStatus>,
+        messages: &Option<Vec<ExecutionMessage>>,
+    ) {
+        // Ignore nodes that have not been executed (e.g. skipped or locked nodes)
+        if status.is_none() && messages.is_none() {
+            return;
+        }
+
+        let messages = messages
+            .iter()
+            .flatten()
+            .filter(|message| {
+                matches!(
+                    message.level,
+                    MessageLevel::Warning | MessageLevel::Error | MessageLevel::Exception
+                )
+            })
+            .cloned()
+            .collect();
+
+        self.results.push(CheckResult {
+            node_type,
+            node_id,
+            status: status.clone(),
+            line: None,
+            column: None,
+            messages,
+        });
+    }
+}
+
+impl Visitor for Collector {
+    fn visit_block(&mut self, block: &Block) -> WalkControl {
+        macro_rules! variants {
+            ($( $variant:ident ),*) => {
+                match block {
+                    $(Block::$variant(node) => self.collect(
+                        node.node_type(),
+                        node.node_id(),
+                        &node.options.execution_status,
+                        &node.options.execution_messages,
+                    ),)*
+                    _ => {}
+                }
+            };
+        }
+        variants!(
+            CallBlock,
+            CodeChunk,
+            ForBlock,
+            Form,
+            IfBlock,
+            IncludeBlock,
+            InstructionBlock,
+            PromptBlock
+        );
+
+        WalkControl::Continue
+    }
+
+    fn visit_inline(&mut self, inline: &Inline) -> WalkControl {
+        macro_rules! variants {
+            ($( $variant:ident ),*) => {
+                match inline {
+                    $(Inline::$variant(node) => self.collect(
+                        node.node_type(),
+                        node.node_id(),
+                        &node.options.execution_status,
+                        &node.options.execution_messages,
+                    ),)*
+                    _ => {}
+                }
+            };
+        }
+        variants!(CodeExpression, InstructionInline, Parameter);
+
+        WalkControl::Continue
+    }
+
+    fn visit_if_block_clause(&mut self, clause: &IfBlockClause) -> WalkControl {
+        self.collect(
+            clause.node_type(),
+            clause.node_id(),
+            &clause.options.execution_status,
+            &clause.options.execution_messages,
+        );
+
+        WalkControl::Continue
+    }
+}
+
+/// Set the location of each result in the source document
+///
+/// Encodes the document to the format of the source to obtain a mapping
+/// between nodes and positions. Locations are not available for formats which
+/// do not provide a mapping, or if the source can not be read.
+async fn locate(root: &Node, path: &Path, results: &mut [CheckResult]) {
+    let Ok(source) = read_to_string(path).await else {
+        return;
+    };
+
+    let (generated, info) = match codecs::to_string_with_info(
+        root,
+        Some(EncodeOptions {
+            format: Some(Format::from_path(path)),
+            losses: LossesResponse::Ignore,
+            ..Default::default()
+        }),
+    )
+    .await
+    {
+        Ok(result) => result,
+        Err(error) => {
+            tracing::debug!("Unable to locate nodes in `{}`: {error}", path.display());
+            return;
+        }
+    };
+
+    let poshmap = PoshMap::new(&source, &generated, info.mapping);
+    for result in results {
+        if let Some(range) = poshmap.node_id_to_range8(&result.node_id) {
+            result.line = Some(range.start.line + 1);
+            result.column = Some(range.start.column + 1);
+        }
+    }
+}
+
+/// Generate a JUnit XML report
+///
+/// Each executable node is a test case in a single test suite for the document.
+fn junit(path: &Path, results: &[CheckResult], strict: bool) -> String {
+    let path = escape(path.to_string_lossy()).to_string();
+    let tests = results.len();
+    let failures = results
+        .iter()
+        .filter(|result| result.failed(strict))
+        .count();
+
+    let mut xml = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites tests=\"{tests}\" failures=\"{failures}\">\n  <testsuite name=\"{path}\" tests=\"{tests}\" failures=\"{failures}\">\n"
+    );
+
+    for result in results {
+        let line = result
+            .line
+            .map(|line| format!(" line=\"{line}\""))
+            .unwrap_or_default();
+        xml.push_str(&format!(
+            "    <testcase name=\"{} {}\" classname=\"{path}\" file=\"{path}\"{line}",
+            result.node_type, result.node_id
+        ));
+
+        if result.messages.is_empty() && !result.failed(strict) {
+            xml.push_str("/>\n");
+            continue;
+        }
+        xml.push_str(">\n");
+
+        let formatted = escape(
+            result
+                .messages
+                .iter()
+                .map(|message| message.formatted())
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        )
+        .to_string();
+
+        if result.failed(strict) {
+            let first = result.messages.first();
+            let message = first
+                .map(|message| escape(message.message.as_str()).to_string())
+                .unwrap_or_else(|| {
+                    result
+                        .status
+                        .as_ref()
+                        .map(|status| status.to_string())
+                        .unwrap_or_default()
+                });
+            let error_type = first
+                .and_then(|message| message.error_type.as_deref())
+                .map(|error_type| escape(error_type).to_string())
+                .unwrap_or_else(|| "Error".to_string());
+            xml.push_str(&format!(
+                "      <failure message=\"{message}\" type=\"{error_type}\">{formatted}</failure>\n"
+            ));
+        } else {
+            xml.push_str(&format!("      <system-err>{formatted}</system-err>\n"));
+        }
+
+        xml.push_str("    </testcase>\n");
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use schema::CodeChunk;
+
+    use super::*;
+
+    fn result(status: Option<ExecutionStatus>, messages: Vec<ExecutionMessage>) -> CheckResult {
+        CheckResult {
+            node_type: NodeType::CodeChunk,
+            node_id: CodeChunk::new("x".into()).node_id(),
+            status,
+            line: Some(3),
+            column: Some(1),
+            messages,
+        }
+    }
+
+    #[test]
+    fn check_result() {
+        let succeeded = result(Some(ExecutionStatus::Succeeded), vec![]);
+        assert!(!succeeded.has_errors());
+        assert!(!succeeded.has_warnings());
+        assert!(!succeeded.failed(true));
+
+        let errored = result(Some(ExecutionStatus::Exceptions), vec![]);
+        assert!(errored.has_errors());
+        assert!(errored.failed(false));
+
+        let interrupted = result(Some(ExecutionStatus::Interrupted), vec![]);
+        assert!(interrupted.failed(false));
+
+        // Messages are considered even if the status is not set
+        let exception = result(
+            None,
+            vec![ExecutionMessage::new(
+                MessageLevel::Exception,
+                "oops".to_string(),
+            )],
+        );
+        assert!(exception.has_errors());
+        assert!(exception.failed(false));
+
+        // Warnings only fail the check when strict
+        let warned = result(
+            Some(ExecutionStatus::Warnings),
+            vec![ExecutionMessage::new(
+                MessageLevel::Warning,
+                "careful".to_string(),
+            )],
+        );
+        assert!(!warned.has_errors());
+        assert!(warned.has_warnings());
+        assert!(!warned.failed(false));
+        assert!(warned.failed(true));
+    }
+
+    #[test]
+    fn junit_report() {
+        let results = [
+            result(Some(ExecutionStatus::Succeeded), vec![]),
+            result(
+                Some(ExecutionStatus::Exceptions),
+                vec![ExecutionMessage {
+                    error_type: Some("ValueError".to_string()),
+                    ..ExecutionMessage::new(MessageLevel::Exception, "a < b".to_string())
+                }],
+            ),
+            result(
+                Some(ExecutionStatus::Warnings),
+                vec![ExecutionMessage::new(
+                    MessageLevel::Warning,
+                    "careful".to_string(),
+                )],
+            ),
+        ];
+        let path = Path::new("docs/a&b.md");
+
+        let xml = junit(path, &results, false);
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains(r#"<testsuites tests="3" failures="1">"#));
+        assert!(xml.contains(r#"<testsuite name="docs/a&amp;b.md" tests="3" failures="1">"#));
+        assert!(xml.contains(r#"line="3""#));
+        assert!(xml.contains(r#"<failure message="a &lt; b" type="ValueError">"#));
+        assert!(xml.contains("<system-err>"));
+        assert_eq!(xml.matches("<testcase ").count(), 3);
+        assert_eq!(xml.matches("/>\n").count(), 1);
+
+        // Warnings become failures when strict
+        let xml = junit(path, &results, true);
+        assert!(xml.contains(r#"<testsuites tests="3" failures="2">"#));
+        assert!(!xml.contains("<system-err>"));
+        assert!(xml.ends_with("</testsuites>\n"));
+    }
+}
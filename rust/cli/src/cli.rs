@@ -10,7 +10,7 @@ use version::STENCILA_VERSION;
 use crate::{
     compile, convert, execute,
     logging::{LoggingFormat, LoggingLevel},
-    new, preview, render, sync, uninstall, upgrade,
+    new, preview, provenance, render, sync, uninstall, upgrade,
 };
 
 /// CLI subcommands and global options
@@ -107,12 +107,13 @@ pub enum Command {
 
     Preview(preview::Cli),
     Publish(publish::cli::Cli),
+    Provenance(provenance::Cli),
 
     Serve(ServeOptions),
     /// Run the Language Server Protocol server
     Lsp,
 
-    Prompts(prompts::cli::Cli),
+    Prompts(eval::Cli),
     Models(models::cli::Cli),
     Kernels(kernels::cli::Cli),
     Codecs(codecs::cli::Cli),
@@ -156,6 +157,7 @@ impl Cli {
 
             Command::Preview(preview) => preview.run().await?,
             Command::Publish(publish) => publish.run().await?,
+            Command::Provenance(provenance) => provenance.run().await?,
 
             Command::Serve(options) => server::serve(options).await?,
 
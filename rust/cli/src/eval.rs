@@ -0,0 +1,287 @@
+use std::path::PathBuf;
+
+use cli_utils::{
+    table::{self, Attribute, Cell, Color},
+    Code, ToStdout,
+};
+use codecs::Format;
+use common::{
+    clap::{self, Args, Parser, Subcommand},
+    eyre::{bail, Result},
+    itertools::Itertools,
+    serde_json, tempfile,
+};
+use document::{CommandWait, Document};
+use node_execute::ExecuteOptions;
+use prompts::eval::{EvalCase, EvalResult};
+use schema::{
+    Article, Author, AuthorRole, AuthorRoleAuthor, AuthorRoleName, Block, ExecutionMessage, Inline,
+    MessageLevel, Node, SoftwareApplication, Visitor, WalkControl,
+};
+
+/// Manage prompts
+#[derive(Debug, Parser)]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    #[command(flatten)]
+    Prompts(prompts::cli::Command),
+
+    Eval(Eval),
+}
+
+impl Cli {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Some(Command::Prompts(command)) => command.run().await,
+            Some(Command::Eval(eval)) => eval.run().await,
+            None => prompts::cli::Command::List(prompts::cli::List).run().await,
+        }
+    }
+}
+
+/// Evaluate prompts against test cases
+///
+/// Each test case is a YAML file in the directory specifying an instruction,
+/// the document context in which it is executed, and assertions about the
+/// suggestions generated for it. Each test case is executed using each of the
+/// models and the suggestions checked against the assertions.
+#[derive(Debug, Args)]
+struct Eval {
+    /// The directory containing the test cases
+    dir: PathBuf,
+
+    /// The id pattern of a model to evaluate against
+    ///
+    /// Can be used multiple times. If not specified, the model is
+    /// selected as for any other instruction.
+    #[arg(long, short)]
+    model: Vec<String>,
+
+    /// Output the results as JSON
+    #[arg(long)]
+    json: bool,
+
+    /// Do not use cached outputs of generative models
+    #[arg(long)]
+    no_cache: bool,
+}
+
+impl Eval {
+    async fn run(self) -> Result<()> {
+        let cases = EvalCase::read_dir(&self.dir).await?;
+        if cases.is_empty() {
+            bail!("No test cases found in `{}`", self.dir.display())
+        }
+
+        let models = if self.model.is_empty() {
+            vec![None]
+        } else {
+            self.model
+                .iter()
+                .map(|model| Some(model.as_str()))
+                .collect()
+        };
+
+        let mut results = Vec::new();
+        for case in &cases {
+            for model in &models {
+                results.append(&mut self.eval(case, *model).await?);
+            }
+        }
+
+        if self.json {
+            Code::new(Format::Json, &serde_json::to_string_pretty(&results)?).to_stdout();
+        } else {
+            let mut table = table::new();
+            table.set_header(["Case", "Model", "Replicate", "Result", "Details"]);
+            for result in &results {
+                let details = match &result.error {
+                    Some(error) => error.clone(),
+                    None => result
+                        .failures
+                        .iter()
+                        .map(|(assertion, reason)| format!("{assertion:?}: {reason}"))
+                        .join("\n"),
+                };
+
+                table.add_row([
+                    Cell::new(&result.case).add_attribute(Attribute::Bold),
+                    Cell::new(&result.model),
+                    Cell::new(result.replicate),
+                    if result.passed() {
+                        Cell::new("pass").fg(Color::Green)
+                    } else {
+                        Cell::new("fail").fg(Color::Red)
+                    },
+                    Cell::new(details),
+                ]);
+            }
+            println!("{table}");
+        }
+
+        let failed = results.iter().filter(|result| !result.passed()).count();
+        if failed > 0 {
+            bail!("{failed} of {} evaluations failed", results.len())
+        }
+
+        Ok(())
+    }
+
+    /// Evaluate a test case using a model
+    async fn eval(&self, case: &EvalCase, model: Option<&str>) -> Result<Vec<EvalResult>> {
+        let article = case.to_article(model).await?;
+        let root = execute(article, self.execute_options()).await?;
+
+        // Get the instruction, which is always the last block
+        let Some(Block::InstructionBlock(instruction)) = (match root {
+            Node::Article(Article { content, .. }) => content.into_iter().last(),
+            _ => None,
+        }) else {
+            bail!("Expected the last block to be an instruction")
+        };
+
+        let error = errors(&instruction.options.execution_messages);
+        let suggestions = instruction.suggestions.unwrap_or_default();
+        if suggestions.is_empty() {
+            return Ok(vec![EvalResult {
+                case: case.name.clone(),
+                model: model.unwrap_or_default().to_string(),
+                replicate: 0,
+                failures: Vec::new(),
+                error: Some(if error.is_empty() {
+                    "No suggestions generated".to_string()
+                } else {
+                    error.join("; ")
+                }),
+            }]);
+        }
+
+        let mut results = Vec::new();
+        for (replicate, suggestion) in suggestions.into_iter().enumerate() {
+            // Use the id of the model that actually generated the suggestion
+            let model = suggestion
+                .authors
+                .iter()
+                .flatten()
+                .find_map(|author| match author {
+                    Author::AuthorRole(AuthorRole {
+                        role_name: AuthorRoleName::Generator,
+                        author:
+                            AuthorRoleAuthor::SoftwareApplication(SoftwareApplication {
+                                id: Some(id),
+                                ..
+                            }),
+                        ..
+                    }) => Some(id.clone()),
+                    _ => None,
+                })
+                .or_else(|| model.map(String::from))
+                .unwrap_or_default();
+
+            // Only execute the suggestion if necessary
+            let errors = if case
+                .assertions
+                .iter()
+                .any(|assertion| assertion.requires_execution())
+            {
+                let article = case.with_context(suggestion.content.clone()).await?;
+                let root = execute(article, ExecuteOptions::default()).await?;
+
+                let mut collector = ErrorCollector::default();
+                collector.visit(&root);
+                Some(collector.errors)
+            } else {
+                None
+            };
+
+            let failures = case
+                .assertions
+                .iter()
+                .filter_map(|assertion| {
+                    assertion
+                        .check(&suggestion.content, errors.as_deref())
+                        .err()
+                        .map(|reason| (assertion.clone(), reason))
+                })
+                .collect();
+
+            results.push(EvalResult {
+                case: case.name.clone(),
+                model,
+                replicate,
+                failures,
+                error: None,
+            })
+        }
+
+        Ok(results)
+    }
+
+    /// Get the options for executing test cases
+    fn execute_options(&self) -> ExecuteOptions {
+        ExecuteOptions {
+            no_cache: self.no_cache,
+            ..Default::default()
+        }
+    }
+}
+
+/// Execute an article in a temporary document and return the executed root node
+///
+/// A temporary directory is used as the home of the document so that any files
+/// written during execution do not pollute the test case directory.
+async fn execute(article: Article, options: ExecuteOptions) -> Result<Node> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("main.json");
+    codecs::to_path(&Node::Article(article), &path, None).await?;
+
+    let doc = Document::open(&path).await?;
+    doc.compile(CommandWait::Yes).await?;
+    doc.execute(options, CommandWait::Yes).await?;
+
+    let root = doc.root_read().await.clone();
+    Ok(root)
+}
+
+/// Get the formatted error and exception messages from a list of execution messages
+fn errors(messages: &Option<Vec<ExecutionMessage>>) -> Vec<String> {
+    messages
+        .iter()
+        .flatten()
+        .filter_map(|message| {
+            matches!(message.level, MessageLevel::Error | MessageLevel::Exception)
+                .then(|| message.formatted())
+        })
+        .collect()
+}
+
+/// A visitor that collects execution errors from executable nodes
+#[derive(Default)]
+struct ErrorCollector {
+    errors: Vec<String>,
+}
+
+impl Visitor for ErrorCollector {
+    fn visit_block(&mut self, block: &Block) -> WalkControl {
+        if let Block::CodeChunk(chunk) = block {
+            self.errors
+                .append(&mut errors(&chunk.options.execution_messages));
+        }
+
+        WalkControl::Continue
+    }
+
+    fn visit_inline(&mut self, inline: &Inline) -> WalkControl {
+        if let Inline::CodeExpression(expr) = inline {
+            self.errors
+                .append(&mut errors(&expr.options.execution_messages));
+        }
+
+        WalkControl::Continue
+    }
+}
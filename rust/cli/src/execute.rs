@@ -10,7 +10,10 @@ use document::{CommandWait, Document, SaveDocumentSidecar, SaveDocumentSource};
 use format::Format;
 use node_execute::ExecuteOptions;
 
-use crate::options::{EncodeOptions, StripOptions};
+use crate::{
+    check::CheckOptions,
+    options::{EncodeOptions, StripOptions},
+};
 
 /// Execute a document
 #[derive(Debug, Parser)]
@@ -36,6 +39,9 @@ pub struct Cli {
     #[clap(flatten)]
     execute_options: ExecuteOptions,
 
+    #[command(flatten)]
+    check_options: CheckOptions,
+
     #[command(flatten)]
     encode_options: EncodeOptions,
 
@@ -54,6 +60,7 @@ impl Cli {
             output,
             to,
             execute_options,
+            check_options,
             encode_options,
             strip_options,
             no_save,
@@ -91,7 +98,7 @@ impl Cli {
             }
         }
 
-        Ok(())
+        check_options.check(&doc, &input).await
     }
 }
 
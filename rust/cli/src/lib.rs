@@ -3,14 +3,17 @@
 mod cli;
 pub use crate::cli::{Cli, Command};
 
+mod check;
 mod compile;
 mod convert;
 pub mod errors;
+mod eval;
 mod execute;
 pub mod logging;
 mod new;
 mod options;
 mod preview;
+mod provenance;
 mod render;
 mod sync;
 mod uninstall;
@@ -0,0 +1,183 @@
+use std::path::PathBuf;
+
+use cli_utils::{
+    table::{self, Attribute, Cell, CellAlignment, Table},
+    Code, ToStdout,
+};
+use common::{
+    clap::{self, Parser},
+    eyre::Result,
+    serde_json,
+};
+use document::{Document, ProvenanceSummary};
+use format::Format;
+use schema::ProvenanceCount;
+
+/// Summarize the provenance of the content of a document
+///
+/// Displays the number of characters, and the percentage that were written by
+/// humans and machines, for the document as a whole, and for each section and author.
+#[derive(Debug, Parser)]
+pub struct Cli {
+    /// The path of the document to summarize
+    input: PathBuf,
+
+    /// Output the summary as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+impl Cli {
+    pub async fn run(self) -> Result<()> {
+        let doc = Document::open(&self.input).await?;
+        let summary = doc.provenance().await;
+
+        if self.json {
+            Code::new(Format::Json, &serde_json::to_string_pretty(&summary)?).to_stdout();
+            return Ok(());
+        }
+
+        let [sections, authors] = tables(summary);
+        println!("{sections}");
+        println!("{authors}");
+
+        Ok(())
+    }
+}
+
+/// Create tables summarizing provenance for each section, and for each author
+fn tables(summary: ProvenanceSummary) -> [Table; 2] {
+    let ProvenanceSummary {
+        counts,
+        sections,
+        authors,
+        ..
+    } = summary;
+
+    let mut sections_table = table::new();
+    sections_table.set_header(["Section", "Characters", "Human", "Machine", "Unverified"]);
+    for section in sections {
+        let heading = match (section.heading, section.level) {
+            (Some(heading), Some(level)) => [
+                &"  ".repeat(level.saturating_sub(1) as usize),
+                heading.as_str(),
+            ]
+            .concat(),
+            _ => "-".to_string(),
+        };
+        sections_table.add_row(row(heading, &section.counts));
+    }
+    sections_table
+        .add_row(row("Total".to_string(), &counts).map(|cell| cell.add_attribute(Attribute::Bold)));
+
+    let mut authors_table = table::new();
+    authors_table.set_header(["Author", "Characters", "Human", "Machine", "Unverified"]);
+    for author in authors {
+        authors_table.add_row(row(author.name, &author.counts));
+    }
+
+    [sections_table, authors_table]
+}
+
+/// Create a row of a provenance table
+fn row(name: String, counts: &[ProvenanceCount]) -> [Cell; 5] {
+    let characters: u64 = counts.iter().map(|count| count.character_count).sum();
+    [
+        Cell::new(name),
+        Cell::new(characters).set_alignment(CellAlignment::Right),
+        Cell::new(format!("{}%", ProvenanceCount::human_percent(counts)))
+            .set_alignment(CellAlignment::Right),
+        Cell::new(format!("{}%", ProvenanceCount::machine_percent(counts)))
+            .set_alignment(CellAlignment::Right),
+        Cell::new(format!(
+            "{}%",
+            ProvenanceCount::machine_unverified_percent(counts)
+        ))
+        .set_alignment(CellAlignment::Right),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use document::{ProvenanceAuthor, ProvenanceSection};
+    use schema::ProvenanceCategory;
+
+    use super::*;
+
+    /// Get the cells of each row of a table
+    fn rows(mut table: Table) -> Vec<Vec<String>> {
+        table.force_no_tty();
+        table
+            .to_string()
+            .lines()
+            .map(|line| {
+                line.split_whitespace()
+                    .filter(|cell| *cell != "│")
+                    .map(String::from)
+                    .collect::<Vec<_>>()
+            })
+            .filter(|cells| !cells.is_empty())
+            .collect()
+    }
+
+    #[test]
+    fn tables_rows() {
+        let human = ProvenanceCount::new(ProvenanceCategory::Hw, 30);
+        let machine = ProvenanceCount::new(ProvenanceCategory::Mw, 10);
+
+        let summary = ProvenanceSummary {
+            counts: vec![human.clone(), machine.clone()],
+            sections: vec![
+                ProvenanceSection {
+                    heading: None,
+                    level: None,
+                    counts: vec![human.clone()],
+                },
+                ProvenanceSection {
+                    heading: Some("Methods".to_string()),
+                    level: Some(2),
+                    counts: vec![machine.clone()],
+                },
+            ],
+            authors: vec![ProvenanceAuthor {
+                name: "Alice".to_string(),
+                counts: vec![human, machine],
+            }],
+            ..Default::default()
+        };
+
+        let [sections, authors] = tables(summary);
+
+        let sections = rows(sections);
+        assert!(sections.contains(&vec![
+            "-".to_string(),
+            "30".to_string(),
+            "100%".to_string(),
+            "0%".to_string(),
+            "0%".to_string()
+        ]));
+        assert!(sections.contains(&vec![
+            "Methods".to_string(),
+            "10".to_string(),
+            "0%".to_string(),
+            "100%".to_string(),
+            "100%".to_string()
+        ]));
+        assert!(sections.contains(&vec![
+            "Total".to_string(),
+            "40".to_string(),
+            "75%".to_string(),
+            "25%".to_string(),
+            "25%".to_string()
+        ]));
+
+        let authors = rows(authors);
+        assert!(authors.contains(&vec![
+            "Alice".to_string(),
+            "40".to_string(),
+            "75%".to_string(),
+            "25%".to_string(),
+            "25%".to_string()
+        ]));
+    }
+}
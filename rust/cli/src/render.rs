@@ -1,16 +1,33 @@
-use std::path::PathBuf;
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use notify::{EventKind, RecursiveMode, Watcher};
 
 use cli_utils::{Code, ToStdout};
 use codecs::LossesResponse;
 use common::{
     clap::{self, Parser},
-    eyre::Result,
+    eyre::{bail, eyre, Result},
+    itertools::Itertools,
+    serde_json::{self, Value},
+    tokio::{self, fs::read_to_string, sync::mpsc, time},
+    tracing,
+};
+use document::{
+    Command, CommandNodes, CommandScope, CommandWait, Document, SaveDocumentSidecar,
+    SaveDocumentSource, SyncDirection,
 };
-use document::{CommandWait, Document, SaveDocumentSidecar, SaveDocumentSource};
 use format::Format;
 use node_execute::ExecuteOptions;
+use schema::{Block, ExecutionDependencyNode, File, Inline, NodeId, Visitor, WalkControl};
 
-use crate::options::{EncodeOptions, StripOptions};
+use crate::{
+    check::CheckOptions,
+    options::{EncodeOptions, StripOptions},
+};
 
 /// Render a document
 ///
@@ -25,6 +42,8 @@ pub struct Cli {
     /// The path of the file to write the rendered document to
     ///
     /// If not supplied the output content is written to `stdout`.
+    /// When using `--params`, the path should contain placeholders for
+    /// parameter names e.g. `report-{site}.html`.
     output: Option<PathBuf>,
 
     /// The format to encode to (or codec to use)
@@ -34,9 +53,38 @@ pub struct Cli {
     #[arg(long, short)]
     to: Option<String>,
 
+    /// Set the value of a parameter in the document
+    ///
+    /// Use the form `name=value` and repeat for each parameter (e.g. `--param site=A12 --param n=10`).
+    /// Values are parsed according to the type of the parameter, or as JSON if it has no type,
+    /// falling back to a string.
+    #[arg(long = "param", value_name = "NAME=VALUE", value_parser = parse_parameter)]
+    param: Vec<(String, String)>,
+
+    /// A CSV or JSON file of parameter sets to render the document with
+    ///
+    /// The document is rendered once for each row of a CSV file (with a header
+    /// row of parameter names), or each object in a JSON array. Values in each
+    /// set are applied after, and override, any supplied using `--param`.
+    #[arg(long, value_name = "FILE")]
+    params: Option<PathBuf>,
+
+    /// Watch the document and re-render it when it changes
+    ///
+    /// The document is kept open and when its source file changes, it is re-compiled,
+    /// stale nodes are re-executed, and it is re-exported to the output file. Files
+    /// that the document depends upon (e.g. the sources of `IncludeBlock`s and files
+    /// read by code chunks) are also watched and nodes depending on them re-executed
+    /// when they change. The document is not saved after re-rendering.
+    #[arg(long, conflicts_with = "params")]
+    watch: bool,
+
     #[clap(flatten)]
     execute_options: ExecuteOptions,
 
+    #[command(flatten)]
+    check_options: CheckOptions,
+
     #[command(flatten)]
     encode_options: EncodeOptions,
 
@@ -44,27 +92,109 @@ pub struct Cli {
     strip_options: StripOptions,
 
     /// Do not save the document after compiling it
+    ///
+    /// The document is never saved when parameters are supplied so that
+    /// the values of the parameters are not written to it.
     #[arg(long)]
     no_save: bool,
 }
 
 impl Cli {
     pub async fn run(self) -> Result<()> {
-        let Self {
-            input,
-            output,
-            to,
-            execute_options,
-            encode_options,
-            strip_options,
-            no_save,
-        } = self;
-
-        let doc = Document::open(&input).await?;
+        let mut execute_options = self.execute_options.clone();
+        execute_options.parameters.clone_from(&self.param);
+
+        let Some(params) = &self.params else {
+            if let Some(failure) = self
+                .render(execute_options, self.output.as_deref(), &self.check_options)
+                .await?
+            {
+                bail!(failure)
+            }
+            return Ok(());
+        };
+
+        let Some(output) = self.output.as_ref().and_then(|output| output.to_str()) else {
+            bail!("An output path is required when using `--params`")
+        };
+        if !output.contains('{') {
+            bail!(
+                "The output path should contain placeholders for parameter names when using `--params` e.g. `report-{{name}}.html`"
+            )
+        }
+
+        let sets = read_parameter_sets(params).await?;
+        tracing::info!(
+            "Rendering {} with {} parameter sets",
+            self.input.display(),
+            sets.len()
+        );
+
+        let count = sets.len();
+        let mut failed = 0;
+        for set in sets {
+            let mut path = output.to_string();
+            for (name, value) in &set {
+                let placeholder = ["{", name, "}"].concat();
+                if path.contains(&placeholder) {
+                    check_path_value(name, value)?;
+                    path = path.replace(&placeholder, value);
+                }
+            }
+            if path.contains('{') {
+                bail!("Not all placeholders in the output path were replaced: {path}");
+            }
+
+            let check_options = self.check_options.with_placeholders(&set);
+
+            let mut execute_options = execute_options.clone();
+            execute_options.parameters.extend(set);
+
+            // Continue rendering other sets if the check fails for one of them
+            // (but not if rendering itself fails)
+            if let Some(failure) = self
+                .render(execute_options, Some(Path::new(&path)), &check_options)
+                .await?
+            {
+                tracing::error!("While rendering `{path}`: {failure}");
+                failed += 1;
+            }
+        }
+
+        if failed > 0 {
+            bail!("The check failed for {failed} of {count} parameter sets")
+        }
+
+        Ok(())
+    }
+
+    /// Render the document once using the execution options and output path
+    ///
+    /// Returns a description of the failure if the check of the document failed.
+    async fn render(
+        &self,
+        mut execute_options: ExecuteOptions,
+        output: Option<&Path>,
+        check_options: &CheckOptions,
+    ) -> Result<Option<String>> {
+        let has_parameters = !execute_options.parameters.is_empty();
+        if has_parameters {
+            // Digests of nodes do not depend upon the values of parameters
+            // so force all nodes to be executed
+            execute_options.force_all = true;
+        }
+
+        let doc = Document::open(&self.input).await?;
         doc.compile(CommandWait::Yes).await?;
-        doc.execute(execute_options, CommandWait::Yes).await?;
 
-        if !no_save {
+        if has_parameters {
+            check_parameter_names(&doc, &execute_options.parameters).await?;
+        }
+
+        doc.execute(execute_options.clone(), CommandWait::Yes)
+            .await?;
+
+        if !(self.no_save || has_parameters) {
             doc.save_with(
                 CommandWait::Yes,
                 SaveDocumentSource::Yes,
@@ -73,19 +203,35 @@ impl Cli {
             .await?;
         }
 
-        let mut encode_options = encode_options.build(
-            Some(input.as_ref()),
-            output.as_deref(),
-            to,
+        self.export(&doc, output).await?;
+
+        let failure = check_options.failure(&doc, &self.input).await?;
+        if !self.watch {
+            return Ok(failure);
+        }
+        if let Some(failure) = failure {
+            tracing::error!("{failure}");
+        }
+
+        self.watch_changes(&doc, execute_options, output, check_options)
+            .await?;
+
+        Ok(None)
+    }
+
+    /// Export the rendered document to the output file, or `stdout`
+    async fn export(&self, doc: &Document, output: Option<&Path>) -> Result<()> {
+        let mut encode_options = self.encode_options.build(
+            Some(self.input.as_ref()),
+            output,
+            self.to.clone(),
             Format::Markdown,
-            strip_options,
+            self.strip_options.clone(),
             LossesResponse::Debug,
         );
         encode_options.render = Some(true);
 
-        let content = doc
-            .export(output.as_deref(), Some(encode_options.clone()))
-            .await?;
+        let content = doc.export(output, Some(encode_options.clone())).await?;
 
         if !content.is_empty() {
             Code::new(encode_options.format.unwrap_or_default(), &content).to_stdout();
@@ -93,4 +239,562 @@ impl Cli {
 
         Ok(())
     }
+
+    /// Watch the source file of the document, and the files it depends upon,
+    /// and re-render the document when they change
+    ///
+    /// Changes to the source file are synced to the document using the same
+    /// mechanism as for other synced documents, and the document re-rendered once
+    /// each update has been applied. Changes to dependencies are detected by watching
+    /// their parent directories, rather than the files themselves, because many editors
+    /// save files by writing to a temporary file and renaming it, which would otherwise
+    /// end the watch.
+    async fn watch_changes(
+        &self,
+        doc: &Document,
+        execute_options: ExecuteOptions,
+        output: Option<&Path>,
+        check_options: &CheckOptions,
+    ) -> Result<()> {
+        let mut updates = doc.watch_updates();
+        updates.borrow_and_update();
+        doc.sync_file(&self.input, SyncDirection::In, None, None)
+            .await?;
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+                Ok(event) => {
+                    if matches!(event.kind, EventKind::Create(..) | EventKind::Modify(..)) {
+                        for path in event.paths {
+                            sender.send(path).ok();
+                        }
+                    }
+                }
+                Err(error) => tracing::error!("While watching files: {error}"),
+            })?;
+
+        let dir = self
+            .input
+            .canonicalize()?
+            .parent()
+            .map_or_else(|| PathBuf::from("."), PathBuf::from);
+
+        tracing::info!("Watching `{}` for changes", self.input.display());
+
+        let mut dirs = HashSet::new();
+        loop {
+            // Update the watched directories based on the current dependencies
+            // of the document (these may change when the source changes)
+            let dependencies = {
+                let mut collector = DependencyCollector::new(&dir);
+                collector.visit(&*doc.root_read().await);
+                collector.dependencies
+            };
+            for path in dependencies.keys() {
+                if let Some(parent) = path.parent() {
+                    if dirs.insert(parent.to_path_buf()) {
+                        if let Err(error) = watcher.watch(parent, RecursiveMode::NonRecursive) {
+                            tracing::warn!("Unable to watch `{}`: {error}", parent.display());
+                        }
+                    }
+                }
+            }
+
+            tracing::debug!("Watching {} dependencies for changes", dependencies.len());
+
+            // Wait for the source to be updated, or for a dependency to change (in which case
+            // collect any other changes within the debounce period e.g. multiple events when a
+            // file is saved, or several files written by a script)
+            let (source_changed, changed) = tokio::select! {
+                Ok(..) = updates.changed() => (true, HashSet::new()),
+                Some(path) = receiver.recv() => (false, debounce(path, &mut receiver).await),
+                else => break,
+            };
+
+            let node_ids = changed
+                .iter()
+                .filter_map(|path| dependencies.get(path))
+                .flatten()
+                .cloned()
+                .unique()
+                .collect_vec();
+            if !source_changed && node_ids.is_empty() {
+                continue;
+            }
+
+            let rendered = async {
+                if source_changed {
+                    tracing::info!("Source changed, re-rendering");
+                } else {
+                    tracing::info!("Dependencies changed, re-rendering");
+                }
+
+                doc.compile(CommandWait::Yes).await?;
+
+                // Re-execute nodes which depend on changed files (these will
+                // not be stale since their own code has not changed)
+                if !node_ids.is_empty() {
+                    doc.command(
+                        Command::ExecuteNodes((
+                            CommandNodes::new(node_ids, CommandScope::Only),
+                            execute_options.clone(),
+                        )),
+                        CommandWait::Yes,
+                    )
+                    .await?;
+                }
+
+                // Re-execute any stale nodes
+                doc.execute(execute_options.clone(), CommandWait::Yes)
+                    .await?;
+
+                self.export(doc, output).await?;
+
+                check_options.check(doc, &self.input).await
+            };
+            if let Err(error) = rendered.await {
+                tracing::error!("{error}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The period within which further changes to files are collected together
+const DEBOUNCE_MILLIS: u64 = 300;
+
+/// Collect changed paths until none have been received for the debounce period
+///
+/// Used so that multiple events when a file is saved, or several files written by a
+/// script, result in a single re-render.
+async fn debounce(
+    first: PathBuf,
+    receiver: &mut mpsc::UnboundedReceiver<PathBuf>,
+) -> HashSet<PathBuf> {
+    let mut changed = HashSet::from([first]);
+    while let Ok(Some(path)) =
+        time::timeout(Duration::from_millis(DEBOUNCE_MILLIS), receiver.recv()).await
+    {
+        changed.insert(path);
+    }
+    changed
+}
+
+/// A visitor that collects the files that a document depends upon
+/// and the ids of the nodes which depend upon each of them
+struct DependencyCollector<'dir> {
+    /// The directory of the document, relative to which paths are resolved
+    dir: &'dir Path,
+
+    /// The canonical paths of the files and the nodes that depend upon them
+    dependencies: HashMap<PathBuf, Vec<NodeId>>,
+}
+
+impl<'dir> DependencyCollector<'dir> {
+    fn new(dir: &'dir Path) -> Self {
+        Self {
+            dir,
+            dependencies: HashMap::new(),
+        }
+    }
+
+    /// Add a file dependency
+    ///
+    /// Files which do not exist (or are URLs) are ignored.
+    fn add(&mut self, path: &str, node_id: NodeId) {
+        if path.contains("://") {
+            return;
+        }
+
+        if let Ok(path) = self.dir.join(path).canonicalize() {
+            self.dependencies.entry(path).or_default().push(node_id);
+        }
+    }
+}
+
+impl<'dir> Visitor for DependencyCollector<'dir> {
+    fn visit_block(&mut self, block: &Block) -> WalkControl {
+        match block {
+            Block::IncludeBlock(block) => self.add(&block.source, block.node_id()),
+            Block::CodeChunk(chunk) => {
+                // Files which the code chunk has been determined to read, or
+                // which have been declared using a `@reads` or `@watches` tag
+                for dependency in chunk.options.execution_dependencies.iter().flatten() {
+                    if let ExecutionDependencyNode::File(File { path, .. }) =
+                        &dependency.dependency_node
+                    {
+                        self.add(path, chunk.node_id());
+                    }
+                }
+                for tag in chunk.options.execution_tags.iter().flatten() {
+                    if matches!(tag.name.as_str(), "reads" | "watches") {
+                        self.add(&tag.value, chunk.node_id());
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        WalkControl::Continue
+    }
+}
+
+/// A visitor that collects the names of the parameters in a document
+#[derive(Default)]
+struct ParameterCollector {
+    names: Vec<String>,
+}
+
+impl Visitor for ParameterCollector {
+    fn visit_inline(&mut self, inline: &Inline) -> WalkControl {
+        if let Inline::Parameter(parameter) = inline {
+            self.names.push(parameter.name.clone());
+        }
+
+        WalkControl::Continue
+    }
+}
+
+/// Check that each of the supplied parameters is a parameter in the document
+///
+/// Errors naming any unknown parameters so that a misspelt name does not
+/// result in a document silently rendered with the default value.
+async fn check_parameter_names(doc: &Document, parameters: &[(String, String)]) -> Result<()> {
+    let mut collector = ParameterCollector::default();
+    collector.visit(&*doc.root_read().await);
+
+    let unknown = unknown_parameters(&collector.names, parameters);
+    if !unknown.is_empty() {
+        bail!(
+            "Unknown parameter{} {} (the document has {})",
+            if unknown.len() > 1 { "s" } else { "" },
+            unknown.iter().map(|name| format!("`{name}`")).join(", "),
+            if collector.names.is_empty() {
+                "no parameters".to_string()
+            } else {
+                collector
+                    .names
+                    .iter()
+                    .map(|name| format!("`{name}`"))
+                    .join(", ")
+            }
+        )
+    }
+
+    Ok(())
+}
+
+/// Get the names of supplied parameters which are not in a list of known names
+fn unknown_parameters<'p>(known: &[String], parameters: &'p [(String, String)]) -> Vec<&'p str> {
+    parameters
+        .iter()
+        .map(|(name, ..)| name.as_str())
+        .filter(|name| !known.iter().any(|known| known == name))
+        .unique()
+        .collect()
+}
+
+/// Parse a `name=value` pair for the `--param` option
+fn parse_parameter(pair: &str) -> Result<(String, String), String> {
+    match pair.split_once('=') {
+        Some((name, value)) if !name.trim().is_empty() => {
+            Ok((name.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected `name=value`, got `{pair}`")),
+    }
+}
+
+/// Check that the value of a parameter can be used in place of a placeholder in an output path
+///
+/// Values which are empty, contain path separators, or are a relative path component
+/// (e.g. `..`) are rejected so that each parameter set is written to a file alongside
+/// the others rather than elsewhere in the file system.
+fn check_path_value(name: &str, value: &str) -> Result<()> {
+    if value.is_empty() || value == "." || value.contains("..") || value.contains(['/', '\\', '\0'])
+    {
+        bail!("The value of parameter `{name}` can not be used in the output path: `{value}`")
+    }
+
+    Ok(())
+}
+
+/// Read sets of parameter values from a CSV or JSON file
+async fn read_parameter_sets(path: &Path) -> Result<Vec<Vec<(String, String)>>> {
+    let content = read_to_string(path).await?;
+
+    parse_parameter_sets(&content, &Format::from_path(path))
+}
+
+/// Parse sets of parameter values from CSV or JSON content
+fn parse_parameter_sets(content: &str, format: &Format) -> Result<Vec<Vec<(String, String)>>> {
+    if format == &Format::Json {
+        let Value::Array(items) = serde_json::from_str(content)? else {
+            bail!("Expected the JSON file to contain an array of objects")
+        };
+        return items
+            .into_iter()
+            .map(|item| match item {
+                Value::Object(object) => Ok(object
+                    .into_iter()
+                    .map(|(name, value)| {
+                        let value = match value {
+                            Value::String(string) => string,
+                            _ => value.to_string(),
+                        };
+                        (name, value)
+                    })
+                    .collect()),
+                _ => bail!("Expected each item in the JSON array to be an object"),
+            })
+            .collect();
+    }
+
+    let mut rows = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(split_csv_line);
+    let names = rows
+        .next()
+        .ok_or_else(|| eyre!("Expected a header row in the CSV file"))?;
+
+    rows.map(|row| {
+        if row.len() != names.len() {
+            bail!(
+                "Expected {} values in CSV row but got {}",
+                names.len(),
+                row.len()
+            )
+        }
+        Ok(names.iter().cloned().zip(row).collect())
+    })
+    .collect()
+}
+
+/// Split a line of a CSV file into fields
+///
+/// Handles double quoted fields, including escaped quotes within them, but
+/// not fields spanning more than one line.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+    while let Some(char) = chars.next() {
+        match char {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(char),
+        }
+    }
+    fields.push(field.trim().to_string());
+
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use common_dev::pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn set(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn csv_lines() {
+        assert_eq!(split_csv_line("a,b,c"), strings(&["a", "b", "c"]));
+        assert_eq!(split_csv_line(" a , b ,c\r"), strings(&["a", "b", "c"]));
+
+        // Empty fields
+        assert_eq!(split_csv_line("a,,c"), strings(&["a", "", "c"]));
+        assert_eq!(split_csv_line(",b,"), strings(&["", "b", ""]));
+        assert_eq!(split_csv_line(""), strings(&[""]));
+
+        // Quoted fields containing commas and escaped quotes
+        assert_eq!(
+            split_csv_line(r#"a,"b, c",d"#),
+            strings(&["a", "b, c", "d"])
+        );
+        assert_eq!(
+            split_csv_line(r#""say ""hi""",2"#),
+            strings(&[r#"say "hi""#, "2"])
+        );
+        assert_eq!(split_csv_line(r#""","""#), strings(&["", ""]));
+    }
+
+    #[test]
+    fn csv_parameter_sets() -> Result<()> {
+        let csv = Format::from_path(Path::new("params.csv"));
+
+        let sets = parse_parameter_sets("site,n,note\n\nA12,1,\"north, east\"\r\nB7,,\n", &csv)?;
+        assert_eq!(
+            sets,
+            vec![
+                set(&[("site", "A12"), ("n", "1"), ("note", "north, east")]),
+                set(&[("site", "B7"), ("n", ""), ("note", "")]),
+            ]
+        );
+
+        assert!(parse_parameter_sets("", &csv).is_err());
+        assert!(parse_parameter_sets("a,b\n1,2,3", &csv).is_err());
+        assert!(parse_parameter_sets("a,b\n1", &csv).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn json_parameter_sets() -> Result<()> {
+        // Values other than strings are passed on as JSON so that they
+        // are coerced to the type of the parameter when it is executed
+        let sets = parse_parameter_sets(
+            r#"[{"site": "A12", "n": 1, "ok": true, "x": null}, {"site": "B7"}]"#,
+            &Format::Json,
+        )?;
+        assert_eq!(
+            sets,
+            vec![
+                set(&[("site", "A12"), ("n", "1"), ("ok", "true"), ("x", "null")]),
+                set(&[("site", "B7")]),
+            ]
+        );
+
+        assert!(parse_parameter_sets(r#"{"site": "A12"}"#, &Format::Json).is_err());
+        assert!(parse_parameter_sets(r#"["A12"]"#, &Format::Json).is_err());
+        assert!(parse_parameter_sets("[", &Format::Json).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_parameter_names() {
+        let known = strings(&["site", "n"]);
+
+        assert!(unknown_parameters(&known, &set(&[("site", "A12"), ("n", "1")])).is_empty());
+        assert_eq!(
+            unknown_parameters(
+                &known,
+                &set(&[("sit", "A12"), ("n", "1"), ("m", "2"), ("sit", "B7")])
+            ),
+            vec!["sit", "m"]
+        );
+        assert_eq!(unknown_parameters(&[], &set(&[("n", "1")])), vec!["n"]);
+    }
+
+    #[test]
+    fn path_values() {
+        assert!(check_path_value("site", "A12").is_ok());
+        assert!(check_path_value("site", "north-east_2").is_ok());
+        assert!(check_path_value("date", "2024.01.02").is_ok());
+
+        assert!(check_path_value("site", "").is_err());
+        assert!(check_path_value("site", ".").is_err());
+        assert!(check_path_value("site", "..").is_err());
+        assert!(check_path_value("site", "../secret").is_err());
+        assert!(check_path_value("site", "a/b").is_err());
+        assert!(check_path_value("site", "/etc/passwd").is_err());
+        assert!(check_path_value("site", "a\\b").is_err());
+        assert!(check_path_value("site", "a..b").is_err());
+    }
+
+    #[test]
+    fn dependencies() -> Result<()> {
+        use common::tempfile;
+        use schema::{
+            Article, CodeChunk, CodeChunkOptions, ExecutionDependency, ExecutionDependencyRelation,
+            ExecutionTag, IncludeBlock, Node,
+        };
+
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("inc.md"), "")?;
+        std::fs::write(dir.path().join("data.csv"), "")?;
+        std::fs::write(dir.path().join("other.csv"), "")?;
+
+        let include = IncludeBlock::new("inc.md".to_string());
+        let chunk = CodeChunk {
+            options: Box::new(CodeChunkOptions {
+                execution_dependencies: Some(vec![ExecutionDependency::new(
+                    ExecutionDependencyRelation::Reads,
+                    ExecutionDependencyNode::File(File::new(
+                        "data.csv".to_string(),
+                        "data.csv".to_string(),
+                    )),
+                )]),
+                execution_tags: Some(vec![
+                    ExecutionTag::new("watches".to_string(), "other.csv".to_string(), false),
+                    ExecutionTag::new("timeout".to_string(), "inc.md".to_string(), false),
+                ]),
+                ..Default::default()
+            }),
+            ..CodeChunk::new("read('data.csv')".into())
+        };
+        let ignored = [
+            IncludeBlock::new("https://example.org/inc.md".to_string()),
+            IncludeBlock::new("missing.md".to_string()),
+        ];
+
+        let article = Node::Article(Article::new(
+            [
+                Block::IncludeBlock(include.clone()),
+                Block::CodeChunk(chunk.clone()),
+            ]
+            .into_iter()
+            .chain(ignored.into_iter().map(Block::IncludeBlock))
+            .collect(),
+        ));
+
+        let mut collector = DependencyCollector::new(dir.path());
+        collector.visit(&article);
+
+        let path = |name: &str| dir.path().join(name).canonicalize();
+        assert_eq!(
+            collector.dependencies,
+            HashMap::from([
+                (path("inc.md")?, vec![include.node_id()]),
+                (path("data.csv")?, vec![chunk.node_id()]),
+                (path("other.csv")?, vec![chunk.node_id()]),
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn debounced() {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+
+        // Changes within the debounce period are collected together (including
+        // repeated changes to the same file)...
+        let send = |path: &str| sender.send(PathBuf::from(path)).unwrap();
+        send("b");
+        send("a");
+        tokio::spawn({
+            let sender = sender.clone();
+            async move {
+                time::sleep(Duration::from_millis(DEBOUNCE_MILLIS / 3)).await;
+                sender.send(PathBuf::from("c")).ok();
+                time::sleep(Duration::from_millis(DEBOUNCE_MILLIS * 3)).await;
+                sender.send(PathBuf::from("d")).ok();
+            }
+        });
+        let changed = debounce(PathBuf::from("a"), &mut receiver).await;
+        assert_eq!(changed, HashSet::from(["a", "b", "c"].map(PathBuf::from)));
+
+        // ...but not those after it
+        assert_eq!(receiver.recv().await, Some(PathBuf::from("d")));
+    }
 }
@@ -0,0 +1,215 @@
+use codec::{
+    common::{
+        eyre::{bail, Result},
+        serde_json::{self, Map, Value},
+    },
+    format::Format,
+    schema::{
+        shortcuts::t, Article, Block, CodeChunk, ExecutionMessage, MessageLevel, Node, Object,
+        Primitive, RawBlock,
+    },
+    Codec, DecodeInfo, DecodeOptions, Losses,
+};
+use codec_markdown::MarkdownCodec;
+use jupyter_outputs::{mime_bundle_to_node, mime_text, strip_ansi};
+
+use crate::nbformat::{Cell, CodeCell, Notebook, Output, TextCell};
+
+/// Decode a Jupyter Notebook to an `Article`
+pub(super) async fn decode(
+    json: &str,
+    _options: Option<DecodeOptions>,
+) -> Result<(Node, DecodeInfo)> {
+    let notebook: Notebook = serde_json::from_str(json)?;
+    if notebook.nbformat != 4 {
+        bail!(
+            "Only version 4 of the Jupyter Notebook format is supported, got version {}",
+            notebook.nbformat
+        )
+    }
+
+    let lang = language(&notebook.metadata);
+
+    let mut losses = Losses::none();
+    let mut content = Vec::new();
+    for cell in notebook.cells {
+        match cell {
+            Cell::Markdown(cell) => content.append(&mut markdown_cell(cell, &mut losses).await?),
+            Cell::Code(cell) => content.push(code_cell(cell, lang.clone())),
+            Cell::Raw(cell) => content.push(raw_cell(cell)),
+        }
+    }
+
+    let title = notebook
+        .metadata
+        .get("title")
+        .and_then(|title| title.as_str())
+        .map(|title| vec![t(title)]);
+
+    let article = Article {
+        title,
+        ..Article::new(content)
+    };
+
+    Ok((
+        Node::Article(article),
+        DecodeInfo {
+            losses,
+            ..Default::default()
+        },
+    ))
+}
+
+/// Get the programming language of a notebook from its metadata
+fn language(metadata: &Map<String, Value>) -> Option<String> {
+    metadata
+        .get("kernelspec")
+        .and_then(|kernelspec| kernelspec.get("language"))
+        .or_else(|| {
+            metadata
+                .get("language_info")
+                .and_then(|info| info.get("name"))
+        })
+        .and_then(|lang| lang.as_str())
+        .map(|lang| lang.to_lowercase())
+}
+
+/// Get the cell metadata which is not otherwise handled
+///
+/// Used to preserve the metadata of code and raw cells (e.g. tags and
+/// extension specific metadata) so that it can be written back when encoding.
+fn unhandled_metadata(mut metadata: Map<String, Value>, handled: &[&str]) -> Option<Object> {
+    metadata.retain(|key, ..| !handled.contains(&key.as_str()));
+
+    let mut object = Object::new();
+    for (key, value) in metadata {
+        if let Ok(value) = serde_json::from_value::<Primitive>(value) {
+            object.insert(key, value);
+        }
+    }
+
+    (!object.is_empty()).then_some(object)
+}
+
+/// Decode a Markdown cell to blocks
+///
+/// Attachments (e.g. images pasted into the cell) are inlined as data URIs.
+async fn markdown_cell(cell: TextCell, losses: &mut Losses) -> Result<Vec<Block>> {
+    // There is no node to preserve the metadata of Markdown cells on
+    if !cell.metadata.is_empty() {
+        losses.add("Cell.metadata");
+    }
+
+    let mut md = cell.source.0;
+    for (name, bundle) in cell.attachments.iter().flatten() {
+        let Some((media_type, data)) = bundle.as_object().and_then(|bundle| bundle.iter().next())
+        else {
+            continue;
+        };
+        md = md.replace(
+            &["attachment:", name].concat(),
+            &format!("data:{media_type};base64,{}", mime_text(data).trim()),
+        );
+    }
+
+    let (node, info) = MarkdownCodec
+        .from_str(
+            &md,
+            Some(DecodeOptions {
+                format: Some(Format::Markdown),
+                ..Default::default()
+            }),
+        )
+        .await?;
+    losses.merge(info.losses);
+
+    Ok(match node {
+        Node::Article(article) => article.content,
+        _ => Vec::new(),
+    })
+}
+
+/// Decode a code cell to a `CodeChunk`
+fn code_cell(mut cell: CodeCell, lang: Option<String>) -> Block {
+    let outputs_hidden = cell
+        .metadata
+        .get("jupyter")
+        .and_then(|jupyter| jupyter.get("outputs_hidden"))
+        .or_else(|| cell.metadata.get("collapsed"))
+        .and_then(|hidden| hidden.as_bool())
+        .unwrap_or_default();
+
+    let mut outputs = Vec::new();
+    let mut messages = Vec::new();
+    for output in cell.outputs {
+        match output {
+            Output::ExecuteResult { data, .. } | Output::DisplayData { data, .. } => {
+                outputs.push(mime_bundle_to_node(&data))
+            }
+            Output::Stream { name, text } => {
+                if name == "stderr" {
+                    messages.push(ExecutionMessage::new(MessageLevel::Warning, text.0));
+                } else {
+                    outputs.push(Node::String(text.0));
+                }
+            }
+            Output::Error {
+                ename,
+                evalue,
+                traceback,
+            } => {
+                let stack_trace = traceback
+                    .iter()
+                    .map(|line| strip_ansi(line))
+                    .collect::<Vec<_>>();
+                messages.push(ExecutionMessage {
+                    error_type: Some(ename),
+                    stack_trace: (!stack_trace.is_empty()).then(|| stack_trace.join("\n")),
+                    ..ExecutionMessage::new(MessageLevel::Exception, evalue)
+                });
+            }
+        }
+    }
+
+    // The `outputs_hidden` flag is represented by `is_invisible` so remove it (and the
+    // `jupyter` key if nothing else is in it) before preserving other metadata
+    if let Some(Value::Object(jupyter)) = cell.metadata.get_mut("jupyter") {
+        jupyter.remove("outputs_hidden");
+        if jupyter.is_empty() {
+            cell.metadata.remove("jupyter");
+        }
+    }
+
+    let mut chunk = CodeChunk {
+        id: cell.id,
+        programming_language: lang,
+        outputs: (!outputs.is_empty()).then_some(outputs),
+        is_invisible: outputs_hidden.then_some(true),
+        ..CodeChunk::new(cell.source.0.into())
+    };
+    chunk.options.execution_count = cell.execution_count;
+    chunk.options.execution_messages = (!messages.is_empty()).then_some(messages);
+    chunk.options.metadata = unhandled_metadata(cell.metadata, &["collapsed"]);
+
+    Block::CodeChunk(chunk)
+}
+
+/// Decode a raw cell to a `RawBlock`
+///
+/// The format of the block is taken from the `format` (a media type, e.g. `text/html`)
+/// in the cell's metadata, defaulting to plain text.
+fn raw_cell(cell: TextCell) -> Block {
+    let format = cell
+        .metadata
+        .get("format")
+        .or_else(|| cell.metadata.get("raw_mimetype"))
+        .and_then(|format| format.as_str())
+        .and_then(|media_type| Format::from_media_type(media_type).ok())
+        .unwrap_or(Format::Text);
+
+    Block::RawBlock(RawBlock {
+        id: cell.id,
+        metadata: unhandled_metadata(cell.metadata, &["format", "raw_mimetype"]),
+        ..RawBlock::new(format.to_string(), cell.source.0.into())
+    })
+}
@@ -0,0 +1,389 @@
+use std::collections::HashSet;
+
+use codec::{
+    common::{
+        eyre::Result,
+        seahash,
+        serde::Serialize,
+        serde_json::{self, json, ser::PrettyFormatter, Map, Serializer, Value},
+    },
+    format::Format,
+    schema::{
+        Article, Block, CodeChunk, Datatable, ImageObject, MessageLevel, Node, Object, RawBlock,
+    },
+    Codec, EncodeInfo, EncodeOptions, Losses,
+};
+use codec_markdown::MarkdownCodec;
+use codec_text_trait::to_text;
+
+use crate::nbformat::{Cell, CodeCell, MultilineString, Notebook, Output, TextCell};
+
+/// Encode an `Article` as a Jupyter Notebook
+pub(super) async fn encode(
+    node: &Node,
+    options: Option<EncodeOptions>,
+) -> Result<(String, EncodeInfo)> {
+    let Node::Article(article) = node else {
+        return Ok((
+            String::new(),
+            EncodeInfo {
+                losses: Losses::one(node.to_string()),
+                ..Default::default()
+            },
+        ));
+    };
+
+    let mut losses = Losses::none();
+    let mut cells = Vec::new();
+    let mut lang: Option<String> = None;
+
+    // Consecutive blocks which are not code chunks or raw blocks are
+    // encoded together as a Markdown cell
+    let mut prose = Vec::new();
+    for block in &article.content {
+        match block {
+            Block::CodeChunk(chunk) => {
+                markdown_cell(&mut prose, &mut cells, &mut losses).await?;
+
+                match (&lang, &chunk.programming_language) {
+                    (None, Some(chunk_lang)) => lang = Some(chunk_lang.to_lowercase()),
+                    (Some(lang), Some(chunk_lang)) if !chunk_lang.eq_ignore_ascii_case(lang) => {
+                        losses.add("CodeChunk.programmingLanguage")
+                    }
+                    _ => {}
+                }
+
+                cells.push(code_cell(chunk));
+            }
+            Block::RawBlock(raw) => {
+                markdown_cell(&mut prose, &mut cells, &mut losses).await?;
+                cells.push(raw_cell(raw));
+            }
+            _ => prose.push(block.clone()),
+        }
+    }
+    markdown_cell(&mut prose, &mut cells, &mut losses).await?;
+    assign_ids(&mut cells);
+
+    let notebook = Notebook {
+        metadata: metadata(article, lang.as_deref()),
+        nbformat: 4,
+        nbformat_minor: 5,
+        cells,
+    };
+
+    let json = if options
+        .and_then(|options| options.compact)
+        .unwrap_or_default()
+    {
+        serde_json::to_string(&notebook)?
+    } else {
+        // Use the same indentation as Jupyter
+        let mut bytes = Vec::new();
+        let mut serializer =
+            Serializer::with_formatter(&mut bytes, PrettyFormatter::with_indent(b" "));
+        notebook.serialize(&mut serializer)?;
+        String::from_utf8(bytes)?
+    };
+
+    Ok((
+        json,
+        EncodeInfo {
+            losses,
+            ..Default::default()
+        },
+    ))
+}
+
+/// Assign ids to cells that do not have one
+///
+/// Jupyter uses random ids of eight characters for new cells. Instead, ids are
+/// derived from the type and source of each cell so that encoding the same document
+/// always produces the same notebook (avoiding spurious diffs under version control).
+fn assign_ids(cells: &mut [Cell]) {
+    let mut ids: HashSet<String> = cells
+        .iter()
+        .filter_map(|cell| match cell {
+            Cell::Markdown(TextCell { id, .. })
+            | Cell::Raw(TextCell { id, .. })
+            | Cell::Code(CodeCell { id, .. }) => id.clone(),
+        })
+        .collect();
+
+    for cell in cells {
+        let (id, source) = match cell {
+            Cell::Markdown(TextCell { id, source, .. }) => (id, ["markdown", &source.0].concat()),
+            Cell::Raw(TextCell { id, source, .. }) => (id, ["raw", &source.0].concat()),
+            Cell::Code(CodeCell { id, source, .. }) => (id, ["code", &source.0].concat()),
+        };
+        if id.is_some() {
+            continue;
+        }
+
+        // Rehash in the unlikely case of a collision, or more likely, if
+        // there are cells with the same type and source
+        let mut hash = seahash::hash(source.as_bytes());
+        let mut candidate = format!("{:08x}", hash as u32);
+        while ids.contains(&candidate) {
+            hash = seahash::hash(&hash.to_le_bytes());
+            candidate = format!("{:08x}", hash as u32);
+        }
+
+        ids.insert(candidate.clone());
+        *id = Some(candidate);
+    }
+}
+
+/// Create the metadata for a cell from the metadata preserved on a node
+fn cell_metadata(metadata: &Option<Object>) -> Map<String, Value> {
+    match metadata
+        .as_ref()
+        .and_then(|metadata| serde_json::to_value(metadata).ok())
+    {
+        Some(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+/// Create the metadata for a notebook
+fn metadata(article: &Article, lang: Option<&str>) -> Map<String, Value> {
+    let mut metadata = Map::new();
+
+    if let Some(lang) = lang {
+        let (name, display_name) = match lang {
+            "python" | "py" => ("python3", "Python 3"),
+            "r" => ("ir", "R"),
+            _ => (lang, lang),
+        };
+        metadata.insert(
+            "kernelspec".to_string(),
+            json!({
+                "name": name,
+                "display_name": display_name,
+                "language": lang
+            }),
+        );
+        metadata.insert("language_info".to_string(), json!({ "name": lang }));
+    }
+
+    if let Some(title) = &article.title {
+        metadata.insert("title".to_string(), json!(to_text(title)));
+    }
+
+    metadata
+}
+
+/// Encode blocks as a Markdown cell, draining them
+async fn markdown_cell(
+    blocks: &mut Vec<Block>,
+    cells: &mut Vec<Cell>,
+    losses: &mut Losses,
+) -> Result<()> {
+    if blocks.is_empty() {
+        return Ok(());
+    }
+
+    let (md, info) = MarkdownCodec
+        .to_string(
+            &Node::Article(Article::new(blocks.drain(..).collect())),
+            Some(EncodeOptions {
+                format: Some(Format::Markdown),
+                ..Default::default()
+            }),
+        )
+        .await?;
+    losses.merge(info.losses);
+
+    cells.push(Cell::Markdown(TextCell {
+        id: None,
+        metadata: Map::new(),
+        source: MultilineString(md.trim_end().to_string()),
+        attachments: None,
+    }));
+
+    Ok(())
+}
+
+/// Encode a `CodeChunk` as a code cell
+fn code_cell(chunk: &CodeChunk) -> Cell {
+    let execution_count = chunk.options.execution_count;
+
+    let mut outputs = chunk
+        .outputs
+        .iter()
+        .flatten()
+        .map(|output| node_to_output(output, execution_count))
+        .collect::<Vec<_>>();
+
+    for message in chunk.options.execution_messages.iter().flatten() {
+        outputs.push(match message.level {
+            MessageLevel::Exception | MessageLevel::Error => Output::Error {
+                ename: message
+                    .error_type
+                    .clone()
+                    .unwrap_or_else(|| "Error".to_string()),
+                evalue: message.message.clone(),
+                traceback: message
+                    .stack_trace
+                    .iter()
+                    .flat_map(|trace| trace.lines())
+                    .map(String::from)
+                    .collect(),
+            },
+            _ => Output::Stream {
+                name: "stderr".to_string(),
+                text: MultilineString(message.message.clone()),
+            },
+        });
+    }
+
+    let mut metadata = cell_metadata(&chunk.options.metadata);
+    if chunk.is_invisible == Some(true) {
+        match metadata
+            .entry("jupyter")
+            .or_insert_with(|| Value::Object(Map::new()))
+        {
+            Value::Object(jupyter) => {
+                jupyter.insert("outputs_hidden".to_string(), json!(true));
+            }
+            jupyter => *jupyter = json!({ "outputs_hidden": true }),
+        }
+    }
+
+    Cell::Code(CodeCell {
+        id: chunk.id.clone(),
+        metadata,
+        source: MultilineString(chunk.code.to_string()),
+        execution_count,
+        outputs,
+    })
+}
+
+/// Encode a `RawBlock` as a raw cell
+fn raw_cell(raw: &RawBlock) -> Cell {
+    let mut metadata = cell_metadata(&raw.metadata);
+    metadata.insert(
+        "format".to_string(),
+        json!(Format::from_name(&raw.format).media_type()),
+    );
+
+    Cell::Raw(TextCell {
+        id: raw.id.clone(),
+        metadata,
+        source: MultilineString(raw.content.to_string()),
+        attachments: None,
+    })
+}
+
+/// Encode an output of a code chunk as a cell output
+///
+/// Strings are encoded as `stdout` streams, images, HTML and datatables as
+/// `display_data`, and all other nodes as an `execute_result`.
+fn node_to_output(node: &Node, execution_count: Option<i64>) -> Output {
+    let display_data = |data: Value| Output::DisplayData {
+        data: match data {
+            Value::Object(data) => data,
+            _ => Map::new(),
+        },
+        metadata: Map::new(),
+    };
+
+    match node {
+        Node::String(text) => Output::Stream {
+            name: "stdout".to_string(),
+            text: MultilineString(text.clone()),
+        },
+        Node::ImageObject(image) => display_data(image_bundle(image)),
+        Node::RawBlock(raw) if raw.format == "html" => {
+            display_data(json!({ "text/html": raw.content.to_string() }))
+        }
+        Node::Datatable(datatable) => display_data(json!({
+            "application/vnd.dataresource+json": datatable_to_data_resource(datatable),
+            "text/plain": to_text(datatable)
+        })),
+        _ => {
+            let mut data = Map::new();
+            let value = serde_json::to_value(node).unwrap_or_default();
+            data.insert("text/plain".to_string(), json!(value.to_string()));
+            if !matches!(
+                node,
+                Node::Null(..)
+                    | Node::Boolean(..)
+                    | Node::Integer(..)
+                    | Node::UnsignedInteger(..)
+                    | Node::Number(..)
+            ) {
+                data.insert("application/json".to_string(), value);
+            }
+            Output::ExecuteResult {
+                execution_count,
+                data,
+                metadata: Map::new(),
+            }
+        }
+    }
+}
+
+/// Create a MIME bundle for an image
+///
+/// Images with data URIs have their data put in the bundle. Otherwise an HTML `<img>`
+/// referencing the image's URL is used.
+fn image_bundle(image: &ImageObject) -> Value {
+    let url = &image.content_url;
+    if let Some((header, data)) = url
+        .strip_prefix("data:")
+        .and_then(|rest| rest.split_once(','))
+    {
+        let media_type = header.split(';').next().unwrap_or_default();
+        let data = if header.ends_with(";base64") {
+            data.to_string()
+        } else {
+            data.replace("%23", "#")
+        };
+        let mut bundle = Map::new();
+        bundle.insert(media_type.to_string(), json!(data));
+        bundle.insert("text/plain".to_string(), json!("<Image>"));
+        Value::Object(bundle)
+    } else {
+        json!({ "text/html": format!(r#"<img src="{url}">"#), "text/plain": url })
+    }
+}
+
+/// Encode a `Datatable` as a Table Data Resource
+fn datatable_to_data_resource(datatable: &Datatable) -> Value {
+    let fields = datatable
+        .columns
+        .iter()
+        .map(|column| json!({ "name": column.name }))
+        .collect::<Vec<_>>();
+
+    let rows = datatable
+        .columns
+        .iter()
+        .map(|column| column.values.len())
+        .max()
+        .unwrap_or_default();
+    let data = (0..rows)
+        .map(|row| {
+            datatable
+                .columns
+                .iter()
+                .map(|column| {
+                    (
+                        column.name.clone(),
+                        column
+                            .values
+                            .get(row)
+                            .and_then(|value| serde_json::to_value(value).ok())
+                            .unwrap_or_default(),
+                    )
+                })
+                .collect::<Map<_, _>>()
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "schema": { "fields": fields },
+        "data": data
+    })
+}
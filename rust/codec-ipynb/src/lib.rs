@@ -0,0 +1,111 @@
+use codec::{
+    common::{async_trait::async_trait, eyre::Result},
+    format::Format,
+    schema::{Node, NodeType},
+    status::Status,
+    Codec, CodecSupport, DecodeInfo, DecodeOptions, EncodeInfo, EncodeOptions,
+};
+
+mod decode;
+mod encode;
+mod nbformat;
+
+#[cfg(test)]
+mod tests;
+
+/// A codec for Jupyter Notebooks
+///
+/// Markdown cells are decoded and encoded using the Markdown codec, code cells
+/// as `CodeChunk`s (with outputs mapped to and from MIME bundles), and raw cells
+/// as `RawBlock`s.
+pub struct IpynbCodec;
+
+#[async_trait]
+impl Codec for IpynbCodec {
+    fn name(&self) -> &str {
+        "ipynb"
+    }
+
+    fn status(&self) -> Status {
+        Status::UnderDevelopment
+    }
+
+    fn supports_from_format(&self, format: &Format) -> CodecSupport {
+        use CodecSupport::*;
+        match format {
+            Format::Ipynb => LowLoss,
+            _ => None,
+        }
+    }
+
+    fn supports_to_format(&self, format: &Format) -> CodecSupport {
+        use CodecSupport::*;
+        match format {
+            Format::Ipynb => LowLoss,
+            _ => None,
+        }
+    }
+
+    fn supports_from_type(&self, node_type: NodeType) -> CodecSupport {
+        use CodecSupport::*;
+        use NodeType::*;
+        match node_type {
+            // Data
+            String | Null | Boolean | Integer | UnsignedInteger | Number | Array | Object
+            | Datatable => LowLoss,
+            // Prose Inlines and Blocks (via Markdown)
+            Text | Emphasis | Strong | Subscript | Superscript | Underline => NoLoss,
+            Link | ImageObject => LowLoss,
+            Heading | Paragraph | QuoteBlock | ThematicBreak => NoLoss,
+            List | ListItem | Table | TableRow | TableCell => LowLoss,
+            // Code
+            CodeInline | CodeBlock => NoLoss,
+            CodeChunk | RawBlock => LowLoss,
+            // Math
+            MathInline | MathBlock => NoLoss,
+            // Works
+            Article => LowLoss,
+            _ => None,
+        }
+    }
+
+    fn supports_to_type(&self, node_type: NodeType) -> CodecSupport {
+        use CodecSupport::*;
+        use NodeType::*;
+        match node_type {
+            // Data
+            String | Null | Boolean | Integer | UnsignedInteger | Number | Array | Object
+            | Datatable => LowLoss,
+            // Prose Inlines and Blocks (via Markdown)
+            Text | Emphasis | Strong | Subscript | Superscript | Underline => NoLoss,
+            Link | ImageObject => LowLoss,
+            Heading | Paragraph | QuoteBlock | ThematicBreak => NoLoss,
+            List | ListItem | Table | TableRow | TableCell => LowLoss,
+            // Code
+            CodeInline | CodeBlock => NoLoss,
+            CodeChunk | RawBlock => LowLoss,
+            // Math
+            MathInline | MathBlock => NoLoss,
+            // Works
+            Article => LowLoss,
+            // Other block types are encoded as Markdown
+            _ => HighLoss,
+        }
+    }
+
+    async fn from_str(
+        &self,
+        str: &str,
+        options: Option<DecodeOptions>,
+    ) -> Result<(Node, DecodeInfo)> {
+        decode::decode(str, options).await
+    }
+
+    async fn to_string(
+        &self,
+        node: &Node,
+        options: Option<EncodeOptions>,
+    ) -> Result<(String, EncodeInfo)> {
+        encode::encode(node, options).await
+    }
+}
@@ -0,0 +1,139 @@
+//! Types for version 4 of the Jupyter Notebook format
+//!
+//! See https://nbformat.readthedocs.io/en/latest/format_description.html. Only
+//! those properties that are used by the codec are typed, with others kept as
+//! JSON values so that they are not lost when a notebook is read.
+
+use codec::common::{
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
+    serde_json::{Map, Value},
+};
+
+/// A Jupyter Notebook
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(crate = "codec::common::serde")]
+pub(crate) struct Notebook {
+    #[serde(default)]
+    pub metadata: Map<String, Value>,
+
+    pub nbformat: u64,
+
+    pub nbformat_minor: u64,
+
+    #[serde(default)]
+    pub cells: Vec<Cell>,
+}
+
+/// A cell in a notebook
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(
+    tag = "cell_type",
+    rename_all = "lowercase",
+    crate = "codec::common::serde"
+)]
+pub(crate) enum Cell {
+    Markdown(TextCell),
+    Code(CodeCell),
+    Raw(TextCell),
+}
+
+/// A Markdown or raw cell
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(crate = "codec::common::serde")]
+pub(crate) struct TextCell {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(default)]
+    pub metadata: Map<String, Value>,
+
+    pub source: MultilineString,
+
+    /// Files (e.g. images) attached to the cell keyed by file name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<Map<String, Value>>,
+}
+
+/// A code cell
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(crate = "codec::common::serde")]
+pub(crate) struct CodeCell {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(default)]
+    pub metadata: Map<String, Value>,
+
+    pub source: MultilineString,
+
+    pub execution_count: Option<i64>,
+
+    #[serde(default)]
+    pub outputs: Vec<Output>,
+}
+
+/// An output of a code cell
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(
+    tag = "output_type",
+    rename_all = "snake_case",
+    crate = "codec::common::serde"
+)]
+pub(crate) enum Output {
+    ExecuteResult {
+        execution_count: Option<i64>,
+        data: Map<String, Value>,
+        #[serde(default)]
+        metadata: Map<String, Value>,
+    },
+    DisplayData {
+        data: Map<String, Value>,
+        #[serde(default)]
+        metadata: Map<String, Value>,
+    },
+    Stream {
+        name: String,
+        text: MultilineString,
+    },
+    Error {
+        ename: String,
+        evalue: String,
+        #[serde(default)]
+        traceback: Vec<String>,
+    },
+}
+
+/// A string that may be split into lines in the notebook
+///
+/// Jupyter writes strings as arrays of lines (each including its trailing newline)
+/// to make notebooks more readable in diffs, and the same is done here.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct MultilineString(pub String);
+
+impl Serialize for MultilineString {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.0.split_inclusive('\n'))
+    }
+}
+
+impl<'de> Deserialize<'de> for MultilineString {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged, crate = "codec::common::serde")]
+        enum Lines {
+            One(String),
+            Many(Vec<String>),
+        }
+
+        Ok(Self(match Lines::deserialize(deserializer)? {
+            Lines::One(string) => string,
+            Lines::Many(lines) => lines.concat(),
+        }))
+    }
+}
@@ -0,0 +1,229 @@
+use codec::{
+    common::{
+        eyre::{bail, Result},
+        serde_json, tokio,
+    },
+    schema::{
+        shortcuts::{art, cc, em, h1, p, t},
+        Article, Block, CodeChunk, MessageLevel, Node, RawBlock,
+    },
+};
+use common_dev::pretty_assertions::assert_eq;
+
+use super::*;
+
+#[tokio::test]
+async fn decode() -> Result<()> {
+    let ipynb = r##"{
+ "metadata": {
+  "kernelspec": {"name": "python3", "display_name": "Python 3", "language": "python"}
+ },
+ "nbformat": 4,
+ "nbformat_minor": 5,
+ "cells": [
+  {
+   "cell_type": "markdown",
+   "id": "a1",
+   "metadata": {},
+   "source": ["# Heading\n", "\n", "Some *emphasis*."]
+  },
+  {
+   "cell_type": "code",
+   "id": "b2",
+   "metadata": {"jupyter": {"outputs_hidden": true}},
+   "source": "print('hello')\n1 + 2",
+   "execution_count": 3,
+   "outputs": [
+    {"output_type": "stream", "name": "stdout", "text": ["hello\n"]},
+    {"output_type": "execute_result", "execution_count": 3, "data": {"text/plain": "3"}, "metadata": {}},
+    {"output_type": "display_data", "data": {"image/png": "iVBORw0KGgo=\n", "text/plain": "<Figure>"}, "metadata": {}},
+    {"output_type": "error", "ename": "ValueError", "evalue": "bad", "traceback": ["\u001b[0;31mValueError\u001b[0m: bad"]}
+   ]
+  },
+  {
+   "cell_type": "raw",
+   "id": "c3",
+   "metadata": {"format": "text/html"},
+   "source": "<hr>"
+  }
+ ]
+}"##;
+
+    let (node, ..) = IpynbCodec.from_str(ipynb, None).await?;
+    let Node::Article(Article { content, .. }) = node else {
+        bail!("Expected an article")
+    };
+
+    assert_eq!(content[0], h1([t("Heading")]));
+    assert_eq!(content[1], p([t("Some "), em([t("emphasis")]), t(".")]));
+
+    let Block::CodeChunk(chunk) = &content[2] else {
+        bail!("Expected a code chunk")
+    };
+    assert_eq!(chunk.id.as_deref(), Some("b2"));
+    assert_eq!(chunk.code.to_string(), "print('hello')\n1 + 2");
+    assert_eq!(chunk.programming_language.as_deref(), Some("python"));
+    assert_eq!(chunk.is_invisible, Some(true));
+    assert_eq!(chunk.options.execution_count, Some(3));
+
+    let outputs = chunk.outputs.clone().unwrap_or_default();
+    assert_eq!(outputs[0], Node::String("hello\n".to_string()));
+    assert_eq!(outputs[1], Node::Integer(3));
+    let Node::ImageObject(image) = &outputs[2] else {
+        bail!("Expected an image")
+    };
+    assert_eq!(image.content_url, "data:image/png;base64,iVBORw0KGgo=");
+
+    let messages = chunk.options.execution_messages.clone().unwrap_or_default();
+    assert_eq!(messages[0].level, MessageLevel::Exception);
+    assert_eq!(messages[0].error_type.as_deref(), Some("ValueError"));
+    assert_eq!(messages[0].stack_trace.as_deref(), Some("ValueError: bad"));
+
+    assert_eq!(
+        content[3],
+        Block::RawBlock(RawBlock {
+            id: Some("c3".to_string()),
+            ..RawBlock::new("html".to_string(), "<hr>".into())
+        })
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn roundtrip() -> Result<()> {
+    let codec = IpynbCodec;
+
+    let mut chunk = CodeChunk {
+        id: Some("abc123".to_string()),
+        outputs: Some(vec![Node::String("hello\n".to_string()), Node::Number(1.5)]),
+        ..CodeChunk::new("print('hello')\n1.5".into())
+    };
+    chunk.programming_language = Some("python".to_string());
+    chunk.options.execution_count = Some(1);
+
+    let Node::Article(mut article) = art([
+        h1([t("Heading")]),
+        p([t("A paragraph.")]),
+        Block::CodeChunk(chunk),
+        p([t("Another paragraph.")]),
+    ]) else {
+        bail!("Expected an article")
+    };
+    article.title = Some(vec![t("A notebook")]);
+    let doc1 = Node::Article(article);
+
+    let (ipynb, ..) = codec.to_string(&doc1, None).await?;
+    assert!(ipynb.contains(r#""nbformat": 4"#));
+    assert!(ipynb.contains(r#""name": "python3""#));
+
+    let (doc2, ..) = codec.from_str(&ipynb, None).await?;
+    assert_eq!(doc2, doc1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn code_chunks_without_ids() -> Result<()> {
+    let codec = IpynbCodec;
+
+    let (ipynb, ..) = codec
+        .to_string(&art([cc("x = 1", Some("r"))]), None)
+        .await?;
+    assert!(ipynb.contains(r#""name": "ir""#));
+
+    let (node, ..) = codec.from_str(&ipynb, None).await?;
+    let Node::Article(Article { content, .. }) = node else {
+        bail!("Expected an article")
+    };
+    let Block::CodeChunk(chunk) = &content[0] else {
+        bail!("Expected a code chunk")
+    };
+    // An id is generated for the cell
+    assert_eq!(chunk.id.as_ref().map(|id| id.len()), Some(8));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cell_metadata() -> Result<()> {
+    let ipynb = r##"{
+ "metadata": {},
+ "nbformat": 4,
+ "nbformat_minor": 5,
+ "cells": [
+  {
+   "cell_type": "code",
+   "id": "a1",
+   "metadata": {"tags": ["parameters"], "jupyter": {"outputs_hidden": true, "source_hidden": true}},
+   "source": "x = 1",
+   "execution_count": null,
+   "outputs": []
+  },
+  {
+   "cell_type": "raw",
+   "id": "b2",
+   "metadata": {"format": "text/html", "slideshow": {"slide_type": "skip"}},
+   "source": "<hr>"
+  }
+ ]
+}"##;
+
+    let codec = IpynbCodec;
+    let (node, info) = codec.from_str(ipynb, None).await?;
+    assert!(info.losses.is_empty());
+
+    let (encoded, ..) = codec.to_string(&node, None).await?;
+    let encoded: serde_json::Value = serde_json::from_str(&encoded)?;
+    let cells = &encoded["cells"];
+
+    assert_eq!(cells[0]["id"], "a1");
+    assert_eq!(
+        cells[0]["metadata"]["tags"],
+        serde_json::json!(["parameters"])
+    );
+    assert_eq!(
+        cells[0]["metadata"]["jupyter"],
+        serde_json::json!({"outputs_hidden": true, "source_hidden": true})
+    );
+
+    assert_eq!(cells[1]["id"], "b2");
+    assert_eq!(cells[1]["metadata"]["format"], "text/html");
+    assert_eq!(
+        cells[1]["metadata"]["slideshow"],
+        serde_json::json!({"slide_type": "skip"})
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cell_ids_are_deterministic() -> Result<()> {
+    let codec = IpynbCodec;
+
+    let doc = art([
+        p([t("A paragraph.")]),
+        cc("x = 1", Some("python")),
+        p([t("A paragraph.")]),
+        cc("x = 1", Some("python")),
+    ]);
+
+    let (first, ..) = codec.to_string(&doc, None).await?;
+    let (second, ..) = codec.to_string(&doc, None).await?;
+    assert_eq!(first, second);
+
+    // Cells with the same type and source get distinct ids
+    let notebook: serde_json::Value = serde_json::from_str(&first)?;
+    let ids: Vec<&str> = notebook["cells"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|cell| cell["id"].as_str())
+        .collect();
+    assert_eq!(ids.len(), 4);
+    assert!(ids.iter().all(|id| id.len() == 8));
+    assert_ne!(ids[0], ids[2]);
+    assert_ne!(ids[1], ids[3]);
+
+    Ok(())
+}
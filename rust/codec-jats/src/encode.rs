@@ -9,7 +9,8 @@ pub(super) fn encode(node: &Node, options: Option<EncodeOptions>) -> Result<(Str
         ..
     } = options.unwrap_or_default();
 
-    if !matches!(node, Node::Article(..)) {
+    // Reviews can be encoded standalone as review reports (`<sub-article>`s)
+    if !matches!(node, Node::Article(..) | Node::Review(..)) {
         return Ok((
             String::new(),
             EncodeInfo {
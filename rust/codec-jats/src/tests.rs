@@ -1,6 +1,10 @@
 use codec::{
-    common::tokio,
-    schema::shortcuts::{art, aud, img, p, sti, vid},
+    common::{serde_json, tokio},
+    schema::{
+        shortcuts::{art, aud, img, p, sti, t, vid},
+        Article, ArticleOptions, Block, Claim, ClaimType, Comment, CommentOptions,
+        CreativeWorkType, Primitive, PropertyValue, PropertyValueOptions, Review, ReviewOptions,
+    },
 };
 use common_dev::pretty_assertions::assert_eq;
 
@@ -66,3 +70,85 @@ async fn spans() -> Result<()> {
 
     Ok(())
 }
+
+/// Test that the reviews of an article are encoded as review report `<sub-article>`s
+/// and that they are recorded as lost when decoding
+#[tokio::test]
+async fn reviews() -> Result<()> {
+    let codec = JatsCodec {};
+
+    let claim = Block::Claim(Claim {
+        id: Some("theorem-1".to_string()),
+        label: Some("1".to_string()),
+        ..Claim::new(ClaimType::Theorem, vec![p([t("A theorem.")])])
+    });
+
+    let review = Review {
+        id: Some("review-1".to_string()),
+        options: Box::new(ReviewOptions {
+            content: Some(vec![p([t("A summary.")])]),
+            ratings: Some(vec![PropertyValue {
+                options: Box::new(PropertyValueOptions {
+                    name: Some("rigor".to_string()),
+                    ..Default::default()
+                }),
+                ..PropertyValue::new(Primitive::Integer(4))
+            }]),
+            comments: Some(vec![Comment {
+                id: Some("comment-1".to_string()),
+                options: Box::new(CommentOptions {
+                    comment_aspect: Some("theorem-1".to_string()),
+                    ..Default::default()
+                }),
+                ..Comment::new(vec![p([t("Needs a proof.")])])
+            }]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let doc1 = Node::Article(Article {
+        options: Box::new(ArticleOptions {
+            parts: Some(vec![CreativeWorkType::Review(review)]),
+            ..Default::default()
+        }),
+        ..Article::new(vec![claim])
+    });
+
+    let (jats, ..) = codec
+        .to_string(
+            &doc1,
+            Some(EncodeOptions {
+                compact: Some(true),
+                ..Default::default()
+            }),
+        )
+        .await?;
+
+    for expected in [
+        r#"<statement content-type="Theorem"><label>1</label>"#,
+        r#"</body><sub-article article-type="referee-report" id="review-1">"#,
+        "<custom-meta><meta-name>rigor</meta-name><meta-value>4</meta-value></custom-meta>",
+        "<body><p>A summary.</p>",
+        r#"<sec sec-type="comment" id="comment-1"><p><xref rid="theorem-1">theorem-1</xref></p><p>Needs a proof.</p></sec>"#,
+    ] {
+        assert!(jats.contains(expected), "expected `{expected}` in:\n{jats}");
+    }
+
+    let (doc2, info) = codec.from_str(&jats, None).await?;
+    let Node::Article(doc2) = doc2 else {
+        unreachable!()
+    };
+    assert_eq!(doc2.options.parts, None);
+    assert!(serde_json::to_value(&info.losses)?
+        .get("//article/sub-article")
+        .is_some());
+
+    // Claims are not yet decoded from JATS but should be recorded as lost
+    assert!(doc2.content.is_empty());
+    assert!(serde_json::to_value(&info.losses)?
+        .get("//article/body/statement")
+        .is_some());
+
+    Ok(())
+}
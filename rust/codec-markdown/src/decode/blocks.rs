@@ -12,12 +12,12 @@ use codec::{
     common::{indexmap::IndexMap, tracing},
     schema::{
         shortcuts, Admonition, AdmonitionType, Block, CallArgument, CallBlock, Claim, CodeBlock,
-        CodeChunk, DeleteBlock, ExecutionMode, Figure, ForBlock, Heading, IfBlock, IfBlockClause,
-        IncludeBlock, Inline, InsertBlock, InstructionBlock, InstructionMessage, InstructionModel,
-        LabelType, List, ListItem, ListOrder, MathBlock, ModifyBlock, Node, Paragraph, PromptBlock,
-        QuoteBlock, RawBlock, ReplaceBlock, Section, StyledBlock, SuggestionBlock,
-        SuggestionStatus, Table, TableCell, TableRow, TableRowType, Text, ThematicBreak,
-        Walkthrough, WalkthroughStep,
+        CodeChunk, Comment, DeleteBlock, ExecutionMode, Figure, ForBlock, Form, FormOptions,
+        Heading, IfBlock, IfBlockClause, IncludeBlock, Inline, InsertBlock, InstructionBlock,
+        InstructionMessage, InstructionModel, IntegerOrString, LabelType, List, ListItem,
+        ListOrder, MathBlock, ModifyBlock, Node, Paragraph, PromptBlock, QuoteBlock, RawBlock,
+        ReplaceBlock, Section, StyledBlock, SuggestionBlock, SuggestionStatus, Table, TableCell,
+        TableRow, TableRowType, Text, ThematicBreak, Walkthrough, WalkthroughStep,
     },
 };
 
@@ -501,6 +501,7 @@ fn block(input: &mut Located<&str>) -> PResult<Block> {
             figure,
             table,
             for_block,
+            form,
             instruction_block,
             suggestion_block,
             delete_block,
@@ -725,6 +726,44 @@ fn for_block(input: &mut Located<&str>) -> PResult<Block> {
     .parse_next(input)
 }
 
+/// Parse a [`Form`] node
+fn form(input: &mut Located<&str>) -> PResult<Block> {
+    delimited(
+        "form",
+        opt(preceded(multispace0, attrs)),
+        (multispace0, eof),
+    )
+    .map(|options| {
+        let mut options: IndexMap<&str, _> = options.unwrap_or_default().into_iter().collect();
+
+        let derive_from = options.swap_remove("from").flatten().map(node_to_string);
+        let derive_action = options
+            .swap_remove("action")
+            .flatten()
+            .and_then(|node| node_to_string(node).replace('-', "").parse().ok());
+        let derive_item = options
+            .swap_remove("item")
+            .flatten()
+            .map(|node| match node {
+                Node::Integer(index) => IntegerOrString::Integer(index),
+                Node::String(key) => IntegerOrString::String(key.replace("\\\"", "\"")),
+                _ => IntegerOrString::String(node_to_string(node)),
+            });
+
+        Block::Form(Form {
+            execution_mode: execution_mode_from_options(options),
+            options: Box::new(FormOptions {
+                derive_from,
+                derive_action,
+                derive_item,
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+    })
+    .parse_next(input)
+}
+
 /// Parse an `if` or `elif` fenced div into an [`IfBlockClause`]
 fn if_elif(input: &mut Located<&str>) -> PResult<(bool, IfBlockClause)> {
     alt((
@@ -1041,6 +1080,7 @@ fn finalize(parent: &mut Block, mut children: Vec<Block>, context: &mut Context)
     | Block::DeleteBlock(DeleteBlock { content, .. })
     | Block::InsertBlock(InsertBlock { content, .. })
     | Block::Claim(Claim { content, .. })
+    | Block::Form(Form { content, .. })
     | Block::Section(Section { content, .. })
     | Block::StyledBlock(StyledBlock { content, .. }) = parent
     {
@@ -1323,6 +1363,54 @@ fn md_to_block(md: mdast::Node, context: &mut Context) -> Option<(Block, Option<
     })
 }
 
+/// Transform a footnote-style comment block into a [`Comment`]
+///
+/// Called for footnote definitions with a `comment-` prefix that are not
+/// referenced by any footnote in the document.
+///
+/// Any attributes at the start of the first paragraph of the comment are
+/// parsed for the node it is on (`on`) and the comment it replies to (`reply`).
+pub(super) fn comment(id: String, mut content: Vec<Block>, context: &mut Context) {
+    let mut on = None;
+    let mut reply = None;
+
+    if let Some(Block::Paragraph(Paragraph {
+        content: inlines, ..
+    })) = content.first_mut()
+    {
+        if let Some(Inline::Text(text)) = inlines.first_mut() {
+            let value = text.value.to_string();
+            let mut input = Located::new(value.as_str());
+            if let Ok(options) = attrs(&mut input) {
+                for (name, value) in options {
+                    match name {
+                        "on" => on = value.map(node_to_string),
+                        "reply" => reply = value.map(node_to_string),
+                        _ => {}
+                    }
+                }
+
+                let rest = input.trim_start();
+                if rest.is_empty() {
+                    inlines.remove(0);
+                } else {
+                    text.value = rest.into();
+                }
+            }
+        }
+
+        if inlines.is_empty() {
+            content.remove(0);
+        }
+    }
+
+    let mut comment = Comment::new(content);
+    comment.id = Some(id);
+    comment.options.comment_aspect = on;
+
+    context.comment(comment, reply);
+}
+
 /// Transform a [`mdast::Code`] node to a block if it is a recognized MyST directive
 ///
 /// Note that `if`, `elif`, `else`, and `for` directives are are handled elsewhere
@@ -1729,7 +1817,7 @@ fn mds_to_table_cells(mds: Vec<mdast::Node>, context: &mut Context) -> Vec<Table
 
 #[cfg(test)]
 mod tests {
-    use codec::schema::{ClaimType, ExecutionMode, Node};
+    use codec::schema::{ClaimType, ExecutionMode, FormDeriveAction, Node};
     use common_dev::pretty_assertions::assert_eq;
 
     use super::*;
@@ -1922,6 +2010,108 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_form() {
+        // Without options
+        assert_eq!(
+            form(&mut Located::new("form")).unwrap(),
+            Block::Form(Form::default())
+        );
+
+        // With options
+        assert_eq!(
+            form(&mut Located::new(
+                "form {from=data.samples action=update-or-delete item=3}"
+            ))
+            .unwrap(),
+            Block::Form(Form {
+                options: Box::new(FormOptions {
+                    derive_from: Some("data.samples".to_string()),
+                    derive_action: Some(FormDeriveAction::UpdateOrDelete),
+                    derive_item: Some(IntegerOrString::Integer(3)),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+        );
+
+        // With execution mode and a string item
+        assert_eq!(
+            form(&mut Located::new("form {always from=samples item=\"S1\"}")).unwrap(),
+            Block::Form(Form {
+                execution_mode: Some(ExecutionMode::Always),
+                options: Box::new(FormOptions {
+                    derive_from: Some("samples".to_string()),
+                    derive_item: Some(IntegerOrString::String("S1".to_string())),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+        );
+
+        // A string item that looks like an integer, with spaces, braces and escaped quotes
+        assert_eq!(
+            form(&mut Located::new(r#"form {item="3"}"#)).unwrap(),
+            Block::Form(Form {
+                options: Box::new(FormOptions {
+                    derive_item: Some(IntegerOrString::String("3".to_string())),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            form(&mut Located::new(r#"form {item="a {\"b\"}"}"#)).unwrap(),
+            Block::Form(Form {
+                options: Box::new(FormOptions {
+                    derive_item: Some(IntegerOrString::String(r#"a {"b"}"#.to_string())),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+        );
+
+        // Not a form
+        assert!(form(&mut Located::new("formula")).is_err());
+    }
+
+    #[test]
+    fn test_comment() {
+        use codec::schema::shortcuts::{p, t};
+
+        let mut context = Context::default();
+
+        // Anchored to a node
+        comment(
+            "comment-1".to_string(),
+            vec![p([t("{on=\"pgh_abc#3-10\"} Is this right?")])],
+            &mut context,
+        );
+
+        // A reply with attributes only on the first line
+        comment(
+            "comment-2".to_string(),
+            vec![p([t("{reply=\"comment-1\"}")]), p([t("Yes")])],
+            &mut context,
+        );
+
+        let comments = context.take_comments().unwrap();
+        assert_eq!(comments.len(), 1);
+
+        let first = &comments[0];
+        assert_eq!(first.id.as_deref(), Some("comment-1"));
+        assert_eq!(
+            first.options.comment_aspect.as_deref(),
+            Some("pgh_abc#3-10")
+        );
+        assert_eq!(first.content, vec![p([t("Is this right?")])]);
+
+        let replies = first.options.comments.as_ref().unwrap();
+        assert_eq!(replies.len(), 1);
+        assert_eq!(replies[0].id.as_deref(), Some("comment-2"));
+        assert_eq!(replies[0].content, vec![p([t("Yes")])]);
+    }
+
     #[test]
     fn test_if_elif() {
         // Simple
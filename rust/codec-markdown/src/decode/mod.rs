@@ -1,4 +1,4 @@
-use std::{collections::HashMap, ops::Range};
+use std::ops::Range;
 
 use markdown::{
     mdast::{self, Root},
@@ -10,17 +10,22 @@ use markdown::{
 use codec::{
     common::{
         eyre::{bail, eyre, Result},
+        indexmap::IndexMap,
         serde_json::{self, json},
         serde_yaml, tracing,
     },
     format::Format,
     schema::{
-        Article, Block, Inline, Node, NodeId, NodeType, Null, Prompt, VisitorMut, WalkControl,
+        Article, Block, Comment, Inline, Node, NodeId, NodeType, Null, Prompt, VisitorMut,
+        WalkControl,
     },
     DecodeInfo, DecodeOptions, Losses, Mapping,
 };
 
-use self::{blocks::mds_to_blocks, inlines::mds_to_inlines};
+use self::{
+    blocks::{comment, mds_to_blocks},
+    inlines::mds_to_inlines,
+};
 
 mod blocks;
 mod check;
@@ -77,6 +82,13 @@ pub(super) fn decode(content: &str, options: Option<DecodeOptions>) -> Result<(N
 
     if !context.footnotes.is_empty() {
         context.visit(&mut node);
+        context.footnotes_to_comments();
+    }
+
+    if let Node::Article(article) = &mut node {
+        if let Some(comments) = context.take_comments() {
+            article.comments = Some(comments);
+        }
     }
 
     let info = DecodeInfo {
@@ -196,8 +208,11 @@ struct Context {
     /// YAML frontmatter
     yaml: Option<String>,
 
-    /// Footnote content
-    footnotes: HashMap<String, Vec<Block>>,
+    /// Footnote content (in the order defined)
+    footnotes: IndexMap<String, Vec<Block>>,
+
+    /// Comments, and the id of the comment they reply to, if any
+    comments: Vec<(Comment, Option<String>)>,
 
     /// Losses during decoding
     losses: Losses,
@@ -215,6 +230,63 @@ impl Context {
         self.footnotes.insert(id, blocks);
     }
 
+    /// Transform footnotes which were not referenced, and have a `comment-` prefix, into comments
+    ///
+    /// Should be called after footnote content has been applied to the footnotes which reference it
+    /// so that footnotes with a `comment-` prefix are not treated as comments.
+    fn footnotes_to_comments(&mut self) {
+        for (id, blocks) in std::mem::take(&mut self.footnotes) {
+            if id.starts_with("comment-") {
+                comment(id, blocks, self);
+            }
+        }
+    }
+
+    /// Store a comment so that it can be added to the article, or to the comment it replies to, later
+    fn comment(&mut self, comment: Comment, reply: Option<String>) {
+        self.comments.push((comment, reply));
+    }
+
+    /// Take the comments, nesting replies within the comments that they reply to
+    ///
+    /// Replies to comments that do not exist are treated as top-level comments.
+    fn take_comments(&mut self) -> Option<Vec<Comment>> {
+        fn find<'c>(comments: &'c mut [Comment], id: &str) -> Option<&'c mut Comment> {
+            for comment in comments {
+                if comment.id.as_deref() == Some(id) {
+                    return Some(comment);
+                }
+                if let Some(found) = comment
+                    .options
+                    .comments
+                    .as_deref_mut()
+                    .and_then(|replies| find(replies, id))
+                {
+                    return Some(found);
+                }
+            }
+            None
+        }
+
+        if self.comments.is_empty() {
+            return None;
+        }
+
+        let mut comments = Vec::new();
+        for (comment, reply) in self.comments.drain(..) {
+            match reply.and_then(|id| find(&mut comments, &id)) {
+                Some(parent) => parent
+                    .options
+                    .comments
+                    .get_or_insert_with(Vec::new)
+                    .push(comment),
+                None => comments.push(comment),
+            }
+        }
+
+        Some(comments)
+    }
+
     /// Record the loss of a MDAST type
     fn lost(&mut self, label: &str) {
         self.losses.add(label)
@@ -352,7 +424,7 @@ impl VisitorMut for Context {
     fn visit_inline(&mut self, inline: &mut Inline) -> WalkControl {
         if let Inline::Note(note) = inline {
             if let Some(id) = note.id.take() {
-                if let Some(content) = self.footnotes.remove(&id) {
+                if let Some(content) = self.footnotes.shift_remove(&id) {
                     note.content = content;
                 }
             }
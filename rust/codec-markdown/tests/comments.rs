@@ -0,0 +1,55 @@
+use codec::{
+    common::{eyre::Result, tokio},
+    format::Format,
+    schema::{Article, Block, Inline, Node, Paragraph},
+    Codec, DecodeOptions,
+};
+use codec_markdown::MarkdownCodec;
+use common_dev::pretty_assertions::assert_eq;
+
+/// Test that footnotes with a `comment-` prefix are only decoded as comments
+/// if they are not referenced in the document
+#[tokio::test]
+async fn footnotes_are_not_comments() -> Result<()> {
+    let codec = MarkdownCodec {};
+
+    let (node, ..) = codec
+        .from_str(
+            r#"A paragraph[^comment-1].
+
+[^comment-1]: A footnote.
+
+[^comment-2]: {on="/content/0"} A comment.
+"#,
+            Some(DecodeOptions {
+                format: Some(Format::Smd),
+                ..Default::default()
+            }),
+        )
+        .await?;
+
+    let Node::Article(Article {
+        content, comments, ..
+    }) = node
+    else {
+        panic!("expected an article")
+    };
+
+    let Some(Block::Paragraph(Paragraph { content, .. })) = content.first() else {
+        panic!("expected a paragraph")
+    };
+    let Some(Inline::Note(note)) = content.get(1) else {
+        panic!("expected a note")
+    };
+    assert_eq!(note.content.len(), 1);
+
+    let comments = comments.expect("should have comments");
+    assert_eq!(comments.len(), 1);
+    assert_eq!(comments[0].id.as_deref(), Some("comment-2"));
+    assert_eq!(
+        comments[0].options.comment_aspect.as_deref(),
+        Some("/content/0")
+    );
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use codec::{
+    common::{eyre::Result, tokio},
+    schema::{
+        shortcuts::{art, p, t},
+        Article, Block, Form, FormDeriveAction, FormOptions, IntegerOrString, Node,
+    },
+    Codec,
+};
+use codec_markdown::MarkdownCodec;
+use common_dev::pretty_assertions::assert_eq;
+
+/// Test that the derive options of forms, in particular items, roundtrip
+/// through Markdown
+#[tokio::test]
+async fn derive_item_roundtrip() -> Result<()> {
+    let codec = MarkdownCodec {};
+
+    for item in [
+        IntegerOrString::Integer(3),
+        IntegerOrString::String("S1".to_string()),
+        IntegerOrString::String("3".to_string()),
+        IntegerOrString::String("true".to_string()),
+        IntegerOrString::String("sample one".to_string()),
+        IntegerOrString::String("a}b".to_string()),
+        IntegerOrString::String(r#"say "hi""#.to_string()),
+    ] {
+        let form = Block::Form(Form {
+            options: Box::new(FormOptions {
+                derive_from: Some("samples".to_string()),
+                derive_action: Some(FormDeriveAction::Update),
+                derive_item: Some(item.clone()),
+                ..Default::default()
+            }),
+            ..Form::new(vec![p([t("Content")])])
+        });
+
+        let (md, ..) = codec.to_string(&art([form]), None).await?;
+        let (node, ..) = codec.from_str(&md, None).await?;
+
+        let Node::Article(Article { content, .. }) = node else {
+            panic!("expected an article")
+        };
+        let Some(Block::Form(form)) = content.first() else {
+            panic!("expected a form, got:\n{md}")
+        };
+        assert_eq!(form.options.derive_item, Some(item), "markdown:\n{md}");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,459 @@
+//! Threaded comments on the nodes of a document
+//!
+//! Comments are stored in the `comments` property of the root `Article`, with replies
+//! in the `comments` of the comment they reply to. This means that they are persisted
+//! in the document's sidecar file but do not alter its content.
+//!
+//! A comment is anchored to a node by its `commentAspect` which is the `id` of the
+//! node, optionally followed by a range of characters within its text, e.g. `intro#10-25`.
+//! The `id` is used, rather than the node's `NodeId`, because node ids are not persisted.
+//! If the node does not have an `id`, it is assigned one, based on its `NodeId`, so that
+//! the comment remains anchored to it when the document is saved and reloaded.
+//!
+//! Comments can also be added to a `Review` of the document (see the `reviews` module),
+//! in which case they are stored in the `comments` of the review instead.
+
+use common::{
+    chrono::Local,
+    eyre::{bail, eyre, Result},
+    serde_json,
+};
+use schema::{Article, Comment, Date, Node, NodeProperty, Patch, PatchOp, PatchPath, PatchValue};
+
+use crate::{reviews, CommandComment};
+
+/// Create the patches needed to add a comment to the root node of a document
+///
+/// If the comment is anchored to a node which does not have an `id`, then
+/// a patch to set its `id` is also created.
+pub(super) fn add_comment(root: &Node, comment: CommandComment) -> Result<Vec<Patch>> {
+    let Node::Article(article) = root else {
+        bail!("Comments can only be added to articles")
+    };
+
+    let CommandComment {
+        node_id,
+        range,
+        reply_to,
+        review,
+        content,
+        authors,
+    } = comment;
+
+    let mut comment = Comment {
+        id: Some(next_comment_id(article)),
+        content,
+        authors,
+        date_published: Some(Date::new(Local::now().date_naive().to_string())),
+        ..Default::default()
+    };
+
+    let mut patches = Vec::new();
+
+    let target = if let Some(parent_id) = reply_to {
+        // Add as a reply to the parent comment
+        let parent = article
+            .comments
+            .iter()
+            .flatten()
+            .chain(
+                reviews::reviews(article)
+                    .flat_map(|review| review.options.comments.iter().flatten()),
+            )
+            .find_map(|comment| find_comment(comment, &parent_id))
+            .ok_or_else(|| eyre!("No comment with id `{parent_id}`"))?;
+        parent.node_id()
+    } else {
+        // Anchor to the node, if any, and add to the article
+        if let Some(node_id) = node_id {
+            let node = node_find::find(root, node_id.clone())
+                .ok_or_else(|| eyre!("No node with id `{node_id}`"))?;
+
+            let id = match id_property(&node) {
+                Some(id) => id,
+                None => {
+                    let id = node_id.to_string();
+                    patches.push(Patch {
+                        node_id: Some(node_id),
+                        ops: vec![(
+                            PatchPath::from(NodeProperty::Id),
+                            PatchOp::Set(PatchValue::String(id.clone())),
+                        )],
+                        ..Default::default()
+                    });
+                    id
+                }
+            };
+
+            comment.options.comment_aspect = Some(match range {
+                Some((start, end)) => format!("{id}#{start}-{end}"),
+                None => id,
+            });
+        }
+
+        // Add to the review, if any, otherwise to the article
+        match review {
+            Some(review_id) => reviews::find_review(article, &review_id)
+                .ok_or_else(|| eyre!("No review with id `{review_id}`"))?
+                .node_id(),
+            None => article.node_id(),
+        }
+    };
+
+    patches.push(Patch {
+        node_id: Some(target),
+        ops: vec![(
+            PatchPath::from(NodeProperty::Comments),
+            PatchOp::Push(PatchValue::Json(serde_json::to_value(comment)?)),
+        )],
+        ..Default::default()
+    });
+
+    Ok(patches)
+}
+
+/// Create the patch needed to remove a comment, and its replies, from the root node of a document
+pub(super) fn remove_comment(root: &Node, id: &str) -> Result<Patch> {
+    let Node::Article(article) = root else {
+        bail!("Comments can only be removed from articles")
+    };
+
+    // Find the node id of the article, or comment, that the comment belongs to and its index there
+    fn find_parent(comments: &[Comment], id: &str, parent: &Comment) -> Option<(Comment, usize)> {
+        for (index, comment) in comments.iter().enumerate() {
+            if comment.id.as_deref() == Some(id) {
+                return Some((parent.clone(), index));
+            }
+            if let Some(found) = comment
+                .options
+                .comments
+                .as_ref()
+                .and_then(|replies| find_parent(replies, id, comment))
+            {
+                return Some(found);
+            }
+        }
+        None
+    }
+
+    // The article, and each of its reviews, that have comments
+    let containers = [(article.node_id(), article.comments.as_deref())]
+        .into_iter()
+        .chain(
+            reviews::reviews(article)
+                .map(|review| (review.node_id(), review.options.comments.as_deref())),
+        );
+
+    let (node_id, index) = containers
+        .filter_map(|(node_id, comments)| Some((node_id, comments?)))
+        .find_map(|(node_id, comments)| {
+            if let Some(index) = comments
+                .iter()
+                .position(|comment| comment.id.as_deref() == Some(id))
+            {
+                return Some((node_id, index));
+            }
+            comments
+                .iter()
+                .find_map(|comment| {
+                    comment
+                        .options
+                        .comments
+                        .as_ref()
+                        .and_then(|replies| find_parent(replies, id, comment))
+                })
+                .map(|(parent, index)| (parent.node_id(), index))
+        })
+        .ok_or_else(|| eyre!("No comment with id `{id}`"))?;
+
+    Ok(Patch {
+        node_id: Some(node_id),
+        ops: vec![(
+            PatchPath::from(NodeProperty::Comments),
+            PatchOp::Remove(vec![index]),
+        )],
+        ..Default::default()
+    })
+}
+
+/// Find a comment, or one of its replies, with an id
+fn find_comment<'comment>(comment: &'comment Comment, id: &str) -> Option<&'comment Comment> {
+    if comment.id.as_deref() == Some(id) {
+        return Some(comment);
+    }
+
+    comment
+        .options
+        .comments
+        .iter()
+        .flatten()
+        .find_map(|reply| find_comment(reply, id))
+}
+
+/// Generate the next id for a comment in an article
+///
+/// Ids are of the form `comment-<n>` where `n` is one more than the largest
+/// `n` used in the article (including in replies and reviews) so that ids are not reused
+/// after a comment is removed.
+fn next_comment_id(article: &Article) -> String {
+    fn max(comments: &[Comment]) -> u64 {
+        comments
+            .iter()
+            .map(|comment| {
+                let n = comment
+                    .id
+                    .as_deref()
+                    .and_then(|id| id.strip_prefix("comment-"))
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or_default();
+                let replies = comment
+                    .options
+                    .comments
+                    .as_deref()
+                    .map(max)
+                    .unwrap_or_default();
+                n.max(replies)
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    let n = article
+        .comments
+        .as_deref()
+        .into_iter()
+        .chain(reviews::reviews(article).filter_map(|review| review.options.comments.as_deref()))
+        .map(max)
+        .max()
+        .unwrap_or_default()
+        + 1;
+    format!("comment-{n}")
+}
+
+/// Get the `id` property of a node
+///
+/// There is no generic accessor for the `id` property of nodes, so
+/// this serializes the node to get it.
+fn id_property(node: &Node) -> Option<String> {
+    serde_json::to_value(node)
+        .ok()?
+        .get("id")?
+        .as_str()
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use common_dev::pretty_assertions::assert_eq;
+    use schema::{
+        shortcuts::{art, p, t},
+        Block, CreativeWorkType, NodeId, Review,
+    };
+
+    use super::*;
+
+    fn article(root: &Node) -> &Article {
+        let Node::Article(article) = root else {
+            unreachable!()
+        };
+        article
+    }
+
+    fn article_mut(root: &mut Node) -> &mut Article {
+        let Node::Article(article) = root else {
+            unreachable!()
+        };
+        article
+    }
+
+    fn apply(root: &mut Node, patches: Vec<Patch>) -> Result<()> {
+        for patch in patches {
+            schema::patch(root, patch)?;
+        }
+        Ok(())
+    }
+
+    fn comment(id: &str, replies: Vec<Comment>) -> Comment {
+        let mut comment = Comment::new(vec![p([t("A comment.")])]);
+        comment.id = Some(id.to_string());
+        if !replies.is_empty() {
+            comment.options.comments = Some(replies);
+        }
+        comment
+    }
+
+    /// Test that comment ids are one more than the largest used in the
+    /// article, including in replies and reviews
+    #[test]
+    fn next_comment_ids() {
+        let mut article = Article::default();
+        assert_eq!(next_comment_id(&article), "comment-1");
+
+        article.comments = Some(vec![
+            comment("comment-2", vec![comment("comment-5", vec![])]),
+            comment("other", vec![]),
+        ]);
+        assert_eq!(next_comment_id(&article), "comment-6");
+
+        let mut review = Review::new();
+        review.options.comments = Some(vec![comment("comment-9", vec![])]);
+        article.options.parts = Some(vec![CreativeWorkType::Review(review)]);
+        assert_eq!(next_comment_id(&article), "comment-10");
+    }
+
+    /// Test that comments anchored to a node without an `id` assign one to
+    /// it and that comments are anchored to the existing `id` otherwise
+    #[test]
+    fn add_anchored() -> Result<()> {
+        let mut root = art([p([t("First")]), p([t("Second")])]);
+
+        let (first, second) = match &mut article_mut(&mut root).content[..] {
+            [Block::Paragraph(first), Block::Paragraph(second)] => {
+                second.id = Some("second".to_string());
+                (first.node_id(), second.node_id())
+            }
+            _ => unreachable!(),
+        };
+
+        let patches = add_comment(
+            &root,
+            CommandComment::on(Some(first.clone()), Some((1, 3)), vec![], None),
+        )?;
+        assert_eq!(patches.len(), 2);
+        apply(&mut root, patches)?;
+
+        let patches = add_comment(&root, CommandComment::on(Some(second), None, vec![], None))?;
+        assert_eq!(patches.len(), 1);
+        apply(&mut root, patches)?;
+
+        let Some(Block::Paragraph(paragraph)) = article(&root).content.first() else {
+            unreachable!()
+        };
+        assert_eq!(paragraph.id, Some(first.to_string()));
+
+        let comments = article(&root).comments.clone().unwrap_or_default();
+        assert_eq!(
+            comments
+                .iter()
+                .map(|comment| (
+                    comment.id.as_deref(),
+                    comment.options.comment_aspect.as_deref()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some("comment-1"), Some(format!("{first}#1-3").as_str())),
+                (Some("comment-2"), Some("second")),
+            ]
+        );
+
+        // Unknown nodes, and non-articles, are errors
+        assert!(add_comment(
+            &root,
+            CommandComment::on(Some(NodeId::new(b"pgh", b"unknown")), None, vec![], None)
+        )
+        .is_err());
+        assert!(add_comment(&Node::Null(schema::Null), CommandComment::default()).is_err());
+
+        Ok(())
+    }
+
+    /// Test that replies are added to, and removed from, the comment they reply to
+    /// and that removing a comment removes its replies
+    #[test]
+    fn add_and_remove_replies() -> Result<()> {
+        let mut root = art([p([t("Content")])]);
+
+        apply(&mut root, add_comment(&root, CommandComment::default())?)?;
+        apply(
+            &mut root,
+            add_comment(
+                &root,
+                CommandComment::reply("comment-1".to_string(), vec![], None),
+            )?,
+        )?;
+        apply(
+            &mut root,
+            add_comment(
+                &root,
+                CommandComment::reply("comment-2".to_string(), vec![], None),
+            )?,
+        )?;
+        assert!(add_comment(
+            &root,
+            CommandComment::reply("comment-9".to_string(), vec![], None)
+        )
+        .is_err());
+
+        let ids = |root: &Node| {
+            fn ids(comments: &[Comment]) -> Vec<String> {
+                comments
+                    .iter()
+                    .flat_map(|comment| {
+                        comment.id.clone().into_iter().chain(
+                            comment
+                                .options
+                                .comments
+                                .as_deref()
+                                .map(ids)
+                                .unwrap_or_default(),
+                        )
+                    })
+                    .collect()
+            }
+            ids(article(root).comments.as_deref().unwrap_or_default())
+        };
+        assert_eq!(ids(&root), vec!["comment-1", "comment-2", "comment-3"]);
+
+        schema::patch(&mut root, remove_comment(&root, "comment-3")?)?;
+        assert_eq!(ids(&root), vec!["comment-1", "comment-2"]);
+
+        schema::patch(&mut root, remove_comment(&root, "comment-1")?)?;
+        assert_eq!(ids(&root), Vec::<String>::new());
+        assert!(remove_comment(&root, "comment-1").is_err());
+
+        // Ids are not reused after removal
+        apply(&mut root, add_comment(&root, CommandComment::default())?)?;
+        assert_eq!(ids(&root), vec!["comment-4"]);
+
+        Ok(())
+    }
+
+    /// Test that comments can be added to, and removed from, a review
+    #[test]
+    fn review_comments() -> Result<()> {
+        let mut root = art([p([t("Content")])]);
+        schema::patch(&mut root, reviews::add_review(&root, Review::new())?)?;
+
+        apply(
+            &mut root,
+            add_comment(
+                &root,
+                CommandComment::default().in_review("review-1".to_string()),
+            )?,
+        )?;
+        assert!(add_comment(
+            &root,
+            CommandComment::default().in_review("review-2".to_string())
+        )
+        .is_err());
+
+        let review = reviews::find_review(article(&root), "review-1").expect("should be found");
+        assert_eq!(
+            review
+                .options
+                .comments
+                .iter()
+                .flatten()
+                .map(|comment| comment.id.clone())
+                .collect::<Vec<_>>(),
+            vec![Some("comment-1".to_string())]
+        );
+        assert!(article(&root).comments.is_none());
+
+        schema::patch(&mut root, remove_comment(&root, "comment-1")?)?;
+        let review = reviews::find_review(article(&root), "review-1").expect("should be found");
+        assert_eq!(review.options.comments.iter().flatten().count(), 0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+//! The execution status of the executable nodes in a document
+
+use common::serde::Serialize;
+use schema::{
+    Block, ExecutionRequired, ExecutionStatus, Inline, Node, NodeId, NodeType, Timestamp, Visitor,
+    WalkControl,
+};
+
+/// The execution status of an executable node in a document
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase", crate = "common::serde")]
+pub struct NodeExecution {
+    /// The id of the node
+    pub node_id: NodeId,
+
+    /// The type of the node
+    pub node_type: NodeType,
+
+    /// The status of the most recent execution of the node
+    pub execution_status: Option<ExecutionStatus>,
+
+    /// Whether, and why, the node requires execution
+    pub execution_required: Option<ExecutionRequired>,
+
+    /// The timestamp when the most recent execution of the node ended
+    pub execution_ended: Option<Timestamp>,
+}
+
+/// Get the execution status of all the executable nodes in a root node
+pub(super) fn executions(root: &Node) -> Vec<NodeExecution> {
+    let mut collector = Collector::default();
+    collector.visit(root);
+    collector.executions
+}
+
+/// A visitor which collects the execution status of executable nodes
+#[derive(Default)]
+struct Collector {
+    executions: Vec<NodeExecution>,
+}
+
+impl Collector {
+    fn collect(
+        &mut self,
+        node_id: NodeId,
+        node_type: NodeType,
+        execution_status: &Option<ExecutionStatus>,
+        execution_required: &Option<ExecutionRequired>,
+        execution_ended: &Option<Timestamp>,
+    ) {
+        self.executions.push(NodeExecution {
+            node_id,
+            node_type,
+            execution_status: execution_status.clone(),
+            execution_required: execution_required.clone(),
+            execution_ended: execution_ended.clone(),
+        })
+    }
+}
+
+/// Collect the execution status of a node from its `options`
+macro_rules! collect {
+    ($collector:expr, $node:expr, $node_type:ident) => {
+        $collector.collect(
+            $node.node_id(),
+            NodeType::$node_type,
+            &$node.options.execution_status,
+            &$node.options.execution_required,
+            &$node.options.execution_ended,
+        )
+    };
+}
+
+impl Visitor for Collector {
+    fn visit_block(&mut self, block: &Block) -> WalkControl {
+        match block {
+            Block::CallBlock(node) => collect!(self, node, CallBlock),
+            Block::CodeChunk(node) => collect!(self, node, CodeChunk),
+            Block::ForBlock(node) => collect!(self, node, ForBlock),
+            Block::Form(node) => collect!(self, node, Form),
+            Block::IfBlock(node) => collect!(self, node, IfBlock),
+            Block::IncludeBlock(node) => collect!(self, node, IncludeBlock),
+            Block::InstructionBlock(node) => collect!(self, node, InstructionBlock),
+            Block::PromptBlock(node) => collect!(self, node, PromptBlock),
+            _ => {}
+        }
+
+        WalkControl::Continue
+    }
+
+    fn visit_inline(&mut self, inline: &Inline) -> WalkControl {
+        match inline {
+            Inline::Button(node) => collect!(self, node, Button),
+            Inline::CodeExpression(node) => collect!(self, node, CodeExpression),
+            Inline::InstructionInline(node) => collect!(self, node, InstructionInline),
+            Inline::Parameter(node) => collect!(self, node, Parameter),
+            _ => {}
+        }
+
+        WalkControl::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_dev::pretty_assertions::assert_eq;
+    use schema::{
+        shortcuts::{art, cc, ce, frb, p, par, t},
+        ExecutionRequired::NeverExecuted,
+        ExecutionStatus::Succeeded,
+    };
+
+    use super::*;
+
+    /// Test that executable blocks and inlines, including those nested
+    /// within other nodes, are collected with their execution status
+    #[test]
+    fn collected() {
+        let mut chunk = cc("x = 1", Some("python"));
+        if let Block::CodeChunk(chunk) = &mut chunk {
+            chunk.options.execution_status = Some(Succeeded);
+            chunk.options.execution_required = Some(NeverExecuted);
+        }
+
+        let root = art([
+            p([t("Not executable")]),
+            chunk,
+            frb(
+                "item",
+                "[1, 2]",
+                [p([ce("item", Some("python")), par("a")])],
+            ),
+        ]);
+
+        let executions = executions(&root)
+            .into_iter()
+            .map(|execution| {
+                (
+                    execution.node_type,
+                    execution.execution_status,
+                    execution.execution_required,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            executions,
+            vec![
+                (NodeType::CodeChunk, Some(Succeeded), Some(NeverExecuted)),
+                (NodeType::ForBlock, None, None),
+                (NodeType::CodeExpression, None, None),
+                (NodeType::Parameter, None, None),
+            ]
+        );
+    }
+}
@@ -3,6 +3,7 @@
 use std::{
     fs::File,
     io,
+    ops::Range,
     path::{Path, PathBuf},
     sync::{atomic::AtomicU64, Arc},
     time::{Duration, SystemTime},
@@ -25,9 +26,13 @@ use common::{
 use format::Format;
 use kernels::Kernels;
 use node_execute::ExecuteOptions;
-use schema::{Article, AuthorRole, Node, NodeId, NodeType, Patch};
+use schema::{Article, Author, AuthorRole, Block, Comment, Node, NodeId, NodeType, Patch, Review};
 
+mod comments;
 mod config;
+mod executions;
+mod provenance;
+mod reviews;
 mod sync_directory;
 mod sync_dom;
 mod sync_file;
@@ -36,6 +41,8 @@ mod sync_object;
 mod task_command;
 mod task_update;
 
+pub use executions::NodeExecution;
+pub use provenance::{ProvenanceAuthor, ProvenanceSection, ProvenanceSummary};
 pub use sync_dom::DomPatch;
 
 #[derive(Default)]
@@ -111,6 +118,27 @@ pub enum Command {
     /// immediately execute as it avoid race conditions associated with
     /// sending separate patch and execute commands.
     PatchExecuteNodes((Patch, CommandNodes, ExecuteOptions)),
+
+    /// Add a comment to the document
+    ///
+    /// The comment is anchored to a node, or if `reply_to` is set, added
+    /// as a reply to an existing comment.
+    AddComment(CommandComment),
+
+    /// Remove a comment, and any replies to it, from the document
+    RemoveComment {
+        /// The id of the comment
+        id: String,
+    },
+
+    /// Add a review to the document
+    AddReview(Review),
+
+    /// Remove a review from the document
+    RemoveReview {
+        /// The id of the review
+        id: String,
+    },
 }
 
 /// Whether the document source file should be saved
@@ -150,6 +178,70 @@ impl CommandNodes {
     }
 }
 
+/// A comment to add to a document
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "common::serde")]
+pub struct CommandComment {
+    /// The node that the comment is anchored to
+    ///
+    /// If `None`, and the comment is not a reply, then the comment
+    /// is on the document as a whole.
+    #[serde(alias = "nodeId")]
+    node_id: Option<NodeId>,
+
+    /// The range of characters within the text of the node that the comment is on
+    range: Option<(usize, usize)>,
+
+    /// The id of the comment that this comment is a reply to
+    #[serde(alias = "replyTo")]
+    reply_to: Option<String>,
+
+    /// The id of the review that the comment is part of
+    ///
+    /// If `None`, the comment is added to the document itself.
+    review: Option<String>,
+
+    /// The content of the comment
+    content: Vec<Block>,
+
+    /// The authors of the comment
+    authors: Option<Vec<Author>>,
+}
+
+impl CommandComment {
+    /// Create a comment anchored to a node (or a range of characters within it)
+    pub fn on(
+        node_id: Option<NodeId>,
+        range: Option<(usize, usize)>,
+        content: Vec<Block>,
+        authors: Option<Vec<Author>>,
+    ) -> Self {
+        Self {
+            node_id,
+            range,
+            content,
+            authors,
+            ..Default::default()
+        }
+    }
+
+    /// Add the comment to a review of the document
+    pub fn in_review(mut self, review: String) -> Self {
+        self.review = Some(review);
+        self
+    }
+
+    /// Create a comment which is a reply to another comment
+    pub fn reply(reply_to: String, content: Vec<Block>, authors: Option<Vec<Author>>) -> Self {
+        Self {
+            reply_to: Some(reply_to),
+            content,
+            authors,
+            ..Default::default()
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "kebab-case", crate = "common::serde")]
 pub enum CommandScope {
@@ -168,7 +260,13 @@ pub enum CommandScope {
 }
 
 /// The status of a command
-#[derive(Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(
+    tag = "status",
+    content = "error",
+    rename_all = "kebab-case",
+    crate = "common::serde"
+)]
 pub enum CommandStatus {
     Ignored,
     Waiting,
@@ -232,6 +330,12 @@ pub(crate) struct Update {
 
     /// The authors of the update
     pub authors: Option<Vec<AuthorRole>>,
+
+    /// The range of the root's content replaced by a range of the content of `node`
+    ///
+    /// If `Some`, and consistent with the lengths of the content of the root and
+    /// of `node`, then only those blocks are merged into the root rather than the whole node.
+    pub blocks: Option<(Range<usize>, Range<usize>)>,
 }
 
 impl Update {
@@ -240,6 +344,7 @@ impl Update {
             node,
             format,
             authors,
+            blocks: None,
         }
     }
 }
@@ -251,6 +356,9 @@ type DocumentRoot = Arc<RwLock<Node>>;
 type DocumentWatchSender = watch::Sender<Node>;
 type DocumentWatchReceiver = watch::Receiver<Node>;
 
+type DocumentUpdateCountSender = watch::Sender<u64>;
+type DocumentUpdateCountReceiver = watch::Receiver<u64>;
+
 type DocumentUpdateSender = mpsc::Sender<Update>;
 type DocumentUpdateReceiver = mpsc::Receiver<Update>;
 
@@ -287,6 +395,9 @@ pub struct Document {
     /// A channel receiver for watching for changes to the root [`Node`]
     watch_receiver: DocumentWatchReceiver,
 
+    /// A channel receiver for watching the number of updates applied to the root [`Node`]
+    update_count_receiver: DocumentUpdateCountReceiver,
+
     /// A channel sender for sending updates to the root [`Node`]
     update_sender: DocumentUpdateSender,
 
@@ -362,6 +473,7 @@ impl Document {
             None => Node::Article(Article::default()),
         };
         let (watch_sender, watch_receiver) = watch::channel(root.clone());
+        let (update_count_sender, update_count_receiver) = watch::channel(0);
         let root = Arc::new(RwLock::new(root));
 
         let (update_sender, update_receiver) = mpsc::channel(8);
@@ -379,6 +491,7 @@ impl Document {
                     patch_receiver,
                     root,
                     watch_sender,
+                    update_count_sender,
                     command_sender,
                 )
                 .await
@@ -416,6 +529,7 @@ impl Document {
             root,
             kernels,
             watch_receiver,
+            update_count_receiver,
             update_sender,
             patch_sender,
             command_counter,
@@ -541,6 +655,47 @@ impl Document {
         self.root.read().await
     }
 
+    /// Get a node in the document by its [`NodeId`]
+    pub async fn find_node(&self, node_id: NodeId) -> Option<Node> {
+        node_find::find(&*self.root.read().await, node_id)
+    }
+
+    /// Get the execution status of all the executable nodes in the document
+    pub async fn executions(&self) -> Vec<NodeExecution> {
+        executions::executions(&*self.root.read().await)
+    }
+
+    /// Get the comments on the document
+    ///
+    /// Replies to comments are in the `comments` of each comment.
+    pub async fn comments(&self) -> Vec<Comment> {
+        match &*self.root.read().await {
+            Node::Article(Article {
+                comments: Some(comments),
+                ..
+            }) => comments.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Get a summary of the provenance of the content of the document
+    ///
+    /// Aggregates the number of characters in each provenance category
+    /// for the document as a whole, and for each section and author.
+    pub async fn provenance(&self) -> ProvenanceSummary {
+        ProvenanceSummary::from_node(&*self.root.read().await)
+    }
+
+    /// Get the reviews of the document
+    ///
+    /// Comments within each review are in the `comments` of the review.
+    pub async fn reviews(&self) -> Vec<Review> {
+        match &*self.root.read().await {
+            Node::Article(article) => reviews::reviews(article).cloned().collect(),
+            _ => Vec::new(),
+        }
+    }
+
     /// Import a file into a new, or existing, document
     ///
     /// By default the format of the `source` file is inferred from its extension but
@@ -583,6 +738,16 @@ impl Document {
         self.watch_receiver.clone()
     }
 
+    /// Subscribe to the number of updates applied to the document's root node
+    ///
+    /// Unlike [`Document::watch`], which is notified of all changes to the root node
+    /// (including patches made while compiling and executing it), receivers are only
+    /// notified once an update (e.g. from a change to the document's source file) has
+    /// been merged into the root node.
+    pub fn watch_updates(&self) -> watch::Receiver<u64> {
+        self.update_count_receiver.clone()
+    }
+
     /// Update the root node of the document
     pub async fn update(
         &self,
@@ -596,6 +761,28 @@ impl Document {
             .await?)
     }
 
+    /// Update a range of blocks in the content of the root node of the document
+    ///
+    /// The `replaced` blocks of the root's content are merged with the `replacement`
+    /// blocks of the content of `node` (an article which is otherwise the same as the root).
+    /// Avoids diffing, and patching, the unchanged blocks of large documents.
+    pub async fn update_blocks(
+        &self,
+        node: Node,
+        replaced: Range<usize>,
+        replacement: Range<usize>,
+        format: Option<Format>,
+        authors: Option<Vec<AuthorRole>>,
+    ) -> Result<()> {
+        Ok(self
+            .update_sender
+            .send(Update {
+                blocks: Some((replaced, replacement)),
+                ..Update::new(node, format, authors)
+            })
+            .await?)
+    }
+
     /// Perform a command on the document and optionally wait for it to complete
     pub async fn command(&self, command: Command, wait: CommandWait) -> Result<()> {
         match wait {
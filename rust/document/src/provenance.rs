@@ -0,0 +1,335 @@
+//! Summaries of the provenance of the content of a document
+//!
+//! The provenance of each run of characters in the text of a document is encoded
+//! in a single byte (see `schema::cord_provenance`). This module walks over a document
+//! and aggregates the number of characters in each `ProvenanceCategory`, for the
+//! document as a whole, for each section (as delimited by headings), and for each author.
+
+use std::collections::BTreeMap;
+
+use codec_text_trait::TextCodec;
+use common::serde::Serialize;
+use schema::{
+    cord_provenance::category, Author, Block, Inline, Node, NodeId, NodeType, PatchNode,
+    ProvenanceCategory, ProvenanceCount, Visitor, WalkControl,
+};
+
+/// A summary of the provenance of the content of a document
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase", crate = "common::serde")]
+pub struct ProvenanceSummary {
+    /// Counts of characters in each provenance category for the entire document
+    pub counts: Vec<ProvenanceCount>,
+
+    /// The percentage of characters that were written or edited by humans
+    pub human_percent: u64,
+
+    /// The percentage of characters that were written by machines, and not edited by humans
+    pub machine_percent: u64,
+
+    /// The percentage of characters that were written by machines and have not been verified
+    pub machine_unverified_percent: u64,
+
+    /// The percentage of characters that have been verified
+    pub verified_percent: u64,
+
+    /// Counts of characters in each provenance category for each section of the document
+    pub sections: Vec<ProvenanceSection>,
+
+    /// Counts of characters in each provenance category for each author of the document
+    pub authors: Vec<ProvenanceAuthor>,
+}
+
+/// A summary of the provenance of the content of a section of a document
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase", crate = "common::serde")]
+pub struct ProvenanceSection {
+    /// The text of the heading of the section
+    ///
+    /// `None` for any content before the first heading.
+    pub heading: Option<String>,
+
+    /// The level of the heading of the section
+    pub level: Option<i64>,
+
+    /// Counts of characters in each provenance category for the section
+    pub counts: Vec<ProvenanceCount>,
+}
+
+/// A summary of the provenance of the content last edited by an author
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase", crate = "common::serde")]
+pub struct ProvenanceAuthor {
+    /// The name of the author
+    pub name: String,
+
+    /// Counts of characters in each provenance category for the author
+    pub counts: Vec<ProvenanceCount>,
+}
+
+impl ProvenanceSummary {
+    /// Create a provenance summary for a node
+    pub fn from_node(node: &Node) -> Self {
+        let mut walker = ProvenanceWalker::default();
+        walker.visit(node);
+
+        let counts = walker
+            .sections
+            .iter()
+            .flat_map(|(.., counts)| counts.iter())
+            .fold(Counts::new(), |mut all, (category, count)| {
+                *all.entry(category.clone()).or_default() += count;
+                all
+            });
+        let counts = to_provenance_counts(counts);
+
+        let sections = walker
+            .sections
+            .into_iter()
+            .filter(|(.., counts)| !counts.is_empty())
+            .map(|(heading, level, counts)| ProvenanceSection {
+                heading,
+                level,
+                counts: to_provenance_counts(counts),
+            })
+            .collect();
+
+        let authors = walker
+            .authors
+            .into_iter()
+            .map(|(name, counts)| ProvenanceAuthor {
+                name,
+                counts: to_provenance_counts(counts),
+            })
+            .collect();
+
+        Self {
+            human_percent: ProvenanceCount::human_percent(&counts),
+            machine_percent: ProvenanceCount::machine_percent(&counts),
+            machine_unverified_percent: ProvenanceCount::machine_unverified_percent(&counts),
+            verified_percent: ProvenanceCount::verified_percent(&counts),
+            counts,
+            sections,
+            authors,
+        }
+    }
+}
+
+/// Counts of characters for each provenance category
+type Counts = BTreeMap<ProvenanceCategory, u64>;
+
+/// Convert [`Counts`] into a vector of [`ProvenanceCount`]s, including percentages
+fn to_provenance_counts(counts: Counts) -> Vec<ProvenanceCount> {
+    let total: u64 = counts.values().sum();
+    counts
+        .into_iter()
+        .map(|(category, count)| ProvenanceCount {
+            character_percent: if total > 0 {
+                Some((count as f64 / total as f64 * 100.).round() as u64)
+            } else {
+                None
+            },
+            ..ProvenanceCount::new(category, count)
+        })
+        .collect()
+}
+
+/// A visitor which collects provenance counts for each section and author
+#[derive(Default)]
+struct ProvenanceWalker {
+    /// The heading text, heading level, and counts for each section
+    sections: Vec<(Option<String>, Option<i64>, Counts)>,
+
+    /// The counts for each author, by name
+    authors: BTreeMap<String, Counts>,
+
+    /// The authors of the node about to be entered, if any
+    next_authors: Option<Vec<Author>>,
+
+    /// The authors of each node in the current ancestry
+    ///
+    /// The authorship of a run of characters refers to the index of an author
+    /// in the closest ancestor node that has `authors`.
+    authors_stack: Vec<Option<Vec<Author>>>,
+}
+
+impl ProvenanceWalker {
+    /// Record the provenance of the text of a node
+    fn text(&mut self, text: &schema::Text) {
+        if self.sections.is_empty() {
+            self.sections.push((None, None, Counts::new()));
+        }
+        let Some((.., section)) = self.sections.last_mut() else {
+            return;
+        };
+
+        let authors = self.authors_stack.iter().rev().flatten().next();
+
+        for run in &text.value.authorship {
+            let category = category(run.provenance);
+            let length = run.length as u64;
+
+            *section.entry(category.clone()).or_default() += length;
+
+            // The last author of the run is the least significant byte
+            let index = (run.authors & 0xFF) as u8;
+            let name = if run.count == 0 || index == u8::MAX {
+                None
+            } else {
+                authors.and_then(|authors| authors.get(index as usize))
+            }
+            .map_or_else(|| "Unknown".to_string(), |author| author.name());
+
+            *self
+                .authors
+                .entry(name)
+                .or_default()
+                .entry(category)
+                .or_default() += length;
+        }
+    }
+}
+
+impl Visitor for ProvenanceWalker {
+    fn visit_node(&mut self, node: &Node) -> WalkControl {
+        self.next_authors = node.authors();
+
+        WalkControl::Continue
+    }
+
+    fn visit_block(&mut self, block: &Block) -> WalkControl {
+        if let Block::Heading(heading) = block {
+            self.sections.push((
+                Some(heading.content.to_text().0),
+                Some(heading.level),
+                Counts::new(),
+            ));
+        }
+
+        self.next_authors = block.authors();
+
+        WalkControl::Continue
+    }
+
+    fn visit_inline(&mut self, inline: &Inline) -> WalkControl {
+        if let Inline::Text(text) = inline {
+            self.text(text);
+        }
+
+        self.next_authors = inline.authors();
+
+        WalkControl::Continue
+    }
+
+    fn enter_struct(&mut self, _node_type: NodeType, _node_id: NodeId) {
+        self.authors_stack.push(self.next_authors.take());
+    }
+
+    fn exit_struct(&mut self) {
+        self.authors_stack.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_dev::pretty_assertions::assert_eq;
+    use schema::{
+        cord_provenance::{human_written, machine_written},
+        shortcuts::{art, h1, p},
+        Cord, CordAuthorship, Person, SoftwareApplication, Text,
+    };
+
+    use super::*;
+
+    /// Create a text node with runs of authorship
+    fn text(string: &str, runs: &[(u8, u64, u8, u32)]) -> Inline {
+        Inline::Text(Text::new(Cord {
+            string: string.to_string(),
+            authorship: runs
+                .iter()
+                .cloned()
+                .map(CordAuthorship::from_tuple)
+                .collect(),
+        }))
+    }
+
+    fn count(category: ProvenanceCategory, count: u64, percent: u64) -> ProvenanceCount {
+        ProvenanceCount {
+            character_percent: Some(percent),
+            ..ProvenanceCount::new(category, count)
+        }
+    }
+
+    #[test]
+    fn from_node() {
+        let hw = human_written();
+        let mw = machine_written();
+
+        let mut root = art([
+            p([text("Intro", &[(1, 0, hw, 5)])]),
+            h1([text("Methods", &[(1, 0, hw, 7)])]),
+            p([text(
+                "Generated text...",
+                &[(1, 1, mw, 10), (0, 0, hw, 3), (1, 0xFF, hw, 4)],
+            )]),
+        ]);
+        if let Node::Article(article) = &mut root {
+            article.authors = Some(vec![
+                Author::Person(Person {
+                    given_names: Some(vec!["Alice".to_string()]),
+                    family_names: Some(vec!["Smith".to_string()]),
+                    ..Default::default()
+                }),
+                Author::SoftwareApplication(SoftwareApplication::new("Bot".to_string())),
+            ]);
+        }
+
+        let summary = ProvenanceSummary::from_node(&root);
+
+        use ProvenanceCategory::*;
+        assert_eq!(summary.counts, vec![count(Hw, 19, 66), count(Mw, 10, 34)]);
+        assert_eq!(summary.human_percent, 66);
+        assert_eq!(summary.machine_percent, 34);
+        assert_eq!(summary.machine_unverified_percent, 34);
+
+        let sections = summary
+            .sections
+            .into_iter()
+            .map(|section| (section.heading, section.level, section.counts))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sections,
+            vec![
+                (None, None, vec![count(Hw, 5, 100)]),
+                (
+                    Some("Methods".to_string()),
+                    Some(1),
+                    vec![count(Hw, 14, 58), count(Mw, 10, 42)]
+                ),
+            ]
+        );
+
+        let authors = summary
+            .authors
+            .into_iter()
+            .map(|author| (author.name, author.counts))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            authors,
+            vec![
+                ("Alice Smith".to_string(), vec![count(Hw, 12, 100)]),
+                ("Bot".to_string(), vec![count(Mw, 10, 100)]),
+                ("Unknown".to_string(), vec![count(Hw, 7, 100)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let summary = ProvenanceSummary::from_node(&art(Vec::<Block>::new()));
+        assert!(summary.counts.is_empty());
+        assert!(summary.sections.is_empty());
+        assert!(summary.authors.is_empty());
+        assert_eq!(summary.machine_percent, 0);
+    }
+}
@@ -0,0 +1,174 @@
+//! Peer reviews of a document
+//!
+//! Reviews are stored as `Review` nodes in the `parts` of the root `Article`
+//! (analogous to a JATS `<sub-article>`). Like comments, this means that they are
+//! persisted in the document's sidecar file but do not alter its content.
+//!
+//! Comments within a review are anchored to claims, sections and other nodes in
+//! the article in the same way as other comments (see the `comments` module).
+
+use common::{
+    chrono::Local,
+    eyre::{bail, eyre, Result},
+    serde_json,
+};
+use schema::{
+    Article, CreativeWorkType, Date, Node, NodeProperty, Patch, PatchOp, PatchPath, PatchValue,
+    Review,
+};
+
+/// Create the patch needed to add a review to the root node of a document
+pub(super) fn add_review(root: &Node, mut review: Review) -> Result<Patch> {
+    let Node::Article(article) = root else {
+        bail!("Reviews can only be added to articles")
+    };
+
+    review.id = Some(next_review_id(article));
+    if review.options.date_published.is_none() {
+        review.options.date_published = Some(Date::new(Local::now().date_naive().to_string()));
+    }
+
+    Ok(Patch {
+        node_id: Some(article.node_id()),
+        ops: vec![(
+            PatchPath::from(NodeProperty::Parts),
+            PatchOp::Push(PatchValue::Json(serde_json::to_value(
+                CreativeWorkType::Review(review),
+            )?)),
+        )],
+        ..Default::default()
+    })
+}
+
+/// Create the patch needed to remove a review from the root node of a document
+pub(super) fn remove_review(root: &Node, id: &str) -> Result<Patch> {
+    let Node::Article(article) = root else {
+        bail!("Reviews can only be removed from articles")
+    };
+
+    let index = article
+        .options
+        .parts
+        .iter()
+        .flatten()
+        .position(|part| {
+            matches!(part, CreativeWorkType::Review(review) if review.id.as_deref() == Some(id))
+        })
+        .ok_or_else(|| eyre!("No review with id `{id}`"))?;
+
+    Ok(Patch {
+        node_id: Some(article.node_id()),
+        ops: vec![(
+            PatchPath::from(NodeProperty::Parts),
+            PatchOp::Remove(vec![index]),
+        )],
+        ..Default::default()
+    })
+}
+
+/// Get the reviews of an article
+pub(super) fn reviews(article: &Article) -> impl Iterator<Item = &Review> {
+    article
+        .options
+        .parts
+        .iter()
+        .flatten()
+        .filter_map(|part| match part {
+            CreativeWorkType::Review(review) => Some(review),
+            _ => None,
+        })
+}
+
+/// Find a review in an article
+pub(super) fn find_review<'article>(
+    article: &'article Article,
+    id: &str,
+) -> Option<&'article Review> {
+    reviews(article).find(|review| review.id.as_deref() == Some(id))
+}
+
+/// Generate the next id for a review in an article
+///
+/// Ids are of the form `review-<n>` so that they are not reused after a review is removed.
+fn next_review_id(article: &Article) -> String {
+    let n = reviews(article)
+        .filter_map(|review| {
+            review
+                .id
+                .as_deref()
+                .and_then(|id| id.strip_prefix("review-"))
+                .and_then(|n| n.parse::<u64>().ok())
+        })
+        .max()
+        .unwrap_or_default()
+        + 1;
+    format!("review-{n}")
+}
+
+#[cfg(test)]
+mod tests {
+    use common_dev::pretty_assertions::assert_eq;
+    use schema::{
+        shortcuts::{art, p, t},
+        Primitive, PropertyValue, PropertyValueOptions, ReviewOptions,
+    };
+
+    use super::*;
+
+    fn article(root: &Node) -> &Article {
+        let Node::Article(article) = root else {
+            unreachable!()
+        };
+        article
+    }
+
+    /// Test that reviews, including their content and ratings, can be added to,
+    /// and removed from, an article and that ids are not reused
+    #[test]
+    fn add_and_remove() -> Result<()> {
+        let mut root = art([p([t("Content")])]);
+
+        let review = Review {
+            options: Box::new(ReviewOptions {
+                content: Some(vec![p([t("A summary.")])]),
+                ratings: Some(vec![PropertyValue {
+                    options: Box::new(PropertyValueOptions {
+                        name: Some("rigor".to_string()),
+                        ..Default::default()
+                    }),
+                    ..PropertyValue::new(Primitive::Integer(4))
+                }]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        schema::patch(&mut root, add_review(&root, review.clone())?)?;
+        schema::patch(&mut root, add_review(&root, Review::new())?)?;
+
+        let ids = reviews(article(&root))
+            .map(|review| review.id.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            vec![Some("review-1".to_string()), Some("review-2".to_string())]
+        );
+
+        let added = find_review(article(&root), "review-1").expect("should be found");
+        assert_eq!(added.options.content, review.options.content);
+        assert_eq!(added.options.ratings, review.options.ratings);
+        assert!(added.options.date_published.is_some());
+
+        schema::patch(&mut root, remove_review(&root, "review-1")?)?;
+        assert!(find_review(article(&root), "review-1").is_none());
+        assert!(remove_review(&root, "review-1").is_err());
+
+        schema::patch(&mut root, add_review(&root, Review::new())?)?;
+        assert!(find_review(article(&root), "review-3").is_some());
+
+        // Reviews can only be added to articles
+        assert!(add_review(&Node::Null(schema::Null), Review::new()).is_err());
+
+        Ok(())
+    }
+}
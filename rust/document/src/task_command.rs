@@ -7,11 +7,12 @@ use common::{
     tracing,
 };
 use node_execute::{compile, execute, interrupt, ExecuteOptions};
+use schema::Patch;
 
 use crate::{
-    Command, CommandNodes, CommandStatus, Document, DocumentCommandReceiver,
-    DocumentCommandStatusSender, DocumentKernels, DocumentPatchSender, DocumentRoot,
-    SaveDocumentSidecar, SaveDocumentSource,
+    comments, reviews, task_update::record_suggestion_outcomes, Command, CommandNodes,
+    CommandStatus, Document, DocumentCommandReceiver, DocumentCommandStatusSender, DocumentKernels,
+    DocumentPatchSender, DocumentRoot, SaveDocumentSidecar, SaveDocumentSource,
 };
 
 impl Document {
@@ -35,6 +36,16 @@ impl Document {
             }
         }
 
+        // Local function to send patches and get the resulting command status
+        fn send_patches(sender: &DocumentPatchSender, patches: Vec<Patch>) -> CommandStatus {
+            for patch in patches {
+                if let Err(error) = sender.send(patch) {
+                    return CommandStatus::Failed(format!("While sending patch: {error}"));
+                }
+            }
+            CommandStatus::Succeeded
+        }
+
         // The details of the command that is currently running
         let mut current_command_details: Option<(Command, u64, JoinHandle<()>)> = None;
 
@@ -140,6 +151,42 @@ impl Document {
                     };
                     send_status(&status_sender, command_id, status);
                 }
+                AddComment(comment) => {
+                    let status = match comments::add_comment(&*root.read().await, comment) {
+                        Ok(patches) => send_patches(&patch_sender, patches),
+                        Err(error) => {
+                            CommandStatus::Failed(format!("While adding comment: {error}"))
+                        }
+                    };
+                    send_status(&status_sender, command_id, status);
+                }
+                RemoveComment { id } => {
+                    let status = match comments::remove_comment(&*root.read().await, &id) {
+                        Ok(patch) => send_patches(&patch_sender, vec![patch]),
+                        Err(error) => {
+                            CommandStatus::Failed(format!("While removing comment: {error}"))
+                        }
+                    };
+                    send_status(&status_sender, command_id, status);
+                }
+                AddReview(review) => {
+                    let status = match reviews::add_review(&*root.read().await, review) {
+                        Ok(patch) => send_patches(&patch_sender, vec![patch]),
+                        Err(error) => {
+                            CommandStatus::Failed(format!("While adding review: {error}"))
+                        }
+                    };
+                    send_status(&status_sender, command_id, status);
+                }
+                RemoveReview { id } => {
+                    let status = match reviews::remove_review(&*root.read().await, &id) {
+                        Ok(patch) => send_patches(&patch_sender, vec![patch]),
+                        Err(error) => {
+                            CommandStatus::Failed(format!("While removing review: {error}"))
+                        }
+                    };
+                    send_status(&status_sender, command_id, status);
+                }
                 CompileDocument => {
                     let status_sender = status_sender.clone();
                     let task = tokio::spawn(async move {
@@ -172,6 +219,7 @@ impl Document {
                 ExecuteNodes((nodes, options)) | PatchExecuteNodes((.., nodes, options)) => {
                     if let PatchExecuteNodes((patch, ..)) = command {
                         let root = &mut *root.write().await;
+                        record_suggestion_outcomes(root, &patch);
                         if let Err(error) = schema::patch(root, patch) {
                             CommandStatus::Failed(format!("While applying patch to root: {error}"));
                         }
@@ -1,11 +1,16 @@
 use common::{
+    eyre::Result,
     tokio::{self},
     tracing,
 };
+use schema::{
+    Article, Block, InstructionBlock, InstructionType, Node, NodeProperty, Patch, PatchOp,
+    PatchSlot, PatchValue, SuggestionBlock, SuggestionStatus,
+};
 
 use crate::{
     Command, Document, DocumentCommandSender, DocumentPatchReceiver, DocumentRoot,
-    DocumentUpdateReceiver, DocumentWatchSender,
+    DocumentUpdateCountSender, DocumentUpdateReceiver, DocumentWatchSender, Update,
 };
 
 impl Document {
@@ -13,6 +18,7 @@ impl Document {
     ///
     /// The root node is received on the `update_receiver` channel, dumped into
     /// the store, loaded back from the store, and sent to watchers on the `watch_sender` channel.
+    /// The count of updates on the `update_count_sender` channel is then incremented.
     ///
     /// Loading back from the store, rather than just sending watchers the received node, is
     /// necessary because the incoming node may be partial (e.g. from a format such as Markdown)
@@ -25,6 +31,7 @@ impl Document {
         mut patch_receiver: DocumentPatchReceiver,
         root: DocumentRoot,
         watch_sender: DocumentWatchSender,
+        update_count_sender: DocumentUpdateCountSender,
         command_sender: DocumentCommandSender,
     ) {
         tracing::debug!("Document update task started");
@@ -35,7 +42,7 @@ impl Document {
                     tracing::trace!("Document root node update received");
 
                     let root = &mut *root.write().await;
-                    if let Err(error) = schema::merge(root, &update.node, update.format, update.authors) {
+                    if let Err(error) = merge_update(root, update) {
                         tracing::error!("While merging update into root: {error}");
                     }
 
@@ -45,6 +52,7 @@ impl Document {
                     tracing::trace!("Document root node patch received");
 
                     let root = &mut *root.write().await;
+                    record_suggestion_outcomes(root, &patch);
                     if let Err(error) = schema::patch(root, patch) {
                         tracing::error!("While applying patch to root: {error}");
                     }
@@ -66,6 +74,11 @@ impl Document {
                 }
             }
 
+            // Notify watchers of updates (as opposed to patches)
+            if compile {
+                update_count_sender.send_modify(|count| *count += 1);
+            }
+
             // Recompile the document
             // TODO: consider recompiling on patches as well but with care taken
             // to ignore patches that are from the compilation of execution already
@@ -82,3 +95,257 @@ impl Document {
         tracing::debug!("Document update task stopped");
     }
 }
+
+/// Merge an update into the root node
+///
+/// If the update is for a range of blocks, and the lengths of the content of the root
+/// and the updated node are consistent with that range, then only those blocks are merged.
+/// Otherwise, the whole node is merged.
+fn merge_update(root: &mut Node, update: Update) -> Result<()> {
+    let Update {
+        node,
+        format,
+        authors,
+        blocks,
+    } = update;
+
+    if let (
+        Some((replaced, replacement)),
+        Node::Article(Article { content, .. }),
+        Node::Article(Article {
+            content: new_content,
+            ..
+        }),
+    ) = (blocks, &mut *root, &node)
+    {
+        if replaced.start <= replaced.end
+            && replaced.end <= content.len()
+            && replacement.start <= replacement.end
+            && replacement.end <= new_content.len()
+            && content.len() - replaced.len() + replacement.len() == new_content.len()
+        {
+            let mut blocks: Vec<Block> = content.drain(replaced.clone()).collect();
+            let result = schema::merge(
+                &mut blocks,
+                &new_content[replacement].to_vec(),
+                format,
+                authors,
+            );
+            content.splice(replaced.start..replaced.start, blocks);
+            return result;
+        }
+    }
+
+    schema::merge(root, &node, format, authors)
+}
+
+/// Record the outcomes of any suggestions that are accepted or rejected by a patch
+///
+/// Archiving an instruction accepts its active suggestion (and implicitly rejects
+/// the others), and providing feedback for a revision rejects the active suggestion.
+/// These outcomes are recorded so that model selection can learn local preferences.
+///
+/// Must be called before the patch is applied because archiving an
+/// instruction removes it from the document.
+pub(super) fn record_suggestion_outcomes(root: &Node, patch: &Patch) {
+    let Some((instruction_type, outcomes)) = suggestion_outcomes(root, patch) else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        if let Err(error) = models::scores::record_outcomes(&instruction_type, &outcomes).await {
+            tracing::error!("While recording suggestion outcomes: {error}");
+        }
+    });
+}
+
+/// Get the outcomes of any suggestions that are accepted or rejected by a patch
+///
+/// The patch may set the active suggestion before archiving the instruction, or setting
+/// feedback (e.g. when accepting or revising a suggestion other than the active one)
+/// in which case that suggestion, rather than the currently active one, is used.
+fn suggestion_outcomes(
+    root: &Node,
+    patch: &Patch,
+) -> Option<(InstructionType, Vec<(SuggestionBlock, SuggestionStatus)>)> {
+    let node_id = patch.node_id.as_ref()?;
+
+    let archive = patch
+        .ops
+        .iter()
+        .any(|(path, op)| path.is_empty() && matches!(op, PatchOp::Archive));
+    let revise = patch.ops.iter().any(|(path, op)| {
+        path.front() == Some(&PatchSlot::Property(NodeProperty::Feedback))
+            && matches!(op, PatchOp::Set(..))
+    });
+    if !(archive || revise) {
+        return None;
+    }
+
+    let Some(Node::InstructionBlock(InstructionBlock {
+        instruction_type,
+        active_suggestion,
+        suggestions: Some(suggestions),
+        ..
+    })) = node_find::find(root, node_id.clone())
+    else {
+        return None;
+    };
+
+    let set_active = patch.ops.iter().find_map(|(path, op)| match op {
+        PatchOp::Set(PatchValue::Json(value))
+            if path.front() == Some(&PatchSlot::Property(NodeProperty::ActiveSuggestion)) =>
+        {
+            value.as_u64()
+        }
+        _ => None,
+    });
+
+    let active = set_active.or(active_suggestion).map(|index| index as usize);
+    let outcomes = suggestions
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, suggestion)| {
+            let is_active = Some(index) == active;
+            let status = if archive && is_active {
+                SuggestionStatus::Accepted
+            } else if archive || is_active {
+                SuggestionStatus::Rejected
+            } else {
+                return None;
+            };
+            Some((suggestion, status))
+        })
+        .collect();
+
+    Some((instruction_type, outcomes))
+}
+
+#[cfg(test)]
+mod tests {
+    use common_dev::pretty_assertions::assert_eq;
+    use schema::{
+        shortcuts::{art, p, t},
+        PatchPath,
+    };
+
+    use super::*;
+
+    /// Test that merging a range of blocks is the same as merging the whole node
+    #[test]
+    fn merge_update_blocks() -> Result<()> {
+        let old = art([p([t("a")]), p([t("b")]), p([t("c")])]);
+        let new = art([p([t("a")]), p([t("x")]), p([t("y")]), p([t("c")])]);
+
+        let mut expected = old.clone();
+        merge_update(&mut expected, Update::new(new.clone(), None, None))?;
+
+        let mut root = old.clone();
+        let update = Update {
+            blocks: Some((1..2, 1..3)),
+            ..Update::new(new.clone(), None, None)
+        };
+        merge_update(&mut root, update)?;
+
+        let (Node::Article(root), Node::Article(old), Node::Article(expected)) =
+            (root, old, expected)
+        else {
+            unreachable!()
+        };
+        assert_eq!(root.content, expected.content);
+        assert_eq!(root.content[0].node_id(), old.content[0].node_id());
+        assert_eq!(root.content[3].node_id(), old.content[2].node_id());
+
+        // Ranges inconsistent with the lengths of content fallback to merging the whole node
+        let mut root = Node::Article(old.clone());
+        let update = Update {
+            blocks: Some((0..1, 1..3)),
+            ..Update::new(new, None, None)
+        };
+        merge_update(&mut root, update)?;
+        let Node::Article(root) = root else {
+            unreachable!()
+        };
+        assert_eq!(root.content, expected.content);
+
+        Ok(())
+    }
+
+    /// Test that the outcomes of suggestions are derived from archive and feedback
+    /// patches, including those that also set the active suggestion
+    #[test]
+    fn outcomes() {
+        let suggestions = ["a", "b", "c"]
+            .map(|text| SuggestionBlock::new(vec![p([t(text)])]))
+            .to_vec();
+        let instruction = InstructionBlock {
+            active_suggestion: Some(0),
+            suggestions: Some(suggestions),
+            ..InstructionBlock::new(InstructionType::Edit)
+        };
+        let node_id = instruction.node_id();
+        let root = Node::Article(Article::new(vec![Block::InstructionBlock(instruction)]));
+
+        let statuses = |ops: Vec<(PatchPath, PatchOp)>| {
+            let patch = Patch {
+                node_id: Some(node_id.clone()),
+                ops,
+                ..Default::default()
+            };
+            suggestion_outcomes(&root, &patch).map(|(.., outcomes)| {
+                outcomes
+                    .into_iter()
+                    .map(|(suggestion, status)| (suggestion.content, status))
+                    .collect::<Vec<_>>()
+            })
+        };
+        let set_active = |index: u64| {
+            (
+                PatchPath::from(NodeProperty::ActiveSuggestion),
+                PatchOp::Set(PatchValue::Json(index.into())),
+            )
+        };
+        let archive = || (PatchPath::new(), PatchOp::Archive);
+        let feedback = || {
+            (
+                PatchPath::from(NodeProperty::Feedback),
+                PatchOp::Set(PatchValue::None),
+            )
+        };
+
+        use SuggestionStatus::*;
+
+        // Archiving accepts the active suggestion and rejects the others
+        assert_eq!(
+            statuses(vec![archive()]),
+            Some(vec![
+                (vec![p([t("a")])], Accepted),
+                (vec![p([t("b")])], Rejected),
+                (vec![p([t("c")])], Rejected)
+            ])
+        );
+
+        // Unless the patch sets the active suggestion first
+        assert_eq!(
+            statuses(vec![set_active(2), archive()]),
+            Some(vec![
+                (vec![p([t("a")])], Rejected),
+                (vec![p([t("b")])], Rejected),
+                (vec![p([t("c")])], Accepted)
+            ])
+        );
+
+        // Feedback rejects the active suggestion, including one set by the patch
+        assert_eq!(
+            statuses(vec![feedback()]),
+            Some(vec![(vec![p([t("a")])], Rejected)])
+        );
+        assert_eq!(
+            statuses(vec![set_active(1), feedback()]),
+            Some(vec![(vec![p([t("b")])], Rejected)])
+        );
+
+        // Other patches have no outcomes
+        assert_eq!(statuses(vec![set_active(1)]), None);
+    }
+}
@@ -38,6 +38,8 @@ pub enum Format {
     Qmd,
     Myst,
     Llmd,
+    // Notebook formats
+    Ipynb,
     // Typesetting/text formats
     Latex,
     Text,
@@ -116,6 +118,7 @@ impl Format {
             Flac => "FLAC",
             Gif => "GIF",
             Html => "HTML",
+            Ipynb => "Jupyter Notebook",
             Jats => "JATS",
             JavaScript => "JavaScript",
             Jinja => "Jinja",
@@ -223,6 +226,7 @@ impl Format {
             "flac" => Flac,
             "gif" => Gif,
             "html" => Html,
+            "ipynb" | "jupyter" => Ipynb,
             "jats" | "jats.xml" => Jats,
             "javascript" | "js" => JavaScript,
             "jinja" => Jinja,
@@ -315,6 +319,7 @@ impl Format {
             "application/json" => Ok(Json),
             "application/json+zip" => Ok(JsonZip),
             "application/ld+json" => Ok(JsonLd),
+            "application/x-ipynb+json" => Ok(Ipynb),
             "application/yaml" => Ok(Yaml),
             "text/jats+xml" => Ok(Jats),
             "text/markdown" => Ok(Markdown),
@@ -341,6 +346,7 @@ impl Format {
             Json => "application/json".to_string(),
             JsonZip => "application/json+zip".to_string(),
             JsonLd => "application/ld+json".to_string(),
+            Ipynb => "application/x-ipynb+json".to_string(),
             Yaml => "application/yaml".to_string(),
             Jats => "text/jats+xml".to_string(),
             Markdown => "text/markdown".to_string(),
@@ -391,6 +397,7 @@ impl Display for Format {
             Flac => "flac",
             Gif => "gif",
             Html => "html",
+            Ipynb => "ipynb",
             Jats => "jats",
             JavaScript => "js",
             Jinja => "jinja",
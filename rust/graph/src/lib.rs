@@ -4,7 +4,7 @@ use std::collections::HashMap;
 
 use petgraph::{
     stable_graph::{NodeIndex, StableGraph},
-    visit::{EdgeRef, IntoEdgeReferences, IntoNodeReferences},
+    visit::{Dfs, EdgeRef, IntoEdgeReferences, IntoNodeReferences},
 };
 
 use common::{
@@ -46,9 +46,15 @@ pub enum GraphNode {
     File {
         path: String,
     },
+    ForBlock {
+        id: String,
+    },
     Function {
         name: String,
     },
+    IfBlock {
+        id: String,
+    },
     Parameter {
         name: String,
     },
@@ -190,6 +196,24 @@ pub struct Graph {
 }
 
 impl Graph {
+    /// Get the index of a node, adding it to the graph if necessary
+    fn index(&mut self, node: GraphNode) -> NodeIndex {
+        if let Some(index) = self.indices.get(&node) {
+            *index
+        } else {
+            let index = self.graph.add_node(node.clone());
+            self.indices.insert(node, index);
+            index
+        }
+    }
+
+    /// Add an edge between two nodes
+    pub fn add_edge(&mut self, from: GraphNode, edge: GraphEdge, to: GraphNode) {
+        let from = self.index(from);
+        let to = self.index(to);
+        self.graph.add_edge(from, to, edge);
+    }
+
     /// Add a dependency relation between two nodes
     pub fn add_dependency(
         &mut self,
@@ -197,28 +221,11 @@ impl Graph {
         relation: &ExecutionDependencyRelation,
         from: &ExecutionDependencyNode,
     ) -> Result<()> {
-        let from = GraphNode::try_from(from)?;
-        let from = if let Some(index) = self.indices.get(&from) {
-            *index
-        } else {
-            let index = self.graph.add_node(from.clone());
-            self.indices.insert(from, index);
-            index
-        };
-
-        let to = GraphNode::try_from(to)?;
-        let to = if let Some(index) = self.indices.get(&to) {
-            *index
-        } else {
-            let index = self.graph.add_node(to.clone());
-            self.indices.insert(to, index);
-            index
-        };
-
-        let edge = GraphEdge::from(relation);
-
-        self.graph.add_edge(from, to, edge);
-
+        self.add_edge(
+            GraphNode::try_from(from)?,
+            GraphEdge::from(relation),
+            GraphNode::try_from(to)?,
+        );
         Ok(())
     }
 
@@ -229,29 +236,33 @@ impl Graph {
         relation: &ExecutionDependantRelation,
         to: &ExecutionDependantNode,
     ) -> Result<()> {
-        let from = GraphNode::try_from(from)?;
-        let from = if let Some(index) = self.indices.get(&from) {
-            *index
-        } else {
-            let index = self.graph.add_node(from.clone());
-            self.indices.insert(from, index);
-            index
-        };
+        self.add_edge(
+            GraphNode::try_from(from)?,
+            GraphEdge::from(relation),
+            GraphNode::try_from(to)?,
+        );
+        Ok(())
+    }
 
-        let to = GraphNode::try_from(to)?;
-        let to = if let Some(index) = self.indices.get(&to) {
-            *index
-        } else {
-            let index = self.graph.add_node(to.clone());
-            self.indices.insert(to, index);
-            index
+    /// Get the nodes which directly, or transitively, depend upon a node
+    ///
+    /// Nodes are returned in the order in which they are visited in a depth
+    /// first search from the node, which is not included.
+    pub fn dependants(&self, node: &GraphNode) -> Vec<&GraphNode> {
+        let Some(start) = self.indices.get(node) else {
+            return Vec::new();
         };
 
-        let edge = GraphEdge::from(relation);
-
-        self.graph.add_edge(from, to, edge);
-
-        Ok(())
+        let mut dependants = Vec::new();
+        let mut dfs = Dfs::new(&self.graph, *start);
+        while let Some(index) = dfs.next(&self.graph) {
+            if index != *start {
+                if let Some(node) = self.graph.node_weight(index) {
+                    dependants.push(node);
+                }
+            }
+        }
+        dependants
     }
 
     /// Convert the graph to some format
@@ -301,3 +312,35 @@ impl Serialize for Graph {
         .serialize(serializer)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dependants() {
+        let button = GraphNode::Button {
+            id: "btn".to_string(),
+        };
+        let variable = |name: &str| GraphNode::Variable {
+            name: name.to_string(),
+        };
+        let chunk = |id: &str| GraphNode::CodeChunk { id: id.to_string() };
+
+        let mut graph = Graph::default();
+        graph.add_edge(button.clone(), GraphEdge::Assigns, variable("a"));
+        graph.add_edge(variable("a"), GraphEdge::Uses, chunk("c1"));
+        graph.add_edge(chunk("c1"), GraphEdge::Assigns, variable("b"));
+        graph.add_edge(variable("b"), GraphEdge::Uses, chunk("c2"));
+        graph.add_edge(variable("c"), GraphEdge::Uses, chunk("c3"));
+
+        let dependants = graph.dependants(&button);
+        assert_eq!(dependants.len(), 4);
+        assert!(dependants.contains(&&chunk("c1")));
+        assert!(dependants.contains(&&chunk("c2")));
+        assert!(!dependants.contains(&&chunk("c3")));
+        assert!(!dependants.contains(&&button));
+
+        assert!(graph.dependants(&chunk("c4")).is_empty());
+    }
+}
@@ -0,0 +1,101 @@
+//! Conversion of Jupyter outputs to Stencila nodes
+//!
+//! Used for both the outputs of code cells in Jupyter Notebooks (by `codec-ipynb`)
+//! and the outputs of Jupyter kernels (by `kernel-jupyter`).
+
+use common::{
+    once_cell::sync::Lazy,
+    regex::Regex,
+    serde_json::{self, Map, Value},
+};
+use schema::{Datatable, DatatableColumn, ImageObject, Node, Primitive, RawBlock};
+
+/// Get the text of a value in a MIME bundle
+///
+/// Textual values may be strings or arrays of lines.
+pub fn mime_text(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        Value::Array(lines) => lines.iter().filter_map(|line| line.as_str()).collect(),
+        _ => value.to_string(),
+    }
+}
+
+/// Convert a MIME bundle (e.g. the `data` of an output) to a Stencila node
+///
+/// Uses the richest MIME type in the bundle that can be converted.
+pub fn mime_bundle_to_node(bundle: &Map<String, Value>) -> Node {
+    if let Some(datatable) = bundle
+        .get("application/vnd.dataresource+json")
+        .and_then(data_resource_to_datatable)
+    {
+        return Node::Datatable(datatable);
+    }
+
+    for media_type in ["image/png", "image/jpeg", "image/gif", "image/svg+xml"] {
+        if let Some(data) = bundle.get(media_type).map(mime_text) {
+            let url = if media_type == "image/svg+xml" {
+                format!("data:{media_type};utf8,{}", data.replace('#', "%23"))
+            } else {
+                format!("data:{media_type};base64,{}", data.trim())
+            };
+            return Node::ImageObject(ImageObject {
+                media_type: Some(media_type.to_string()),
+                ..ImageObject::new(url)
+            });
+        }
+    }
+
+    if let Some(node) = bundle
+        .get("application/json")
+        .and_then(|json| serde_json::from_value(json.clone()).ok())
+    {
+        return node;
+    }
+
+    if let Some(html) = bundle.get("text/html").map(mime_text) {
+        return Node::RawBlock(RawBlock::new("html".to_string(), html.into()));
+    }
+
+    if let Some(plain) = bundle.get("text/plain").map(mime_text) {
+        // Attempt to parse as JSON (e.g. numbers) falling back to a string
+        return serde_json::from_str(&plain).unwrap_or(Node::String(plain));
+    }
+
+    Node::String(String::new())
+}
+
+/// Convert a Table Data Resource (`application/vnd.dataresource+json`) to a `Datatable`
+///
+/// See https://specs.frictionlessdata.io/tabular-data-resource/. This MIME type is
+/// emitted by, for example, Pandas when `pd.options.display.html.table_schema = True`.
+pub fn data_resource_to_datatable(json: &Value) -> Option<Datatable> {
+    let fields = json.get("schema")?.get("fields")?.as_array()?;
+    let rows = json.get("data")?.as_array()?;
+
+    let columns = fields
+        .iter()
+        .filter_map(|field| field.get("name")?.as_str())
+        .map(|name| {
+            let values = rows
+                .iter()
+                .map(|row| {
+                    row.get(name)
+                        .and_then(|value| serde_json::from_value(value.clone()).ok())
+                        .unwrap_or_default()
+                })
+                .collect::<Vec<Primitive>>();
+            DatatableColumn::new(name.to_string(), values)
+        })
+        .collect();
+
+    Some(Datatable::new(columns))
+}
+
+/// Strip ANSI escape codes (e.g. used for coloring tracebacks) from a string
+pub fn strip_ansi(string: &str) -> String {
+    static REGEX: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"\x1b\[[0-9;]*[A-Za-z]").expect("invalid regex"));
+
+    REGEX.replace_all(string, "").to_string()
+}
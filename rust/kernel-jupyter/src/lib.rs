@@ -0,0 +1,877 @@
+//! A bridge to Jupyter kernels
+//!
+//! Allows code to be executed using any Jupyter kernel installed on the machine
+//! (e.g. `ir`, `julia-1.10`, `ipykernel` in a particular virtual environment).
+//! Kernels are discovered from their kernelspecs, launched with a connection file,
+//! and communicated with using the Jupyter messaging protocol over ZeroMQ.
+
+use std::{
+    collections::HashMap,
+    env,
+    fs::{read_dir, read_to_string, write},
+    net::TcpListener,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use kernel::{
+    common::{
+        async_trait::async_trait,
+        dirs,
+        eyre::{bail, eyre, Result},
+        serde::Deserialize,
+        serde_json::{self, json, Value},
+        tempfile::{tempdir, TempDir},
+        tokio::{
+            self,
+            process::Command,
+            sync::{mpsc, oneshot, watch},
+            time,
+        },
+        tracing,
+        uuid::Uuid,
+        which::which,
+    },
+    format::Format,
+    generate_id,
+    schema::{ExecutionMessage, MessageLevel, Node, Null, SoftwareApplication},
+    Kernel, KernelAvailability, KernelInstance, KernelInterrupt, KernelKill, KernelProvider,
+    KernelSignal, KernelStatus, KernelTerminate,
+};
+use zeromq::{DealerSocket, Socket, SocketRecv, SocketSend, SubSocket, ZmqMessage};
+
+mod messages;
+mod outputs;
+
+use messages::JupyterMessage;
+use outputs::{error_to_message, mime_bundle_to_node};
+
+/// The prefix added to the names of kernelspecs to create kernel names
+///
+/// Avoids clashes with the names of builtin kernels (e.g. `python`).
+const PREFIX: &str = "jupyter-";
+
+/// The time to wait for a Jupyter kernel to start and reply to a `kernel_info_request`
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The time to wait for a message on the IOPub channel, during startup, before
+/// repeating a `kernel_info_request`
+const IOPUB_RETRY: Duration = Duration::from_millis(250);
+
+/// Get a list of the Jupyter kernels installed on the machine
+///
+/// Searches the same directories as `jupyter kernelspec list` (although not
+/// all of those used by Jupyter on Windows). Where there is more than one kernelspec
+/// with the same name, the first one found is used.
+pub fn list() -> Vec<Box<dyn Kernel>> {
+    let mut kernels: Vec<Box<dyn Kernel>> = Vec::new();
+    let mut names = Vec::new();
+
+    for dir in kernelspec_dirs() {
+        let Ok(entries) = read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if names.contains(&name) {
+                continue;
+            }
+
+            let path = entry.path().join("kernel.json");
+            let spec = match read_to_string(&path)
+                .map_err(|error| eyre!(error))
+                .and_then(|json| Ok(serde_json::from_str::<KernelSpec>(&json)?))
+            {
+                Ok(spec) => spec,
+                Err(error) => {
+                    tracing::debug!("While reading kernelspec `{}`: {error}", path.display());
+                    continue;
+                }
+            };
+
+            names.push(name.clone());
+            kernels.push(Box::new(JupyterKernel {
+                name: [PREFIX, &name].concat(),
+                spec,
+            }));
+        }
+    }
+
+    kernels
+}
+
+/// Get the directories that may contain Jupyter kernelspecs
+///
+/// See https://jupyter-client.readthedocs.io/en/latest/kernels.html#kernel-specs
+fn kernelspec_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if let Some(paths) = env::var_os("JUPYTER_PATH") {
+        dirs.extend(env::split_paths(&paths).map(|path| path.join("kernels")));
+    }
+
+    if let Some(dir) = env::var_os("JUPYTER_DATA_DIR") {
+        dirs.push(PathBuf::from(dir).join("kernels"));
+    } else if cfg!(target_os = "macos") {
+        if let Some(home) = dirs::home_dir() {
+            dirs.push(home.join("Library").join("Jupyter").join("kernels"));
+        }
+    } else if let Some(data) = dirs::data_dir() {
+        dirs.push(data.join("jupyter").join("kernels"));
+    }
+
+    for var in ["VIRTUAL_ENV", "CONDA_PREFIX"] {
+        if let Some(prefix) = env::var_os(var) {
+            dirs.push(PathBuf::from(prefix).join("share/jupyter/kernels"));
+        }
+    }
+
+    dirs.push(PathBuf::from("/usr/local/share/jupyter/kernels"));
+    dirs.push(PathBuf::from("/usr/share/jupyter/kernels"));
+
+    dirs
+}
+
+/// A Jupyter kernelspec
+///
+/// See https://jupyter-client.readthedocs.io/en/latest/kernels.html#kernel-specs
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "kernel::common::serde")]
+struct KernelSpec {
+    /// The command line arguments used to start the kernel
+    argv: Vec<String>,
+
+    /// The name of the language of the kernel
+    language: String,
+
+    /// How the kernel should be interrupted: `signal` (default) or `message`
+    #[serde(default)]
+    interrupt_mode: Option<String>,
+
+    /// Environment variables to set for the kernel
+    #[serde(default)]
+    env: HashMap<String, String>,
+}
+
+impl KernelSpec {
+    /// Whether the kernel should be interrupted using an `interrupt_request` message
+    fn interrupt_by_message(&self) -> bool {
+        self.interrupt_mode.as_deref() == Some("message")
+    }
+}
+
+/// A kernel for executing code using an installed Jupyter kernel
+pub struct JupyterKernel {
+    /// The name of the kernel (the name of the kernelspec with a prefix)
+    name: String,
+
+    /// The kernelspec of the kernel
+    spec: KernelSpec,
+}
+
+impl Kernel for JupyterKernel {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn provider(&self) -> KernelProvider {
+        KernelProvider::Environment
+    }
+
+    fn availability(&self) -> KernelAvailability {
+        match self.spec.argv.first() {
+            Some(program) if which(program).is_ok() || Path::new(program).exists() => {
+                KernelAvailability::Available
+            }
+            _ => KernelAvailability::Unavailable,
+        }
+    }
+
+    fn supports_languages(&self) -> Vec<Format> {
+        vec![Format::from_name(&self.spec.language)]
+    }
+
+    fn supports_interrupt(&self) -> KernelInterrupt {
+        if cfg!(unix) || self.spec.interrupt_by_message() {
+            KernelInterrupt::Yes
+        } else {
+            KernelInterrupt::No
+        }
+    }
+
+    fn supports_terminate(&self) -> KernelTerminate {
+        KernelTerminate::Yes
+    }
+
+    fn supports_kill(&self) -> KernelKill {
+        if cfg!(unix) {
+            KernelKill::Yes
+        } else {
+            KernelKill::No
+        }
+    }
+
+    fn create_instance(&self) -> Result<Box<dyn KernelInstance>> {
+        Ok(Box::new(JupyterKernelInstance::new(
+            &self.name,
+            self.spec.clone(),
+        )))
+    }
+}
+
+/// An instance of a Jupyter kernel
+pub struct JupyterKernelInstance {
+    /// The id of the instance
+    id: String,
+
+    /// The kernelspec used to start the instance
+    spec: KernelSpec,
+
+    /// Additional environment variables to set for the instance
+    environment: Vec<(String, String)>,
+
+    /// The status of the instance
+    ///
+    /// Shared with the task which waits for the kernel process to exit.
+    status_sender: Arc<watch::Sender<KernelStatus>>,
+
+    /// A channel for sending signals to the instance
+    signal_sender: Option<mpsc::Sender<KernelSignal>>,
+
+    /// The temporary directory containing the connection file
+    connection_dir: Option<TempDir>,
+
+    /// The id of the session used in message headers
+    session: String,
+
+    /// The key used to sign messages
+    key: String,
+
+    /// The socket for the shell channel
+    shell: Option<DealerSocket>,
+
+    /// The socket for the IOPub channel
+    iopub: Option<SubSocket>,
+
+    /// Kills the kernel process when the instance is dropped
+    _drop_guard: Option<oneshot::Sender<()>>,
+}
+
+impl JupyterKernelInstance {
+    /// Create a new instance
+    fn new(name: &str, spec: KernelSpec) -> Self {
+        let (status_sender, _) = watch::channel(KernelStatus::Pending);
+
+        Self {
+            id: generate_id(name),
+            spec,
+            environment: Vec::new(),
+            status_sender: Arc::new(status_sender),
+            signal_sender: None,
+            connection_dir: None,
+            session: Uuid::new_v4().to_string(),
+            key: Uuid::new_v4().to_string(),
+            shell: None,
+            iopub: None,
+            _drop_guard: None,
+        }
+    }
+
+    /// Set the status of the instance
+    fn set_status(&self, status: KernelStatus) {
+        self.status_sender.send_if_modified(|previous| {
+            if *previous == status {
+                false
+            } else {
+                *previous = status;
+                true
+            }
+        });
+    }
+
+    /// Wait until messages are being received on the IOPub channel
+    ///
+    /// A SUB socket only receives messages published after its subscription has reached
+    /// the kernel (the "slow joiner" problem) so the status messages for a single request
+    /// may be missed. So `kernel_info_request`s are repeated until any message is received
+    /// on IOPub. Replies to these requests on the shell channel are ignored by later requests.
+    async fn wait_for_iopub(&mut self) -> Result<()> {
+        let (Some(shell), Some(iopub)) = (self.shell.as_mut(), self.iopub.as_mut()) else {
+            bail!("Jupyter kernel `{}` has not been started", self.id)
+        };
+
+        let mut status = self.status_sender.subscribe();
+        loop {
+            let request = JupyterMessage::new(&self.session, "kernel_info_request", json!({}));
+            shell.send(request.to_zmq(&self.key)?).await?;
+
+            tokio::select! {
+                message = iopub.recv() => {
+                    message?;
+                    return Ok(());
+                }
+                _ = time::sleep(IOPUB_RETRY) => {}
+                _ = status.wait_for(|status| matches!(status, KernelStatus::Stopped | KernelStatus::Failed)) => {
+                    bail!("Jupyter kernel `{}` stopped unexpectedly", self.id)
+                }
+            }
+        }
+    }
+
+    /// Send a request on the shell channel and wait for the reply
+    ///
+    /// Returns the reply and the messages broadcast on the IOPub channel while
+    /// the request was being handled (i.e. until the kernel returns to `idle`).
+    async fn request(
+        &mut self,
+        msg_type: &str,
+        content: Value,
+    ) -> Result<(JupyterMessage, Vec<JupyterMessage>)> {
+        let (Some(shell), Some(iopub)) = (self.shell.as_mut(), self.iopub.as_mut()) else {
+            bail!("Jupyter kernel `{}` has not been started", self.id)
+        };
+
+        let request = JupyterMessage::new(&self.session, msg_type, content);
+        let msg_id = request.header.msg_id.clone();
+        shell.send(request.to_zmq(&self.key)?).await?;
+
+        // Receive a message but bail if the kernel process exits while waiting
+        let mut status = self.status_sender.subscribe();
+        let id = &self.id;
+        let key = &self.key;
+        macro_rules! receive {
+            ($socket:expr) => {{
+                tokio::select! {
+                    message = $socket.recv() => message?,
+                    _ = status.wait_for(|status| matches!(status, KernelStatus::Stopped | KernelStatus::Failed)) => {
+                        bail!("Jupyter kernel `{id}` stopped unexpectedly")
+                    }
+                }
+            }};
+        }
+        let decode = |message: ZmqMessage| match JupyterMessage::from_zmq(message, key) {
+            Ok(message) => Some(message),
+            Err(error) => {
+                tracing::warn!("While decoding message from Jupyter kernel `{id}`: {error}");
+                None
+            }
+        };
+
+        let mut broadcasts = Vec::new();
+        loop {
+            let Some(message) = decode(receive!(iopub)) else {
+                continue;
+            };
+            if !message.is_reply_to(&msg_id) {
+                continue;
+            }
+            if message.msg_type() == "status" {
+                if message.content["execution_state"] == "idle" {
+                    break;
+                }
+                continue;
+            }
+            broadcasts.push(message);
+        }
+
+        let reply = loop {
+            if let Some(message) = decode(receive!(shell)) {
+                if message.is_reply_to(&msg_id) {
+                    break message;
+                }
+            }
+        };
+
+        Ok((reply, broadcasts))
+    }
+
+    /// Convert the messages broadcast while executing code into outputs and messages
+    fn outputs_and_messages(broadcasts: Vec<JupyterMessage>) -> (Vec<Node>, Vec<ExecutionMessage>) {
+        let mut outputs = Vec::new();
+        let mut messages = Vec::new();
+        for message in broadcasts {
+            let content = &message.content;
+            match message.msg_type() {
+                "execute_result" | "display_data" => {
+                    outputs.push(mime_bundle_to_node(&content["data"]));
+                }
+                "stream" => {
+                    let text = content["text"].as_str().unwrap_or_default().to_string();
+                    if content["name"] == "stderr" {
+                        messages.push(ExecutionMessage::new(MessageLevel::Warning, text));
+                    } else {
+                        outputs.push(Node::String(text));
+                    }
+                }
+                "error" => messages.push(error_to_message(content)),
+                _ => {}
+            }
+        }
+        (outputs, messages)
+    }
+
+    /// Create a channel and task for sending signals to the kernel
+    ///
+    /// The task owns the socket for the control channel which is used for
+    /// `interrupt_request`s (if the kernelspec's interrupt mode is `message`)
+    /// and `shutdown_request`s.
+    fn setup_signals_channel(
+        &self,
+        control: DealerSocket,
+        pid: Option<u32>,
+    ) -> mpsc::Sender<KernelSignal> {
+        let (signal_sender, mut signal_receiver) = mpsc::channel(1);
+
+        let id = self.id.clone();
+        let session = self.session.clone();
+        let key = self.key.clone();
+        let interrupt_by_message = self.spec.interrupt_by_message();
+        let mut control = control;
+
+        tokio::spawn(async move {
+            while let Some(signal) = signal_receiver.recv().await {
+                let request = match signal {
+                    KernelSignal::Interrupt if interrupt_by_message => {
+                        Some(("interrupt_request", json!({})))
+                    }
+                    KernelSignal::Terminate => {
+                        Some(("shutdown_request", json!({"restart": false})))
+                    }
+                    _ => None,
+                };
+
+                let result = match request {
+                    Some((msg_type, content)) => {
+                        match JupyterMessage::new(&session, msg_type, content).to_zmq(&key) {
+                            Ok(message) => {
+                                control.send(message).await.map_err(|error| eyre!(error))
+                            }
+                            Err(error) => Err(error),
+                        }
+                    }
+                    None => send_signal(pid, signal),
+                };
+                if let Err(error) = result {
+                    tracing::error!("While signalling Jupyter kernel `{id}`: {error}");
+                }
+            }
+        });
+
+        signal_sender
+    }
+}
+
+/// Send an operating system signal to the kernel process
+#[cfg(unix)]
+fn send_signal(pid: Option<u32>, signal: KernelSignal) -> Result<()> {
+    use nix::{
+        sys::signal::{kill, Signal},
+        unistd::Pid,
+    };
+
+    let Some(pid) = pid else {
+        bail!("Kernel process id is unknown")
+    };
+    let signal = match signal {
+        KernelSignal::Interrupt => Signal::SIGINT,
+        KernelSignal::Terminate => Signal::SIGTERM,
+        KernelSignal::Kill => Signal::SIGKILL,
+    };
+    kill(Pid::from_raw(pid as i32), signal)?;
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn send_signal(_pid: Option<u32>, _signal: KernelSignal) -> Result<()> {
+    bail!("Signals are not supported on this platform")
+}
+
+/// Apply memory and CPU limits to the command used to start the kernel process
+///
+/// Limits are applied as resource limits on the process, as for microkernels.
+#[cfg(unix)]
+fn apply_limits(command: &mut Command, limits: &KernelLimits) {
+    use nix::sys::resource::{setrlimit, Resource};
+
+    let KernelLimits { memory, cpu, .. } = *limits;
+    if memory.is_none() && cpu.is_none() {
+        return;
+    }
+
+    // Use the data segment limit, rather than the address space limit, for memory
+    // because some runtimes reserve, but do not use, large amounts of virtual memory
+    #[cfg(target_os = "linux")]
+    let memory_resource = Resource::RLIMIT_DATA;
+    #[cfg(not(target_os = "linux"))]
+    let memory_resource = Resource::RLIMIT_AS;
+
+    // SAFETY: the closure is run in the child process after forking, and before exec,
+    // so only makes system calls and does not allocate.
+    unsafe {
+        command.pre_exec(move || {
+            if let Some(megabytes) = memory {
+                let bytes = megabytes.saturating_mul(1024 * 1024);
+                setrlimit(memory_resource, bytes, bytes)?;
+            }
+
+            if let Some(seconds) = cpu {
+                setrlimit(Resource::RLIMIT_CPU, seconds, seconds)?;
+            }
+
+            Ok(())
+        });
+    }
+}
+
+/// Get a free port on the local machine
+///
+/// There is a small chance that the port will be taken by another
+/// process before the kernel binds to it, as for the Jupyter client.
+fn free_port() -> Result<u16> {
+    Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
+}
+
+#[async_trait]
+impl KernelInstance for JupyterKernelInstance {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    async fn status(&self) -> Result<KernelStatus> {
+        Ok(*self.status_sender.borrow())
+    }
+
+    fn status_watcher(&self) -> Result<watch::Receiver<KernelStatus>> {
+        Ok(self.status_sender.subscribe())
+    }
+
+    fn signal_sender(&self) -> Result<mpsc::Sender<KernelSignal>> {
+        match &self.signal_sender {
+            Some(sender) => Ok(sender.clone()),
+            None => bail!("Jupyter kernel has not started yet!"),
+        }
+    }
+
+    fn set_environment(&mut self, variables: Vec<(String, String)>) {
+        self.environment = variables;
+    }
+
+    async fn start(&mut self, directory: &Path) -> Result<()> {
+        self.set_status(KernelStatus::Starting);
+
+        let ports = [
+            free_port()?,
+            free_port()?,
+            free_port()?,
+            free_port()?,
+            free_port()?,
+        ];
+        let [shell_port, iopub_port, stdin_port, control_port, hb_port] = ports;
+        let connection = json!({
+            "transport": "tcp",
+            "ip": "127.0.0.1",
+            "shell_port": shell_port,
+            "iopub_port": iopub_port,
+            "stdin_port": stdin_port,
+            "control_port": control_port,
+            "hb_port": hb_port,
+            "signature_scheme": "hmac-sha256",
+            "key": self.key,
+            "kernel_name": self.spec.language,
+        });
+        let connection_dir = tempdir()?;
+        let connection_file = connection_dir.path().join("connection.json");
+        write(&connection_file, serde_json::to_string_pretty(&connection)?)?;
+
+        let mut args = self
+            .spec
+            .argv
+            .iter()
+            .map(|arg| arg.replace("{connection_file}", &connection_file.to_string_lossy()));
+        let Some(program) = args.next() else {
+            bail!("Kernelspec for `{}` has no arguments", self.id)
+        };
+
+        let mut child = Command::new(program)
+            .args(args)
+            .envs(&self.spec.env)
+            .envs(self.environment.iter().cloned())
+            .current_dir(directory)
+            .kill_on_drop(true)
+            .spawn()?;
+        let pid = child.id();
+
+        // Wait for the kernel process to exit (updating the status) or
+        // for the instance to be dropped (killing the process)
+        let (drop_guard, dropped) = oneshot::channel::<()>();
+        let status_sender = self.status_sender.clone();
+        let id = self.id.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                result = child.wait() => {
+                    let status = match result {
+                        Ok(status) if status.success() => KernelStatus::Stopped,
+                        Ok(status) => {
+                            tracing::debug!("Jupyter kernel `{id}` exited with {status}");
+                            KernelStatus::Failed
+                        }
+                        Err(error) => {
+                            tracing::error!("While waiting for Jupyter kernel `{id}`: {error}");
+                            KernelStatus::Failed
+                        }
+                    };
+                    status_sender.send_replace(status);
+                }
+                _ = dropped => {
+                    child.kill().await.ok();
+                }
+            }
+        });
+
+        let endpoint = |port: u16| format!("tcp://127.0.0.1:{port}");
+
+        let mut shell = DealerSocket::new();
+        shell.connect(&endpoint(shell_port)).await?;
+
+        let mut iopub = SubSocket::new();
+        iopub.connect(&endpoint(iopub_port)).await?;
+        iopub.subscribe("").await?;
+
+        let mut control = DealerSocket::new();
+        control.connect(&endpoint(control_port)).await?;
+
+        self.shell = Some(shell);
+        self.iopub = Some(iopub);
+        self.signal_sender = Some(self.setup_signals_channel(control, pid));
+        self.connection_dir = Some(connection_dir);
+        self._drop_guard = Some(drop_guard);
+
+        // Wait for the kernel to be ready: first until the IOPub subscription is established
+        // and then for a reply to a `kernel_info_request` (which relies on IOPub for the `idle` status)
+        match time::timeout(STARTUP_TIMEOUT, async {
+            self.wait_for_iopub().await?;
+            self.request("kernel_info_request", json!({})).await
+        })
+        .await
+        {
+            Ok(Ok(..)) => {}
+            Ok(Err(error)) => {
+                self.set_status(KernelStatus::Failed);
+                bail!("While starting Jupyter kernel `{}`: {error}", self.id)
+            }
+            Err(..) => {
+                self.set_status(KernelStatus::Failed);
+                bail!(
+                    "Jupyter kernel `{}` did not start within {STARTUP_TIMEOUT:?}",
+                    self.id
+                )
+            }
+        }
+
+        self.set_status(KernelStatus::Ready);
+
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        self.set_status(KernelStatus::Stopping);
+
+        if let Some(sender) = &self.signal_sender {
+            sender.send(KernelSignal::Terminate).await.ok();
+        }
+
+        // Dropping the guard kills the process if it has not already exited
+        self._drop_guard.take();
+        self.shell.take();
+        self.iopub.take();
+        self.connection_dir.take();
+
+        self.set_status(KernelStatus::Stopped);
+
+        Ok(())
+    }
+
+    async fn execute(&mut self, code: &str) -> Result<(Vec<Node>, Vec<ExecutionMessage>)> {
+        self.set_status(KernelStatus::Busy);
+
+        let (reply, broadcasts) = self
+            .request(
+                "execute_request",
+                json!({
+                    "code": code,
+                    "silent": false,
+                    "store_history": true,
+                    "user_expressions": {},
+                    "allow_stdin": false,
+                    "stop_on_error": true
+                }),
+            )
+            .await?;
+
+        let (outputs, mut messages) = Self::outputs_and_messages(broadcasts);
+
+        // Some kernels only put errors in the reply, rather than also broadcasting them
+        if reply.content["status"] == "error"
+            && !messages
+                .iter()
+                .any(|message| message.level == MessageLevel::Exception)
+        {
+            messages.push(error_to_message(&reply.content));
+        }
+
+        self.set_status(KernelStatus::Ready);
+
+        Ok((outputs, messages))
+    }
+
+    async fn evaluate(&mut self, code: &str) -> Result<(Node, Vec<ExecutionMessage>)> {
+        self.set_status(KernelStatus::Busy);
+
+        // Use a user expression so that evaluation does not change the execution
+        // count or history, or produce outputs
+        let (reply, broadcasts) = self
+            .request(
+                "execute_request",
+                json!({
+                    "code": "",
+                    "silent": true,
+                    "store_history": false,
+                    "user_expressions": {"value": code},
+                    "allow_stdin": false,
+                    "stop_on_error": false
+                }),
+            )
+            .await?;
+
+        let (_, mut messages) = Self::outputs_and_messages(broadcasts);
+
+        let value = &reply.content["user_expressions"]["value"];
+        let output = if value["status"] == "ok" {
+            mime_bundle_to_node(&value["data"])
+        } else {
+            if !value.is_null() {
+                messages.push(error_to_message(value));
+            }
+            Node::Null(Null)
+        };
+
+        self.set_status(KernelStatus::Ready);
+
+        Ok((output, messages))
+    }
+
+    async fn info(&mut self) -> Result<SoftwareApplication> {
+        let (reply, ..) = self.request("kernel_info_request", json!({})).await?;
+
+        let language_info = &reply.content["language_info"];
+        let name = language_info["name"]
+            .as_str()
+            .unwrap_or(&self.spec.language)
+            .to_string();
+
+        let mut info = SoftwareApplication::new(name);
+        info.options.software_version = language_info["version"].as_str().map(String::from);
+        info.options.operating_system = Some(env::consts::OS.to_string());
+
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_dev::pretty_assertions::assert_eq;
+    use kernel::common::{eyre::Ok, tokio};
+
+    use super::*;
+
+    /// Get an instance of the Jupyter kernel for Python if it is installed
+    fn python_instance() -> Result<Option<Box<dyn KernelInstance>>> {
+        let Some(kernel) = list().into_iter().find(|kernel| {
+            kernel.name() == [PREFIX, "python3"].concat()
+                && matches!(kernel.availability(), KernelAvailability::Available)
+        }) else {
+            return Ok(None);
+        };
+
+        Ok(Some(kernel.create_instance()?))
+    }
+
+    /// Requires `ipykernel` to be installed
+    #[test_log::test(tokio::test)]
+    async fn execution() -> Result<()> {
+        let Some(mut instance) = python_instance()? else {
+            return Ok(());
+        };
+        instance.start_here().await?;
+        assert_eq!(instance.status().await?, KernelStatus::Ready);
+
+        let (outputs, messages) = instance.execute("a = 1\nprint(a)\na + 2").await?;
+        assert_eq!(messages, []);
+        assert_eq!(outputs, [Node::String("1\n".to_string()), Node::Integer(3)]);
+
+        let (outputs, messages) = instance.execute("foo").await?;
+        assert_eq!(outputs, []);
+        assert_eq!(messages[0].error_type.as_deref(), Some("NameError"));
+
+        let (output, messages) = instance.evaluate("a * 10").await?;
+        assert_eq!(messages, []);
+        assert_eq!(output, Node::Integer(10));
+
+        let info = instance.info().await?;
+        assert_eq!(info.name, "python");
+        assert!(info.options.software_version.is_some());
+
+        instance.stop().await?;
+        assert_eq!(instance.status().await?, KernelStatus::Stopped);
+
+        Ok(())
+    }
+    /// Jupyter kernels are not started in restricted mode
+    #[tokio::test]
+    async fn restricted() {
+        let mut instance = JupyterKernelInstance::new(
+            "jupyter-test",
+            KernelSpec {
+                argv: vec!["false".to_string()],
+                language: "test".to_string(),
+                interrupt_mode: None,
+                env: HashMap::new(),
+            },
+        );
+        instance.set_limits(KernelLimits {
+            restricted: true,
+            ..Default::default()
+        });
+
+        let error = instance.start_here().await.unwrap_err();
+        assert!(error.to_string().contains("restricted mode"));
+    }
+
+    /// Resource limits are applied to the kernel process
+    ///
+    /// Requires `ipykernel` to be installed
+    #[cfg(unix)]
+    #[test_log::test(tokio::test)]
+    async fn limits() -> Result<()> {
+        let Some(mut instance) = python_instance()? else {
+            return Ok(());
+        };
+        instance.set_limits(KernelLimits {
+            cpu: Some(600),
+            ..Default::default()
+        });
+        instance.start_here().await?;
+
+        let (output, messages) = instance
+            .evaluate("__import__('resource').getrlimit(__import__('resource').RLIMIT_CPU)[0]")
+            .await?;
+        assert_eq!(messages, []);
+        assert_eq!(output, Node::Integer(600));
+
+        instance.stop().await?;
+
+        Ok(())
+    }
+}
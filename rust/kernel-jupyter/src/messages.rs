@@ -0,0 +1,177 @@
+//! Messages of the Jupyter messaging protocol
+//!
+//! See https://jupyter-client.readthedocs.io/en/latest/messaging.html for details
+//! of the protocol, including the wire format implemented here.
+
+use bytes::Bytes;
+use hmac::{Hmac, Mac};
+use kernel::common::{
+    chrono::Utc,
+    eyre::{bail, eyre, Result},
+    serde::{Deserialize, Serialize},
+    serde_json::{self, Value},
+    uuid::Uuid,
+};
+use sha2::Sha256;
+use zeromq::ZmqMessage;
+
+/// The version of the messaging protocol implemented
+const PROTOCOL_VERSION: &str = "5.3";
+
+/// The delimiter between routing identities and the rest of a message
+const DELIMITER: &[u8] = b"<IDS|MSG>";
+
+/// The header of a message
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default, crate = "kernel::common::serde")]
+pub(crate) struct Header {
+    pub msg_id: String,
+    pub session: String,
+    pub username: String,
+    pub date: String,
+    pub msg_type: String,
+    pub version: String,
+}
+
+/// A message sent to, or received from, a Jupyter kernel
+#[derive(Debug, Clone)]
+pub(crate) struct JupyterMessage {
+    pub header: Header,
+    pub parent_header: Value,
+    pub metadata: Value,
+    pub content: Value,
+}
+
+impl JupyterMessage {
+    /// Create a new message for a session
+    pub fn new(session: &str, msg_type: &str, content: Value) -> Self {
+        Self {
+            header: Header {
+                msg_id: Uuid::new_v4().to_string(),
+                session: session.to_string(),
+                username: "stencila".to_string(),
+                date: Utc::now().to_rfc3339(),
+                msg_type: msg_type.to_string(),
+                version: PROTOCOL_VERSION.to_string(),
+            },
+            parent_header: Value::Object(Default::default()),
+            metadata: Value::Object(Default::default()),
+            content,
+        }
+    }
+
+    /// Get the type of the message
+    pub fn msg_type(&self) -> &str {
+        &self.header.msg_type
+    }
+
+    /// Whether the message is in response to another message
+    pub fn is_reply_to(&self, msg_id: &str) -> bool {
+        self.parent_header
+            .get("msg_id")
+            .and_then(|id| id.as_str())
+            .is_some_and(|id| id == msg_id)
+    }
+
+    /// Encode the message into ZeroMQ frames, signed with a key
+    pub fn to_zmq(&self, key: &str) -> Result<ZmqMessage> {
+        let parts = [
+            serde_json::to_vec(&self.header)?,
+            serde_json::to_vec(&self.parent_header)?,
+            serde_json::to_vec(&self.metadata)?,
+            serde_json::to_vec(&self.content)?,
+        ];
+
+        let signature = sign(key, &parts)?;
+
+        let mut frames = vec![Bytes::from_static(DELIMITER), Bytes::from(signature)];
+        frames.extend(parts.into_iter().map(Bytes::from));
+
+        ZmqMessage::try_from(frames).map_err(|error| eyre!("{error:?}"))
+    }
+
+    /// Decode a message from ZeroMQ frames, verifying its signature with a key
+    pub fn from_zmq(message: ZmqMessage, key: &str) -> Result<Self> {
+        let frames = message.into_vec();
+
+        let Some(delimiter) = frames.iter().position(|frame| frame.as_ref() == DELIMITER) else {
+            bail!("Message has no delimiter")
+        };
+        let Some([signature, header, parent_header, metadata, content]) = frames
+            .get(delimiter + 1..delimiter + 6)
+            .map(|frames| frames.to_vec())
+            .and_then(|frames| <[Bytes; 5]>::try_from(frames).ok())
+        else {
+            bail!("Message has too few frames")
+        };
+
+        if !key.is_empty() {
+            let expected = sign(
+                key,
+                &[
+                    header.to_vec(),
+                    parent_header.to_vec(),
+                    metadata.to_vec(),
+                    content.to_vec(),
+                ],
+            )?;
+            if expected.as_bytes() != signature.as_ref() {
+                bail!("Message has invalid signature")
+            }
+        }
+
+        Ok(Self {
+            header: serde_json::from_slice(&header)?,
+            parent_header: serde_json::from_slice(&parent_header)?,
+            metadata: serde_json::from_slice(&metadata)?,
+            content: serde_json::from_slice(&content)?,
+        })
+    }
+}
+
+/// Sign the parts of a message using HMAC-SHA256
+///
+/// Returns the signature as a hex string, or an empty string if the key is empty
+/// (which signals that authentication is disabled).
+fn sign(key: &str, parts: &[Vec<u8>]) -> Result<String> {
+    if key.is_empty() {
+        return Ok(String::new());
+    }
+
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(key.as_bytes()).map_err(|error| eyre!("{error}"))?;
+    for part in parts {
+        mac.update(part);
+    }
+
+    Ok(mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use kernel::common::serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn roundtrip() -> Result<()> {
+        let key = "a-secret-key";
+
+        let message = JupyterMessage::new("session", "execute_request", json!({"code": "1 + 2"}));
+        let decoded = JupyterMessage::from_zmq(message.to_zmq(key)?, key)?;
+
+        assert_eq!(decoded.header.msg_id, message.header.msg_id);
+        assert_eq!(decoded.msg_type(), "execute_request");
+        assert_eq!(decoded.content, json!({"code": "1 + 2"}));
+
+        // Wrong key is detected
+        assert!(JupyterMessage::from_zmq(message.to_zmq(key)?, "another-key").is_err());
+
+        Ok(())
+    }
+}
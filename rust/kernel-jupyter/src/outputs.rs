@@ -0,0 +1,109 @@
+//! Conversion of the outputs of Jupyter kernels to Stencila nodes
+
+use jupyter_outputs::strip_ansi;
+use kernel::{
+    common::serde_json::Value,
+    schema::{ExecutionMessage, MessageLevel, Node},
+};
+
+/// Convert a MIME bundle (e.g. the `data` of an `execute_result` or `display_data`
+/// message) to a Stencila node
+///
+/// Uses the same conversion as for the outputs of code cells in Jupyter Notebooks.
+pub(crate) fn mime_bundle_to_node(bundle: &Value) -> Node {
+    match bundle.as_object() {
+        Some(bundle) => jupyter_outputs::mime_bundle_to_node(bundle),
+        None => Node::String(String::new()),
+    }
+}
+
+/// Convert the content of an `error` message (or of an errored `user_expression`)
+/// to an execution message
+pub(crate) fn error_to_message(content: &Value) -> ExecutionMessage {
+    let get = |name: &str| {
+        content
+            .get(name)
+            .and_then(|value| value.as_str())
+            .map(String::from)
+    };
+
+    let traceback = content
+        .get("traceback")
+        .and_then(|traceback| traceback.as_array())
+        .map(|lines| {
+            lines
+                .iter()
+                .filter_map(|line| line.as_str())
+                .map(strip_ansi)
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .filter(|traceback| !traceback.is_empty());
+
+    ExecutionMessage {
+        error_type: get("ename"),
+        stack_trace: traceback,
+        ..ExecutionMessage::new(MessageLevel::Exception, get("evalue").unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kernel::common::serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn mime_bundles() {
+        assert_eq!(
+            mime_bundle_to_node(&json!({"text/plain": "3"})),
+            Node::Integer(3)
+        );
+        assert_eq!(
+            mime_bundle_to_node(&json!({"text/plain": "'a'"})),
+            Node::String("'a'".to_string())
+        );
+
+        let Node::ImageObject(image) = mime_bundle_to_node(&json!({
+            "text/plain": "<Figure>",
+            "image/png": "iVBORw0KGgo=\n"
+        })) else {
+            panic!("expected image")
+        };
+        assert_eq!(image.content_url, "data:image/png;base64,iVBORw0KGgo=");
+
+        let Node::RawBlock(raw) = mime_bundle_to_node(&json!({
+            "text/plain": "a",
+            "text/html": ["<p>", "a</p>"]
+        })) else {
+            panic!("expected raw block")
+        };
+        assert_eq!(raw.content.to_string(), "<p>a</p>");
+
+        let Node::Datatable(table) = mime_bundle_to_node(&json!({
+            "application/vnd.dataresource+json": {
+                "schema": {"fields": [{"name": "a"}, {"name": "b"}]},
+                "data": [{"a": 1, "b": "x"}, {"a": 2, "b": "y"}]
+            }
+        })) else {
+            panic!("expected datatable")
+        };
+        assert_eq!(table.columns.len(), 2);
+        assert_eq!(table.columns[0].values.len(), 2);
+    }
+
+    #[test]
+    fn errors() {
+        let message = error_to_message(&json!({
+            "ename": "ValueError",
+            "evalue": "bad value",
+            "traceback": ["\u{1b}[0;31mValueError\u{1b}[0m: bad value"]
+        }));
+        assert_eq!(message.error_type.as_deref(), Some("ValueError"));
+        assert_eq!(message.message, "bad value");
+        assert_eq!(
+            message.stack_trace.as_deref(),
+            Some("ValueError: bad value")
+        );
+    }
+}
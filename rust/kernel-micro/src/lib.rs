@@ -11,7 +11,8 @@ use which::which;
 // the `Microkernel` trait
 pub use kernel::{
     common, format, schema, tests, Kernel, KernelAvailability, KernelForks, KernelInstance,
-    KernelInterrupt, KernelKill, KernelProvider, KernelSignal, KernelStatus, KernelTerminate,
+    KernelInterrupt, KernelKill, KernelLimits, KernelProvider, KernelSignal, KernelStatus,
+    KernelTerminate,
 };
 
 use kernel::{
@@ -172,6 +173,8 @@ pub trait Microkernel: Sync + Send + Kernel {
             executable_args,
             default_message_level,
             executable_path: None,
+            environment: Vec::new(),
+            limits: KernelLimits::default(),
             working_dir: None,
             command: None,
             child: None,
@@ -206,6 +209,12 @@ pub struct MicrokernelInstance {
     /// The resolved path to the executable
     executable_path: Option<PathBuf>,
 
+    /// Environment variables to set when starting the microkernel process
+    environment: Vec<(String, String)>,
+
+    /// Limits on the resources that the microkernel process may use
+    limits: KernelLimits,
+
     /// The command used to start the microkernel instance (for main processes only, not forks)
     command: Option<Command>,
 
@@ -352,6 +361,14 @@ impl KernelInstance for MicrokernelInstance {
         }
     }
 
+    fn set_environment(&mut self, variables: Vec<(String, String)>) {
+        self.environment = variables;
+    }
+
+    fn set_limits(&mut self, limits: KernelLimits) {
+        self.limits = limits;
+    }
+
     async fn start(&mut self, directory: &Path) -> Result<()> {
         self.set_status(KernelStatus::Starting)?;
 
@@ -417,6 +434,18 @@ impl KernelInstance for MicrokernelInstance {
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
+        // Set any environment variables (e.g. secrets). Note that these are
+        // deliberately not logged, or included in error messages below.
+        command.envs(self.environment.iter().cloned());
+
+        // Apply any limits on resources, and restricted mode, to the process
+        #[cfg(unix)]
+        self.apply_limits(&mut command, directory)?;
+        #[cfg(not(unix))]
+        if self.limits != KernelLimits::default() {
+            tracing::warn!("Kernel limits are not supported on this platform");
+        }
+
         // If this is the R microkernel and the `R_HOME` env var is not set then
         // set it to the grandparent of the executable path.
         // This is intended to fix this issue where, when using the R microkernel
@@ -738,6 +767,8 @@ impl KernelInstance for MicrokernelInstance {
                 executable_args: Default::default(),
                 working_dir: None,
                 executable_path: None,
+                environment: Vec::new(),
+                limits: self.limits.clone(),
                 command: None,
                 default_message_level,
                 child: None,
@@ -944,6 +975,73 @@ impl MicrokernelInstance {
         }
     }
 
+    /// Apply resource limits, and restricted mode, to the command used to start the microkernel
+    ///
+    /// Memory and CPU limits are applied as resource limits on the process. Restricted mode
+    /// is only supported on Linux (x86_64 and aarch64, version 5.12 or later). In restricted mode
+    /// the process has no network access, can only write to a scratch directory, and can not
+    /// make system calls which could be used to undo these restrictions (see [`restrict`]).
+    #[cfg(unix)]
+    #[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+    fn apply_limits(&self, command: &mut Command, directory: &Path) -> Result<()> {
+        use nix::sys::resource::{setrlimit, Resource};
+
+        let KernelLimits {
+            memory,
+            cpu,
+            restricted,
+            ..
+        } = self.limits;
+        if memory.is_none() && cpu.is_none() && !restricted {
+            return Ok(());
+        }
+
+        // Prepare everything needed by restricted mode here, rather than in
+        // the `pre_exec` closure, to avoid allocation after forking
+        #[cfg(target_os = "linux")]
+        let restrictions = if restricted {
+            Some(restrictions_for(directory)?)
+        } else {
+            None
+        };
+        #[cfg(not(target_os = "linux"))]
+        if restricted {
+            bail!("Restricted mode for kernels is only supported on Linux")
+        }
+
+        // Use the data segment limit, rather than the address space limit, for memory
+        // because some runtimes (e.g. V8 for Node.js) reserve, but do not use, large
+        // amounts of virtual memory
+        #[cfg(target_os = "linux")]
+        let memory_resource = Resource::RLIMIT_DATA;
+        #[cfg(not(target_os = "linux"))]
+        let memory_resource = Resource::RLIMIT_AS;
+
+        // SAFETY: the closure is run in the child process after forking, and before exec,
+        // so only makes system calls and does not allocate.
+        unsafe {
+            command.pre_exec(move || {
+                if let Some(megabytes) = memory {
+                    let bytes = megabytes.saturating_mul(1024 * 1024);
+                    setrlimit(memory_resource, bytes, bytes)?;
+                }
+
+                if let Some(seconds) = cpu {
+                    setrlimit(Resource::RLIMIT_CPU, seconds, seconds)?;
+                }
+
+                #[cfg(target_os = "linux")]
+                if let Some(restrictions) = &restrictions {
+                    restrict(restrictions)?;
+                }
+
+                Ok(())
+            });
+        }
+
+        Ok(())
+    }
+
     /// Create an `Err` if messages from the kernel include an error
     fn check_for_errors(&self, messages: Vec<ExecutionMessage>, action: &str) -> Result<()> {
         if messages.iter().any(|m| m.level == MessageLevel::Error) {
@@ -958,6 +1056,275 @@ impl MicrokernelInstance {
     }
 }
 
+/// The restrictions to apply to a microkernel process in restricted mode
+#[cfg(target_os = "linux")]
+struct Restrictions {
+    /// The mapping of the user id of the process within its user namespace
+    ///
+    /// Created before forking because it requires allocation. Maps the current
+    /// user id to itself so that the process can create files in the scratch directory.
+    uid_map: String,
+
+    /// The mapping of the group id of the process within its user namespace
+    gid_map: String,
+
+    /// The Berkeley Packet Filter program used as the seccomp filter for the process
+    ///
+    /// Created before forking because it requires allocation.
+    filter: Vec<nix::libc::sock_filter>,
+}
+
+/// The directory which a microkernel process in restricted mode can write to
+///
+/// A new, empty, in-memory file system is mounted here for each restricted process.
+#[cfg(target_os = "linux")]
+const RESTRICTED_SCRATCH_DIR: &str = "/tmp";
+
+/// System calls denied to a microkernel process in restricted mode
+///
+/// These are calls that could be used to undo the restrictions (e.g. by
+/// changing mounts or namespaces), to inspect or alter other processes, or to
+/// interact with the kernel in ways that a kernel for executing code does not need.
+#[cfg(target_os = "linux")]
+const RESTRICTED_SYSCALLS: &[nix::libc::c_long] = {
+    use nix::libc::*;
+    &[
+        SYS_acct,
+        SYS_add_key,
+        SYS_bpf,
+        SYS_chroot,
+        SYS_delete_module,
+        SYS_finit_module,
+        SYS_fsconfig,
+        SYS_fsmount,
+        SYS_fsopen,
+        SYS_fspick,
+        SYS_init_module,
+        SYS_kexec_file_load,
+        SYS_kexec_load,
+        SYS_keyctl,
+        SYS_mount,
+        SYS_mount_setattr,
+        SYS_move_mount,
+        SYS_name_to_handle_at,
+        SYS_open_by_handle_at,
+        SYS_open_tree,
+        SYS_perf_event_open,
+        SYS_pivot_root,
+        SYS_process_vm_readv,
+        SYS_process_vm_writev,
+        SYS_ptrace,
+        SYS_reboot,
+        SYS_request_key,
+        SYS_setns,
+        SYS_swapoff,
+        SYS_swapon,
+        SYS_umount2,
+        SYS_unshare,
+        SYS_userfaultfd,
+    ]
+};
+
+/// Get the restrictions to apply to a microkernel process started in a directory
+#[cfg(target_os = "linux")]
+fn restrictions_for(directory: &Path) -> Result<Restrictions> {
+    use nix::libc::{
+        sock_filter, BPF_ABS, BPF_JEQ, BPF_JGE, BPF_JMP, BPF_K, BPF_LD, BPF_RET, BPF_W, EPERM,
+        SECCOMP_RET_ALLOW, SECCOMP_RET_ERRNO, SECCOMP_RET_KILL_PROCESS,
+    };
+
+    // The audit architecture that system calls must be made with. Checking this
+    // prevents the filter being bypassed using the system call numbers of another
+    // architecture (e.g. 32-bit calls on a 64-bit system).
+    #[cfg(target_arch = "x86_64")]
+    const AUDIT_ARCH: Option<u32> = Some(0xC000_003E);
+    #[cfg(target_arch = "aarch64")]
+    const AUDIT_ARCH: Option<u32> = Some(0xC000_00B7);
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    const AUDIT_ARCH: Option<u32> = None;
+    let Some(audit_arch) = AUDIT_ARCH else {
+        bail!("Restricted mode for kernels is not supported on this architecture")
+    };
+
+    // The scratch directory is mounted over so the working directory can not be within it
+    if directory
+        .canonicalize()?
+        .starts_with(RESTRICTED_SCRATCH_DIR)
+    {
+        bail!("Restricted mode for kernels can not be used within `{RESTRICTED_SCRATCH_DIR}`")
+    }
+
+    // Offsets of fields in the `seccomp_data` struct passed to the filter
+    const NR_OFFSET: u32 = 0;
+    const ARCH_OFFSET: u32 = 4;
+
+    // Narrowing casts of BPF codes are necessary because `libc` defines them as `u32`
+    let stmt = |code: u32, k: u32| sock_filter {
+        code: code as u16,
+        jt: 0,
+        jf: 0,
+        k,
+    };
+    let jump = |code: u32, k: u32, jt: u8, jf: u8| sock_filter {
+        code: code as u16,
+        jt,
+        jf,
+        k,
+    };
+
+    let mut filter = vec![
+        // Kill the process if the architecture is not the expected one
+        stmt(BPF_LD | BPF_W | BPF_ABS, ARCH_OFFSET),
+        jump(BPF_JMP | BPF_JEQ | BPF_K, audit_arch, 1, 0),
+        stmt(BPF_RET | BPF_K, SECCOMP_RET_KILL_PROCESS),
+        // Load the system call number
+        stmt(BPF_LD | BPF_W | BPF_ABS, NR_OFFSET),
+    ];
+
+    // Deny the x32 ABI, which uses the same audit architecture as x86_64 but
+    // with system call numbers offset by this bit
+    #[cfg(target_arch = "x86_64")]
+    filter.extend([
+        jump(BPF_JMP | BPF_JGE | BPF_K, 0x4000_0000, 0, 1),
+        stmt(BPF_RET | BPF_K, SECCOMP_RET_ERRNO | EPERM as u32),
+    ]);
+
+    for syscall in RESTRICTED_SYSCALLS {
+        filter.extend([
+            jump(BPF_JMP | BPF_JEQ | BPF_K, *syscall as u32, 0, 1),
+            stmt(BPF_RET | BPF_K, SECCOMP_RET_ERRNO | EPERM as u32),
+        ]);
+    }
+
+    filter.push(stmt(BPF_RET | BPF_K, SECCOMP_RET_ALLOW));
+
+    let uid = nix::unistd::getuid();
+    let gid = nix::unistd::getgid();
+
+    Ok(Restrictions {
+        uid_map: format!("{uid} {uid} 1"),
+        gid_map: format!("{gid} {gid} 1"),
+        filter,
+    })
+}
+
+/// Restrict the current process
+///
+/// Called in a microkernel process after forking and before exec. In order:
+///
+/// - creates new user, network and mount namespaces so that the process has no
+///   network access (other than an unconfigured loopback interface) and changes
+///   to mounts are not visible outside of the process
+///
+/// - makes all mounts read-only and mounts an empty, writable, in-memory file
+///   system at the scratch directory (`/tmp`)
+///
+/// - prevents the process, and its children, from gaining privileges and installs
+///   a seccomp filter which denies system calls that could be used to undo these
+///   restrictions
+#[cfg(target_os = "linux")]
+fn restrict(restrictions: &Restrictions) -> nix::Result<()> {
+    use std::{
+        ffi::CStr,
+        os::fd::{FromRawFd, OwnedFd},
+    };
+
+    use nix::{
+        errno::Errno,
+        fcntl::{open, OFlag},
+        libc::{
+            c_uint, c_ulong, prctl, sock_fprog, syscall, SYS_mount_setattr, AT_RECURSIVE,
+            PR_SET_NO_NEW_PRIVS, PR_SET_SECCOMP, SECCOMP_MODE_FILTER,
+        },
+        mount::{mount, MsFlags},
+        sched::{unshare, CloneFlags},
+        sys::stat::Mode,
+        unistd::write,
+    };
+
+    unshare(CloneFlags::CLONE_NEWUSER | CloneFlags::CLONE_NEWNET | CloneFlags::CLONE_NEWNS)?;
+
+    // Map the user and group ids of the process. Setting groups must be denied
+    // before the group id can be mapped by an unprivileged process.
+    for (path, content) in [
+        (c"/proc/self/uid_map", restrictions.uid_map.as_bytes()),
+        (c"/proc/self/setgroups", b"deny".as_slice()),
+        (c"/proc/self/gid_map", restrictions.gid_map.as_bytes()),
+    ] {
+        let fd = open(path, OFlag::O_WRONLY | OFlag::O_CLOEXEC, Mode::empty())?;
+        // SAFETY: the file descriptor was just opened and is not used elsewhere
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        write(&fd, content)?;
+    }
+
+    // Make all mounts private so that changes are not propagated outside of the namespace
+    mount(
+        None::<&CStr>,
+        c"/",
+        None::<&CStr>,
+        MsFlags::MS_REC | MsFlags::MS_PRIVATE,
+        None::<&CStr>,
+    )?;
+
+    // Make all mounts read-only. Unlike re-mounting, `mount_setattr` applies to
+    // all mounts in the tree and does not require the existing, locked, mount
+    // flags to be known. Requires Linux 5.12 or later.
+    #[repr(C)]
+    struct MountAttr {
+        attr_set: u64,
+        attr_clr: u64,
+        propagation: u64,
+        userns_fd: u64,
+    }
+    const MOUNT_ATTR_RDONLY: u64 = 0x0000_0001;
+    let attr = MountAttr {
+        attr_set: MOUNT_ATTR_RDONLY,
+        attr_clr: 0,
+        propagation: 0,
+        userns_fd: 0,
+    };
+    // SAFETY: the path is a valid C string and `attr` is a valid `mount_attr` struct
+    // whose size is passed as the last argument
+    let result = unsafe {
+        syscall(
+            SYS_mount_setattr,
+            -1,
+            c"/".as_ptr(),
+            AT_RECURSIVE as c_uint,
+            &attr as *const MountAttr,
+            std::mem::size_of::<MountAttr>(),
+        )
+    };
+    Errno::result(result)?;
+
+    // Mount an empty, writable, file system at the scratch directory
+    mount(
+        Some(c"tmpfs"),
+        RESTRICTED_SCRATCH_DIR,
+        Some(c"tmpfs"),
+        MsFlags::MS_NOSUID | MsFlags::MS_NODEV,
+        None::<&CStr>,
+    )?;
+
+    // SAFETY: these calls only pass integers, and a pointer to a `sock_fprog`
+    // referencing the filter, which outlives the call
+    unsafe {
+        Errno::result(prctl(PR_SET_NO_NEW_PRIVS, 1 as c_ulong, 0, 0, 0))?;
+
+        let program = sock_fprog {
+            len: restrictions.filter.len() as u16,
+            filter: restrictions.filter.as_ptr() as *mut _,
+        };
+        Errno::result(prctl(
+            PR_SET_SECCOMP,
+            SECCOMP_MODE_FILTER as c_ulong,
+            &program as *const sock_fprog,
+        ))?;
+    }
+
+    Ok(())
+}
+
 /// Receive outputs on stdout and messages on stderr during kernel startup
 /// (until READY flag). Used to "clear" streams and be ready to accept tasks but
 /// to also report any messages received.
@@ -241,6 +241,20 @@ pub trait KernelInstance: Sync + Send {
         Ok(KernelStatus::Ready)
     }
 
+    /// Set environment variables for the kernel instance
+    ///
+    /// Must be called before `start` to have any effect. Intended for kernels that
+    /// run in a separate process (e.g. microkernels) and used to provide secrets to
+    /// them without those being written into the document. Ignored by default.
+    fn set_environment(&mut self, variables: Vec<(String, String)>) {}
+
+    /// Set limits on the resources that the kernel instance may use
+    ///
+    /// Must be called before `start` to have any effect. Intended for kernels that
+    /// run in a separate process (e.g. microkernels) and which can have limits applied
+    /// to that process. Ignored by default.
+    fn set_limits(&mut self, limits: KernelLimits) {}
+
     /// Start the kernel in a working directory
     async fn start(&mut self, directory: &Path) -> Result<()> {
         Ok(())
@@ -376,6 +390,55 @@ pub enum KernelSignal {
     Kill,
 }
 
+/// Limits on the resources that a kernel instance may use
+///
+/// Not all kernels support all limits (see `KernelInstance::set_limits`).
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, crate = "common::serde")]
+pub struct KernelLimits {
+    /// The maximum memory, in megabytes, that the kernel instance may use
+    pub memory: Option<u64>,
+
+    /// The maximum CPU time, in seconds, that the kernel instance may use
+    pub cpu: Option<u64>,
+
+    /// The maximum time, in seconds, for each execution task
+    ///
+    /// If exceeded, the kernel instance is interrupted and, if it does not
+    /// respond to the interrupt, killed. Applied to tasks by the set of kernels
+    /// that the instance belongs to, rather than by the instance itself.
+    pub timeout: Option<u64>,
+
+    /// Whether the kernel instance should be run in restricted mode
+    ///
+    /// In restricted mode the kernel instance has no network access, the file
+    /// system is read-only except for a temporary scratch directory, and system
+    /// calls that could be used to undo these restrictions are denied.
+    pub restricted: bool,
+}
+
+impl KernelLimits {
+    /// Combine these limits with others using the most restrictive of each
+    ///
+    /// Used so that the limits specified in a document (which may be untrusted)
+    /// can only tighten, and never loosen, those configured by the user.
+    pub fn tighten(&self, other: &KernelLimits) -> Self {
+        fn min(a: Option<u64>, b: Option<u64>) -> Option<u64> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            }
+        }
+
+        Self {
+            memory: min(self.memory, other.memory),
+            cpu: min(self.cpu, other.cpu),
+            timeout: min(self.timeout, other.timeout),
+            restricted: self.restricted || other.restricted,
+        }
+    }
+}
+
 /// Standard tests for implementations of the `Kernel` and `KernelInstance` traits
 pub mod tests {
     use std::{env, time::Duration};
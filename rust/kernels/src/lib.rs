@@ -1,7 +1,9 @@
 use std::{
     env, fmt,
+    future::Future,
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 use kernel::{
@@ -10,13 +12,14 @@ use kernel::{
         tokio::{
             self,
             sync::{broadcast, mpsc, Mutex, RwLock},
+            time,
         },
         tracing,
     },
     format::Format,
-    schema::{ExecutionMessage, Node},
-    Kernel, KernelForks, KernelInstance, KernelVariableRequest, KernelVariableRequester,
-    KernelVariableResponse,
+    schema::{ExecutionMessage, MessageLevel, Node},
+    Kernel, KernelForks, KernelInstance, KernelLimits, KernelSignal, KernelStatus,
+    KernelVariableRequest, KernelVariableRequester, KernelVariableResponse,
 };
 use kernel_asciimath::AsciiMathKernel;
 use kernel_bash::BashKernel;
@@ -31,9 +34,13 @@ use kernel_rhai::RhaiKernel;
 use kernel_style::StyleKernel;
 use kernel_tex::TexKernel;
 
-pub use kernel::{KernelAvailability, KernelProvider, KernelType};
+pub use kernel::{KernelAvailability, KernelLimits, KernelProvider, KernelType};
 
 pub mod cli;
+mod limits;
+mod mirror;
+
+use mirror::Mirrors;
 
 /// Get a list of available kernels
 pub async fn list() -> Vec<Box<dyn Kernel>> {
@@ -53,6 +60,10 @@ pub async fn list() -> Vec<Box<dyn Kernel>> {
         Box::<TexKernel>::default() as Box<dyn Kernel>,
     ];
 
+    // After builtin kernels so that those are preferred for a language
+    // (e.g. `PythonKernel` rather than the Jupyter kernel for Python)
+    kernels.append(&mut kernel_jupyter::list());
+
     let provided_by_plugins = &mut plugins::kernels::list().await;
     kernels.append(provided_by_plugins);
 
@@ -88,6 +99,9 @@ struct KernelInstanceEntry {
 
     /// The instance itself
     instance: Arc<Mutex<Box<dyn KernelInstance>>>,
+
+    /// The maximum time for each task, configured by the user for the kernel
+    timeout_limit: Option<Duration>,
 }
 
 type KernelInstances = Arc<RwLock<Vec<KernelInstanceEntry>>>;
@@ -102,6 +116,17 @@ pub struct Kernels {
     /// The kernel instances
     instances: KernelInstances,
 
+    /// The names of the secrets to set as environment variables when starting kernels
+    secrets: Vec<String>,
+
+    /// The limits on resources that kernel instances may use
+    ///
+    /// Combined with any limits configured by the user when starting kernels.
+    limits: KernelLimits,
+
+    /// Variables that have been mirrored between kernel instances
+    mirrors: Mirrors,
+
     /// A sender of requests from kernels for variables
     variable_request_sender: KernelVariableRequester,
 
@@ -149,6 +174,9 @@ impl Kernels {
         Self {
             home,
             instances,
+            secrets: Vec::new(),
+            limits: KernelLimits::default(),
+            mirrors: Mirrors::default(),
             variable_request_sender,
             variable_response_sender,
         }
@@ -199,6 +227,41 @@ impl Kernels {
         tracing::debug!("Kernels variable request task stopped");
     }
 
+    /// Set the names of the secrets to provide to kernel instances
+    ///
+    /// Each secret is set as an environment variable when kernel instances are
+    /// started. Only user-defined secrets are provided, and callers are responsible
+    /// for only including secrets that the user has allowed the document to access.
+    /// Only affects instances created after this is called.
+    pub fn set_secrets(&mut self, secrets: Vec<String>) {
+        self.secrets = secrets;
+    }
+
+    /// Set the limits on the resources that kernel instances may use
+    ///
+    /// These limits can only tighten, not loosen, any limits configured by the
+    /// user. Only affects instances created after this is called.
+    pub fn set_limits(&mut self, limits: KernelLimits) {
+        self.limits = limits;
+    }
+
+    /// Get the environment variables for the secrets
+    ///
+    /// Secrets that are not available, or that are not user-defined secrets
+    /// (e.g. API keys used by Stencila), are warned about and ignored.
+    fn secrets_environment(&self) -> Vec<(String, String)> {
+        self.secrets
+            .iter()
+            .filter_map(|name| match secrets::get_user_defined(name) {
+                Ok(value) => Some((name.clone(), value)),
+                Err(error) => {
+                    tracing::warn!("{error}");
+                    None
+                }
+            })
+            .collect()
+    }
+
     /// Create a kernel instance
     ///
     /// The `language` argument can be the name of a kernel or a programming language.
@@ -239,6 +302,11 @@ impl Kernels {
                 self.variable_response_sender.subscribe(),
             );
         }
+        if !self.secrets.is_empty() {
+            instance.set_environment(self.secrets_environment());
+        }
+        let configured = limits::configured(&kernel.name());
+        instance.set_limits(configured.tighten(&self.limits));
         instance.start(&self.home).await?;
         let instance = Arc::new(Mutex::new(instance));
 
@@ -247,6 +315,7 @@ impl Kernels {
             kernel: Arc::new(kernel),
             id,
             instance: instance.clone(),
+            timeout_limit: configured.timeout.map(Duration::from_secs),
         });
 
         Ok(instance)
@@ -271,12 +340,16 @@ impl Kernels {
 
         let id = instance.id().to_string();
         let instance = Arc::new(Mutex::new(instance));
+        let timeout_limit = limits::configured(&kernel.name())
+            .timeout
+            .map(Duration::from_secs);
 
         let mut instances = self.instances.write().await;
         instances.push(KernelInstanceEntry {
             kernel,
             id,
             instance,
+            timeout_limit,
         });
 
         Ok(())
@@ -343,20 +416,113 @@ impl Kernels {
             .collect()
     }
 
+    /// Get a kernel instance for a language, creating one if necessary
+    ///
+    /// If the existing instance for the language has stopped, or failed (e.g. because
+    /// it crashed, or was killed after a timeout) it is removed and a new instance created.
+    async fn instance_for(
+        &mut self,
+        language: Option<&str>,
+    ) -> Result<Arc<Mutex<Box<dyn KernelInstance>>>> {
+        if let Some(instance) = self.get_instance_for(language).await? {
+            if is_alive(&instance).await {
+                return Ok(instance);
+            }
+
+            let id = instance.lock().await.id().to_string();
+            tracing::debug!("Kernel instance `{id}` has stopped, restarting");
+            self.remove_instance(&id).await;
+        }
+
+        self.create_instance(language).await
+    }
+
+    /// Remove a kernel instance from the set
+    async fn remove_instance(&mut self, id: &str) {
+        self.instances.write().await.retain(|entry| entry.id != id);
+    }
+
+    /// Get the timeout for a task in a kernel instance
+    ///
+    /// This is the single place that the timeout for a task is resolved. In order of
+    /// precedence, the timeout is the one specified for the task (i.e. from a `@timeout`
+    /// tag on a node, or the `--timeout` option), or the `timeout` in the document's config.
+    /// Either is capped by any `timeout` configured by the user for the kernel.
+    async fn task_timeout(&self, id: &str, timeout: Option<Duration>) -> Option<Duration> {
+        let timeout = timeout.or(self.limits.timeout.map(Duration::from_secs));
+
+        let limit = self
+            .instances
+            .read()
+            .await
+            .iter()
+            .find(|entry| entry.id == id)
+            .and_then(|entry| entry.timeout_limit);
+
+        match (timeout, limit) {
+            (Some(timeout), Some(limit)) => Some(timeout.min(limit)),
+            (timeout, limit) => timeout.or(limit),
+        }
+    }
+
+    /// Check that a kernel instance is still alive after a task
+    ///
+    /// If the instance has stopped, or failed, it is removed from the set (so that it
+    /// is restarted for the next task) and a message added to those from the task.
+    async fn check_alive(
+        &mut self,
+        instance: &Arc<Mutex<Box<dyn KernelInstance>>>,
+        id: &str,
+        messages: &mut Vec<ExecutionMessage>,
+    ) {
+        if is_alive(instance).await {
+            return;
+        }
+
+        self.remove_instance(id).await;
+        messages.push(ExecutionMessage {
+            error_type: Some("KernelError".to_string()),
+            ..ExecutionMessage::new(
+                MessageLevel::Exception,
+                "Kernel stopped unexpectedly and will be restarted. Code executed previously in the kernel will need to be re-executed.".to_string(),
+            )
+        });
+    }
+
     /// Execute some code in a kernel instance
     pub async fn execute(
         &mut self,
         code: &str,
         language: Option<&str>,
     ) -> Result<(Vec<Node>, Vec<ExecutionMessage>, String)> {
-        let instance = match self.get_instance_for(language).await? {
-            Some(instance) => instance,
-            None => self.create_instance(language).await?,
-        };
+        self.execute_within(code, language, None).await
+    }
 
-        let mut instance = instance.lock().await;
-        let (nodes, messages) = instance.execute(code).await?;
-        let id = instance.id().to_string();
+    /// Execute some code in a kernel instance within a time limit
+    ///
+    /// Any variables referred to in the code that are in other kernel instances
+    /// are mirrored into the instance before the code is executed.
+    ///
+    /// If the execution takes longer than the timeout (see [`Kernels::task_timeout`]) the
+    /// instance is interrupted and, if it does not respond to the interrupt, killed. If the
+    /// instance stops during the execution (e.g. because it was killed, or crashed) it is
+    /// restarted before the next execution.
+    pub async fn execute_within(
+        &mut self,
+        code: &str,
+        language: Option<&str>,
+        timeout: Option<Duration>,
+    ) -> Result<(Vec<Node>, Vec<ExecutionMessage>, String)> {
+        let instance = self.instance_for(language).await?;
+
+        let id = instance.lock().await.id().to_string();
+        self.mirror_into(&id, code).await?;
+
+        let timeout = self.task_timeout(&id, timeout).await;
+        let task = async { instance.lock().await.execute(code).await };
+        let (nodes, mut messages) = within(&instance, timeout, task).await?;
+        self.mirrors_invalidate(&id);
+        self.check_alive(&instance, &id, &mut messages).await;
 
         Ok((nodes, messages, id))
     }
@@ -367,14 +533,30 @@ impl Kernels {
         code: &str,
         language: Option<&str>,
     ) -> Result<(Node, Vec<ExecutionMessage>, String)> {
-        let instance = match self.get_instance_for(language).await? {
-            Some(instance) => instance,
-            None => self.create_instance(language).await?,
-        };
+        self.evaluate_within(code, language, None).await
+    }
 
-        let mut instance = instance.lock().await;
-        let (node, messages) = instance.evaluate(code).await?;
-        let id = instance.id().to_string();
+    /// Evaluate a code expression in a kernel instance within a time limit
+    ///
+    /// Any variables referred to in the code that are in other kernel instances
+    /// are mirrored into the instance before the code is evaluated. Timeouts, and
+    /// stopped instances, are handled as for [`Kernels::execute_within`].
+    pub async fn evaluate_within(
+        &mut self,
+        code: &str,
+        language: Option<&str>,
+        timeout: Option<Duration>,
+    ) -> Result<(Node, Vec<ExecutionMessage>, String)> {
+        let instance = self.instance_for(language).await?;
+
+        let id = instance.lock().await.id().to_string();
+        self.mirror_into(&id, code).await?;
+
+        let timeout = self.task_timeout(&id, timeout).await;
+        let task = async { instance.lock().await.evaluate(code).await };
+        let (node, mut messages) = within(&instance, timeout, task).await?;
+        self.mirrors_invalidate(&id);
+        self.check_alive(&instance, &id, &mut messages).await;
 
         Ok((node, messages, id))
     }
@@ -405,14 +587,22 @@ impl Kernels {
     }
 
     /// Remove a variable from the kernels
+    ///
+    /// Removes the variable from the first kernel instance and from any
+    /// other instances that it has been mirrored to, or from.
     pub async fn remove(&mut self, name: &str) -> Result<()> {
-        // TODO: remove from all kernels that the variable has been mirrored to
         let Some(instance) = self.get_instance_for(None).await? else {
             bail!("No kernel instances to remove variable from")
         };
+        instance.lock().await.remove(name).await?;
 
-        let mut instance = instance.lock().await;
-        instance.remove(name).await
+        for id in self.mirrors_remove(name) {
+            if let Some(instance) = self.get_instance(&id).await {
+                instance.lock().await.remove(name).await?;
+            }
+        }
+
+        Ok(())
     }
 
     /// Whether all kernels in the set support forking
@@ -430,6 +620,8 @@ impl Kernels {
     /// Errors if any of the forks fails (i.e. a complete fork is not possible).
     pub async fn fork(&self) -> Result<Self> {
         let mut kernels = Self::new(&self.home);
+        kernels.secrets.clone_from(&self.secrets);
+        kernels.limits.clone_from(&self.limits);
         for entry in self.instances.read().await.iter() {
             let kernel = entry.kernel.clone();
             let instance = entry.instance.lock().await.fork().await?;
@@ -439,11 +631,99 @@ impl Kernels {
     }
 }
 
+/// The time to wait for a kernel instance to respond to a signal, after
+/// a task has timed out, before escalating
+const SIGNAL_GRACE: Duration = Duration::from_secs(5);
+
+/// Whether a kernel instance is alive (i.e. has not stopped or failed)
+async fn is_alive(instance: &Arc<Mutex<Box<dyn KernelInstance>>>) -> bool {
+    !matches!(
+        instance.lock().await.status().await,
+        Ok(KernelStatus::Stopped | KernelStatus::Failed)
+    )
+}
+
+/// Perform a task in a kernel instance within a time limit
+///
+/// If the `timeout` is exceeded, the kernel instance is sent an interrupt signal and,
+/// if the task does not complete within a grace period, a kill signal. A message
+/// describing the timeout is added to those returned by the task.
+async fn within<T, F>(
+    instance: &Arc<Mutex<Box<dyn KernelInstance>>>,
+    timeout: Option<Duration>,
+    task: F,
+) -> Result<(T, Vec<ExecutionMessage>)>
+where
+    T: Default,
+    F: Future<Output = Result<(T, Vec<ExecutionMessage>)>>,
+{
+    let Some(timeout) = timeout else {
+        return task.await;
+    };
+
+    // Get the signal sender before the task locks the instance
+    let signal_sender = instance.lock().await.signal_sender().ok();
+    let signal = |signal: KernelSignal| {
+        if let Some(sender) = &signal_sender {
+            if let Err(error) = sender.try_send(signal) {
+                tracing::error!("While signalling kernel: {error}")
+            }
+        }
+    };
+
+    tokio::pin!(task);
+
+    if let Ok(result) = time::timeout(timeout, &mut task).await {
+        return result;
+    }
+
+    tracing::debug!("Kernel task timed out, interrupting");
+    signal(KernelSignal::Interrupt);
+
+    let (result, outcome) = match time::timeout(SIGNAL_GRACE, &mut task).await {
+        Ok(result) => (result, "was interrupted"),
+        Err(..) => {
+            tracing::debug!("Kernel did not respond to interrupt, killing");
+            signal(KernelSignal::Kill);
+
+            match time::timeout(SIGNAL_GRACE, &mut task).await {
+                Ok(result) => (result, "the kernel was killed"),
+                Err(..) => (
+                    Ok((T::default(), Vec::new())),
+                    "the kernel could not be stopped",
+                ),
+            }
+        }
+    };
+
+    let (value, mut messages) = result.unwrap_or_else(|error| {
+        (
+            T::default(),
+            vec![ExecutionMessage::new(
+                MessageLevel::Error,
+                error.to_string(),
+            )],
+        )
+    });
+    messages.push(ExecutionMessage {
+        error_type: Some("TimeoutError".to_string()),
+        ..ExecutionMessage::new(
+            MessageLevel::Exception,
+            format!(
+                "Execution exceeded the timeout of {} seconds and {outcome}",
+                timeout.as_secs()
+            ),
+        )
+    });
+
+    Ok((value, messages))
+}
+
 #[cfg(test)]
 mod tests {
     use common_dev::pretty_assertions::assert_eq;
     use kernel::{
-        common::tokio,
+        common::{itertools::Itertools, tokio},
         schema::{MessageLevel, Node},
     };
 
@@ -476,6 +756,104 @@ mod tests {
         Ok(())
     }
 
+    /// Test mirroring of variables between kernels
+    #[test_log::test(tokio::test(flavor = "multi_thread", worker_threads = 2))]
+    async fn mirroring() -> Result<()> {
+        let mut kernels = Kernels::new_here();
+
+        kernels.execute("var a = 'one'", Some("js")).await?;
+
+        // Mirrored from JavaScript to Rhai
+        let (node, messages, ..) = kernels.evaluate(r#"a + "!""#, Some("rhai")).await?;
+        assert_eq!(messages, vec![]);
+        assert_eq!(node, Node::String("one!".to_string()));
+
+        // Changes in the source are mirrored
+        kernels.execute("a = 'two'", Some("js")).await?;
+        let (node, messages, ..) = kernels.evaluate(r#"a + "!""#, Some("rhai")).await?;
+        assert_eq!(messages, vec![]);
+        assert_eq!(node, Node::String("two!".to_string()));
+
+        // Reassignment in the copy is mirrored back
+        kernels.execute(r#"a = "three""#, Some("rhai")).await?;
+        let (node, messages, ..) = kernels.evaluate("a + '!'", Some("js")).await?;
+        assert_eq!(messages, vec![]);
+        assert_eq!(node, Node::String("three!".to_string()));
+
+        // Changes in value, with the same type and hint, made by evaluating
+        // an expression with side effects, are mirrored
+        kernels.evaluate("a = 'four'", Some("js")).await?;
+        let (node, messages, ..) = kernels.evaluate(r#"a + "!""#, Some("rhai")).await?;
+        assert_eq!(messages, vec![]);
+        assert_eq!(node, Node::String("four!".to_string()));
+
+        // Removal is from all instances
+        kernels.remove("a").await?;
+        let node = kernels.get("a").await?;
+        assert_eq!(node, None);
+
+        Ok(())
+    }
+
+    /// Test that kernels are interrupted, or killed, after a timeout and restarted if necessary
+    #[test_log::test(tokio::test(flavor = "multi_thread", worker_threads = 2))]
+    async fn timeout_and_restart() -> Result<()> {
+        if !get("bash")
+            .await
+            .is_some_and(|kernel| kernel.is_available())
+        {
+            return Ok(());
+        }
+
+        let mut kernels = Kernels::new_here();
+
+        // Interrupts are ignored so that the instance has to be killed
+        let (.., messages, first) = kernels
+            .execute_within(
+                "trap '' INT; sleep 30",
+                Some("bash"),
+                Some(Duration::from_secs(1)),
+            )
+            .await?;
+        let timeouts = messages
+            .iter()
+            .filter(|message| message.error_type.as_deref() == Some("TimeoutError"))
+            .collect_vec();
+        assert_eq!(timeouts.len(), 1);
+        assert!(timeouts[0].message.ends_with("the kernel was killed"));
+
+        // The killed instance should have been removed and a new one started
+        assert!(!kernels.has_instance(&first).await);
+        let (outputs, messages, second) = kernels.execute("echo 1", Some("bash")).await?;
+        assert_eq!(messages, vec![]);
+        assert_eq!(outputs, vec![Node::Integer(1)]);
+        assert_ne!(first, second);
+
+        Ok(())
+    }
+
+    /// Test the precedence of timeouts for a task
+    #[tokio::test]
+    async fn task_timeout() {
+        let mut kernels = Kernels::new_here();
+        assert_eq!(kernels.task_timeout("none", None).await, None);
+
+        kernels.set_limits(KernelLimits {
+            timeout: Some(60),
+            ..Default::default()
+        });
+        assert_eq!(
+            kernels.task_timeout("none", None).await,
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            kernels
+                .task_timeout("none", Some(Duration::from_secs(600)))
+                .await,
+            Some(Duration::from_secs(600))
+        );
+    }
+
     /// Test forking a set of kernels
     ///
     /// The `evaluate` calls using Jinja test variable connections
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use kernel::{
+    common::{eyre::Result, serde_yaml, tracing},
+    KernelLimits,
+};
+
+/// Get the limits configured by the user for a kernel
+///
+/// Limits are read from `limits.yaml` in the kernels configuration directory.
+/// That file is a map of kernel names to limits, with a `default` entry for
+/// kernels that do not have an entry of their own. e.g.
+///
+/// ```yaml
+/// default:
+///   memory: 4096
+///   timeout: 600
+/// python:
+///   memory: 8192
+///   timeout: 600
+///   restricted: true
+/// ```
+pub(crate) fn configured(kernel_name: &str) -> KernelLimits {
+    match read() {
+        Ok(config) => resolve(config, kernel_name),
+        Err(error) => {
+            tracing::warn!("While reading kernel limits: {error}");
+            KernelLimits::default()
+        }
+    }
+}
+
+/// Read the configured limits
+fn read() -> Result<HashMap<String, KernelLimits>> {
+    let path = app::get_app_dir(app::DirType::Kernels, false)?.join("limits.yaml");
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let yaml = std::fs::read_to_string(path)?;
+    Ok(serde_yaml::from_str(&yaml)?)
+}
+
+/// Resolve the limits for a kernel
+fn resolve(mut config: HashMap<String, KernelLimits>, kernel_name: &str) -> KernelLimits {
+    config
+        .remove(kernel_name)
+        .or_else(|| config.remove("default"))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolving() -> Result<()> {
+        let config: HashMap<String, KernelLimits> = serde_yaml::from_str(
+            r#"
+default:
+  memory: 1024
+python:
+  timeout: 60
+  restricted: true
+"#,
+        )?;
+
+        let limits = resolve(config.clone(), "python");
+        assert_eq!(limits.memory, None);
+        assert_eq!(limits.timeout, Some(60));
+        assert!(limits.restricted);
+
+        let limits = resolve(config.clone(), "r");
+        assert_eq!(limits.memory, Some(1024));
+        assert!(!limits.restricted);
+
+        // Document limits can only tighten configured limits
+        let limits = resolve(config, "python").tighten(&KernelLimits {
+            memory: Some(512),
+            timeout: Some(3600),
+            restricted: false,
+            ..Default::default()
+        });
+        assert_eq!(limits.memory, Some(512));
+        assert_eq!(limits.timeout, Some(60));
+        assert!(limits.restricted);
+
+        Ok(())
+    }
+}
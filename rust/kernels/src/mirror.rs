@@ -0,0 +1,204 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{Hash, Hasher},
+};
+
+use kernel::{
+    common::{eyre::Result, itertools::Itertools, seahash::SeaHasher, serde_json, tracing},
+    format::Format,
+    schema::{Node, Variable},
+};
+
+use crate::Kernels;
+
+/// A variable that has been mirrored between kernel instances
+///
+/// Used to avoid unnecessarily re-mirroring variables, and to detect when
+/// a variable has changed in its source, or been reassigned in another instance
+/// (which then becomes the source).
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Mirror {
+    /// The id of the kernel instance that is the source of the variable
+    source: String,
+
+    /// The digest of the variable in each instance when it was last listed or set
+    ///
+    /// Used to detect when the variable has been reassigned in an instance.
+    digests: HashMap<String, u64>,
+
+    /// The ids of the instances which have an up-to-date copy of the variable
+    synced: HashSet<String>,
+}
+
+/// Mirrors of variables between kernel instances
+pub(crate) type Mirrors = HashMap<String, Mirror>;
+
+/// Calculate a digest of a variable and its value
+///
+/// Includes the value so that a change in value is detected even if the type and
+/// hints of the variable are unchanged (e.g. when the hint for a string is its length).
+fn digest(variable: &Variable, value: &Node) -> u64 {
+    let mut hash = SeaHasher::new();
+    variable.native_type.hash(&mut hash);
+    variable.node_type.hash(&mut hash);
+    variable.native_hint.hash(&mut hash);
+    serde_json::to_string(&variable.hint)
+        .unwrap_or_default()
+        .hash(&mut hash);
+    serde_json::to_string(value)
+        .unwrap_or_default()
+        .hash(&mut hash);
+    hash.finish()
+}
+
+impl Kernels {
+    /// Mirror variables from other kernel instances into an instance
+    ///
+    /// Variables in other instances which the code may use (as determined by parsing it
+    /// in the language of the target) are got from those instances and set in the target
+    /// instance. Conversion between languages is
+    /// done by the kernels themselves (e.g. a Python `DataFrame` is got as a `Datatable`
+    /// which is set as an R `data.frame`). Kernels which request variables on demand
+    /// (e.g. Jinja) are neither the source, nor the target, of mirrors.
+    pub(crate) async fn mirror_into(&mut self, target_id: &str, code: &str) -> Result<()> {
+        let (target, sources, format) = {
+            let instances = self.instances.read().await;
+
+            let Some(target) = instances.iter().find(|entry| entry.id == target_id) else {
+                return Ok(());
+            };
+            if target.kernel.supports_variable_requests() {
+                return Ok(());
+            }
+
+            let sources = instances
+                .iter()
+                .filter(|entry| entry.id != target_id && !entry.kernel.supports_variable_requests())
+                .map(|entry| (entry.id.clone(), entry.instance.clone()))
+                .collect_vec();
+
+            let format = target
+                .kernel
+                .supports_languages()
+                .first()
+                .cloned()
+                .unwrap_or(Format::Unknown);
+
+            (target.instance.clone(), sources, format)
+        };
+
+        let uses = parsers::uses(code, &format);
+
+        let mut mirrored = Vec::new();
+        for (source_id, source) in sources {
+            let mut source = source.lock().await;
+
+            let variables = match source.list().await {
+                Ok(variables) => variables,
+                Err(error) => {
+                    tracing::debug!("While listing variables in `{source_id}`: {error}");
+                    continue;
+                }
+            };
+
+            for variable in variables {
+                let name = &variable.name;
+                if !uses.contains(name) {
+                    continue;
+                }
+
+                let value = match source.get(name).await {
+                    Ok(Some(value)) => value,
+                    Ok(None) => continue,
+                    Err(error) => {
+                        tracing::warn!(
+                            "While getting variable `{name}` from `{source_id}`: {error}"
+                        );
+                        continue;
+                    }
+                };
+
+                let digest = digest(&variable, &value);
+                let mirror = self.mirrors.entry(name.clone()).or_insert_with(|| Mirror {
+                    source: source_id.clone(),
+                    ..Default::default()
+                });
+
+                if mirror.source == source_id {
+                    if mirror.digests.get(&source_id) != Some(&digest) {
+                        // Changed in the source so invalidate copies
+                        mirror.digests.insert(source_id.clone(), digest);
+                        mirror.synced.clear();
+                    } else if mirror.synced.contains(target_id) {
+                        // Target already has an up-to-date copy
+                        continue;
+                    }
+                } else if mirror.digests.get(&source_id) == Some(&digest) {
+                    // An unchanged copy so not a source
+                    continue;
+                } else {
+                    // Reassigned in this instance so it becomes the source
+                    mirror.source.clone_from(&source_id);
+                    mirror.digests.insert(source_id.clone(), digest);
+                    mirror.synced.clear();
+                }
+
+                if let Err(error) = target.lock().await.set(name, &value).await {
+                    tracing::warn!("While mirroring variable `{name}` to `{target_id}`: {error}");
+                    continue;
+                }
+
+                tracing::trace!("Mirrored variable `{name}` from `{source_id}` to `{target_id}`");
+                mirrored.push(name.clone());
+            }
+        }
+
+        if mirrored.is_empty() {
+            return Ok(());
+        }
+
+        // Record the digests of the variables in the target so that reassignments
+        // there can be detected. The value is got back from the target, rather than
+        // using the value that was set, because its representation may differ.
+        let mut target = target.lock().await;
+        let variables = target.list().await.unwrap_or_default();
+        for name in mirrored {
+            let value = target.get(&name).await.ok().flatten();
+            if let Some(mirror) = self.mirrors.get_mut(&name) {
+                if let (Some(variable), Some(value)) = (
+                    variables.iter().find(|variable| variable.name == name),
+                    value,
+                ) {
+                    mirror
+                        .digests
+                        .insert(target_id.to_string(), digest(variable, &value));
+                }
+                mirror.synced.insert(target_id.to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Invalidate the mirrors of variables from an instance
+    ///
+    /// Should be called after code is executed, or evaluated, in an instance
+    /// because that may have changed the variables it is the source of.
+    pub(crate) fn mirrors_invalidate(&mut self, source_id: &str) {
+        for mirror in self.mirrors.values_mut() {
+            if mirror.source == source_id {
+                mirror.synced.clear();
+            }
+        }
+    }
+
+    /// Get the ids of the instances that a variable has been mirrored to (including its source)
+    ///
+    /// Removes the record of the mirror.
+    pub(crate) fn mirrors_remove(&mut self, name: &str) -> Vec<String> {
+        match self.mirrors.remove(name) {
+            Some(mirror) => mirror.digests.into_keys().collect(),
+            None => Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,405 @@
+//! Handling of code action related messages
+//!
+//! See https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#textDocument_codeAction
+//!
+//! Code actions are provided for the suggestions of instructions. They offer the same
+//! functionality as the code lenses for instructions but, because code actions are
+//! standard across editors, they are better supported in editors other than VS Code.
+//!
+//! The LSP has no standard request for text input, so code actions can not ask the user
+//! for feedback when revising a suggestion. Clients which can prompt for input (e.g. using
+//! client-side middleware) may append the feedback as a final argument to the command.
+//! Otherwise, as in Neovim and Helix, the suggestion is revised without feedback.
+
+use std::{
+    collections::HashMap,
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+use async_lsp::{
+    lsp_types::{
+        CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, CodeActionResponse,
+        Command, MessageType, Range, ShowDocumentParams, ShowMessageParams, Url,
+    },
+    ClientSocket, LanguageClient, ResponseError,
+};
+
+use codecs::{EncodeOptions, Format};
+use common::{
+    eyre::{bail, eyre, Result},
+    once_cell::sync::Lazy,
+    serde_json::json,
+    similar::TextDiff,
+    tempfile::{self, TempPath},
+    tokio::sync::RwLock,
+    tracing,
+};
+use document::Document;
+use schema::{shortcuts::p, Article, Block, Node, NodeId, NodeType};
+
+use crate::{
+    commands::{ACCEPT_NODE, DIFF_NODE, REJECT_NODE, REVISE_SUGGESTION},
+    text_document::TextNode,
+};
+
+/// Handle a request for code actions for a range of a document
+pub(crate) async fn request(
+    CodeActionParams {
+        text_document,
+        range,
+        ..
+    }: CodeActionParams,
+    root: Arc<RwLock<TextNode>>,
+) -> Result<Option<CodeActionResponse>, ResponseError> {
+    Ok(Some(actions(
+        &text_document.uri,
+        &*root.read().await,
+        &range,
+    )))
+}
+
+/// Create the code actions for the suggestions within a range of a document
+fn actions(uri: &Url, root: &TextNode, range: &Range) -> Vec<CodeActionOrCommand> {
+    root.flatten()
+        .filter(|node| {
+            matches!(
+                node.node_type,
+                NodeType::SuggestionBlock | NodeType::SuggestionInline
+            ) && matches!(
+                node.parent_type,
+                NodeType::InstructionBlock | NodeType::InstructionInline
+            ) && node.range != Range::default()
+                && overlaps(&node.range, range)
+        })
+        .flat_map(|node| suggestion_actions(uri, node))
+        .collect()
+}
+
+/// Do two ranges overlap?
+fn overlaps(a: &Range, b: &Range) -> bool {
+    a.start <= b.end && b.start <= a.end
+}
+
+/// Create the code actions for a suggestion
+fn suggestion_actions(uri: &Url, node: &TextNode) -> Vec<CodeActionOrCommand> {
+    let suggestion_args = Some(vec![
+        json!(uri),
+        json!(node.node_type),
+        json!(node.node_id),
+        json!(node.parent_id),
+    ]);
+
+    let action = |title: &str, command: &str, arguments, is_preferred| {
+        CodeActionOrCommand::CodeAction(CodeAction {
+            title: title.to_string(),
+            kind: Some(CodeActionKind::QUICKFIX),
+            command: Some(Command::new(
+                title.to_string(),
+                command.to_string(),
+                arguments,
+            )),
+            is_preferred: Some(is_preferred),
+            ..Default::default()
+        })
+    };
+
+    vec![
+        action(
+            "Accept suggestion",
+            ACCEPT_NODE,
+            suggestion_args.clone(),
+            true,
+        ),
+        action(
+            "Reject suggestion",
+            REJECT_NODE,
+            suggestion_args.clone(),
+            false,
+        ),
+        // Revise this suggestion with any feedback provided as an
+        // additional argument by the client
+        action(
+            "Revise suggestion",
+            REVISE_SUGGESTION,
+            suggestion_args.clone(),
+            false,
+        ),
+        action("Show diff with original", DIFF_NODE, suggestion_args, false),
+    ]
+}
+
+/// The temporary files that the diffs of suggestions have been written to
+///
+/// Keyed by the id of the suggestion so that, if the diff for a suggestion is shown
+/// again, the previous file is deleted. The remaining files are deleted when the server
+/// is shutdown (see [`remove_diff_files`]).
+static DIFF_FILES: Lazy<Mutex<HashMap<NodeId, TempPath>>> = Lazy::new(Mutex::default);
+
+/// Show the difference between a suggestion and the original content of its instruction
+///
+/// Both are encoded to the format of the document and the unified diff is written to
+/// a new, uniquely named, temporary file which the client is asked to show.
+pub(super) async fn show_diff(
+    uri: &Url,
+    format: Format,
+    suggestion_id: NodeId,
+    instruction_id: NodeId,
+    doc: Arc<RwLock<Document>>,
+    mut client: ClientSocket,
+) {
+    let instruction = doc.read().await.find_node(instruction_id).await;
+    let diff = match diff(&format, &suggestion_id, instruction).await {
+        Ok(diff) => diff,
+        Err(error) => {
+            client
+                .show_message(ShowMessageParams {
+                    typ: MessageType::ERROR,
+                    message: format!("While creating diff for suggestion: {error}"),
+                })
+                .ok();
+            return;
+        }
+    };
+
+    // Create the file exclusively, with a random name, so that an existing file or
+    // symlink at a predictable path can not be written to
+    let temp_path = match tempfile::Builder::new()
+        .prefix(&format!("{suggestion_id}-"))
+        .suffix(".diff")
+        .tempfile()
+        .and_then(|mut file| {
+            file.write_all(diff.as_bytes())?;
+            Ok(file.into_temp_path())
+        }) {
+        Ok(temp_path) => temp_path,
+        Err(error) => {
+            tracing::error!("While writing diff file: {error}");
+            return;
+        }
+    };
+
+    let Ok(diff_uri) = Url::from_file_path(&temp_path) else {
+        tracing::error!("Unable to create URI for diff file");
+        return;
+    };
+
+    if let Ok(mut files) = DIFF_FILES.lock() {
+        files.insert(suggestion_id, temp_path);
+    }
+
+    if let Err(error) = client
+        .show_document(ShowDocumentParams {
+            uri: diff_uri,
+            external: Some(false),
+            take_focus: Some(true),
+            selection: None,
+        })
+        .await
+    {
+        tracing::error!("While showing diff for {uri}: {error}");
+    }
+}
+
+/// Delete the temporary files that diffs have been written to
+pub(super) fn remove_diff_files() {
+    if let Ok(mut files) = DIFF_FILES.lock() {
+        files.clear();
+    }
+}
+
+/// Create a unified diff between a suggestion and the original content of its instruction
+async fn diff(
+    format: &Format,
+    suggestion_id: &NodeId,
+    instruction: Option<Node>,
+) -> Result<String> {
+    let (original, suggested) = match instruction {
+        Some(Node::InstructionBlock(instruction)) => {
+            let suggestion = instruction
+                .suggestions
+                .iter()
+                .flatten()
+                .find(|suggestion| &suggestion.node_id() == suggestion_id)
+                .ok_or_else(|| eyre!("Suggestion not found in instruction"))?;
+            (
+                instruction.content.clone().unwrap_or_default(),
+                suggestion.content.clone(),
+            )
+        }
+        Some(Node::InstructionInline(instruction)) => {
+            let suggestion = instruction
+                .suggestions
+                .iter()
+                .flatten()
+                .find(|suggestion| &suggestion.node_id() == suggestion_id)
+                .ok_or_else(|| eyre!("Suggestion not found in instruction"))?;
+            (
+                vec![p(instruction.content.clone().unwrap_or_default())],
+                vec![p(suggestion.content.clone())],
+            )
+        }
+        _ => bail!("Instruction not found in document"),
+    };
+
+    let original = encode(original, format).await?;
+    let suggested = encode(suggested, format).await?;
+
+    Ok(TextDiff::from_lines(&original, &suggested)
+        .unified_diff()
+        .header("original", "suggestion")
+        .to_string())
+}
+
+/// Encode blocks to a string in a format
+async fn encode(content: Vec<Block>, format: &Format) -> Result<String> {
+    codecs::to_string(
+        &Node::Article(Article::new(content)),
+        Some(EncodeOptions {
+            format: Some(format.clone()),
+            ..Default::default()
+        }),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use async_lsp::lsp_types::Position;
+    use common::tokio;
+    use common_dev::pretty_assertions::assert_eq;
+    use schema::{
+        shortcuts::t, InstructionBlock, InstructionInline, InstructionType, SuggestionBlock,
+        SuggestionInline,
+    };
+
+    use super::*;
+
+    fn range(start: (u32, u32), end: (u32, u32)) -> Range {
+        Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1))
+    }
+
+    #[test]
+    fn overlapping() {
+        let a = range((1, 0), (3, 10));
+
+        assert!(overlaps(&a, &a));
+        assert!(overlaps(&a, &range((0, 0), (1, 0))));
+        assert!(overlaps(&a, &range((2, 5), (2, 5))));
+        assert!(overlaps(&a, &range((3, 10), (4, 0))));
+        assert!(overlaps(&range((2, 5), (2, 5)), &a));
+
+        assert!(!overlaps(&a, &range((0, 0), (0, 10))));
+        assert!(!overlaps(&a, &range((3, 11), (4, 0))));
+    }
+
+    #[test]
+    fn action_list() -> Result<()> {
+        let uri = Url::parse("file:///doc.smd")?;
+        let instruction_id = NodeId::new(b"isb", b"1");
+        let suggestion_id = NodeId::new(b"sgb", b"1");
+
+        let root = TextNode {
+            children: vec![
+                TextNode {
+                    range: range((0, 0), (5, 0)),
+                    node_type: NodeType::InstructionBlock,
+                    node_id: instruction_id.clone(),
+                    children: vec![TextNode {
+                        range: range((2, 0), (4, 0)),
+                        parent_type: NodeType::InstructionBlock,
+                        parent_id: instruction_id.clone(),
+                        node_type: NodeType::SuggestionBlock,
+                        node_id: suggestion_id.clone(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                TextNode {
+                    range: range((6, 0), (7, 0)),
+                    node_type: NodeType::Paragraph,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        // No actions outside of suggestions
+        assert!(actions(&uri, &root, &range((0, 0), (1, 0))).is_empty());
+        assert!(actions(&uri, &root, &range((6, 0), (6, 0))).is_empty());
+
+        // Actions for the suggestion under the cursor, all with the ids of the
+        // suggestion and its instruction as arguments
+        let list = actions(&uri, &root, &range((3, 2), (3, 2)));
+        let commands = list
+            .iter()
+            .map(|action| match action {
+                CodeActionOrCommand::CodeAction(CodeAction {
+                    command: Some(command),
+                    ..
+                }) => (command.command.as_str(), command.arguments.clone()),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+
+        let args = Some(vec![
+            json!(uri),
+            json!(NodeType::SuggestionBlock),
+            json!(suggestion_id),
+            json!(instruction_id),
+        ]);
+        assert_eq!(
+            commands,
+            vec![
+                (ACCEPT_NODE, args.clone()),
+                (REJECT_NODE, args.clone()),
+                (REVISE_SUGGESTION, args.clone()),
+                (DIFF_NODE, args),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn diffs() -> Result<()> {
+        let format = Format::Markdown;
+
+        let suggestion = SuggestionBlock::new(vec![p([t("Hello there")])]);
+        let suggestion_id = suggestion.node_id();
+        let instruction = InstructionBlock {
+            content: Some(vec![p([t("Hello world")])]),
+            suggestions: Some(vec![suggestion]),
+            ..InstructionBlock::new(InstructionType::Edit)
+        };
+        let block = Node::InstructionBlock(instruction);
+
+        let block_diff = diff(&format, &suggestion_id, Some(block.clone())).await?;
+        let lines = block_diff.lines().collect::<Vec<_>>();
+        assert_eq!(&lines[..2], &["--- original", "+++ suggestion"]);
+        assert!(lines.contains(&"-Hello world"));
+        assert!(lines.contains(&"+Hello there"));
+
+        let suggestion = SuggestionInline::new(vec![t("there")]);
+        let inline_suggestion_id = suggestion.node_id();
+        let instruction = InstructionInline {
+            content: Some(vec![t("world")]),
+            suggestions: Some(vec![suggestion]),
+            ..InstructionInline::new(InstructionType::Edit)
+        };
+        let inline = Node::InstructionInline(instruction);
+
+        let inline_diff = diff(&format, &inline_suggestion_id, Some(inline)).await?;
+        let lines = inline_diff.lines().collect::<Vec<_>>();
+        assert!(lines.contains(&"-world"));
+        assert!(lines.contains(&"+there"));
+
+        // Errors if the suggestion is not in the instruction, or there is no instruction
+        assert!(diff(&format, &inline_suggestion_id, Some(block))
+            .await
+            .is_err());
+        assert!(diff(&format, &suggestion_id, None).await.is_err());
+
+        Ok(())
+    }
+}
@@ -112,6 +112,7 @@ pub(crate) async fn request(
                         }
                         lenses
                     }
+                    NodeType::Button => vec![lens(RUN_NODE)],
                     NodeType::MathBlock | NodeType::RawBlock | NodeType::StyledBlock => {
                         vec![lens(VIEW_NODE)]
                     }
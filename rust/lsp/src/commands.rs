@@ -32,16 +32,17 @@ use common::{
     tracing,
 };
 use document::{
-    Command, CommandNodes, CommandScope, CommandStatus, Document, SaveDocumentSidecar,
-    SaveDocumentSource,
+    Command, CommandComment, CommandNodes, CommandScope, CommandStatus, Document,
+    SaveDocumentSidecar, SaveDocumentSource,
 };
 use node_execute::ExecuteOptions;
 use schema::{
-    AuthorRole, AuthorRoleName, NodeId, NodeProperty, NodeType, Patch, PatchOp, PatchPath,
-    PatchValue, Timestamp,
+    shortcuts::{p, t},
+    Author, AuthorRole, AuthorRoleName, Node, NodeId, NodeProperty, NodeType, Patch, PatchOp,
+    PatchPath, PatchValue, Timestamp,
 };
 
-use crate::{formatting::format_doc, text_document::TextNode, ServerState};
+use crate::{code_action, formatting::format_doc, text_document::TextNode, ServerState};
 
 pub(super) const PATCH_NODE: &str = "stencila.patch-node";
 pub(super) const PATCH_CURR: &str = "stencila.patch-curr";
@@ -67,6 +68,13 @@ pub(super) const NEXT_NODE: &str = "stencila.next-node";
 pub(super) const ARCHIVE_NODE: &str = "stencila.archive-node";
 pub(super) const REVISE_NODE: &str = "stencila.revise-node";
 
+pub(super) const ACCEPT_NODE: &str = "stencila.accept-node";
+pub(super) const REJECT_NODE: &str = "stencila.reject-node";
+pub(super) const DIFF_NODE: &str = "stencila.diff-node";
+pub(super) const REVISE_SUGGESTION: &str = "stencila.revise-suggestion";
+
+pub(super) const COMMENT_CURR: &str = "stencila.comment-curr";
+
 pub(super) const SAVE_DOC: &str = "stencila.save-doc";
 pub(super) const EXPORT_DOC: &str = "stencila.export-doc";
 
@@ -92,6 +100,11 @@ pub(super) fn commands() -> Vec<String> {
         NEXT_NODE,
         ARCHIVE_NODE,
         REVISE_NODE,
+        ACCEPT_NODE,
+        REJECT_NODE,
+        DIFF_NODE,
+        REVISE_SUGGESTION,
+        COMMENT_CURR,
         SAVE_DOC,
         EXPORT_DOC,
     ]
@@ -370,6 +383,93 @@ pub(super) async fn execute_command(
                 true,
             )
         }
+        ACCEPT_NODE | REJECT_NODE | REVISE_SUGGESTION => {
+            // Args (after document URI) are the type and id of the suggestion, the id
+            // of the instruction that it is a suggestion for, and, when revising, any feedback
+            let node_type = node_type_arg(args.next())?;
+            let suggestion_id = node_id_arg(args.next())?;
+            let instruction_id = node_id_arg(args.next())?;
+            let feedback = args
+                .next()
+                .map(PatchValue::Json)
+                .unwrap_or(PatchValue::None);
+
+            let index = if node_type == NodeType::SuggestionBlock && command != REJECT_NODE {
+                suggestion_index(&doc, &instruction_id, &suggestion_id).await
+            } else {
+                None
+            };
+
+            let Some((title, patch)) = suggestion_patch(
+                &command,
+                node_type,
+                suggestion_id,
+                instruction_id.clone(),
+                index,
+                feedback,
+            ) else {
+                tracing::error!("Suggestion not found in instruction");
+                return Ok(None);
+            };
+            let patch = Patch {
+                authors: Some(vec![author]),
+                ..patch
+            };
+
+            let command = if command == REVISE_SUGGESTION {
+                Command::PatchExecuteNodes((
+                    patch,
+                    CommandNodes::new(vec![instruction_id], CommandScope::Only),
+                    ExecuteOptions {
+                        retain_suggestions: true,
+                        ..Default::default()
+                    },
+                ))
+            } else {
+                Command::PatchNode(patch)
+            };
+
+            (title, command, false, true)
+        }
+        DIFF_NODE => {
+            args.next(); // Skip the currently unused node type arg
+            let suggestion_id = node_id_arg(args.next())?;
+            let instruction_id = node_id_arg(args.next())?;
+
+            code_action::show_diff(&uri, format, suggestion_id, instruction_id, doc, client).await;
+
+            return Ok(None);
+        }
+        COMMENT_CURR => {
+            let position = position_arg(args.next())?;
+            let node_id = if let Some(node_id) = root.read().await.node_id_closest(position) {
+                node_id
+            } else {
+                tracing::error!("No node to comment on at current position");
+                return Ok(None);
+            };
+
+            // Next arg is the text of the comment
+            let Some(text) = args
+                .next()
+                .and_then(|value| value.as_str().map(String::from))
+            else {
+                tracing::error!("No text for comment");
+                return Ok(None);
+            };
+
+            (
+                "Adding comment".to_string(),
+                Command::AddComment(CommandComment::on(
+                    Some(node_id),
+                    None,
+                    vec![p([t(text)])],
+                    Some(vec![Author::AuthorRole(author)]),
+                )),
+                false,
+                false,
+            )
+        }
         SAVE_DOC => (
             "Saving document with sidecar".to_string(),
             Command::SaveDocument((SaveDocumentSource::Yes, SaveDocumentSidecar::Yes)),
@@ -482,6 +582,99 @@ pub(super) async fn execute_command(
     Ok(None)
 }
 
+/// Get the index of a suggestion within the suggestions of an instruction block
+async fn suggestion_index(
+    doc: &Arc<RwLock<Document>>,
+    instruction_id: &NodeId,
+    suggestion_id: &NodeId,
+) -> Option<usize> {
+    let Some(Node::InstructionBlock(instruction)) =
+        doc.read().await.find_node(instruction_id.clone()).await
+    else {
+        return None;
+    };
+
+    instruction
+        .suggestions
+        .iter()
+        .flatten()
+        .position(|suggestion| &suggestion.node_id() == suggestion_id)
+}
+
+/// Create the patch to accept, reject, or revise a suggestion
+///
+/// Block suggestions are accepted and revised via their instruction, after making them its
+/// active suggestion, and so require the `index` of the suggestion within the instruction.
+/// Returns the title of the command and the patch, or `None` if the index is required but missing.
+fn suggestion_patch(
+    command: &str,
+    node_type: NodeType,
+    suggestion_id: NodeId,
+    instruction_id: NodeId,
+    index: Option<usize>,
+    feedback: PatchValue,
+) -> Option<(String, Patch)> {
+    let set_active = |index: usize| {
+        (
+            PatchPath::from(NodeProperty::ActiveSuggestion),
+            PatchOp::Set(PatchValue::Json(index.into())),
+        )
+    };
+
+    let (title, node_id, ops) = match (command, node_type) {
+        // Accepting a block suggestion replaces the instruction with it, so
+        // make it the active suggestion and archive the instruction
+        (ACCEPT_NODE, NodeType::SuggestionBlock) => (
+            "Accepting suggestion and archiving command",
+            instruction_id,
+            vec![set_active(index?), (PatchPath::new(), PatchOp::Archive)],
+        ),
+        (ACCEPT_NODE, ..) => (
+            "Accepting suggestion",
+            instruction_id,
+            vec![(PatchPath::new(), PatchOp::Accept(suggestion_id))],
+        ),
+        (REJECT_NODE, ..) => (
+            "Rejecting suggestion",
+            suggestion_id,
+            vec![(
+                PatchPath::from(NodeProperty::SuggestionStatus),
+                PatchOp::Set(PatchValue::String("Rejected".to_string())),
+            )],
+        ),
+        // Make a block suggestion the active suggestion, so that it is the one that is
+        // revised, and then set its feedback (via the instruction, as for `REVISE_NODE`)
+        (_, NodeType::SuggestionBlock) => (
+            "Revising suggestion",
+            instruction_id,
+            vec![
+                set_active(index?),
+                (
+                    PatchPath::from(NodeProperty::Feedback),
+                    PatchOp::Set(feedback),
+                ),
+            ],
+        ),
+        _ => (
+            "Revising suggestion",
+            suggestion_id,
+            vec![(
+                PatchPath::from(NodeProperty::Feedback),
+                PatchOp::Set(feedback),
+            )],
+        ),
+    };
+
+    Some((
+        title.to_string(),
+        Patch {
+            node_id: Some(node_id),
+            ops,
+            ..Default::default()
+        },
+    ))
+}
+
 /// Extract a document URI from a command arg
 pub(super) fn uri_arg(arg: Option<Value>) -> Result<Url, ResponseError> {
     arg.and_then(|value| serde_json::from_value(value).ok())
@@ -622,3 +815,99 @@ pub(crate) fn cancel_progress(
 
     ControlFlow::Continue(())
 }
+
+#[cfg(test)]
+mod tests {
+    use common_dev::pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn suggestion_patches() {
+        let suggestion_id = NodeId::new(b"sgb", b"1");
+        let instruction_id = NodeId::new(b"isb", b"1");
+        let feedback = PatchValue::String("Shorter".to_string());
+
+        let patch = |command, node_type, index| {
+            suggestion_patch(
+                command,
+                node_type,
+                suggestion_id.clone(),
+                instruction_id.clone(),
+                index,
+                feedback.clone(),
+            )
+            .map(|(.., patch)| (patch.node_id, patch.ops))
+        };
+        let set_active = |index: usize| {
+            (
+                PatchPath::from(NodeProperty::ActiveSuggestion),
+                PatchOp::Set(PatchValue::Json(index.into())),
+            )
+        };
+
+        // Accepting a block suggestion sets it as active before archiving the instruction
+        assert_eq!(
+            patch(ACCEPT_NODE, NodeType::SuggestionBlock, Some(2)),
+            Some((
+                Some(instruction_id.clone()),
+                vec![set_active(2), (PatchPath::new(), PatchOp::Archive)]
+            ))
+        );
+        assert_eq!(patch(ACCEPT_NODE, NodeType::SuggestionBlock, None), None);
+
+        // Accepting an inline suggestion accepts it via the instruction
+        assert_eq!(
+            patch(ACCEPT_NODE, NodeType::SuggestionInline, None),
+            Some((
+                Some(instruction_id.clone()),
+                vec![(PatchPath::new(), PatchOp::Accept(suggestion_id.clone()))]
+            ))
+        );
+
+        // Rejecting sets the status of the suggestion itself
+        for node_type in [NodeType::SuggestionBlock, NodeType::SuggestionInline] {
+            assert_eq!(
+                patch(REJECT_NODE, node_type, None),
+                Some((
+                    Some(suggestion_id.clone()),
+                    vec![(
+                        PatchPath::from(NodeProperty::SuggestionStatus),
+                        PatchOp::Set(PatchValue::String("Rejected".to_string())),
+                    )]
+                ))
+            );
+        }
+
+        // Revising a block suggestion sets it as active before setting feedback
+        assert_eq!(
+            patch(REVISE_SUGGESTION, NodeType::SuggestionBlock, Some(1)),
+            Some((
+                Some(instruction_id.clone()),
+                vec![
+                    set_active(1),
+                    (
+                        PatchPath::from(NodeProperty::Feedback),
+                        PatchOp::Set(feedback.clone())
+                    )
+                ]
+            ))
+        );
+        assert_eq!(
+            patch(REVISE_SUGGESTION, NodeType::SuggestionBlock, None),
+            None
+        );
+
+        // Revising an inline suggestion sets its feedback directly
+        assert_eq!(
+            patch(REVISE_SUGGESTION, NodeType::SuggestionInline, None),
+            Some((
+                Some(suggestion_id.clone()),
+                vec![(
+                    PatchPath::from(NodeProperty::Feedback),
+                    PatchOp::Set(feedback.clone())
+                )]
+            ))
+        );
+    }
+}
@@ -0,0 +1,458 @@
+//! Incremental synchronization of text documents
+//!
+//! Applies the range edits sent by the client (`TextDocumentSyncKind::INCREMENTAL`)
+//! to the source of a document, and decodes only the top-level blocks affected
+//! by changes to the source (for Markdown-based formats) rather than the whole document.
+
+use std::{collections::HashMap, ops::Range as IndexRange};
+
+use async_lsp::lsp_types::{Position, TextDocumentContentChangeEvent};
+
+use codecs::{DecodeInfo, DecodeOptions, Format, Mapping, Messages};
+use common::{eyre::Result, once_cell::sync::Lazy, regex::Regex};
+use schema::{Article, Node, NodeId};
+
+/// Apply a change sent by the client to the source of a document
+///
+/// If the change has no range then it replaces the whole source.
+pub(super) fn apply_change(source: &mut String, change: TextDocumentContentChangeEvent) {
+    match change.range {
+        Some(range) => {
+            let start = byte_index(source, range.start);
+            let end = byte_index(source, range.end).max(start);
+            source.replace_range(start..end, &change.text);
+        }
+        None => *source = change.text,
+    }
+}
+
+/// Get the byte index of a (UTF-16 based) position in a string
+///
+/// Positions beyond the end of a line, or of the string, are clamped to those ends.
+fn byte_index(source: &str, position: Position) -> usize {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match source[line_start..].find('\n') {
+            Some(index) => line_start += index + 1,
+            None => return source.len(),
+        }
+    }
+
+    let line = &source[line_start..];
+    let line = line.find('\n').map_or(line, |index| &line[..index]);
+
+    let mut count = 0;
+    for (index, char) in line.char_indices() {
+        if count >= position.character as usize {
+            return line_start + index;
+        }
+        count += char.len_utf16();
+    }
+
+    line_start + line.len()
+}
+
+/// A decoded source of a document
+///
+/// Retained between updates so that the next source can be decoded incrementally.
+pub(super) struct Decoded {
+    /// The source that was decoded
+    pub source: String,
+
+    /// The node decoded from the source
+    pub node: Node,
+
+    /// The mapping between character indices in the source and nodes
+    mapping: Mapping,
+
+    /// The range of top-level blocks in the previous node that were replaced
+    /// by a range of blocks in this node, if decoded incrementally
+    pub blocks: Option<(IndexRange<usize>, IndexRange<usize>)>,
+}
+
+/// Decode a source, incrementally if possible
+///
+/// Falls back to decoding the whole source if there is no previous decoding,
+/// if the format is not Markdown-based, or the change can not be isolated
+/// to a range of top-level blocks.
+pub(super) async fn decode(
+    source: String,
+    format: &Format,
+    previous: Option<Decoded>,
+) -> Result<(Decoded, Messages)> {
+    if let Some(previous) = previous {
+        if format.is_markdown_flavor() {
+            if let Some(result) = decode_blocks(&source, format, previous).await? {
+                return Ok(result);
+            }
+        }
+    }
+
+    let (
+        node,
+        DecodeInfo {
+            messages, mapping, ..
+        },
+    ) = decode_str(&source, format).await?;
+
+    Ok((
+        Decoded {
+            source,
+            node,
+            mapping,
+            blocks: None,
+        },
+        messages,
+    ))
+}
+
+/// Decode a string in a format
+async fn decode_str(source: &str, format: &Format) -> Result<(Node, DecodeInfo)> {
+    codecs::from_str_with_info(
+        source,
+        Some(DecodeOptions {
+            format: Some(format.clone()),
+            ..Default::default()
+        }),
+    )
+    .await
+}
+
+/// Decode only the top-level blocks affected by a change to the source
+///
+/// The changed range of the source is determined from the common prefix and suffix
+/// of the previous and new sources. The blocks spanning that range (plus one block
+/// on either side, in case the change joins or splits blocks) are re-decoded and
+/// spliced into the previously decoded article, and the mapping shifted accordingly.
+///
+/// Returns `None` if the change can not be isolated to a range of blocks (e.g. it
+/// is within front matter before the first block), if it touches lines which may
+/// open or close blocks spanning other blocks (code and math fences, and colon divs),
+/// or if the source has link reference or footnote definitions (which may be used
+/// by blocks that are not re-decoded).
+async fn decode_blocks(
+    source: &str,
+    format: &Format,
+    previous: Decoded,
+) -> Result<Option<(Decoded, Messages)>> {
+    let Decoded {
+        source: old_source,
+        node: Node::Article(mut article),
+        mapping,
+        ..
+    } = previous
+    else {
+        return Ok(None);
+    };
+
+    if article.content.is_empty() {
+        return Ok(None);
+    }
+
+    // Get the range of characters changed in the previous source
+    let old_len = old_source.chars().count();
+    let new_len = source.chars().count();
+    let prefix = old_source
+        .chars()
+        .zip(source.chars())
+        .take_while(|(old, new)| old == new)
+        .count();
+    let suffix = old_source
+        .chars()
+        .rev()
+        .zip(source.chars().rev())
+        .take(old_len.min(new_len) - prefix)
+        .take_while(|(old, new)| old == new)
+        .count();
+    let changed_end = old_len - suffix;
+
+    if DEFINITION.is_match(source)
+        || touched_lines(&old_source, prefix..changed_end).any(is_fence)
+        || touched_lines(source, prefix..(new_len - suffix)).any(is_fence)
+    {
+        return Ok(None);
+    }
+
+    // Get the ranges of the top-level blocks
+    let mut ranges: HashMap<&NodeId, IndexRange<usize>> = HashMap::new();
+    for entry in mapping.entries() {
+        if entry.property.is_none() && entry.authorship.is_none() {
+            ranges
+                .entry(&entry.node_id)
+                .or_insert_with(|| entry.range.clone());
+        }
+    }
+    let Some(ranges) = article
+        .content
+        .iter()
+        .map(|block| ranges.get(&block.node_id()?).cloned())
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Ok(None);
+    };
+
+    // Get the indices of the first and last affected blocks
+    let Some(first) = ranges.iter().rposition(|range| range.start <= prefix) else {
+        return Ok(None);
+    };
+    let first = first.saturating_sub(1);
+    let last = ranges
+        .iter()
+        .position(|range| range.end >= changed_end)
+        .map_or(ranges.len() - 1, |last| (last + 1).min(ranges.len() - 1));
+
+    // Get the range of characters to re-decode in the previous and new sources
+    // (if the last block is affected, extend to the end of the source)
+    let start = ranges[first].start;
+    let old_end = if last == ranges.len() - 1 {
+        old_len
+    } else {
+        ranges[last].end
+    };
+    let Some(new_end) = (old_end + new_len).checked_sub(old_len) else {
+        return Ok(None);
+    };
+    if new_end < start {
+        return Ok(None);
+    }
+
+    // Decode the blocks in the new source. Fallback to decoding the whole source
+    // if the blocks start with a thematic break since that could be
+    // confused with the start of front matter.
+    let bytes = byte_range(source, start..new_end);
+    let slice = &source[bytes.clone()];
+    if slice.starts_with("---") {
+        return Ok(None);
+    }
+    let (
+        Node::Article(Article {
+            content: blocks, ..
+        }),
+        DecodeInfo {
+            messages: mut slice_messages,
+            mapping: slice_mapping,
+            ..
+        },
+    ) = decode_str(slice, format).await?
+    else {
+        return Ok(None);
+    };
+
+    // Splice the new blocks into the article
+    let count = blocks.len();
+    article.content.splice(first..=last, blocks);
+
+    // Create a new mapping with entries before the re-decoded blocks unchanged,
+    // those for the new blocks shifted by the start, and those after shifted by
+    // the change in length
+    let mut new_mapping = Mapping::none();
+    for entry in mapping.entries() {
+        if entry.range.end <= start {
+            new_mapping.add(
+                entry.range.start,
+                entry.range.end,
+                entry.node_type,
+                entry.node_id.clone(),
+                entry.property,
+                entry.authorship,
+            );
+        }
+    }
+    for entry in slice_mapping.entries() {
+        new_mapping.add(
+            entry.range.start + start,
+            entry.range.end + start,
+            entry.node_type,
+            entry.node_id.clone(),
+            entry.property,
+            entry.authorship,
+        );
+    }
+    for entry in mapping.entries() {
+        if entry.range.start >= old_end {
+            new_mapping.add(
+                entry.range.start + new_len - old_len,
+                entry.range.end + new_len - old_len,
+                entry.node_type,
+                entry.node_id.clone(),
+                entry.property,
+                entry.authorship,
+            );
+        }
+    }
+
+    // Make the lines of decoding messages relative to the whole source
+    let lines = source[..bytes.start].matches('\n').count();
+    for message in slice_messages.iter_mut() {
+        message.start_line = message.start_line.map(|line| line + lines);
+        message.end_line = message.end_line.map(|line| line + lines);
+    }
+
+    Ok(Some((
+        Decoded {
+            source: source.to_string(),
+            node: Node::Article(article),
+            mapping: new_mapping,
+            blocks: Some((first..(last + 1), first..(first + count))),
+        },
+        slice_messages,
+    )))
+}
+
+/// A link reference or footnote definition
+static DEFINITION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^ {0,3}\[[^\]]+\]:").expect("invalid regex"));
+
+/// Get the lines of a source touched by a range of character indices
+fn touched_lines(source: &str, range: IndexRange<usize>) -> impl Iterator<Item = &str> {
+    let bytes = byte_range(source, range);
+    let start = source[..bytes.start]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+    let end = source[bytes.end..]
+        .find('\n')
+        .map_or(source.len(), |index| bytes.end + index);
+    source[start..end].lines()
+}
+
+/// Whether a line is a fence which may open or close a block spanning other blocks
+fn is_fence(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("```")
+        || line.starts_with("~~~")
+        || line.starts_with(":::")
+        || line.starts_with("$$")
+}
+
+/// Convert a range of character indices in a string to a range of byte indices
+fn byte_range(source: &str, range: IndexRange<usize>) -> IndexRange<usize> {
+    let byte_index = |chars: usize| {
+        source
+            .char_indices()
+            .nth(chars)
+            .map_or(source.len(), |(index, ..)| index)
+    };
+    byte_index(range.start)..byte_index(range.end)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::futures::executor::block_on;
+    use common_dev::{
+        pretty_assertions::assert_eq,
+        proptest::{
+            prelude::{prop, proptest, ProptestConfig},
+            sample::select,
+        },
+    };
+
+    use super::*;
+
+    /// Blocks from which sources are generated
+    const BLOCKS: &[&str] = &[
+        "Paragraph one.",
+        "A paragraph\nover two lines.",
+        "# Heading",
+        "## Héading two",
+        "- item one\n- item two",
+        "1. first\n2. second",
+        "> A quote",
+        "```python\nx = 1\n\ny = 2\n```",
+        "::: note\n\nA note.\n\n:::",
+        "$$\nx^2\n$$",
+        "A [link][ref] and a footnote[^1].",
+        "[ref]: https://example.org",
+        "[^1]: A footnote.",
+        "***",
+    ];
+
+    /// Text that is inserted into sources
+    const INSERTS: &[&str] = &[
+        "",
+        "x",
+        "é",
+        " more words",
+        "\n",
+        "\n\n",
+        "# ",
+        "- ",
+        "```",
+        "\n```\n",
+        ":::",
+        "\n::: note\n",
+        "$$",
+        "[^1]: Another footnote.\n",
+        "[ref]: https://example.com\n",
+        "\n\nNew paragraph.\n\n",
+    ];
+
+    /// Apply an edit at a proportion of the way through a source
+    fn edit(source: &str, at: f64, delete: usize, insert: &str) -> String {
+        let len = source.chars().count();
+        let start = ((len as f64) * at) as usize;
+        let end = (start + delete).min(len);
+        let bytes = byte_range(source, start..end);
+
+        let mut source = source.to_string();
+        source.replace_range(bytes, insert);
+        source
+    }
+
+    /// Decode a source fully
+    fn decode_full(source: &str) -> Node {
+        block_on(decode(source.to_string(), &Format::Markdown, None))
+            .expect("should decode")
+            .0
+            .node
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(200))]
+
+        /// Incrementally decoding a sequence of edits is the same as decoding fully
+        #[test]
+        fn incremental_equals_full(
+            blocks in prop::collection::vec(select(BLOCKS), 1..8),
+            edits in prop::collection::vec((0.0..=1.0f64, 0..12usize, select(INSERTS)), 1..4)
+        ) {
+            let mut source = blocks.join("\n\n") + "\n";
+            let (mut previous, ..) = block_on(decode(source.clone(), &Format::Markdown, None))
+                .expect("should decode");
+
+            for (at, delete, insert) in edits {
+                source = edit(&source, at, delete, insert);
+                let (decoded, ..) = block_on(decode(source.clone(), &Format::Markdown, Some(previous)))
+                    .expect("should decode");
+
+                assert_eq!(decoded.node, decode_full(&source), "source:\n{source}");
+                previous = decoded;
+            }
+        }
+    }
+
+    /// Edits touching fences or definitions fallback to a full decode
+    #[test]
+    fn fallbacks() {
+        let source = "Para one.\n\nPara two.\n\nPara three.\n";
+        let decoded = |old: &str, new: &str| {
+            block_on(async {
+                let (previous, ..) = decode(old.to_string(), &Format::Markdown, None).await?;
+                decode(new.to_string(), &Format::Markdown, Some(previous)).await
+            })
+            .expect("should decode")
+            .0
+        };
+
+        let new = source.replace("two", "2");
+        assert_eq!(decoded(source, &new).blocks, Some((0..3, 0..3)));
+
+        let new = source.replace("Para two.", "```\nPara two.");
+        assert_eq!(decoded(source, &new).blocks, None);
+
+        let new = source.replace("Para two.", "::: note\nPara two.");
+        assert_eq!(decoded(source, &new).blocks, None);
+
+        let new = source.replace("Para two.", "[^1]: Para two.");
+        assert_eq!(decoded(source, &new).blocks, None);
+    }
+}
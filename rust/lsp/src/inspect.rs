@@ -438,6 +438,7 @@ impl Inspect for CodeChunk {
             outputs: self.outputs.as_ref().map(|outputs| outputs.len()),
             messages: self.options.execution_messages.clone(),
             code_range,
+            code_language: self.programming_language.clone(),
             ..Default::default()
         });
 
@@ -474,6 +475,7 @@ impl Inspect for CodeExpression {
             messages: self.options.execution_messages.clone(),
             outputs: self.output.is_some().then_some(1),
             code_range,
+            code_language: self.programming_language.clone(),
             ..Default::default()
         });
 
@@ -567,7 +569,14 @@ impl Inspect for ForBlock {
 
         let provenance = self.provenance.clone();
 
-        inspector.enter_node(self.node_type(), node_id, None, None, execution, provenance);
+        let variable = inspector
+            .poshmap
+            .node_property_to_range16(&node_id, NodeProperty::Variable)
+            .map(|range| (self.variable.clone(), range16_to_range(range)));
+
+        let node =
+            inspector.enter_node(self.node_type(), node_id, None, None, execution, provenance);
+        node.variable = variable;
         inspector.visit(self);
         inspector.exit_node();
     }
@@ -732,7 +741,6 @@ default!(
     ThematicBreak,
     // Inlines
     AudioObject,
-    Button,
     Cite,
     CiteGroup,
     CodeInline,
@@ -853,7 +861,33 @@ macro_rules! executable {
     };
 }
 
-executable!(CallBlock, IfBlock, Parameter, InstructionInline);
+executable!(Button, CallBlock, IfBlock, InstructionInline);
+
+impl Inspect for Parameter {
+    fn inspect(&self, inspector: &mut Inspector) {
+        let node_id = self.node_id();
+
+        let execution = Some(TextNodeExecution {
+            mode: self.execution_mode.clone(),
+            status: self.options.execution_status.clone(),
+            required: self.options.execution_required.clone(),
+            duration: self.options.execution_duration.clone(),
+            ended: self.options.execution_ended.clone(),
+            messages: self.options.execution_messages.clone(),
+            ..Default::default()
+        });
+
+        let variable = inspector
+            .poshmap
+            .node_property_to_range16(&node_id, NodeProperty::Name)
+            .map(|range| (self.name.clone(), range16_to_range(range)));
+
+        let node = inspector.enter_node(self.node_type(), node_id, None, None, execution, None);
+        node.variable = variable;
+        inspector.visit(self);
+        inspector.exit_node();
+    }
+}
 
 /// Implementation for executable nodes but not recursing into
 /// `content` to avoid lenses for content not rendered to Markdown
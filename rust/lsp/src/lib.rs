@@ -4,6 +4,7 @@ use async_lsp::{lsp_types::Url, ClientSocket};
 
 use common::{eyre::Result, serde::Deserialize, serde_json, tracing};
 
+mod code_action;
 mod code_lens;
 mod commands;
 mod completion;
@@ -12,6 +13,7 @@ mod diagnostics;
 mod dom;
 mod formatting;
 mod hover;
+mod incremental;
 mod inspect;
 mod kernels_;
 mod lifecycle;
@@ -22,6 +24,7 @@ mod run;
 mod symbols;
 mod text_document;
 mod utils;
+mod variables;
 
 pub use run::run;
 use schema::{Organization, Person};
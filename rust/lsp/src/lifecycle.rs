@@ -6,17 +6,17 @@ use std::{ops::ControlFlow, process};
 
 use async_lsp::{
     lsp_types::{
-        CodeLensOptions, CompletionOptions, DocumentSymbolOptions, ExecuteCommandOptions,
-        HoverProviderCapability, InitializeResult, InitializedParams, MessageType, OneOf,
-        ServerCapabilities, ServerInfo, ShowMessageParams, TextDocumentSyncCapability,
-        TextDocumentSyncKind, WorkDoneProgressOptions,
+        CodeActionProviderCapability, CodeLensOptions, CompletionOptions, DocumentSymbolOptions,
+        ExecuteCommandOptions, HoverProviderCapability, InitializeResult, InitializedParams,
+        MessageType, OneOf, ServerCapabilities, ServerInfo, ShowMessageParams,
+        TextDocumentSyncCapability, TextDocumentSyncKind, WorkDoneProgressOptions,
     },
     Error, LanguageClient, ResponseError,
 };
 
 use common::serde_json;
 
-use crate::{commands, ServerState, ServerStatus};
+use crate::{code_action, commands, ServerState, ServerStatus};
 
 pub const STENCILA_VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -41,7 +41,9 @@ pub(super) async fn initialize() -> Result<InitializeResult, ResponseError> {
             version: Some(STENCILA_VERSION.to_string()),
         }),
         capabilities: ServerCapabilities {
-            text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+            text_document_sync: Some(TextDocumentSyncCapability::Kind(
+                TextDocumentSyncKind::INCREMENTAL,
+            )),
             document_symbol_provider: Some(OneOf::Right(DocumentSymbolOptions {
                 label: Some("Nodes".to_string()),
                 work_done_progress_options: WorkDoneProgressOptions {
@@ -55,7 +57,11 @@ pub(super) async fn initialize() -> Result<InitializeResult, ResponseError> {
             code_lens_provider: Some(CodeLensOptions {
                 resolve_provider: Some(true),
             }),
+            code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
             hover_provider: Some(HoverProviderCapability::Simple(true)),
+            definition_provider: Some(OneOf::Left(true)),
+            references_provider: Some(OneOf::Left(true)),
+            rename_provider: Some(OneOf::Left(true)),
             execute_command_provider: Some(ExecuteCommandOptions {
                 commands: commands::commands(),
                 ..Default::default()
@@ -76,9 +82,11 @@ pub(super) fn initialized(
 
 /// Shutdown the language server
 ///
-/// Currently does nothing except change the status of the server.
+/// Changes the status of the server and deletes any temporary files
+/// that were created to show the diffs of suggestions.
 pub(super) fn shutdown(state: &mut ServerState) -> Result<(), ResponseError> {
     state.status = ServerStatus::Shutdown;
+    code_action::remove_diff_files();
 
     Ok(())
 }
@@ -91,6 +99,10 @@ pub(super) fn exit(state: &mut ServerState) -> ControlFlow<Result<(), Error>> {
         ServerStatus::Running => 1,
         ServerStatus::Shutdown => 0,
     };
+
+    // In case the client exits without requesting shutdown
+    code_action::remove_diff_files();
+
     process::exit(code);
 
     #[allow(unreachable_code)]
@@ -13,8 +13,9 @@ use common::serde_json;
 use tracing_subscriber::filter::LevelFilter;
 
 use crate::{
-    code_lens, commands, completion, content, dom, formatting, hover, kernels_, lifecycle, logging,
-    models_, prompts_, symbols, text_document, ServerState, ServerStatus,
+    code_action, code_lens, commands, completion, content, dom, formatting, hover, kernels_,
+    lifecycle, logging, models_, prompts_, symbols, text_document, variables, ServerState,
+    ServerStatus,
 };
 
 /// Run the language server
@@ -83,6 +84,20 @@ pub async fn run(log_level: LevelFilter, log_filter: &str) {
             })
             .request::<request::CodeLensResolve, _>(|_, code_lens| code_lens::resolve(code_lens));
 
+        router.request::<request::CodeActionRequest, _>(|state, params| {
+            let uri = &params.text_document.uri;
+            let root = state
+                .documents
+                .get(uri)
+                .map(|text_doc| text_doc.root.clone());
+            async move {
+                match root {
+                    Some(root) => code_action::request(params, root).await,
+                    None => Ok(None),
+                }
+            }
+        });
+
         router.request::<request::HoverRequest, _>(|state, params| {
             let uri = params
                 .text_document_position_params
@@ -101,6 +116,55 @@ pub async fn run(log_level: LevelFilter, log_filter: &str) {
             }
         });
 
+        router
+            .request::<request::GotoDefinition, _>(|state, params| {
+                let uri = params
+                    .text_document_position_params
+                    .text_document
+                    .uri
+                    .clone();
+                let source_root = state
+                    .documents
+                    .get(&uri)
+                    .map(|text_doc| (text_doc.source.clone(), text_doc.root.clone()));
+                async move {
+                    match source_root {
+                        Some((source, root)) => {
+                            variables::definition(params, uri, source, root).await
+                        }
+                        None => Ok(None),
+                    }
+                }
+            })
+            .request::<request::References, _>(|state, params| {
+                let uri = params.text_document_position.text_document.uri.clone();
+                let source_root = state
+                    .documents
+                    .get(&uri)
+                    .map(|text_doc| (text_doc.source.clone(), text_doc.root.clone()));
+                async move {
+                    match source_root {
+                        Some((source, root)) => {
+                            variables::references(params, uri, source, root).await
+                        }
+                        None => Ok(None),
+                    }
+                }
+            })
+            .request::<request::Rename, _>(|state, params| {
+                let uri = params.text_document_position.text_document.uri.clone();
+                let source_root = state
+                    .documents
+                    .get(&uri)
+                    .map(|text_doc| (text_doc.source.clone(), text_doc.root.clone()));
+                async move {
+                    match source_root {
+                        Some((source, root)) => variables::rename(params, uri, source, root).await,
+                        None => Ok(None),
+                    }
+                }
+            });
+
         router
             .request::<request::ExecuteCommand, _>(|state, params| {
                 let doc_props = params
@@ -14,10 +14,7 @@ use async_lsp::{
     ClientSocket, Error, ErrorCode, LanguageClient, ResponseError,
 };
 
-use codecs::{
-    DecodeInfo, DecodeOptions, EncodeInfo, EncodeOptions, Format, LossesResponse, MessageLevel,
-    Messages,
-};
+use codecs::{EncodeInfo, EncodeOptions, Format, LossesResponse, MessageLevel, Messages};
 use common::{
     eyre::{bail, Report},
     tokio::{
@@ -33,7 +30,7 @@ use schema::{
     Visitor,
 };
 
-use crate::{diagnostics, inspect::Inspector, ServerState};
+use crate::{diagnostics, incremental, inspect::Inspector, ServerState};
 
 /// A Stencila `Node` within a `TextDocument`
 ///
@@ -48,7 +45,6 @@ pub(super) struct TextNode {
     pub parent_type: NodeType,
 
     /// The id of the parent of the node
-    #[allow(unused)]
     pub parent_id: NodeId,
 
     /// The type of the node
@@ -83,6 +79,12 @@ pub(super) struct TextNode {
     /// Whether the node is active (currently for `IfBlockClause` nodes only)
     pub is_active: Option<bool>,
 
+    /// The name, and range, of the variable that the node declares
+    ///
+    /// Currently used only for `Parameter` and `ForBlock` nodes to
+    /// provide definitions of variables.
+    pub variable: Option<(String, Range)>,
+
     /// Provenance details (for nodes with a `provenance` field)
     ///
     /// These detail are used to publish provenance summaries
@@ -105,6 +107,7 @@ pub(super) struct TextNodeExecution {
     pub outputs: Option<usize>,
     pub messages: Option<Vec<ExecutionMessage>>,
     pub code_range: Option<Range>,
+    pub code_language: Option<String>,
     pub authors: Option<Vec<Author>>,
 }
 
@@ -121,6 +124,7 @@ impl Default for TextNode {
             execution: None,
             index_of: None,
             is_active: None,
+            variable: None,
             provenance: None,
             children: Vec::new(),
         }
@@ -300,6 +304,13 @@ pub(super) struct TextDocument {
     /// The source text of the document e.g. Markdown
     pub source: Arc<RwLock<String>>,
 
+    /// The text of the document with all changes from the client applied
+    ///
+    /// Unlike `source`, which is updated in the `update_task` after debouncing,
+    /// this is updated synchronously so that incremental changes can be applied
+    /// in the order that they are received.
+    text: String,
+
     /// The root node in the text document
     ///
     /// This is updated in the `update_task`.
@@ -350,7 +361,7 @@ impl TextDocument {
 
         let watch_receiver = doc.watch();
 
-        let source_string = source.clone();
+        let text = source.clone();
 
         let source = Arc::new(RwLock::new(source));
         let root = Arc::new(RwLock::new(TextNode::default()));
@@ -379,7 +390,7 @@ impl TextDocument {
             });
         }
 
-        if let Err(error) = update_sender.send(source_string) {
+        if let Err(error) = update_sender.send(text.clone()) {
             tracing::error!("While sending initial source: {error}");
         }
 
@@ -387,6 +398,7 @@ impl TextDocument {
             author,
             format,
             source,
+            text,
             root,
             doc,
             update_sender,
@@ -421,6 +433,10 @@ impl TextDocument {
         let (messages_sender, messages_receiver) = mpsc::channel(24);
         tokio::spawn(async move { Self::diagnostics_task(messages_receiver, uri, client).await });
 
+        // The previous decoding of the source, if it had no errors, used
+        // to decode only the blocks affected by changes to the source
+        let mut previous = None;
+
         let mut latest_source = None;
         loop {
             // Debounce updates
@@ -449,21 +465,14 @@ impl TextDocument {
             *source.write().await = new_source.clone();
 
             // Decode the source into a node
-            let (node, DecodeInfo { messages, .. }) = match codecs::from_str_with_info(
-                &new_source,
-                Some(DecodeOptions {
-                    format: Some(format.clone()),
-                    ..Default::default()
-                }),
-            )
-            .await
-            {
-                Ok(node) => node,
-                Err(error) => {
-                    tracing::error!("While decoding document: {error}");
-                    continue;
-                }
-            };
+            let (decoded, messages) =
+                match incremental::decode(new_source, &format, previous.take()).await {
+                    Ok(decoded) => decoded,
+                    Err(error) => {
+                        tracing::error!("While decoding document: {error}");
+                        continue;
+                    }
+                };
             let errors = messages
                 .iter()
                 .any(|message| matches!(message.level, MessageLevel::Error));
@@ -478,16 +487,22 @@ impl TextDocument {
                 continue;
             }
 
-            // Update the Stencila document with the new node
+            // Update the Stencila document with the new node, only merging
+            // the re-decoded blocks if the source was decoded incrementally
+            let node = decoded.node.clone();
+            let blocks = decoded.blocks.clone();
+            previous = Some(decoded);
             let doc = doc.write().await;
-            if let Err(error) = doc
-                .update(
-                    node.clone(),
-                    Some(format.clone()),
-                    Some(vec![author_role.clone()]),
-                )
-                .await
-            {
+            let format = Some(format.clone());
+            let authors = Some(vec![author_role.clone()]);
+            let result = match blocks {
+                Some((replaced, replacement)) => {
+                    doc.update_blocks(node, replaced, replacement, format, authors)
+                        .await
+                }
+                None => doc.update(node, format, authors).await,
+            };
+            if let Err(error) = result {
                 tracing::error!("While updating node: {error}");
             }
         }
@@ -656,10 +671,12 @@ pub(super) fn did_change(
 ) -> ControlFlow<Result<(), Error>> {
     let uri = params.text_document.uri;
     if let Some(text_doc) = state.documents.get_mut(&uri) {
-        // TODO: This assumes a whole document change (with TextDocumentSyncKind::FULL in initialize):
-        // needs more defensiveness and potentially implement incremental sync
-        let source = params.content_changes[0].text.clone();
-        if let Err(error) = text_doc.update_sender.send(source) {
+        // Apply the changes in order (with TextDocumentSyncKind::INCREMENTAL in initialize
+        // each change is a range edit, or, if it has no range, the whole document)
+        for change in params.content_changes {
+            incremental::apply_change(&mut text_doc.text, change);
+        }
+        if let Err(error) = text_doc.update_sender.send(text_doc.text.clone()) {
             tracing::error!("While sending updated source: {error}");
         }
     } else {
@@ -0,0 +1,510 @@
+//! Handling of requests for the definition, references, and renaming of variables
+//!
+//! See https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#textDocument_definition,
+//! https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#textDocument_references, and
+//! https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#textDocument_rename
+//!
+//! Variables are found within the code of the `CodeChunk`s and `CodeExpression`s in the
+//! document (using the code ranges collected by the `Inspector`). Because variables can be
+//! mirrored between kernels, occurrences in code of all languages are considered.
+//!
+//! The code of each node is parsed, as when the document is compiled, to determine the
+//! variables that it uses and assigns. Nodes which neither use nor assign a variable
+//! are ignored and only those which assign it provide definitions. The ranges of
+//! occurrences within the code are located using the syntax of the node's language
+//! (so that strings, comments and attributes are ignored). `Parameter`s and `ForBlock`s
+//! also provide definitions of the variables that they declare.
+
+use std::{collections::HashMap, sync::Arc};
+
+use async_lsp::{
+    lsp_types::{
+        GotoDefinitionParams, GotoDefinitionResponse, Location, Position, Range, ReferenceParams,
+        RenameParams, TextEdit, Url, WorkspaceEdit,
+    },
+    ErrorCode, ResponseError,
+};
+
+use codecs::Format;
+use common::tokio::sync::RwLock;
+use parsers::{is_identifier, is_identifier_char, Syntax};
+use schema::NodeType;
+
+use crate::text_document::TextNode;
+
+/// An occurrence of a variable in the document
+struct Occurrence {
+    /// The range of the variable name in the document
+    range: Range,
+
+    /// Whether the occurrence is a definition of the variable
+    is_definition: bool,
+
+    /// The language of the code that the occurrence is in
+    ///
+    /// `None` for variables declared by `Parameter`s and `ForBlock`s.
+    language: Option<String>,
+}
+
+/// Handle a request to go to the definition of the variable at a position
+///
+/// Responds with the closest definition of the variable before the position,
+/// or if there are none, all the definitions of it in the document.
+pub(super) async fn definition(
+    params: GotoDefinitionParams,
+    uri: Url,
+    source: Arc<RwLock<String>>,
+    root: Arc<RwLock<TextNode>>,
+) -> Result<Option<GotoDefinitionResponse>, ResponseError> {
+    let position = params.text_document_position_params.position;
+
+    let source = source.read().await;
+    let root = root.read().await;
+    let Some((.., occurrences)) = variable_at(&source, &root, position) else {
+        return Ok(None);
+    };
+
+    let definitions: Vec<&Occurrence> = occurrences
+        .iter()
+        .filter(|occurrence| occurrence.is_definition)
+        .collect();
+
+    let locations = match definitions
+        .iter()
+        .rev()
+        .find(|occurrence| occurrence.range.start <= position)
+    {
+        Some(closest) => vec![*closest],
+        None => definitions,
+    }
+    .into_iter()
+    .map(|occurrence| Location::new(uri.clone(), occurrence.range))
+    .collect::<Vec<_>>();
+
+    Ok((!locations.is_empty()).then_some(GotoDefinitionResponse::Array(locations)))
+}
+
+/// Handle a request for the references to the variable at a position
+pub(super) async fn references(
+    params: ReferenceParams,
+    uri: Url,
+    source: Arc<RwLock<String>>,
+    root: Arc<RwLock<TextNode>>,
+) -> Result<Option<Vec<Location>>, ResponseError> {
+    let position = params.text_document_position.position;
+    let include_declaration = params.context.include_declaration;
+
+    let source = source.read().await;
+    let root = root.read().await;
+    let Some((.., occurrences)) = variable_at(&source, &root, position) else {
+        return Ok(None);
+    };
+
+    let locations = occurrences
+        .into_iter()
+        .filter(|occurrence| include_declaration || !occurrence.is_definition)
+        .map(|occurrence| Location::new(uri.clone(), occurrence.range))
+        .collect();
+
+    Ok(Some(locations))
+}
+
+/// Handle a request to rename the variable at a position
+pub(super) async fn rename(
+    params: RenameParams,
+    uri: Url,
+    source: Arc<RwLock<String>>,
+    root: Arc<RwLock<TextNode>>,
+) -> Result<Option<WorkspaceEdit>, ResponseError> {
+    let position = params.text_document_position.position;
+    let new_name = params.new_name;
+
+    if !is_identifier(&new_name) {
+        return Err(ResponseError::new(
+            ErrorCode::INVALID_PARAMS,
+            format!("`{new_name}` is not a valid variable name"),
+        ));
+    }
+
+    let source = source.read().await;
+    let root = root.read().await;
+    let Some((.., occurrences)) = variable_at(&source, &root, position) else {
+        return Ok(None);
+    };
+
+    if let Some(language) = keyword_in(&occurrences, &new_name) {
+        return Err(ResponseError::new(
+            ErrorCode::INVALID_PARAMS,
+            format!("`{new_name}` is a keyword in {language}"),
+        ));
+    }
+
+    let edits = occurrences
+        .into_iter()
+        .map(|occurrence| TextEdit::new(occurrence.range, new_name.clone()))
+        .collect();
+
+    Ok(Some(WorkspaceEdit {
+        changes: Some(HashMap::from([(uri, edits)])),
+        ..Default::default()
+    }))
+}
+
+/// Get the language, if any, of the occurrences of a variable in which a name is a keyword
+///
+/// Variables declared by parameters, and in code without a language, may be used in
+/// any kernel so names which are keywords in any of the common languages are rejected.
+fn keyword_in(occurrences: &[Occurrence], name: &str) -> Option<String> {
+    occurrences.iter().find_map(|occurrence| {
+        let language = occurrence.language.as_deref().unwrap_or_default();
+        Syntax::new(&Format::from_name(language))
+            .is_keyword(name)
+            .then(|| {
+                if language.is_empty() {
+                    "a common language".to_string()
+                } else {
+                    format!("`{language}`")
+                }
+            })
+    })
+}
+
+/// Get the name of the variable at a position, and all its occurrences in the document
+fn variable_at(
+    source: &str,
+    root: &TextNode,
+    position: Position,
+) -> Option<(String, Vec<Occurrence>)> {
+    let contains = |range: &Range| position >= range.start && position <= range.end;
+
+    let mut code_ranges: Vec<(Range, String)> = Vec::new();
+    let mut declarations: Vec<(String, Range)> = Vec::new();
+    for node in root.flatten() {
+        if let Some(variable) = &node.variable {
+            declarations.push(variable.clone());
+        }
+
+        if matches!(
+            node.node_type,
+            NodeType::CodeChunk | NodeType::CodeExpression
+        ) {
+            if let Some(execution) = &node.execution {
+                if let Some(range) = execution.code_range {
+                    let language = execution.code_language.clone().unwrap_or_default();
+                    code_ranges.push((range, language));
+                }
+            }
+        }
+    }
+
+    let lines: Vec<&str> = source.lines().collect();
+
+    // The position must be within a declaration or code
+    let name = match declarations.iter().find(|(.., range)| contains(range)) {
+        Some((name, ..)) => name.clone(),
+        None => {
+            code_ranges.iter().find(|(range, ..)| contains(range))?;
+            identifier_at(lines.get(position.line as usize)?, position.character)?
+        }
+    };
+
+    let occurrences: Vec<Occurrence> = declarations
+        .iter()
+        .filter(|(declared, ..)| declared == &name)
+        .map(|(.., range)| Occurrence {
+            range: *range,
+            is_definition: true,
+            language: None,
+        })
+        .chain(
+            code_ranges
+                .iter()
+                .flat_map(|(range, language)| occurrences_in(&lines, range, language, &name)),
+        )
+        .collect();
+
+    // The position must be within one of the occurrences (e.g. not in a string or comment)
+    occurrences
+        .iter()
+        .any(|occurrence| contains(&occurrence.range))
+        .then_some((name, occurrences))
+}
+
+/// Get the identifier at a UTF-16 based column of a line
+fn identifier_at(line: &str, character: u32) -> Option<String> {
+    let index = utf16_to_byte_index(line, character);
+
+    let start = line[..index]
+        .char_indices()
+        .rev()
+        .take_while(|(.., char)| is_identifier_char(*char))
+        .last()
+        .map_or(index, |(index, ..)| index);
+    let end = line[index..]
+        .char_indices()
+        .find(|(.., char)| !is_identifier_char(*char))
+        .map_or(line.len(), |(offset, ..)| index + offset);
+
+    let name = &line[start..end];
+    is_identifier(name).then(|| name.to_string())
+}
+
+/// Find the occurrences of a variable within a range of code
+///
+/// Returns no occurrences if, when parsed, the code neither uses nor assigns the variable.
+fn occurrences_in(lines: &[&str], range: &Range, language: &str, name: &str) -> Vec<Occurrence> {
+    // Get the code on each line within the range and the byte index that it starts at
+    let mut segments = Vec::new();
+    for line_index in range.start.line..=range.end.line {
+        let Some(line) = lines.get(line_index as usize) else {
+            break;
+        };
+
+        let start = if line_index == range.start.line {
+            utf16_to_byte_index(line, range.start.character)
+        } else {
+            0
+        };
+        let end = if line_index == range.end.line {
+            utf16_to_byte_index(line, range.end.character)
+        } else {
+            line.len()
+        };
+
+        segments.push((line_index, line, start, &line[start..end.max(start)]));
+    }
+
+    let code = segments
+        .iter()
+        .map(|(.., code)| *code)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let info = parsers::parse(&code, language);
+    let assigns = info.assigns.iter().any(|assigned| assigned == name);
+    if !assigns && !info.uses.iter().any(|used| used == name) {
+        return Vec::new();
+    }
+
+    let syntax = Syntax::new(&Format::from_name(language));
+    let mut occurrences = Vec::new();
+    let mut segment_start = 0;
+    let mut segments = segments.into_iter().peekable();
+    for identifier in syntax.identifiers(&code) {
+        if identifier.name != name {
+            continue;
+        }
+
+        // Advance to the segment (i.e. line) containing the identifier
+        while let Some((.., segment)) = segments.peek() {
+            if identifier.index <= segment_start + segment.len() {
+                break;
+            }
+            segment_start += segment.len() + 1;
+            segments.next();
+        }
+        let Some((line_index, line, start, ..)) = segments.peek() else {
+            break;
+        };
+
+        let start = byte_to_utf16_index(line, start + identifier.index - segment_start);
+        let end = start + name.encode_utf16().count() as u32;
+
+        occurrences.push(Occurrence {
+            range: Range::new(
+                Position::new(*line_index, start),
+                Position::new(*line_index, end),
+            ),
+            // Only a definition if the parser also determines that the code assigns the variable
+            is_definition: identifier.is_assignment && assigns,
+            language: Some(language.to_string()),
+        });
+    }
+
+    occurrences
+}
+
+/// Convert a UTF-16 based column to a byte index in a line
+fn utf16_to_byte_index(line: &str, character: u32) -> usize {
+    let mut count = 0;
+    for (index, char) in line.char_indices() {
+        if count >= character as usize {
+            return index;
+        }
+        count += char.len_utf16();
+    }
+    line.len()
+}
+
+/// Convert a byte index in a line to a UTF-16 based column
+fn byte_to_utf16_index(line: &str, index: usize) -> u32 {
+    line[..index].encode_utf16().count() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use common_dev::pretty_assertions::assert_eq;
+
+    use crate::text_document::TextNodeExecution;
+
+    use super::*;
+
+    fn range(start: (u32, u32), end: (u32, u32)) -> Range {
+        Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1))
+    }
+
+    /// Summarize occurrences as their start, and whether they are definitions
+    fn summary(occurrences: &[Occurrence]) -> Vec<(u32, u32, bool)> {
+        occurrences
+            .iter()
+            .map(|occurrence| {
+                (
+                    occurrence.range.start.line,
+                    occurrence.range.start.character,
+                    occurrence.is_definition,
+                )
+            })
+            .collect()
+    }
+
+    /// Get the occurrences of a variable in code
+    fn occurrences(code: &str, lang: &str, name: &str) -> Vec<(u32, u32, bool)> {
+        let lines: Vec<&str> = code.lines().collect();
+        let last = lines.len() as u32 - 1;
+        let range = range((0, 0), (last, lines[last as usize].len() as u32));
+        summary(&occurrences_in(&lines, &range, lang, name))
+    }
+
+    #[test]
+    fn skips_strings_and_comments() {
+        assert_eq!(
+            occurrences("x = 1\nprint('x', x) # x", "python", "x"),
+            vec![(0, 0, true), (1, 11, false)]
+        );
+
+        assert_eq!(
+            occurrences("let x = 1; // x\n/* x */ console.log(\"x\", x)", "js", "x"),
+            vec![(0, 4, true), (1, 25, false)]
+        );
+
+        // Without a language, all common comment syntaxes are ignored
+        assert_eq!(
+            occurrences("x <- 1 # x\nx + 1 // x", "", "x"),
+            vec![(0, 0, true), (1, 0, false)]
+        );
+
+        // Attributes are not occurrences
+        assert_eq!(occurrences("y = obj.x", "python", "x"), vec![]);
+    }
+
+    #[test]
+    fn definitions() {
+        // Keyword arguments are not definitions
+        assert_eq!(
+            occurrences("f(x = 1)\nx = 2", "r", "x"),
+            vec![(0, 2, false), (1, 0, true)]
+        );
+
+        // Functions and loop variables are
+        assert_eq!(
+            occurrences("def x():\n  for y in x():\n    pass", "python", "x"),
+            vec![(0, 4, true), (1, 11, false)]
+        );
+        assert_eq!(
+            occurrences("def x():\n  for y in x():\n    pass", "python", "y"),
+            vec![(1, 6, true)]
+        );
+    }
+
+    /// Create a document with a parameter, a for block, and a code chunk which uses them
+    fn document() -> (String, TextNode) {
+        let source = [
+            "&[n]{num}",
+            "",
+            "::: for i in [1, 2]",
+            "",
+            "```python exec",
+            "x = n * i",
+            "print(x, 'n')",
+            "```",
+        ]
+        .join("\n");
+
+        let root = TextNode {
+            children: vec![
+                TextNode {
+                    node_type: NodeType::Parameter,
+                    variable: Some(("n".to_string(), range((0, 2), (0, 3)))),
+                    ..Default::default()
+                },
+                TextNode {
+                    node_type: NodeType::ForBlock,
+                    variable: Some(("i".to_string(), range((2, 8), (2, 9)))),
+                    ..Default::default()
+                },
+                TextNode {
+                    node_type: NodeType::CodeChunk,
+                    execution: Some(TextNodeExecution {
+                        code_range: Some(range((5, 0), (6, 13))),
+                        code_language: Some("python".to_string()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        (source, root)
+    }
+
+    #[test]
+    fn declarations() {
+        let (source, root) = document();
+
+        // From within code
+        let (name, occurrences) = variable_at(&source, &root, Position::new(5, 4)).unwrap();
+        assert_eq!(name, "n");
+        assert_eq!(summary(&occurrences), vec![(0, 2, true), (5, 4, false)]);
+
+        // From the declaration itself
+        let (name, occurrences) = variable_at(&source, &root, Position::new(2, 8)).unwrap();
+        assert_eq!(name, "i");
+        assert_eq!(summary(&occurrences), vec![(2, 8, true), (5, 8, false)]);
+
+        let (name, occurrences) = variable_at(&source, &root, Position::new(6, 6)).unwrap();
+        assert_eq!(name, "x");
+        assert_eq!(summary(&occurrences), vec![(5, 0, true), (6, 6, false)]);
+
+        // Not within a string, or outside of code
+        assert!(variable_at(&source, &root, Position::new(6, 10)).is_none());
+        assert!(variable_at(&source, &root, Position::new(2, 4)).is_none());
+    }
+
+    #[test]
+    fn keywords() {
+        let (source, root) = document();
+
+        let (.., occurrences) = variable_at(&source, &root, Position::new(6, 6)).unwrap();
+        assert_eq!(
+            keyword_in(&occurrences, "lambda").as_deref(),
+            Some("`python`")
+        );
+        assert_eq!(keyword_in(&occurrences, "function"), None);
+        assert_eq!(keyword_in(&occurrences, "y"), None);
+
+        // Parameters may be used in any language
+        let (.., occurrences) = variable_at(&source, &root, Position::new(0, 2)).unwrap();
+        assert_eq!(
+            keyword_in(&occurrences, "function").as_deref(),
+            Some("a common language")
+        );
+    }
+
+    #[test]
+    fn identifiers() {
+        assert_eq!(identifier_at("foo = bar", 1).as_deref(), Some("foo"));
+        assert_eq!(identifier_at("foo = bar", 7).as_deref(), Some("bar"));
+        assert_eq!(identifier_at("foo = bar", 4), None);
+    }
+}
@@ -22,7 +22,7 @@ pub use secrets;
 mod output;
 mod task;
 pub use output::{ModelOutput, ModelOutputKind};
-pub use task::{ModelTask, ModelTaskKind};
+pub use task::{ModelTask, ModelTaskCache, ModelTaskKind};
 
 /// The type of provider of a model
 ///
@@ -62,6 +62,11 @@ pub struct ModelTask {
     /// The model selection and execution options set by the user
     pub instruction_model: Option<InstructionModel>,
 
+    /// The id of the prompt used to generate the system message for this task
+    ///
+    /// Used to select models based on scores specific to the prompt.
+    pub prompt: Option<String>,
+
     /// The list of input messages
     pub messages: Vec<InstructionMessage>,
 
@@ -195,6 +200,31 @@ pub struct ModelTask {
     /// at the last possible moment before generation (usually just before an API request is made).
     #[serde(default)]
     pub dry_run: bool,
+
+    /// The replicate number of the task
+    ///
+    /// Used to distinguish otherwise identical tasks when more than one output is
+    /// being generated for the same instruction (e.g. so that each is cached separately).
+    pub replicate: Option<u32>,
+
+    /// Options for caching the output of the task
+    #[serde(skip)]
+    pub cache: ModelTaskCache,
+}
+
+/// Options for caching the output of a model task
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ModelTaskCache {
+    /// Do not use any cached output for the task
+    ///
+    /// The output generated for the task will still be cached so
+    /// that it can be used in subsequent tasks.
+    pub bypass: bool,
+
+    /// The maximum age, in seconds, of cached output to use for the task
+    ///
+    /// If `None`, cached output is used regardless of its age.
+    pub ttl: Option<u64>,
 }
 
 impl ModelTask {
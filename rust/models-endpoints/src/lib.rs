@@ -0,0 +1,635 @@
+use std::{path::PathBuf, sync::Arc};
+
+use app::{get_app_dir, DirType};
+use cached::proc_macro::cached;
+
+use model::{
+    common::{
+        async_trait::async_trait,
+        eyre::{bail, Result},
+        futures::future::join_all,
+        itertools::Itertools,
+        reqwest::{Client, Url},
+        serde::{Deserialize, Serialize},
+        serde_with::skip_serializing_none,
+        serde_yaml,
+        tokio::fs::read_to_string,
+        tracing,
+    },
+    schema::{ImageObject, MessagePart, MessageRole},
+    secrets, Model, ModelAvailability, ModelIO, ModelOutput, ModelTask, ModelType,
+};
+
+/// The name of the default env var or secret for the API key of endpoints
+///
+/// Used for endpoints that have `api-key: true` in their configuration.
+/// Endpoints can also specify the name of some other env var or secret.
+const API_KEY: &str = "OPENAI_COMPATIBLE_API_KEY";
+
+/// The context length assumed for models that do not specify one
+const DEFAULT_CONTEXT_LENGTH: usize = 4096;
+
+/// The configuration of an OpenAI-compatible endpoint
+///
+/// Read from `<config>/models/endpoints.yaml` e.g.
+///
+/// ```yaml
+/// - name: llamacpp
+///   url: http://localhost:8080/v1
+///   models:
+///     - name: llama-3.1-8b-instruct
+///       context-length: 8192
+///
+/// - name: lab
+///   url: https://llm.example.org/v1
+///   api-key: LAB_LLM_API_KEY
+/// ```
+///
+/// If no `models` are specified then they are fetched from the
+/// `/models` route of the endpoint.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", crate = "model::common::serde")]
+pub struct EndpointConfig {
+    /// The name of the endpoint
+    ///
+    /// Used as the publisher part of the id of each model e.g. `llamacpp/llama-3.1-8b-instruct`
+    name: String,
+
+    /// The base URL of the endpoint (usually ending in `/v1`)
+    url: String,
+
+    /// The API key to use for the endpoint
+    ///
+    /// Either `true` to use the `OPENAI_COMPATIBLE_API_KEY` env var or secret,
+    /// or the name of another env var or secret.
+    api_key: Option<EndpointApiKey>,
+
+    /// The models available at the endpoint
+    #[serde(default)]
+    models: Vec<EndpointModelConfig>,
+}
+
+/// The API key to use for an endpoint
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged, crate = "model::common::serde")]
+enum EndpointApiKey {
+    Default(bool),
+    Named(String),
+}
+
+impl EndpointConfig {
+    /// Get the name of the env var or secret for the endpoint's API key, if any
+    fn api_key_name(&self) -> Option<&str> {
+        match &self.api_key {
+            Some(EndpointApiKey::Default(true)) => Some(API_KEY),
+            Some(EndpointApiKey::Named(name)) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+/// The configuration of a model available at an endpoint
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", crate = "model::common::serde")]
+struct EndpointModelConfig {
+    /// The name of the model used in requests to the endpoint
+    name: String,
+
+    /// The context length of the model
+    context_length: Option<usize>,
+
+    /// The types of input that the model consumes
+    inputs: Option<Vec<ModelIO>>,
+
+    /// The types of output that the model generates
+    outputs: Option<Vec<ModelIO>>,
+}
+
+/// Get the path of the endpoints configuration file
+pub fn config_path() -> Result<PathBuf> {
+    Ok(get_app_dir(DirType::Config, false)?
+        .join("models")
+        .join("endpoints.yaml"))
+}
+
+/// Read the endpoints configuration file
+///
+/// Returns an empty list if the file does not exist.
+async fn read_config() -> Result<Vec<EndpointConfig>> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+
+    let yaml = read_to_string(&path).await?;
+    Ok(serde_yaml::from_str(&yaml)?)
+}
+
+/// A model available at an OpenAI-compatible endpoint
+///
+/// Uses the `/chat/completions` route of the endpoint so can be used with
+/// servers such as llama.cpp, vLLM and LM Studio, or a mock server during testing.
+pub struct EndpointModel {
+    /// The configuration of the endpoint
+    endpoint: EndpointConfig,
+
+    /// The name of the model used in requests to the endpoint
+    model: String,
+
+    /// The context length of the model
+    context_length: usize,
+
+    /// The type of input that the model consumes
+    inputs: Vec<ModelIO>,
+
+    /// The type of output that the model generates
+    outputs: Vec<ModelIO>,
+
+    /// The HTTP client for accessing the endpoint
+    client: Client,
+}
+
+impl EndpointModel {
+    /// Create a model available at an endpoint
+    fn new(endpoint: EndpointConfig, config: EndpointModelConfig) -> Self {
+        Self {
+            endpoint,
+            model: config.name,
+            context_length: config.context_length.unwrap_or(DEFAULT_CONTEXT_LENGTH),
+            inputs: config.inputs.unwrap_or_else(|| vec![ModelIO::Text]),
+            outputs: config.outputs.unwrap_or_else(|| vec![ModelIO::Text]),
+            client: Client::new(),
+        }
+    }
+
+    /// Get the URL for a route of the endpoint
+    fn url(&self, route: &str) -> String {
+        [self.endpoint.url.trim_end_matches('/'), route].concat()
+    }
+}
+
+#[async_trait]
+impl Model for EndpointModel {
+    fn id(&self) -> String {
+        format!("{}/{}", self.endpoint.name, self.model)
+    }
+
+    fn r#type(&self) -> ModelType {
+        let is_local = Url::parse(&self.endpoint.url)
+            .ok()
+            .and_then(|url| url.host_str().map(String::from))
+            .map(|host| matches!(host.as_str(), "localhost" | "127.0.0.1" | "[::1]"))
+            .unwrap_or_default();
+
+        if is_local {
+            ModelType::Local
+        } else {
+            ModelType::Remote
+        }
+    }
+
+    fn availability(&self) -> ModelAvailability {
+        match self.endpoint.api_key_name() {
+            Some(name) if secrets::env_or_get(name).is_err() => ModelAvailability::RequiresKey,
+            _ => ModelAvailability::Available,
+        }
+    }
+
+    fn context_length(&self) -> usize {
+        self.context_length
+    }
+
+    fn supported_inputs(&self) -> &[ModelIO] {
+        &self.inputs
+    }
+
+    fn supported_outputs(&self) -> &[ModelIO] {
+        &self.outputs
+    }
+
+    async fn perform_task(&self, task: &ModelTask) -> Result<ModelOutput> {
+        let messages = task
+            .messages
+            .iter()
+            .map(|message| {
+                let role = message.role.clone().unwrap_or_default();
+
+                let mut parts = message
+                    .parts
+                    .iter()
+                    .filter_map(|part| match part {
+                        MessagePart::Text(text) => Some(ContentPart::Text {
+                            text: text.to_value_string(),
+                        }),
+                        MessagePart::ImageObject(ImageObject { content_url, .. })
+                            if matches!(role, MessageRole::User)
+                                && self.inputs.contains(&ModelIO::Image) =>
+                        {
+                            Some(ContentPart::ImageUrl {
+                                image_url: ImageUrl {
+                                    url: content_url.clone(),
+                                },
+                            })
+                        }
+                        _ => {
+                            tracing::warn!(
+                                "{role} message part `{part}` is ignored by model `{}`",
+                                self.id()
+                            );
+                            None
+                        }
+                    })
+                    .collect_vec();
+
+                // Use a plain string for content if possible since not
+                // all servers support arrays of content parts
+                let content = if parts
+                    .iter()
+                    .all(|part| matches!(part, ContentPart::Text { .. }))
+                {
+                    MessageContent::Text(
+                        parts
+                            .drain(..)
+                            .filter_map(|part| match part {
+                                ContentPart::Text { text } => Some(text),
+                                _ => None,
+                            })
+                            .join("\n\n"),
+                    )
+                } else {
+                    MessageContent::Parts(parts)
+                };
+
+                Message {
+                    role: role.to_string().to_lowercase(),
+                    content,
+                }
+            })
+            .collect_vec();
+
+        let request = ChatCompletionRequest {
+            model: self.model.clone(),
+            messages,
+            temperature: task.temperature,
+            top_p: task.top_p,
+            max_tokens: task.max_tokens,
+            seed: task.seed,
+            stop: task.stop.clone(),
+            presence_penalty: task.repeat_penalty,
+        };
+
+        // Warn about ignored task options
+        macro_rules! ignore_option {
+            ($name:ident) => {
+                if task.$name.is_some() {
+                    tracing::warn!(
+                        "Option `{}` is ignored by model `{}` for chat completion",
+                        stringify!($name),
+                        self.id()
+                    )
+                }
+            };
+            ($($name:ident),*) => {
+                $( ignore_option!($name); )*
+            }
+        }
+        ignore_option!(
+            mirostat,
+            mirostat_eta,
+            mirostat_tau,
+            num_ctx,
+            num_gqa,
+            num_gpu,
+            num_thread,
+            repeat_last_n,
+            tfs_z,
+            top_k
+        );
+
+        if task.dry_run {
+            return ModelOutput::empty(self);
+        }
+
+        let mut request = self
+            .client
+            .post(self.url("/chat/completions"))
+            .json(&request);
+        if let Some(name) = self.endpoint.api_key_name() {
+            request = request.bearer_auth(secrets::env_or_get(name)?);
+        }
+        let response = request.send().await?;
+
+        if let Err(error) = response.error_for_status_ref() {
+            let message = response.text().await?;
+            bail!("{error}: {message}");
+        }
+
+        let mut response: ChatCompletionResponse = response.json().await?;
+
+        // Get the content of the first choice
+        let text = if response.choices.is_empty() {
+            String::new()
+        } else {
+            response
+                .choices
+                .swap_remove(0)
+                .message
+                .content
+                .unwrap_or_default()
+        };
+
+        ModelOutput::from_text(self, &task.format, text).await
+    }
+}
+
+/// Get a list of all models available at configured endpoints
+///
+/// Endpoints for which the list of models could not be fetched
+/// are logged and ignored.
+pub async fn list() -> Result<Vec<Arc<dyn Model>>> {
+    let endpoints = read_config().await?;
+
+    let futures = endpoints.into_iter().map(|endpoint| async move {
+        let configs = if endpoint.models.is_empty() {
+            match list_endpoint_models(
+                endpoint.url.clone(),
+                endpoint.api_key_name().map(String::from),
+            )
+            .await
+            {
+                Ok(response) => response
+                    .data
+                    .into_iter()
+                    .map(|model| EndpointModelConfig {
+                        name: model.id,
+                        context_length: None,
+                        inputs: None,
+                        outputs: None,
+                    })
+                    .collect_vec(),
+                Err(error) => {
+                    tracing::warn!(
+                        "While listing models for endpoint `{}`: {error}",
+                        endpoint.name
+                    );
+                    vec![]
+                }
+            }
+        } else {
+            endpoint.models.clone()
+        };
+
+        configs
+            .into_iter()
+            .map(|config| Arc::new(EndpointModel::new(endpoint.clone(), config)) as Arc<dyn Model>)
+            .collect_vec()
+    });
+
+    Ok(join_all(futures).await.into_iter().flatten().collect())
+}
+
+/// Fetch the list of models from an endpoint
+///
+/// In-memory cached for two minutes to reduce requests but allow for
+/// models to be added to local servers while the process is running.
+#[cached(time = 120, result = true)]
+async fn list_endpoint_models(url: String, api_key: Option<String>) -> Result<ModelsResponse> {
+    let mut request = Client::new().get([url.trim_end_matches('/'), "/models"].concat());
+    if let Some(name) = api_key {
+        request = request.bearer_auth(secrets::env_or_get(&name)?);
+    }
+    let response = request.send().await?;
+
+    if let Err(error) = response.error_for_status_ref() {
+        let message = response.text().await?;
+        bail!("{error}: {message}");
+    }
+
+    Ok(response.json().await?)
+}
+
+/// An image URL within a content part
+#[derive(Debug, Serialize)]
+#[serde(crate = "model::common::serde")]
+struct ImageUrl {
+    url: String,
+}
+
+/// A part within the content of a message
+#[derive(Debug, Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "snake_case",
+    crate = "model::common::serde"
+)]
+enum ContentPart {
+    Text { text: String },
+    ImageUrl { image_url: ImageUrl },
+}
+
+/// The content of a message
+#[derive(Debug, Serialize)]
+#[serde(untagged, crate = "model::common::serde")]
+enum MessageContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+/// A Chat Completions API message
+#[derive(Debug, Serialize)]
+#[serde(crate = "model::common::serde")]
+struct Message {
+    role: String,
+    content: MessageContent,
+}
+
+/// A Chat Completions API request body
+///
+/// Based on https://platform.openai.com/docs/api-reference/chat/create.
+/// Note: only the most widely supported fields are included.
+#[skip_serializing_none]
+#[derive(Serialize)]
+#[serde(crate = "model::common::serde")]
+struct ChatCompletionRequest {
+    model: String,
+    messages: Vec<Message>,
+    temperature: Option<f32>,
+    top_p: Option<f32>,
+    max_tokens: Option<u16>,
+    seed: Option<i32>,
+    stop: Option<String>,
+    presence_penalty: Option<f32>,
+}
+
+/// The message in a Chat Completions API choice
+#[derive(Deserialize)]
+#[serde(crate = "model::common::serde")]
+struct ChoiceMessage {
+    content: Option<String>,
+}
+
+/// A choice in a Chat Completions API response
+#[derive(Deserialize)]
+#[serde(crate = "model::common::serde")]
+struct Choice {
+    message: ChoiceMessage,
+}
+
+/// A Chat Completions API response body
+///
+/// Note: at present several fields are ignored.
+#[derive(Deserialize)]
+#[serde(crate = "model::common::serde")]
+struct ChatCompletionResponse {
+    choices: Vec<Choice>,
+}
+
+/// A model in a Models API response
+#[derive(Clone, Deserialize)]
+#[serde(crate = "model::common::serde")]
+struct ModelSpec {
+    id: String,
+}
+
+/// A Models API response body
+#[derive(Clone, Deserialize)]
+#[serde(crate = "model::common::serde")]
+struct ModelsResponse {
+    data: Vec<ModelSpec>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use model::{
+        common::{
+            serde_json,
+            tokio::{
+                self,
+                io::{AsyncReadExt, AsyncWriteExt},
+                net::TcpListener,
+            },
+        },
+        test_task_repeat_word,
+    };
+
+    /// Start a mock OpenAI-compatible server which responds to a single request
+    /// with a chat completion containing the last word in the request body
+    async fn mock_server() -> Result<String> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+
+        tokio::spawn(async move {
+            let Ok((mut stream, ..)) = listener.accept().await else {
+                return;
+            };
+
+            // Read the request until the end of the body
+            let mut request = Vec::new();
+            let mut buffer = [0; 4096];
+            loop {
+                let Ok(count) = stream.read(&mut buffer).await else {
+                    return;
+                };
+                request.extend_from_slice(&buffer[..count]);
+
+                let text = String::from_utf8_lossy(&request);
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| {
+                            line.to_lowercase()
+                                .strip_prefix("content-length:")
+                                .and_then(|value| value.trim().parse::<usize>().ok())
+                        })
+                        .unwrap_or_default();
+                    if body.len() >= length {
+                        break;
+                    }
+                }
+                if count == 0 {
+                    break;
+                }
+            }
+
+            let body = serde_json::json!({
+                "id": "chatcmpl-0",
+                "object": "chat.completion",
+                "created": 0,
+                "model": "mock",
+                "choices": [{
+                    "index": 0,
+                    "message": {"role": "assistant", "content": "HELLO"},
+                    "finish_reason": "stop"
+                }]
+            })
+            .to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).await.ok();
+        });
+
+        Ok(format!("http://{address}/v1"))
+    }
+
+    #[test]
+    fn config() -> Result<()> {
+        let endpoints: Vec<EndpointConfig> = serde_yaml::from_str(
+            r#"
+- name: local
+  url: http://localhost:8080/v1
+  models:
+    - name: small
+      context-length: 8192
+      inputs: [text, image]
+- name: remote
+  url: https://llm.example.org/v1
+  api-key: REMOTE_API_KEY
+- name: other
+  url: https://other.example.org/v1
+  api-key: true
+"#,
+        )?;
+
+        assert_eq!(endpoints.len(), 3);
+        assert_eq!(endpoints[0].api_key_name(), None);
+        assert_eq!(endpoints[1].api_key_name(), Some("REMOTE_API_KEY"));
+        assert_eq!(endpoints[2].api_key_name(), Some(API_KEY));
+
+        let model = EndpointModel::new(endpoints[0].clone(), endpoints[0].models[0].clone());
+        assert_eq!(model.id(), "local/small");
+        assert!(matches!(model.r#type(), ModelType::Local));
+        assert_eq!(model.context_length(), 8192);
+        assert_eq!(model.supported_inputs(), &[ModelIO::Text, ModelIO::Image]);
+        assert_eq!(model.supported_outputs(), &[ModelIO::Text]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn perform_task() -> Result<()> {
+        let url = mock_server().await?;
+
+        let model = EndpointModel::new(
+            EndpointConfig {
+                name: "mock".into(),
+                url,
+                api_key: None,
+                models: vec![],
+            },
+            EndpointModelConfig {
+                name: "mock".into(),
+                context_length: None,
+                inputs: None,
+                outputs: None,
+            },
+        );
+        let output = model.perform_task(&test_task_repeat_word()).await?;
+
+        assert_eq!(output.content.trim(), "HELLO".to_string());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,236 @@
+//! Caching of the outputs of model tasks
+//!
+//! Outputs are stored as JSON files in `<cache>/models/` named using a digest of the
+//! task (its messages, model selection criteria, generation parameters etc) and the id of
+//! the model selected to perform it. This makes re-execution of unchanged instructions
+//! reproducible and free.
+
+use std::{
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use app::{get_app_dir, DirType};
+use model::{
+    common::{
+        eyre::{Report, Result},
+        seahash::SeaHasher,
+        serde::{Deserialize, Serialize},
+        serde_json,
+        tokio::fs::{create_dir_all, read_to_string, write},
+        tracing,
+    },
+    ModelOutput, ModelOutputKind, ModelTask,
+};
+
+/// A cached model output
+#[derive(Deserialize, Serialize)]
+#[serde(crate = "model::common::serde")]
+struct CachedOutput {
+    /// The time that the output was cached (seconds since the Unix epoch)
+    cached: u64,
+
+    /// The output of the task
+    output: ModelOutput,
+}
+
+/// Get the current time in seconds since the Unix epoch
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Get the directory that model outputs are cached in
+fn dir() -> Result<PathBuf> {
+    Ok(get_app_dir(DirType::Cache, false)?.join("models"))
+}
+
+/// Get the path of the cache file for a task performed by a model
+///
+/// Properties of the task that are not serialized (e.g. cache options) do
+/// not contribute to the digest. Generation options (e.g. `temperature`) are
+/// serialized and so do contribute.
+fn path(dir: &Path, task: &ModelTask, model_id: &str) -> Result<PathBuf> {
+    let json = serde_json::to_string(task)?;
+
+    let mut hash = SeaHasher::new();
+    json.hash(&mut hash);
+    model_id.hash(&mut hash);
+    let digest = hash.finish();
+
+    Ok(dir.join(format!("{digest:x}.json")))
+}
+
+/// Get the cached output of a task performed by a model, if any
+///
+/// Returns `None` if the task is a dry run, if the cache is to be bypassed,
+/// or if the cached output is older than the task's cache time-to-live.
+pub(crate) async fn get(task: &ModelTask, model_id: &str) -> Option<ModelOutput> {
+    get_from(&dir().ok()?, task, model_id).await
+}
+
+/// Get the cached output of a task performed by a model from a cache directory
+async fn get_from(dir: &Path, task: &ModelTask, model_id: &str) -> Option<ModelOutput> {
+    if task.dry_run || task.cache.bypass {
+        return None;
+    }
+
+    let path = path(dir, task, model_id).ok()?;
+    if !path.exists() {
+        return None;
+    }
+
+    let cached = match async {
+        let json = read_to_string(&path).await?;
+        Ok::<_, Report>(serde_json::from_str::<CachedOutput>(&json)?)
+    }
+    .await
+    {
+        Ok(cached) => cached,
+        Err(error) => {
+            tracing::warn!("While reading cached model output: {error}");
+            return None;
+        }
+    };
+
+    if let Some(ttl) = task.cache.ttl {
+        if now().saturating_sub(cached.cached) > ttl {
+            tracing::debug!("Cached model output is stale");
+            return None;
+        }
+    }
+
+    tracing::debug!("Using cached model output `{}`", path.display());
+    Some(cached.output)
+}
+
+/// Cache the output of a task performed by a model
+///
+/// Only text outputs are cached because URL outputs (e.g. of generated images)
+/// usually expire. Errors are logged rather than returned so that a failure to
+/// cache does not cause the task to fail.
+pub(crate) async fn set(task: &ModelTask, model_id: &str, output: ModelOutput) -> ModelOutput {
+    match dir() {
+        Ok(dir) => set_in(&dir, task, model_id, output).await,
+        Err(error) => {
+            tracing::warn!("While caching model output: {error}");
+            output
+        }
+    }
+}
+
+/// Cache the output of a task performed by a model in a cache directory
+async fn set_in(dir: &Path, task: &ModelTask, model_id: &str, output: ModelOutput) -> ModelOutput {
+    if task.dry_run || !matches!(output.kind, ModelOutputKind::Text) {
+        return output;
+    }
+
+    let cached = CachedOutput {
+        cached: now(),
+        output,
+    };
+
+    if let Err(error) = async {
+        let path = path(dir, task, model_id)?;
+        create_dir_all(dir).await?;
+        write(&path, serde_json::to_string_pretty(&cached)?).await?;
+        Ok::<_, Report>(())
+    }
+    .await
+    {
+        tracing::warn!("While caching model output: {error}");
+    }
+
+    cached.output
+}
+
+#[cfg(test)]
+mod tests {
+    use model::{
+        common::{tempfile::tempdir, tokio},
+        schema::InstructionMessage,
+    };
+
+    use super::*;
+
+    fn output(content: &str) -> ModelOutput {
+        ModelOutput {
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn hit_and_miss() -> Result<()> {
+        let dir = tempdir()?;
+        let dir = dir.path();
+
+        let task = ModelTask {
+            messages: vec![InstructionMessage::from("Hello")],
+            ..Default::default()
+        };
+        assert!(get_from(dir, &task, "a/model").await.is_none());
+
+        set_in(dir, &task, "a/model", output("Hi")).await;
+        assert_eq!(
+            get_from(dir, &task, "a/model")
+                .await
+                .map(|output| output.content),
+            Some("Hi".to_string())
+        );
+
+        // Different messages miss
+        let other = ModelTask {
+            messages: vec![InstructionMessage::from("Goodbye")],
+            ..Default::default()
+        };
+        assert!(get_from(dir, &other, "a/model").await.is_none());
+
+        // Different generation options miss
+        let other = ModelTask {
+            temperature: Some(0.5),
+            ..task.clone()
+        };
+        assert!(get_from(dir, &other, "a/model").await.is_none());
+
+        // Bypassing the cache misses
+        let mut other = task.clone();
+        other.cache.bypass = true;
+        assert!(get_from(dir, &other, "a/model").await.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn model_specific() -> Result<()> {
+        let dir = tempdir()?;
+        let dir = dir.path();
+
+        let task = ModelTask {
+            messages: vec![InstructionMessage::from("Hello")],
+            ..Default::default()
+        };
+        assert_ne!(path(dir, &task, "a/model")?, path(dir, &task, "b/model")?);
+
+        set_in(dir, &task, "a/model", output("From A")).await;
+        set_in(dir, &task, "b/model", output("From B")).await;
+        assert_eq!(
+            get_from(dir, &task, "a/model")
+                .await
+                .map(|output| output.content),
+            Some("From A".to_string())
+        );
+        assert_eq!(
+            get_from(dir, &task, "b/model")
+                .await
+                .map(|output| output.content),
+            Some("From B".to_string())
+        );
+        assert!(get_from(dir, &task, "c/model").await.is_none());
+
+        Ok(())
+    }
+}
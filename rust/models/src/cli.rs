@@ -10,11 +10,14 @@ use model::{
         serde_yaml,
     },
     format::Format,
-    schema::{InstructionMessage, InstructionModel},
+    schema::{InstructionMessage, InstructionModel, InstructionType},
     ModelAvailability, ModelTask, ModelType,
 };
 
-use crate::select;
+use crate::{
+    scores::{ModelOutcomes, ModelScores, Scorer},
+    select,
+};
 
 /// Manage generative models
 #[derive(Debug, Parser)]
@@ -26,6 +29,7 @@ pub struct Cli {
 #[derive(Debug, Subcommand)]
 enum Command {
     List(List),
+    Scores(Scores),
     Execute(Execute),
 }
 
@@ -38,6 +42,7 @@ impl Cli {
 
         match command {
             Command::List(list) => list.run().await?,
+            Command::Scores(scores) => scores.run().await?,
             Command::Execute(execute) => execute.run().await?,
         }
 
@@ -112,6 +117,53 @@ impl List {
     }
 }
 
+/// Show the scores of available models for a task
+///
+/// Scores are resolved from the user's scores file (if any) and builtin defaults,
+/// and adjusted based on previously accepted and rejected suggestions.
+#[derive(Debug, Args)]
+struct Scores {
+    /// The type of instruction to get scores for
+    #[arg(long, short)]
+    r#type: Option<InstructionType>,
+
+    /// The id of the prompt to get scores for
+    #[arg(long, short)]
+    prompt: Option<String>,
+}
+
+impl Scores {
+    async fn run(self) -> Result<()> {
+        let task = ModelTask {
+            instruction_type: self.r#type,
+            prompt: self.prompt,
+            ..Default::default()
+        };
+
+        let scorer = Scorer::new().await;
+
+        let mut table = table::new();
+        table.set_header(["Id", "Score"]);
+        let ids = super::list()
+            .await
+            .into_iter()
+            .filter(|model| model.is_available())
+            .map(|model| model.id());
+        for (id, score) in scorer.rank(&task, ids) {
+            table.add_row([
+                Cell::new(id).add_attribute(Attribute::Bold),
+                Cell::new(score),
+            ]);
+        }
+
+        println!("{table}");
+        println!("Scores file: {}", ModelScores::path()?.display());
+        println!("Outcomes file: {}", ModelOutcomes::path()?.display());
+
+        Ok(())
+    }
+}
+
 /// Execute a model task
 ///
 /// Mainly intended for testing of model selection and routing.
@@ -16,19 +16,24 @@ use model::{
 
 pub use model::{ModelAvailability, ModelType};
 
+use scores::Scorer;
+
+mod cache;
 pub mod cli;
+pub mod scores;
 
 /// Get a list of available models
 pub async fn list() -> Vec<Arc<dyn Model>> {
-    let futures = (0..=6).map(|provider| async move {
+    let futures = (0..=7).map(|provider| async move {
         let (provider, result) = match provider {
             0 => ("Anthropic", models_anthropic::list().await),
-            1 => ("Google", models_google::list().await),
-            2 => ("Mistral", models_mistral::list().await),
-            3 => ("Ollama", models_ollama::list().await),
-            4 => ("OpenAI", models_openai::list().await),
-            5 => ("Plugins", plugins::models::list().await),
-            6 => ("Stencila", models_stencila::list().await),
+            1 => ("Endpoints", models_endpoints::list().await),
+            2 => ("Google", models_google::list().await),
+            3 => ("Mistral", models_mistral::list().await),
+            4 => ("Ollama", models_ollama::list().await),
+            5 => ("OpenAI", models_openai::list().await),
+            6 => ("Plugins", plugins::models::list().await),
+            7 => ("Stencila", models_stencila::list().await),
             _ => return vec![],
         };
 
@@ -52,31 +57,6 @@ pub async fn list() -> Vec<Arc<dyn Model>> {
         .collect_vec()
 }
 
-/// Get an overall score for a model
-///
-/// Task specific, and more frequently updated, scores are available by
-/// using the stencila/auto router alias.
-fn score(id: &str) -> u32 {
-    match id {
-        // Automatic selection based on the task type, assistant and users
-        "stencila/auto" => 100,
-        // Only the top models from supported providers are listed here.
-        "anthropic/claude-3-5-sonnet-20240620" => 98,
-        "anthropic/claude-3-opus-20240229" => 93,
-        "anthropic/claude-3-haiku-20240307" => 74,
-        "google/gemini-1.5-pro-001" => 95,
-        "google/gemini-1.5-flash-001" => 84,
-        "openai/gpt-4o-2024-05-13" => 100,
-        "openai/gpt-4o-2024-08-06" => 100,
-        "openai/gpt-4-turbo-2024-04-09" => 94,
-        "openai/gpt-4o-mini-2024-07-18" => 88,
-        "mistral/mistral-large-2407" => 76,
-        "mistral/mistral-medium-2312" => 70,
-        "mistral/mistral-small-2402" => 71,
-        _ => 50,
-    }
-}
-
 /// Select a model based on selection criteria of the `InstructionModel`
 #[tracing::instrument(skip_all)]
 pub async fn select(task: &ModelTask) -> Result<Arc<dyn Model>> {
@@ -154,10 +134,11 @@ pub async fn select(task: &ModelTask) -> Result<Arc<dyn Model>> {
     }
 
     // Score models, getting max score as we go
+    let scorer = Scorer::new().await;
     let mut max_score = 0u32;
     let mut model_scores = Vec::new();
     for model in models.into_iter() {
-        let score = score(&model.id());
+        let score = scorer.score(task, &model.id());
         if score > max_score {
             max_score = score;
         }
@@ -192,7 +173,18 @@ pub async fn select(task: &ModelTask) -> Result<Arc<dyn Model>> {
 }
 
 /// Perform a model task
+///
+/// If the output of an identical task, performed by the selected model, has
+/// previously been cached then that is returned without performing the task.
 pub async fn perform_task(task: ModelTask) -> Result<ModelOutput> {
     let model = select(&task).await?;
-    model.perform_task(&task).await
+    let model_id = model.id();
+
+    if let Some(output) = cache::get(&task, &model_id).await {
+        return Ok(output);
+    }
+
+    let output = model.perform_task(&task).await?;
+
+    Ok(cache::set(&task, &model_id, output).await)
 }
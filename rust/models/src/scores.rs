@@ -0,0 +1,561 @@
+//! Task-specific scoring of models
+//!
+//! Scores are in the range 0-100 and are used by [`crate::select`] to choose
+//! between models that support a task. They are resolved, in order of precedence, from:
+//!
+//! 1. the user's scores file (`<config>/models/scores.yaml`) for the prompt used by the task,
+//! 2. the user's scores file for the type of instruction the task is for,
+//! 3. the user's scores file defaults,
+//! 4. builtin defaults.
+//!
+//! The resolved score is then adjusted based on the local history of accepted
+//! and rejected suggestions generated by the model (`<config>/models/outcomes.json`).
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use app::{get_app_dir, DirType};
+use model::{
+    common::{
+        eyre::Result,
+        itertools::Itertools,
+        once_cell::sync::Lazy,
+        regex::Regex,
+        serde::{Deserialize, Serialize},
+        serde_json, serde_yaml,
+        tokio::{
+            fs::{create_dir_all, read_to_string, rename, write},
+            sync::Mutex,
+        },
+        tracing,
+    },
+    schema::{
+        Author, AuthorRole, AuthorRoleAuthor, AuthorRoleName, InstructionType, SoftwareApplication,
+        SuggestionBlock, SuggestionStatus,
+    },
+    ModelTask,
+};
+
+/// The score given to models that do not match any entry in any score table
+const DEFAULT_SCORE: u32 = 50;
+
+/// Builtin default scores
+///
+/// Only the top models from supported providers are listed here. Entries with a
+/// trailing wildcard allow newer versions of a model to be scored before they are
+/// explicitly added.
+const BUILTIN_SCORES: &[(&str, u32)] = &[
+    // Automatic selection based on the task type, assistant and users
+    ("stencila/auto", 100),
+    ("anthropic/claude-3-5-sonnet-*", 98),
+    ("anthropic/claude-3-5-sonnet-20240620", 98),
+    ("anthropic/claude-3-opus-*", 93),
+    ("anthropic/claude-3-opus-20240229", 93),
+    ("anthropic/claude-3-haiku-*", 74),
+    ("anthropic/claude-3-haiku-20240307", 74),
+    ("google/gemini-1.5-pro-*", 95),
+    ("google/gemini-1.5-pro-001", 95),
+    ("google/gemini-1.5-flash-*", 84),
+    ("google/gemini-1.5-flash-001", 84),
+    ("openai/gpt-4o-*", 100),
+    ("openai/gpt-4o-2024-05-13", 100),
+    ("openai/gpt-4o-2024-08-06", 100),
+    ("openai/gpt-4-turbo-*", 94),
+    ("openai/gpt-4-turbo-2024-04-09", 94),
+    ("openai/gpt-4o-mini-*", 88),
+    ("openai/gpt-4o-mini-2024-07-18", 88),
+    ("mistral/mistral-large-*", 76),
+    ("mistral/mistral-large-2407", 76),
+    ("mistral/mistral-medium-*", 70),
+    ("mistral/mistral-medium-2312", 70),
+    ("mistral/mistral-small-*", 71),
+    ("mistral/mistral-small-2402", 71),
+];
+
+/// A table of scores keyed by model id or id pattern
+///
+/// Patterns may contain `*` wildcards. When more than one entry in a table
+/// matches a model id, the most specific entry (an exact match, or otherwise
+/// the longest pattern) is used.
+pub type ScoreTable = BTreeMap<String, u32>;
+
+/// Find the score for a model id in a table
+fn lookup<'a, I>(table: I, model_id: &str) -> Option<u32>
+where
+    I: IntoIterator<Item = (&'a str, u32)>,
+{
+    table
+        .into_iter()
+        .filter(|(pattern, ..)| {
+            if !pattern.contains('*') {
+                return *pattern == model_id;
+            }
+
+            let regex = ["^", &pattern.replace('.', r"\.").replace('*', "(.*?)"), "$"].concat();
+            match Regex::new(&regex) {
+                Ok(regex) => regex.is_match(model_id),
+                Err(error) => {
+                    tracing::warn!("Invalid model id pattern `{pattern}`: {error}");
+                    false
+                }
+            }
+        })
+        .max_by_key(|(pattern, ..)| (!pattern.contains('*'), pattern.len()))
+        .map(|(.., score)| score)
+}
+
+/// User defined model scores
+///
+/// Deserialized from a YAML file e.g.
+///
+/// ```yaml
+/// default:
+///   openai/gpt-4o-*: 100
+///
+/// instruction-types:
+///   edit:
+///     anthropic/claude-3-5-sonnet-*: 100
+///
+/// prompts:
+///   stencila/create/figure-mermaid:
+///     google/gemini-1.5-pro-*: 90
+/// ```
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case", crate = "model::common::serde")]
+pub struct ModelScores {
+    /// Scores applying to all tasks
+    pub default: ScoreTable,
+
+    /// Scores for tasks of a specific instruction type (keyed by lowercase type e.g. `create`)
+    pub instruction_types: BTreeMap<String, ScoreTable>,
+
+    /// Scores for tasks using a specific prompt (keyed by prompt id)
+    pub prompts: BTreeMap<String, ScoreTable>,
+}
+
+impl ModelScores {
+    /// Get the path of the user's scores file
+    pub fn path() -> Result<PathBuf> {
+        Ok(get_app_dir(DirType::Config, false)?
+            .join("models")
+            .join("scores.yaml"))
+    }
+
+    /// Read the user's scores file, falling back to empty scores if it does not exist
+    pub async fn read() -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let yaml = read_to_string(&path).await?;
+        Ok(serde_yaml::from_str(&yaml)?)
+    }
+
+    /// Get the base score for a model for a task (i.e. before adjusting for outcomes)
+    pub fn base(&self, task: &ModelTask, model_id: &str) -> u32 {
+        fn table(table: &ScoreTable) -> impl Iterator<Item = (&str, u32)> {
+            table
+                .iter()
+                .map(|(pattern, score)| (pattern.as_str(), *score))
+        }
+
+        let prompt = task
+            .prompt
+            .as_ref()
+            .and_then(|prompt| self.prompts.get(prompt))
+            .and_then(|scores| lookup(table(scores), model_id));
+        if let Some(score) = prompt {
+            return score;
+        }
+
+        let instruction_type = task
+            .instruction_type
+            .as_ref()
+            .and_then(|instruction_type| {
+                self.instruction_types
+                    .get(&instruction_type.to_string().to_lowercase())
+            })
+            .and_then(|scores| lookup(table(scores), model_id));
+        if let Some(score) = instruction_type {
+            return score;
+        }
+
+        lookup(table(&self.default), model_id)
+            .or_else(|| lookup(BUILTIN_SCORES.iter().copied(), model_id))
+            .unwrap_or(DEFAULT_SCORE)
+    }
+}
+
+/// The counts of accepted and rejected suggestions for a model, instruction type and prompt
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", crate = "model::common::serde")]
+pub struct ModelOutcome {
+    /// The id of the model that generated the suggestions
+    pub model: String,
+
+    /// The type of instruction the suggestions were for
+    pub instruction_type: InstructionType,
+
+    /// The id of the prompt used to generate the suggestions
+    pub prompt: Option<String>,
+
+    /// The number of suggestions accepted
+    pub accepted: u32,
+
+    /// The number of suggestions rejected
+    pub rejected: u32,
+}
+
+/// The local history of suggestion outcomes
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(transparent, crate = "model::common::serde")]
+pub struct ModelOutcomes(Vec<ModelOutcome>);
+
+impl ModelOutcomes {
+    /// Get the path of the outcomes file
+    pub fn path() -> Result<PathBuf> {
+        Ok(get_app_dir(DirType::Config, false)?
+            .join("models")
+            .join("outcomes.json"))
+    }
+
+    /// Read the outcomes file, falling back to no outcomes if it does not exist
+    pub async fn read() -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let json = read_to_string(&path).await?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Write the outcomes file
+    ///
+    /// Writes to a temporary file which is then renamed so that a partially
+    /// written file is never read.
+    pub async fn write(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(dir) = path.parent() {
+            create_dir_all(dir).await?;
+        }
+
+        let json = serde_json::to_string_pretty(&self)?;
+        let temp = path.with_extension("json.tmp");
+        write(&temp, json).await?;
+        rename(&temp, &path).await?;
+
+        Ok(())
+    }
+
+    /// Record the outcome of a suggestion
+    pub fn record(
+        &mut self,
+        model: &str,
+        instruction_type: &InstructionType,
+        prompt: Option<&str>,
+        status: &SuggestionStatus,
+    ) {
+        let outcome = match self.0.iter_mut().find(|outcome| {
+            outcome.model == model
+                && &outcome.instruction_type == instruction_type
+                && outcome.prompt.as_deref() == prompt
+        }) {
+            Some(outcome) => outcome,
+            None => {
+                self.0.push(ModelOutcome {
+                    model: model.to_string(),
+                    instruction_type: instruction_type.clone(),
+                    prompt: prompt.map(String::from),
+                    ..Default::default()
+                });
+                self.0.last_mut().expect("just pushed")
+            }
+        };
+
+        match status {
+            SuggestionStatus::Accepted => outcome.accepted += 1,
+            SuggestionStatus::Rejected => outcome.rejected += 1,
+        }
+    }
+
+    /// Adjust a base score for a model according to the outcomes of previous tasks
+    ///
+    /// Uses a smoothed acceptance rate so that a model with no history (or equal
+    /// numbers of accepted and rejected suggestions) retains its base score, while
+    /// consistently accepted models have their score increased by up to 50%, and
+    /// consistently rejected models have it decreased by up to 50%.
+    pub fn adjust(&self, task: &ModelTask, model_id: &str, score: u32) -> u32 {
+        let (accepted, rejected) = self
+            .0
+            .iter()
+            .filter(|outcome| {
+                outcome.model == model_id
+                    && task
+                        .instruction_type
+                        .as_ref()
+                        .map_or(true, |instruction_type| {
+                            &outcome.instruction_type == instruction_type
+                        })
+                    && task
+                        .prompt
+                        .as_ref()
+                        .map_or(true, |prompt| outcome.prompt.as_ref() == Some(prompt))
+            })
+            .fold((0, 0), |(accepted, rejected), outcome| {
+                (accepted + outcome.accepted, rejected + outcome.rejected)
+            });
+
+        let rate = (accepted as f32 + 1.) / ((accepted + rejected) as f32 + 2.);
+        let factor = 0.5 + rate;
+
+        ((score as f32 * factor).round() as u32).min(100)
+    }
+}
+
+/// Scores for models, combining the user's scores file and local outcomes
+#[derive(Debug, Default, Clone)]
+pub struct Scorer {
+    scores: ModelScores,
+    outcomes: ModelOutcomes,
+}
+
+impl Scorer {
+    /// Create a new scorer by reading the user's scores and outcomes files
+    ///
+    /// Errors reading either file are logged and defaults used so that
+    /// model selection is not prevented by a malformed file.
+    pub async fn new() -> Self {
+        let scores = ModelScores::read().await.unwrap_or_else(|error| {
+            tracing::error!("While reading model scores: {error}");
+            ModelScores::default()
+        });
+
+        let outcomes = ModelOutcomes::read().await.unwrap_or_else(|error| {
+            tracing::error!("While reading model outcomes: {error}");
+            ModelOutcomes::default()
+        });
+
+        Self { scores, outcomes }
+    }
+
+    /// Get the score of a model for a task
+    pub fn score(&self, task: &ModelTask, model_id: &str) -> u32 {
+        let base = self.scores.base(task, model_id);
+        self.outcomes.adjust(task, model_id, base)
+    }
+
+    /// Get the scores of models for a task, highest first
+    pub fn rank<I>(&self, task: &ModelTask, model_ids: I) -> Vec<(String, u32)>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        model_ids
+            .into_iter()
+            .map(|id| {
+                let score = self.score(task, &id);
+                (id, score)
+            })
+            .sorted_by(|(.., a), (.., b)| b.cmp(a))
+            .collect()
+    }
+}
+
+/// Get the ids of the model and prompt that generated a suggestion
+fn suggestion_generators(suggestion: &SuggestionBlock) -> (Option<String>, Option<String>) {
+    let mut model = None;
+    let mut prompt = None;
+    for author in suggestion.authors.iter().flatten() {
+        if let Author::AuthorRole(AuthorRole {
+            role_name,
+            author: AuthorRoleAuthor::SoftwareApplication(SoftwareApplication { id: Some(id), .. }),
+            ..
+        }) = author
+        {
+            match role_name {
+                AuthorRoleName::Generator if model.is_none() => model = Some(id.clone()),
+                AuthorRoleName::Prompter if prompt.is_none() => prompt = Some(id.clone()),
+                _ => {}
+            }
+        }
+    }
+    (model, prompt)
+}
+
+/// A lock on the outcomes file
+///
+/// Held while the file is read, updated and written so that outcomes
+/// recorded concurrently (e.g. for suggestions accepted in quick succession)
+/// are not lost.
+static OUTCOMES_LOCK: Lazy<Mutex<()>> = Lazy::new(Mutex::default);
+
+/// Record the outcomes of suggestions for an instruction
+///
+/// Suggestions that were not generated by a model (e.g. those written
+/// by a user) are ignored.
+pub async fn record_outcomes(
+    instruction_type: &InstructionType,
+    suggestions: &[(SuggestionBlock, SuggestionStatus)],
+) -> Result<()> {
+    let outcomes = suggestions
+        .iter()
+        .filter_map(|(suggestion, status)| {
+            let (model, prompt) = suggestion_generators(suggestion);
+            model.map(|model| (model, prompt, status))
+        })
+        .collect_vec();
+    if outcomes.is_empty() {
+        return Ok(());
+    }
+
+    let _lock = OUTCOMES_LOCK.lock().await;
+
+    let mut history = ModelOutcomes::read().await?;
+    for (model, prompt, status) in outcomes {
+        history.record(&model, instruction_type, prompt.as_deref(), status);
+    }
+    history.write().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_most_specific() {
+        let table = [
+            ("openai/gpt-4o-*", 100),
+            ("openai/gpt-4o-mini-*", 88),
+            ("openai/gpt-4o-mini-2024-07-18", 80),
+        ];
+
+        assert_eq!(lookup(table, "openai/gpt-4o-2024-11-20"), Some(100));
+        assert_eq!(lookup(table, "openai/gpt-4o-mini-2025-01-01"), Some(88));
+        assert_eq!(lookup(table, "openai/gpt-4o-mini-2024-07-18"), Some(80));
+        assert_eq!(lookup(table, "mistral/mistral-large-2407"), None);
+    }
+
+    #[test]
+    fn base_precedence() {
+        let scores: ModelScores = serde_yaml::from_str(
+            r#"
+default:
+  openai/gpt-4o-*: 90
+instruction-types:
+  edit:
+    openai/gpt-4o-*: 80
+prompts:
+  stencila/edit/block:
+    openai/gpt-4o-*: 70
+"#,
+        )
+        .unwrap();
+
+        let mut task = ModelTask::default();
+        assert_eq!(scores.base(&task, "openai/gpt-4o-2024-08-06"), 90);
+        assert_eq!(scores.base(&task, "anthropic/claude-3-opus-20240229"), 93);
+        assert_eq!(scores.base(&task, "unknown/model"), DEFAULT_SCORE);
+
+        task.instruction_type = Some(InstructionType::Edit);
+        assert_eq!(scores.base(&task, "openai/gpt-4o-2024-08-06"), 80);
+
+        task.prompt = Some("stencila/edit/block".into());
+        assert_eq!(scores.base(&task, "openai/gpt-4o-2024-08-06"), 70);
+    }
+
+    #[test]
+    fn adjust_for_outcomes() {
+        let task = ModelTask {
+            instruction_type: Some(InstructionType::Create),
+            ..Default::default()
+        };
+
+        let mut outcomes = ModelOutcomes::default();
+        assert_eq!(outcomes.adjust(&task, "a/model", 60), 60);
+
+        for _ in 0..8 {
+            outcomes.record(
+                "a/model",
+                &InstructionType::Create,
+                None,
+                &SuggestionStatus::Accepted,
+            );
+            outcomes.record(
+                "b/model",
+                &InstructionType::Create,
+                None,
+                &SuggestionStatus::Rejected,
+            );
+        }
+        assert!(outcomes.adjust(&task, "a/model", 60) > 60);
+        assert!(outcomes.adjust(&task, "b/model", 60) < 60);
+        assert_eq!(outcomes.adjust(&task, "a/model", 90), 100);
+
+        // Outcomes for other instruction types do not affect score
+        let task = ModelTask {
+            instruction_type: Some(InstructionType::Edit),
+            ..Default::default()
+        };
+        assert_eq!(outcomes.adjust(&task, "a/model", 60), 60);
+    }
+
+    #[test]
+    fn rank_models() {
+        let scores: ModelScores = serde_yaml::from_str(
+            r#"
+default:
+  a/model: 60
+  b/model: 70
+  c/model: 80
+"#,
+        )
+        .unwrap();
+
+        let mut outcomes = ModelOutcomes::default();
+        for _ in 0..8 {
+            outcomes.record(
+                "a/model",
+                &InstructionType::Create,
+                None,
+                &SuggestionStatus::Accepted,
+            );
+            outcomes.record(
+                "c/model",
+                &InstructionType::Create,
+                None,
+                &SuggestionStatus::Rejected,
+            );
+        }
+
+        let scorer = Scorer { scores, outcomes };
+        let ids = ["a/model", "b/model", "c/model", "d/model"].map(String::from);
+
+        let task = ModelTask {
+            instruction_type: Some(InstructionType::Create),
+            ..Default::default()
+        };
+        assert_eq!(
+            scorer.rank(&task, ids.clone()),
+            vec![
+                ("a/model".to_string(), 84),
+                ("b/model".to_string(), 70),
+                ("d/model".to_string(), DEFAULT_SCORE),
+                ("c/model".to_string(), 48)
+            ]
+        );
+
+        let task = ModelTask {
+            instruction_type: Some(InstructionType::Edit),
+            ..Default::default()
+        };
+        assert_eq!(
+            scorer.rank(&task, ids),
+            vec![
+                ("c/model".to_string(), 80),
+                ("b/model".to_string(), 70),
+                ("a/model".to_string(), 60),
+                ("d/model".to_string(), DEFAULT_SCORE)
+            ]
+        );
+    }
+}
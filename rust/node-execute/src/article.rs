@@ -1,3 +1,4 @@
+use kernels::KernelLimits;
 use schema::{diff, Article, PatchSlot};
 
 use crate::{interrupt_impl, prelude::*, HeadingInfo};
@@ -55,6 +56,39 @@ impl Executable for Article {
         // Add article metadata to document context
         executor.document_context.metadata = (&*self).into();
 
+        // Set the secrets required by the article, and allowed by the user,
+        // so that they are available to any kernels that are started
+        let secrets = self
+            .config
+            .as_ref()
+            .and_then(|config| config.secrets.clone())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|name| {
+                let allowed = executor.options.allowed_secrets.contains(name);
+                if !allowed {
+                    tracing::warn!(
+                        "Secret `{name}` required by document has not been allowed (use `--allow-secret {name}`)"
+                    );
+                }
+                allowed
+            })
+            .collect();
+        executor.kernels().await.set_secrets(secrets);
+
+        // Set any limits, required by the article, on the resources that kernels may use
+        let limits = self
+            .config
+            .as_ref()
+            .map(|config| KernelLimits {
+                memory: config.memory_limit,
+                cpu: config.cpu_limit,
+                timeout: config.timeout,
+                restricted: config.restricted.unwrap_or_default(),
+            })
+            .unwrap_or_default();
+        executor.kernels().await.set_limits(limits);
+
         // Set execution status
         self.options.execution_status = Some(ExecutionStatus::Pending);
         executor.patch(
@@ -0,0 +1,349 @@
+use std::str::FromStr;
+
+use graph::{Graph, GraphEdge, GraphNode};
+use schema::{Block, Button, Inline, NodeId, Visitor};
+
+use crate::{interrupt_impl, prelude::*};
+
+impl Executable for Button {
+    #[tracing::instrument(skip_all)]
+    async fn compile(&mut self, executor: &mut Executor) -> WalkControl {
+        let node_id = self.node_id();
+        tracing::trace!("Compiling Button {node_id}");
+
+        let info = parsers::parse(
+            &self.code,
+            self.programming_language.as_deref().unwrap_or_default(),
+        );
+
+        let execution_required =
+            execution_required_digests(&self.options.execution_digest, &info.compilation_digest);
+        executor.patch(
+            &node_id,
+            [
+                set(NodeProperty::CompilationDigest, info.compilation_digest),
+                set(NodeProperty::ExecutionRequired, execution_required),
+            ],
+        );
+
+        WalkControl::Break
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn prepare(&mut self, executor: &mut Executor) -> WalkControl {
+        let node_id = self.node_id();
+        tracing::trace!("Preparing Button {node_id}");
+
+        // Buttons are only executed on demand (i.e. when clicked) so are only
+        // prepared if explicitly listed in the nodes to execute and are
+        // excluded from executions of the whole document
+        if !executor
+            .node_ids
+            .as_ref()
+            .is_some_and(|node_ids| node_ids.contains(&node_id))
+        {
+            return WalkControl::Break;
+        }
+
+        // Set execution status
+        if let Some(status) = executor.node_execution_status(
+            self.node_type(),
+            &node_id,
+            &self.execution_mode,
+            &self.options.compilation_digest,
+            &self.options.execution_digest,
+        ) {
+            self.options.execution_status = Some(status.clone());
+            executor.patch(&node_id, [set(NodeProperty::ExecutionStatus, status)]);
+        }
+
+        WalkControl::Break
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn execute(&mut self, executor: &mut Executor) -> WalkControl {
+        let node_id = self.node_id();
+
+        if !matches!(
+            self.options.execution_status,
+            Some(ExecutionStatus::Pending)
+        ) {
+            tracing::trace!("Skipping Button {node_id}");
+            return WalkControl::Break;
+        }
+
+        tracing::debug!("Executing Button {node_id}");
+
+        executor.patch(
+            &node_id,
+            [
+                set(NodeProperty::ExecutionStatus, ExecutionStatus::Running),
+                none(NodeProperty::ExecutionMessages),
+            ],
+        );
+
+        let started = Timestamp::now();
+        let mut messages = Vec::new();
+        let compilation_digest = self.options.compilation_digest.clone();
+        let count = self.options.execution_count.unwrap_or_default() + 1;
+
+        // Evaluate the code of the button, if any, to determine whether it is disabled
+        let is_disabled = if !self.code.trim().is_empty() {
+            let (output, mut code_messages, ..) = executor
+                .kernels()
+                .await
+                .evaluate(&self.code, self.programming_language.as_deref())
+                .await
+                .unwrap_or_else(|error| {
+                    (
+                        Node::Null(Null),
+                        vec![error_to_execution_message(
+                            "While evaluating button condition",
+                            error,
+                        )],
+                        String::new(),
+                    )
+                });
+            messages.append(&mut code_messages);
+
+            let is_disabled = !is_truthy(&output);
+            executor.patch(&node_id, [set(NodeProperty::IsDisabled, is_disabled)]);
+            is_disabled
+        } else {
+            false
+        };
+
+        // If not disabled, set the button's variable to the number of times it has
+        // been clicked and record it so that the button's dependants are re-executed
+        if !is_disabled {
+            let result = executor
+                .kernels()
+                .await
+                .set(&self.name, &Node::Integer(count))
+                .await;
+            match result {
+                Ok(..) => executor.button_variables.push(self.name.clone()),
+                Err(error) => messages.push(error_to_execution_message(
+                    &format!("While setting button variable `{}`", self.name),
+                    error,
+                )),
+            }
+        }
+
+        let messages = (!messages.is_empty()).then_some(messages);
+
+        let ended = Timestamp::now();
+
+        let status = execution_status(&messages);
+        let duration = execution_duration(&started, &ended);
+
+        self.options.execution_messages = messages.clone();
+
+        executor.patch(
+            &node_id,
+            [
+                set(NodeProperty::ExecutionStatus, status),
+                set(NodeProperty::ExecutionRequired, ExecutionRequired::No),
+                set(NodeProperty::ExecutionMessages, messages),
+                set(NodeProperty::ExecutionDuration, duration),
+                set(NodeProperty::ExecutionEnded, ended),
+                set(NodeProperty::ExecutionCount, count),
+                set(NodeProperty::ExecutionDigest, compilation_digest),
+            ],
+        );
+
+        WalkControl::Break
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn interrupt(&mut self, executor: &mut Executor) -> WalkControl {
+        let node_id = self.node_id();
+        tracing::debug!("Interrupting Button {node_id}");
+
+        interrupt_impl!(self, executor, &node_id);
+
+        WalkControl::Break
+    }
+}
+
+/// Whether the output of evaluating the code of a button is truthy
+fn is_truthy(node: &Node) -> bool {
+    match node {
+        Node::Null(..) => false,
+        Node::Boolean(value) => *value,
+        Node::Integer(value) => *value != 0,
+        Node::UnsignedInteger(value) => *value != 0,
+        Node::Number(value) => *value != 0.,
+        Node::String(value) => !value.is_empty(),
+        Node::Array(value) => !value.is_empty(),
+        _ => true,
+    }
+}
+
+/// Get the ids of the nodes which depend upon the variables of buttons
+///
+/// Builds a dependency graph of the document, in which each button assigns its variable
+/// and each node with code uses, and assigns, the variables determined by parsing that code,
+/// and returns the ids of the nodes which depend, directly or transitively, upon any of the
+/// variables in that graph.
+pub(super) fn dependants(root: &Node, variables: &[String]) -> Vec<NodeId> {
+    let mut builder = GraphBuilder::default();
+    builder.visit(root);
+
+    let mut node_ids = Vec::new();
+    for name in variables {
+        let variable = GraphNode::Variable { name: name.clone() };
+        for node in builder.graph.dependants(&variable) {
+            let (GraphNode::CodeChunk { id }
+            | GraphNode::CodeExpression { id }
+            | GraphNode::ForBlock { id }
+            | GraphNode::IfBlock { id }
+            | GraphNode::StyledBlock { id }
+            | GraphNode::StyledInline { id }) = node
+            else {
+                continue;
+            };
+            if let Ok(node_id) = NodeId::from_str(id) {
+                if !node_ids.contains(&node_id) {
+                    node_ids.push(node_id);
+                }
+            }
+        }
+    }
+    node_ids
+}
+
+/// A visitor which builds a dependency graph of buttons, their variables,
+/// and the nodes with code which use and assign variables
+#[derive(Default)]
+struct GraphBuilder {
+    graph: Graph,
+}
+
+impl GraphBuilder {
+    /// Add edges from the variables used by some code to the node with the code,
+    /// and from the node to the variables that the code assigns
+    fn code(&mut self, code: &str, language: &Option<String>, node: GraphNode) {
+        let info = parsers::parse(code, language.as_deref().unwrap_or_default());
+        for name in info.uses {
+            self.graph
+                .add_edge(GraphNode::Variable { name }, GraphEdge::Uses, node.clone());
+        }
+        for name in info.assigns {
+            self.graph.add_edge(
+                node.clone(),
+                GraphEdge::Assigns,
+                GraphNode::Variable { name },
+            );
+        }
+    }
+}
+
+impl Visitor for GraphBuilder {
+    fn visit_block(&mut self, block: &Block) -> WalkControl {
+        match block {
+            Block::CodeChunk(node) => self.code(
+                &node.code,
+                &node.programming_language,
+                GraphNode::CodeChunk {
+                    id: node.node_id().to_string(),
+                },
+            ),
+            Block::ForBlock(node) => self.code(
+                &node.code,
+                &node.programming_language,
+                GraphNode::ForBlock {
+                    id: node.node_id().to_string(),
+                },
+            ),
+            Block::IfBlock(node) => {
+                let id = node.node_id().to_string();
+                for clause in &node.clauses {
+                    self.code(
+                        &clause.code,
+                        &clause.programming_language,
+                        GraphNode::IfBlock { id: id.clone() },
+                    )
+                }
+            }
+            Block::StyledBlock(node) => self.code(
+                &node.code,
+                &node.style_language,
+                GraphNode::StyledBlock {
+                    id: node.node_id().to_string(),
+                },
+            ),
+            _ => {}
+        };
+
+        WalkControl::Continue
+    }
+
+    fn visit_inline(&mut self, inline: &Inline) -> WalkControl {
+        match inline {
+            Inline::Button(node) => self.graph.add_edge(
+                GraphNode::Button {
+                    id: node.node_id().to_string(),
+                },
+                GraphEdge::Assigns,
+                GraphNode::Variable {
+                    name: node.name.clone(),
+                },
+            ),
+            Inline::CodeExpression(node) => self.code(
+                &node.code,
+                &node.programming_language,
+                GraphNode::CodeExpression {
+                    id: node.node_id().to_string(),
+                },
+            ),
+            Inline::StyledInline(node) => self.code(
+                &node.code,
+                &node.style_language,
+                GraphNode::StyledInline {
+                    id: node.node_id().to_string(),
+                },
+            ),
+            _ => {}
+        };
+
+        WalkControl::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_dev::pretty_assertions::assert_eq;
+    use schema::{shortcuts::p, Article, CodeChunk, Cord};
+
+    use super::*;
+
+    #[test]
+    fn transitive_dependants() {
+        let chunk = |code: &str| {
+            let mut chunk = CodeChunk::new(code.into());
+            chunk.programming_language = Some("python".to_string());
+            chunk
+        };
+
+        let button = Button::new(Cord::default(), "n".to_string());
+        let assigns = chunk("x = n * 2");
+        let uses = chunk("print(x)");
+        let unrelated = chunk("y = 1\nprint(y)");
+
+        let root = Node::Article(Article::new(vec![
+            p([Inline::Button(button)]),
+            Block::CodeChunk(assigns.clone()),
+            Block::CodeChunk(uses.clone()),
+            Block::CodeChunk(unrelated),
+        ]));
+
+        let mut node_ids = dependants(&root, &["n".to_string()]);
+        node_ids.sort_by_key(|node_id| node_id.to_string());
+        let mut expected = vec![assigns.node_id(), uses.node_id()];
+        expected.sort_by_key(|node_id| node_id.to_string());
+        assert_eq!(node_ids, expected);
+
+        assert_eq!(dependants(&root, &["z".to_string()]), vec![]);
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::{BTreeMap, HashSet};
+
+use schema::{Block, Claim, ClaimType, Node, NodeProperty, Visitor};
+
+use crate::prelude::*;
+
+impl Executable for Claim {
+    #[tracing::instrument(skip_all)]
+    async fn compile(&mut self, executor: &mut Executor) -> WalkControl {
+        let node_id = self.node_id();
+        tracing::trace!("Compiling Claim {node_id}");
+
+        let (label, id) = number(self, &mut executor.claim_counts, &mut executor.claim_ids);
+
+        let mut ops = Vec::new();
+        if let Some(label) = label {
+            ops.push(set(NodeProperty::Label, label));
+        }
+        if let Some(id) = id {
+            ops.push(set(NodeProperty::Id, id));
+        }
+
+        if !ops.is_empty() {
+            executor.patch(&node_id, ops);
+        }
+
+        WalkControl::Continue
+    }
+}
+
+/// Number a claim, returning the label and id that need to be set on it (if any)
+///
+/// Claims are numbered separately for each type (e.g. Theorem 1, Lemma 1, Theorem 2).
+/// Like labels, ids that were assigned automatically (e.g. `theorem-2`) are regenerated
+/// when the numbering changes, skipping any ids that are already in use.
+fn number(
+    claim: &Claim,
+    counts: &mut BTreeMap<ClaimType, u32>,
+    used: &mut HashSet<String>,
+) -> (Option<String>, Option<String>) {
+    let count = counts.entry(claim.claim_type.clone()).or_default();
+    *count += 1;
+    let count = *count;
+
+    // Only update the label if it has not been set, or is a number (i.e. was
+    // previously set automatically), so that custom labels are preserved
+    let label = count.to_string();
+    let is_automatic = claim
+        .label
+        .as_ref()
+        .map_or(true, |label| label.parse::<u32>().is_ok());
+    let label = (is_automatic && Some(&label) != claim.label.as_ref()).then_some(label);
+
+    // Ensure that the claim has an id so that it can be referenced, including
+    // by the comments of reviews
+    let id = is_automatic_id(claim).then(|| {
+        let prefix = claim.claim_type.to_string().to_lowercase();
+        let id = (count..)
+            .map(|n| [&prefix, "-", &n.to_string()].concat())
+            .find(|id| !used.contains(id))
+            .unwrap_or_default();
+        used.insert(id.clone());
+        id
+    });
+    let id = id.filter(|id| Some(id) != claim.id.as_ref());
+
+    (label, id)
+}
+
+/// Whether the id of a claim has not been set, or was previously set automatically
+fn is_automatic_id(claim: &Claim) -> bool {
+    let prefix = claim.claim_type.to_string().to_lowercase();
+    claim.id.as_ref().map_or(true, |id| {
+        id.strip_prefix(&prefix)
+            .and_then(|rest| rest.strip_prefix('-'))
+            .map_or(false, |n| n.parse::<u32>().is_ok())
+    })
+}
+
+/// Collect the ids of claims in a node that were not set automatically
+///
+/// These are collected before claims are numbered so that automatic ids
+/// do not duplicate the custom id of a later claim.
+pub(super) fn custom_ids(node: &Node) -> HashSet<String> {
+    #[derive(Default)]
+    struct Collector {
+        ids: HashSet<String>,
+    }
+
+    impl Visitor for Collector {
+        fn visit_block(&mut self, block: &Block) -> WalkControl {
+            if let Block::Claim(claim) = block {
+                if let (Some(id), false) = (&claim.id, is_automatic_id(claim)) {
+                    self.ids.insert(id.clone());
+                }
+            }
+            WalkControl::Continue
+        }
+    }
+
+    let mut collector = Collector::default();
+    collector.visit(node);
+    collector.ids
+}
+
+#[cfg(test)]
+mod tests {
+    use common_dev::pretty_assertions::assert_eq;
+    use schema::Article;
+
+    use super::*;
+
+    fn claim(claim_type: ClaimType, label: Option<&str>, id: Option<&str>) -> Claim {
+        Claim {
+            label: label.map(String::from),
+            id: id.map(String::from),
+            ..Claim::new(claim_type, Vec::new())
+        }
+    }
+
+    #[test]
+    fn numbered_by_type() {
+        let mut counts = BTreeMap::new();
+        let mut used = HashSet::new();
+
+        let mut next = |claim_type| number(&claim(claim_type, None, None), &mut counts, &mut used);
+        assert_eq!(
+            next(ClaimType::Theorem),
+            (Some("1".to_string()), Some("theorem-1".to_string()))
+        );
+        assert_eq!(
+            next(ClaimType::Lemma),
+            (Some("1".to_string()), Some("lemma-1".to_string()))
+        );
+        assert_eq!(
+            next(ClaimType::Theorem),
+            (Some("2".to_string()), Some("theorem-2".to_string()))
+        );
+    }
+
+    #[test]
+    fn existing_labels_and_ids() {
+        let mut counts = BTreeMap::new();
+        let mut used = HashSet::from(["main".to_string()]);
+
+        // Custom labels and ids are preserved but still count
+        assert_eq!(
+            number(
+                &claim(ClaimType::Theorem, Some("A"), Some("main")),
+                &mut counts,
+                &mut used
+            ),
+            (None, None)
+        );
+
+        // Automatic labels and ids are only updated if they have changed
+        assert_eq!(
+            number(
+                &claim(ClaimType::Theorem, Some("2"), Some("theorem-2")),
+                &mut counts,
+                &mut used
+            ),
+            (None, None)
+        );
+        assert_eq!(
+            number(
+                &claim(ClaimType::Theorem, Some("1"), Some("theorem-1")),
+                &mut counts,
+                &mut used
+            ),
+            (Some("3".to_string()), Some("theorem-3".to_string()))
+        );
+    }
+
+    #[test]
+    fn skip_used_ids() {
+        let theorems = [
+            claim(ClaimType::Theorem, None, None),
+            claim(ClaimType::Theorem, None, Some("theorem-2")),
+            claim(ClaimType::Lemma, None, Some("theorem-1")),
+        ];
+
+        let root = Node::Article(Article::new(
+            theorems.iter().cloned().map(Block::Claim).collect(),
+        ));
+        let mut used = custom_ids(&root);
+        assert_eq!(used, HashSet::from(["theorem-1".to_string()]));
+
+        // The first theorem skips the custom id of the lemma and the second
+        // theorem then skips the id assigned to the first
+        let mut counts = BTreeMap::new();
+        let ids = theorems
+            .iter()
+            .map(|claim| number(claim, &mut counts, &mut used).1)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            vec![
+                Some("theorem-2".to_string()),
+                Some("theorem-3".to_string()),
+                None
+            ]
+        );
+    }
+}
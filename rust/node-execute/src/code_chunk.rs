@@ -1,4 +1,4 @@
-use schema::{CodeChunk, ExecutionKind, LabelType, NodeProperty};
+use schema::{Block, CodeChunk, ExecutionKind, LabelType, NodeId, NodeProperty, Visitor};
 
 use crate::{interrupt_impl, prelude::*};
 
@@ -134,10 +134,11 @@ impl Executable for CodeChunk {
         if !self.code.trim().is_empty() {
             let started = Timestamp::now();
 
+            let timeout = executor.node_execution_timeout(&self.options.execution_tags);
             let (outputs, messages, instance) = executor
                 .kernels()
                 .await
-                .execute(&self.code, self.programming_language.as_deref())
+                .execute_within(&self.code, self.programming_language.as_deref(), timeout)
                 .await
                 .unwrap_or_else(|error| {
                     (
@@ -154,7 +155,7 @@ impl Executable for CodeChunk {
 
             let status = execution_status(&messages);
             let kind = execution_kind(executor);
-            let required = execution_required_status(&status);
+            let required = executor.node_execution_required(&status, &instance).await;
             let duration = execution_duration(&started, &ended);
             let count = self.options.execution_count.unwrap_or_default() + 1;
 
@@ -176,6 +177,11 @@ impl Executable for CodeChunk {
                 executor.patch(&node_id, [set(NodeProperty::Outputs, outputs)]);
             }
 
+            // Set so that, if the instance stops later in the execution, this chunk can
+            // also be marked as requiring re-execution (see `Executor::mark_restarted`)
+            self.options.execution_instance = Some(instance.clone());
+            self.options.execution_kind = Some(kind.clone());
+
             executor.patch(
                 &node_id,
                 [
@@ -221,3 +227,34 @@ impl Executable for CodeChunk {
         WalkControl::Break
     }
 }
+
+/// Get the ids of the code chunks in a node, and the ids of the kernel instances
+/// they were last executed in (excluding those executed in forks)
+pub(super) fn executed_in(root: &Node) -> Vec<(NodeId, String)> {
+    let mut collector = InstanceCollector::default();
+    collector.visit(root);
+    collector.chunks
+}
+
+/// A visitor which collects the kernel instances that code chunks were executed in
+#[derive(Default)]
+struct InstanceCollector {
+    chunks: Vec<(NodeId, String)>,
+}
+
+impl Visitor for InstanceCollector {
+    fn visit_block(&mut self, block: &Block) -> WalkControl {
+        if let Block::CodeChunk(chunk) = block {
+            if let (Some(ExecutionKind::Main), Some(instance)) = (
+                &chunk.options.execution_kind,
+                &chunk.options.execution_instance,
+            ) {
+                if !instance.is_empty() {
+                    self.chunks.push((chunk.node_id(), instance.clone()));
+                }
+            }
+        }
+
+        WalkControl::Continue
+    }
+}
@@ -74,11 +74,12 @@ impl Executable for CodeExpression {
         if !self.code.trim().is_empty() {
             let started = Timestamp::now();
 
+            let timeout = executor.node_execution_timeout(&None);
             let (output, messages, instance) = executor
                 .kernels
                 .write()
                 .await
-                .evaluate(&self.code, self.programming_language.as_deref())
+                .evaluate_within(&self.code, self.programming_language.as_deref(), timeout)
                 .await
                 .unwrap_or_else(|error| {
                     (
@@ -96,7 +97,7 @@ impl Executable for CodeExpression {
             let ended = Timestamp::now();
 
             let status = execution_status(&messages);
-            let required = execution_required_status(&status);
+            let required = executor.node_execution_required(&status, &instance).await;
             let duration = execution_duration(&started, &ended);
             let count = self.options.execution_count.unwrap_or_default() + 1;
 
@@ -0,0 +1,607 @@
+use codec_cbor::r#trait::CborCodec;
+use common::{
+    eyre::{bail, eyre, Result},
+    indexmap::IndexMap,
+    serde_json,
+};
+use node_strip::{StripNode, StripScope, StripTargets};
+use schema::{
+    CompilationDigest, Datatable, Form, FormDeriveAction, Inline, IntegerOrString, Object, Visitor,
+};
+
+use crate::{interrupt_impl, prelude::*};
+
+impl Executable for Form {
+    #[tracing::instrument(skip_all)]
+    async fn compile(&mut self, executor: &mut Executor) -> WalkControl {
+        let node_id = self.node_id();
+        tracing::trace!("Compiling Form {node_id}");
+
+        // The compilation digest is a digest of the `content` and the derive options.
+        // The values of parameters, and the execution state and outputs of nodes, are
+        // stripped from the content so that the digest only changes when the form
+        // itself changes (not when it is filled in or submitted).
+        let mut content = self.content.clone();
+        content.strip(&StripTargets::new(
+            vec![StripScope::Execution, StripScope::Output],
+            Vec::new(),
+            vec!["Parameter.value".to_string()],
+        ));
+        let mut state_digest = 0u64;
+        match content.to_cbor() {
+            Ok(bytes) => add_to_digest(&mut state_digest, &bytes),
+            Err(error) => {
+                tracing::error!("While encoding `content` to CBOR: {error}")
+            }
+        }
+        for option in [
+            self.options.derive_from.clone(),
+            self.options
+                .derive_action
+                .as_ref()
+                .map(|action| action.to_string()),
+            self.options.derive_item.as_ref().map(item_to_string),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            add_to_digest(&mut state_digest, option.as_bytes());
+        }
+
+        let compilation_digest = CompilationDigest::new(state_digest);
+        let execution_required =
+            execution_required_digests(&self.options.execution_digest, &compilation_digest);
+        executor.patch(
+            &node_id,
+            [
+                set(NodeProperty::CompilationDigest, compilation_digest),
+                set(NodeProperty::ExecutionRequired, execution_required),
+            ],
+        );
+
+        // Break walk to avoid walking over `content` (already captured in state digest
+        // and compiled when the form is executed)
+        WalkControl::Break
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn prepare(&mut self, executor: &mut Executor) -> WalkControl {
+        let node_id = self.node_id();
+        tracing::trace!("Preparing Form {node_id}");
+
+        // Forms are only executed on demand (i.e. when submitted) so are only
+        // prepared if explicitly listed in the nodes to execute and are
+        // excluded from executions of the whole document
+        if !executor
+            .node_ids
+            .as_ref()
+            .is_some_and(|node_ids| node_ids.contains(&node_id))
+        {
+            return WalkControl::Break;
+        }
+
+        // Set execution status
+        if let Some(status) = executor.node_execution_status(
+            self.node_type(),
+            &node_id,
+            &self.execution_mode,
+            &self.options.compilation_digest,
+            &self.options.execution_digest,
+        ) {
+            self.options.execution_status = Some(status.clone());
+            executor.patch(&node_id, [set(NodeProperty::ExecutionStatus, status)]);
+        }
+
+        // Break to avoid making executable nodes in `content` as pending
+        WalkControl::Break
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn execute(&mut self, executor: &mut Executor) -> WalkControl {
+        let node_id = self.node_id();
+
+        if !matches!(
+            self.options.execution_status,
+            Some(ExecutionStatus::Pending)
+        ) {
+            tracing::trace!("Skipping Form {node_id}");
+            return WalkControl::Break;
+        }
+
+        tracing::debug!("Executing Form {node_id}");
+
+        executor.patch(
+            &node_id,
+            [
+                set(NodeProperty::ExecutionStatus, ExecutionStatus::Running),
+                none(NodeProperty::ExecutionMessages),
+            ],
+        );
+
+        let started = Timestamp::now();
+
+        let mut messages = Vec::new();
+        let compilation_digest = self.options.compilation_digest.clone();
+
+        if let Some(from) = &self.options.derive_from {
+            // Gather the values of the parameters in the form and apply the
+            // derived action to the variable
+            let mut collector = ParameterCollector::default();
+            collector.visit(&self.content);
+
+            let action = self.options.derive_action.clone().unwrap_or_default();
+            if let Err(error) = derive(
+                executor,
+                from,
+                action,
+                self.options.derive_item.as_ref(),
+                collector.values,
+            )
+            .await
+            {
+                messages.push(error_to_execution_message(
+                    "While applying form action",
+                    error,
+                ));
+            }
+        } else {
+            // Execute the content of the form (setting parameters and executing
+            // any code in it). Temporarily remove any executor node ids so that
+            // nodes within the content are executed.
+            let node_ids = executor.node_ids.take();
+            if let Err(error) = executor.compile_prepare_execute(&mut self.content).await {
+                messages.push(error_to_execution_message(
+                    "While executing form content",
+                    error,
+                ));
+            }
+            executor.node_ids = node_ids;
+        }
+
+        let ended = Timestamp::now();
+
+        let messages = (!messages.is_empty()).then_some(messages);
+        let status = execution_status(&messages);
+        let required = execution_required_status(&status);
+        let duration = execution_duration(&started, &ended);
+        let count = self.options.execution_count.unwrap_or_default() + 1;
+
+        self.options.execution_messages = messages.clone();
+
+        executor.patch(
+            &node_id,
+            [
+                set(NodeProperty::ExecutionStatus, status),
+                set(NodeProperty::ExecutionRequired, required),
+                set(NodeProperty::ExecutionMessages, messages),
+                set(NodeProperty::ExecutionDuration, duration),
+                set(NodeProperty::ExecutionEnded, ended),
+                set(NodeProperty::ExecutionCount, count),
+                set(NodeProperty::ExecutionDigest, compilation_digest),
+            ],
+        );
+
+        WalkControl::Break
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn interrupt(&mut self, executor: &mut Executor) -> WalkControl {
+        let node_id = self.node_id();
+        tracing::debug!("Interrupting Form {node_id}");
+
+        interrupt_impl!(self, executor, &node_id);
+
+        // Continue to interrupt executable nodes in `content`
+        WalkControl::Continue
+    }
+}
+
+/// A visitor that collects the names and values of parameters
+#[derive(Default)]
+struct ParameterCollector {
+    values: IndexMap<String, Primitive>,
+}
+
+impl Visitor for ParameterCollector {
+    fn visit_inline(&mut self, inline: &Inline) -> WalkControl {
+        if let Inline::Parameter(parameter) = inline {
+            let value = parameter
+                .value
+                .as_deref()
+                .or(parameter.default.as_deref())
+                .map(to_primitive)
+                .unwrap_or_default();
+            self.values.insert(parameter.name.clone(), value);
+        }
+
+        WalkControl::Continue
+    }
+}
+
+/// Convert a node to a primitive
+///
+/// Uses a JSON round trip so that non-primitive nodes are represented as objects.
+fn to_primitive(node: &Node) -> Primitive {
+    serde_json::to_value(node)
+        .and_then(serde_json::from_value)
+        .unwrap_or_default()
+}
+
+/// Apply the action derived for a form to a variable in the kernels
+///
+/// The variable can be a `Datatable`, in which case parameters correspond to columns,
+/// or an `Array` of objects, in which case parameters correspond to properties.
+/// Items can be identified by their zero-based index or by the value of their first
+/// column or property (e.g. an id). For `UpdateOrDelete`, the item is deleted if
+/// all of the parameters are empty, otherwise it is updated.
+async fn derive(
+    executor: &mut Executor,
+    from: &str,
+    action: FormDeriveAction,
+    item: Option<&IntegerOrString>,
+    values: IndexMap<String, Primitive>,
+) -> Result<()> {
+    let action = match action {
+        FormDeriveAction::UpdateOrDelete => {
+            if values
+                .values()
+                .all(|value| matches!(value, Primitive::Null(..)))
+            {
+                FormDeriveAction::Delete
+            } else {
+                FormDeriveAction::Update
+            }
+        }
+        action => action,
+    };
+
+    let mut kernels = executor.kernels().await;
+    let Some(mut variable) = kernels.get(from).await? else {
+        bail!("Variable `{from}` does not exist")
+    };
+
+    match &mut variable {
+        Node::Datatable(datatable) => derive_datatable(datatable, action, item, values)?,
+        Node::Array(array) => derive_array(array, action, item, values)?,
+        _ => bail!(
+            "Unable to derive form action for variable `{from}` of type `{}`",
+            variable.node_type()
+        ),
+    }
+
+    kernels.set(from, &variable).await
+}
+
+/// Apply a form action to a datatable
+fn derive_datatable(
+    datatable: &mut Datatable,
+    action: FormDeriveAction,
+    item: Option<&IntegerOrString>,
+    mut values: IndexMap<String, Primitive>,
+) -> Result<()> {
+    if let Some(name) = values
+        .keys()
+        .find(|name| !datatable.columns.iter().any(|column| &column.name == *name))
+    {
+        bail!("Datatable does not have a column named `{name}`")
+    }
+
+    if matches!(action, FormDeriveAction::Create) {
+        for column in datatable.columns.iter_mut() {
+            let value = values.swap_remove(&column.name).unwrap_or_default();
+            column.values.push(value);
+        }
+        return Ok(());
+    }
+
+    let rows = datatable
+        .columns
+        .first()
+        .map(|column| column.values.len())
+        .unwrap_or_default();
+    let row = item_index(item, rows, |key| {
+        datatable.columns.first().and_then(|column| {
+            column
+                .values
+                .iter()
+                .position(|value| matches_key(value, key))
+        })
+    })?;
+
+    for column in datatable.columns.iter_mut() {
+        if matches!(action, FormDeriveAction::Delete) {
+            if row < column.values.len() {
+                column.values.remove(row);
+            }
+        } else if let (Some(value), Some(cell)) =
+            (values.swap_remove(&column.name), column.values.get_mut(row))
+        {
+            *cell = value;
+        }
+    }
+
+    Ok(())
+}
+
+/// Apply a form action to an array of objects
+fn derive_array(
+    array: &mut Array,
+    action: FormDeriveAction,
+    item: Option<&IntegerOrString>,
+    values: IndexMap<String, Primitive>,
+) -> Result<()> {
+    if matches!(action, FormDeriveAction::Create) {
+        array.push(Primitive::Object(Object(values)));
+        return Ok(());
+    }
+
+    let index = item_index(item, array.len(), |key| {
+        array.iter().position(|value| match value {
+            Primitive::Object(object) => object
+                .values()
+                .next()
+                .is_some_and(|value| matches_key(value, key)),
+            _ => false,
+        })
+    })?;
+
+    if matches!(action, FormDeriveAction::Delete) {
+        array.remove(index);
+    } else {
+        match &mut array[index] {
+            Primitive::Object(object) => object.extend(values),
+            value => *value = Primitive::Object(Object(values)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Get the index of the item that is the target of an update or delete action
+fn item_index<F>(item: Option<&IntegerOrString>, len: usize, find: F) -> Result<usize>
+where
+    F: Fn(&str) -> Option<usize>,
+{
+    let index = match item {
+        Some(IntegerOrString::Integer(index)) => usize::try_from(*index).ok(),
+        Some(IntegerOrString::String(key)) => find(key),
+        None => bail!("An item is required to update or delete"),
+    };
+
+    index.filter(|index| *index < len).ok_or_else(|| {
+        eyre!(
+            "No item matching `{}`",
+            item.map(item_to_string).unwrap_or_default()
+        )
+    })
+}
+
+/// Whether a value matches the key of an item
+fn matches_key(value: &Primitive, key: &str) -> bool {
+    match value {
+        Primitive::String(value) => value == key,
+        Primitive::Integer(value) => value.to_string() == key,
+        Primitive::UnsignedInteger(value) => value.to_string() == key,
+        _ => false,
+    }
+}
+
+/// Get the identifier of an item as a string
+fn item_to_string(item: &IntegerOrString) -> String {
+    match item {
+        IntegerOrString::Integer(index) => index.to_string(),
+        IntegerOrString::String(key) => key.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_dev::pretty_assertions::assert_eq;
+    use schema::{DatatableColumn, Null};
+
+    use super::*;
+
+    fn s(value: &str) -> Primitive {
+        Primitive::String(value.to_string())
+    }
+
+    fn i(value: i64) -> Primitive {
+        Primitive::Integer(value)
+    }
+
+    fn values(pairs: &[(&str, Primitive)]) -> IndexMap<String, Primitive> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect()
+    }
+
+    fn key(key: &str) -> Option<IntegerOrString> {
+        Some(IntegerOrString::String(key.to_string()))
+    }
+
+    fn index(index: i64) -> Option<IntegerOrString> {
+        Some(IntegerOrString::Integer(index))
+    }
+
+    #[test]
+    fn derive_datatables() {
+        use FormDeriveAction::*;
+
+        let null = Primitive::Null(Null);
+
+        // The action, item, and values, and the expected `id` and `n` columns
+        // after applying it (or `None` if it should fail)
+        #[allow(clippy::type_complexity)]
+        let cases: Vec<(
+            FormDeriveAction,
+            Option<IntegerOrString>,
+            IndexMap<String, Primitive>,
+            Option<(Vec<Primitive>, Vec<Primitive>)>,
+        )> = vec![
+            (
+                Create,
+                None,
+                values(&[("id", s("c")), ("n", i(3))]),
+                Some((vec![s("a"), s("b"), s("c")], vec![i(1), i(2), i(3)])),
+            ),
+            (
+                Create,
+                None,
+                values(&[("id", s("c"))]),
+                Some((vec![s("a"), s("b"), s("c")], vec![i(1), i(2), null.clone()])),
+            ),
+            (
+                Update,
+                index(1),
+                values(&[("n", i(5))]),
+                Some((vec![s("a"), s("b")], vec![i(1), i(5)])),
+            ),
+            (
+                Update,
+                key("a"),
+                values(&[("n", i(7))]),
+                Some((vec![s("a"), s("b")], vec![i(7), i(2)])),
+            ),
+            (
+                Delete,
+                key("b"),
+                values(&[]),
+                Some((vec![s("a")], vec![i(1)])),
+            ),
+            (
+                Delete,
+                index(0),
+                values(&[]),
+                Some((vec![s("b")], vec![i(2)])),
+            ),
+            // Unknown column
+            (Create, None, values(&[("x", i(1))]), None),
+            (Update, index(0), values(&[("x", i(1))]), None),
+            // Missing keys and out of range indices
+            (Update, key("z"), values(&[("n", i(1))]), None),
+            (Delete, key("z"), values(&[]), None),
+            (Update, index(2), values(&[("n", i(1))]), None),
+            (Delete, index(-1), values(&[]), None),
+            (Update, None, values(&[("n", i(1))]), None),
+        ];
+
+        for (action, item, values, expected) in cases {
+            let mut datatable = Datatable::new(vec![
+                DatatableColumn::new("id".to_string(), vec![s("a"), s("b")]),
+                DatatableColumn::new("n".to_string(), vec![i(1), i(2)]),
+            ]);
+            let case = format!("{action:?} {item:?} {values:?}");
+
+            let result = derive_datatable(&mut datatable, action, item.as_ref(), values);
+            match expected {
+                Some((ids, ns)) => {
+                    assert!(result.is_ok(), "{case}: {result:?}");
+                    assert_eq!(datatable.columns[0].values, ids, "{case}");
+                    assert_eq!(datatable.columns[1].values, ns, "{case}");
+                }
+                None => assert!(result.is_err(), "{case}: expected error"),
+            }
+        }
+    }
+
+    #[test]
+    fn derive_arrays() {
+        use FormDeriveAction::*;
+
+        let object =
+            |id: Primitive, n: i64| Primitive::Object(Object::from([("id", id), ("n", i(n))]));
+
+        // The action, item, and values, and the expected array after
+        // applying it (or `None` if it should fail)
+        #[allow(clippy::type_complexity)]
+        let cases: Vec<(
+            FormDeriveAction,
+            Option<IntegerOrString>,
+            IndexMap<String, Primitive>,
+            Option<Vec<Primitive>>,
+        )> = vec![
+            (
+                Create,
+                None,
+                values(&[("id", s("c")), ("n", i(3))]),
+                Some(vec![object(s("a"), 1), object(i(2), 2), object(s("c"), 3)]),
+            ),
+            (
+                Update,
+                index(0),
+                values(&[("n", i(5))]),
+                Some(vec![object(s("a"), 5), object(i(2), 2)]),
+            ),
+            // Integer keys are matched by their string representation
+            (
+                Update,
+                key("2"),
+                values(&[("n", i(9))]),
+                Some(vec![object(s("a"), 1), object(i(2), 9)]),
+            ),
+            (Delete, key("a"), values(&[]), Some(vec![object(i(2), 2)])),
+            // Missing keys and out of range indices
+            (Update, key("z"), values(&[("n", i(1))]), None),
+            (Delete, key("1"), values(&[]), None),
+            (Update, index(2), values(&[("n", i(1))]), None),
+            (Delete, index(-1), values(&[]), None),
+            (Delete, None, values(&[]), None),
+        ];
+
+        for (action, item, values, expected) in cases {
+            let mut array = Array(vec![object(s("a"), 1), object(i(2), 2)]);
+            let case = format!("{action:?} {item:?} {values:?}");
+
+            let result = derive_array(&mut array, action, item.as_ref(), values);
+            match expected {
+                Some(expected) => {
+                    assert!(result.is_ok(), "{case}: {result:?}");
+                    assert_eq!(array.0, expected, "{case}");
+                }
+                None => assert!(result.is_err(), "{case}: expected error"),
+            }
+        }
+    }
+
+    #[test]
+    fn item_indices() {
+        // Only the key `k` is found, at index 1
+        let find = |key: &str| (key == "k").then_some(1);
+
+        for (item, len, expected) in [
+            (index(0), 2, Some(0)),
+            (index(1), 2, Some(1)),
+            (index(2), 2, None),
+            (index(-1), 2, None),
+            (index(0), 0, None),
+            (key("k"), 2, Some(1)),
+            (key("k"), 1, None),
+            (key("x"), 2, None),
+            (None, 2, None),
+        ] {
+            assert_eq!(
+                item_index(item.as_ref(), len, find).ok(),
+                expected,
+                "{item:?} {len}"
+            );
+        }
+    }
+
+    #[test]
+    fn matches_keys() {
+        for (value, key, expected) in [
+            (s("a"), "a", true),
+            (s("a"), "b", false),
+            (s(""), "", true),
+            (i(-3), "-3", true),
+            (i(3), "03", false),
+            (Primitive::UnsignedInteger(3), "3", true),
+            (Primitive::Number(3.0), "3", false),
+            (Primitive::Boolean(true), "true", false),
+            (Primitive::Null(Null), "", false),
+        ] {
+            assert_eq!(matches_key(&value, key), expected, "{value:?} {key}");
+        }
+    }
+}
@@ -8,6 +8,7 @@ use common::{
     itertools::Itertools,
     tokio,
 };
+use model::ModelTaskCache;
 use schema::{
     Author, AuthorRole, AuthorRoleAuthor, AuthorRoleName, CompilationDigest, InstructionBlock,
     InstructionModel, PromptBlock, SoftwareApplication,
@@ -226,7 +227,7 @@ impl Executable for InstructionBlock {
 
         // Create a future for each replicate
         let mut futures = FuturesUnordered::new();
-        for _ in 0..replicates {
+        for replicate in 0..replicates {
             // TODO: rather than repeating all this prep work to create a model task
             // within `prompts::execute_instruction_block` it could be done
             // once, and then clones and moved to each instruction.
@@ -235,6 +236,10 @@ impl Executable for InstructionBlock {
             let system_prompt = system_prompt.to_string();
             let mut instruction = self.clone();
             let dry_run = executor.options.dry_run;
+            let cache = ModelTaskCache {
+                bypass: executor.options.no_cache,
+                ttl: executor.options.cache_ttl,
+            };
             if let Some(id_pattern) = model_id_pattern.clone() {
                 // Apply the model id for revisions
                 let id_pattern = Some(id_pattern);
@@ -255,7 +260,9 @@ impl Executable for InstructionBlock {
                     prompter,
                     &system_prompt,
                     &instruction,
+                    replicate as u32,
                     dry_run,
+                    cache,
                 )
                 .await
             })
@@ -1,6 +1,10 @@
 #![recursion_limit = "256"]
 
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+};
 
 use common::{
     clap::{self, Args},
@@ -11,11 +15,13 @@ use common::{
     tracing,
 };
 use kernels::Kernels;
+use prelude::{execution_required_status, set};
 use prompts::prompt::{DocumentContext, InstructionContext};
 use schema::{
-    AuthorRole, AuthorRoleName, Block, CompilationDigest, ExecutionKind, ExecutionMode,
-    ExecutionStatus, Inline, Link, List, ListItem, ListOrder, Node, NodeId, NodeProperty, NodeType,
-    Paragraph, Patch, PatchOp, PatchPath, Timestamp, VisitorAsync, WalkControl, WalkNode,
+    AuthorRole, AuthorRoleName, Block, ClaimType, CompilationDigest, ExecutionKind, ExecutionMode,
+    ExecutionRequired, ExecutionStatus, ExecutionTag, Inline, Link, List, ListItem, ListOrder,
+    Node, NodeId, NodeProperty, NodeType, Paragraph, Patch, PatchOp, PatchPath, Timestamp,
+    VisitorAsync, WalkControl, WalkNode,
 };
 
 type NodeIds = Vec<NodeId>;
@@ -23,11 +29,14 @@ type NodeIds = Vec<NodeId>;
 mod prelude;
 
 mod article;
+mod button;
 mod call_block;
+mod claim;
 mod code_chunk;
 mod code_expression;
 mod figure;
 mod for_block;
+mod form;
 mod heading;
 mod if_block;
 mod include_block;
@@ -71,7 +80,22 @@ pub async fn execute(
     let mut root = root.read().await.clone();
     let mut executor = Executor::new(home, kernels, patch_sender, node_ids, options);
     executor.prepare(&mut root).await?;
-    executor.execute(&mut root).await
+    executor.execute(&mut root).await?;
+    executor.mark_restarted(&root).await;
+
+    // If any buttons were clicked, execute the nodes that depend upon them
+    let variables = std::mem::take(&mut executor.button_variables);
+    if !variables.is_empty() {
+        let node_ids = button::dependants(&root, &variables);
+        if !node_ids.is_empty() {
+            executor.node_ids = Some(node_ids);
+            executor.prepare(&mut root).await?;
+            executor.execute(&mut root).await?;
+            executor.mark_restarted(&root).await;
+        }
+    }
+
+    Ok(())
 }
 
 /// Walk over a root node and interrupt it and child nodes
@@ -175,12 +199,23 @@ pub struct Executor {
     /// The count of `MathBlock`s
     equation_count: u32,
 
+    /// The count of `Claim`s of each type
+    claim_counts: BTreeMap<ClaimType, u32>,
+
+    /// The ids of `Claim`s that are in use and so can not be assigned automatically
+    claim_ids: HashSet<String>,
+
     /// Whether the current node is the last in a set
     ///
     /// Used for `IfBlock` (and possibly others) to control behavior of execution
     /// of child nodes.
     is_last: bool,
 
+    /// The names of the variables set by buttons during execution
+    ///
+    /// Used to re-execute the nodes that depend upon buttons after they are clicked.
+    button_variables: Vec<String>,
+
     /// Options for execution
     options: ExecuteOptions,
 }
@@ -312,6 +347,46 @@ pub struct ExecuteOptions {
     /// rendering of prompts without making a potentially slow generative model API request.
     #[arg(long)]
     pub dry_run: bool,
+
+    /// Do not use cached outputs of generative models
+    ///
+    /// By default, the outputs of generative models used to execute instructions are cached
+    /// and reused when an instruction is executed again with identical messages, model and
+    /// parameters. Use this flag to bypass the cache and always generate new outputs.
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// The maximum age, in seconds, of cached outputs of generative models to use
+    ///
+    /// By default, cached outputs are used regardless of their age.
+    #[arg(long)]
+    pub cache_ttl: Option<u64>,
+
+    /// The maximum time, in seconds, for the execution of each code chunk and expression
+    ///
+    /// If exceeded, the kernel is interrupted and, if it does not respond, killed and
+    /// then restarted for subsequent executions. Can be overridden for individual code
+    /// chunks using a shorter `@timeout` tag in a comment (e.g. `# @timeout 60`). Takes
+    /// precedence over any `timeout` in the document's config but can not exceed any timeout
+    /// set in the user's kernel configuration.
+    #[arg(long)]
+    pub timeout: Option<u64>,
+
+    /// Allow the document to access a user-defined secret
+    ///
+    /// Secrets listed in the `secrets` of a document's config are only provided to kernels
+    /// if they are allowed using this option. Repeat for each secret (e.g. `--allow-secret DB_USER
+    /// --allow-secret DB_PASSWORD`). Secrets used by Stencila, such as API keys, can not be allowed.
+    #[arg(long = "allow-secret", value_name = "NAME")]
+    pub allowed_secrets: Vec<String>,
+
+    /// The values of parameters to set in the document before execution
+    ///
+    /// Not a command line option of all commands which execute documents. Set using the
+    /// `--param` and `--params` options of the `render` command. Values are parsed according
+    /// to the type of the parameter, or as JSON if it has no type, falling back to a string.
+    #[arg(skip)]
+    pub parameters: Vec<(String, String)>,
 }
 
 /// A phase of an [`Executor`]
@@ -349,7 +424,10 @@ impl Executor {
             table_count: 0,
             figure_count: 0,
             equation_count: 0,
+            claim_counts: BTreeMap::new(),
+            claim_ids: HashSet::new(),
             is_last: false,
+            button_variables: Vec::new(),
             options: options.unwrap_or_default(),
         }
     }
@@ -419,6 +497,8 @@ impl Executor {
         self.table_count = 0;
         self.figure_count = 0;
         self.equation_count = 0;
+        self.claim_counts.clear();
+        self.claim_ids = claim::custom_ids(root);
         root.walk_async(self).await
     }
 
@@ -467,6 +547,66 @@ impl Executor {
         self.kernels.write().await
     }
 
+    /// Get the timeout for the execution of a node
+    ///
+    /// Uses the lesser of the value of any `@timeout` tag in the code of the node and
+    /// the `timeout` option of the executor, so that a document can shorten, but not
+    /// lengthen, the timeout set by the user. If neither is set, the kernels fall back
+    /// to the document's config (see `Kernels::task_timeout`).
+    pub fn node_execution_timeout(
+        &self,
+        tags: &Option<Vec<ExecutionTag>>,
+    ) -> Option<std::time::Duration> {
+        let tag = tags
+            .iter()
+            .flatten()
+            .find(|tag| tag.name == "timeout")
+            .and_then(|tag| tag.value.parse::<u64>().ok());
+
+        match (tag, self.options.timeout) {
+            (Some(tag), Some(option)) => Some(tag.min(option)),
+            (tag, option) => tag.or(option),
+        }
+        .map(std::time::Duration::from_secs)
+    }
+
+    /// Mark code chunks that were executed in kernel instances that have since stopped
+    ///
+    /// When an instance stops (e.g. because it was killed after a timeout) the state of all
+    /// the code chunks executed in it is lost, not just that of the chunk being executed when
+    /// it stopped, so all of them require re-execution.
+    async fn mark_restarted(&self, root: &Node) {
+        let mut kernels = self.kernels().await;
+        for (node_id, instance) in code_chunk::executed_in(root) {
+            if !kernels.has_instance(&instance).await {
+                self.patch(
+                    &node_id,
+                    [set(
+                        NodeProperty::ExecutionRequired,
+                        ExecutionRequired::KernelRestarted,
+                    )],
+                );
+            }
+        }
+    }
+
+    /// Get the execution required status of a node after it has been executed
+    ///
+    /// If the kernel instance that the node was executed in is no longer in the set of
+    /// kernels (because it stopped during the execution and will be restarted) then
+    /// returns `KernelRestarted`.
+    pub async fn node_execution_required(
+        &self,
+        status: &ExecutionStatus,
+        instance: &str,
+    ) -> ExecutionRequired {
+        if !instance.is_empty() && !self.kernels().await.has_instance(instance).await {
+            ExecutionRequired::KernelRestarted
+        } else {
+            execution_required_status(status)
+        }
+    }
+
     /// Get the execution status for a node based on state of node
     /// and options of the executor
     pub fn node_execution_status(
@@ -635,9 +775,11 @@ impl VisitorAsync for Executor {
         use Block::*;
         Ok(match block {
             CallBlock(node) => self.visit_executable(node).await,
+            Claim(node) => self.visit_executable(node).await,
             CodeChunk(node) => self.visit_executable(node).await,
             Figure(node) => self.visit_executable(node).await,
             ForBlock(node) => self.visit_executable(node).await,
+            Form(node) => self.visit_executable(node).await,
             Heading(node) => self.visit_executable(node).await,
             IfBlock(node) => self.visit_executable(node).await,
             IncludeBlock(node) => self.visit_executable(node).await,
@@ -657,6 +799,7 @@ impl VisitorAsync for Executor {
     async fn visit_inline(&mut self, inline: &mut Inline) -> Result<WalkControl> {
         use Inline::*;
         Ok(match inline {
+            Button(node) => self.visit_executable(node).await,
             CodeExpression(node) => self.visit_executable(node).await,
             InstructionInline(node) => self.visit_executable(node).await,
             MathInline(node) => self.visit_executable(node).await,
@@ -1,4 +1,8 @@
-use schema::Parameter;
+use common::{
+    eyre::{bail, Result},
+    serde_json,
+};
+use schema::{Parameter, Validator};
 
 use crate::prelude::*;
 
@@ -9,17 +13,142 @@ impl Executable for Parameter {
 
         tracing::debug!("Executing Parameter {node_id}");
 
+        let started = Timestamp::now();
+        let mut messages = Vec::new();
+
+        // If a value for the parameter was supplied in the execution options then
+        // set it as the value of the parameter (the last one wins if repeated)
+        if let Some((.., value)) = executor
+            .options
+            .parameters
+            .iter()
+            .rev()
+            .find(|(name, ..)| name == &self.name)
+        {
+            match parse_value(value, self.validator.as_ref()) {
+                Ok(value) => {
+                    self.value = Some(Box::new(value.clone()));
+                    executor.patch(&node_id, [set(NodeProperty::Value, value)]);
+                }
+                Err(error) => messages.push(error_to_execution_message(
+                    &format!("While parsing value for parameter `{}`", self.name),
+                    error,
+                )),
+            }
+        }
+
+        // Set the value, or default, of the parameter as a variable in the kernels
+        if let Some(value) = self.value.as_deref().or(self.default.as_deref()) {
+            if let Err(error) = executor.kernels().await.set(&self.name, value).await {
+                messages.push(error_to_execution_message(
+                    &format!("While setting parameter `{}`", self.name),
+                    error,
+                ));
+            }
+        }
+
+        let messages = (!messages.is_empty()).then_some(messages);
+
+        let ended = Timestamp::now();
+
+        let status = execution_status(&messages);
+        let duration = execution_duration(&started, &ended);
+        let count = self.options.execution_count.unwrap_or_default() + 1;
+
         executor.patch(
             &node_id,
-            [set(
-                NodeProperty::ExecutionMessages,
-                vec![ExecutionMessage::new(
-                    MessageLevel::Warning,
-                    "Execution of parameters is not yet implemented".to_string(),
-                )],
-            )],
+            [
+                set(NodeProperty::ExecutionStatus, status),
+                set(NodeProperty::ExecutionRequired, ExecutionRequired::No),
+                set(NodeProperty::ExecutionMessages, messages),
+                set(NodeProperty::ExecutionDuration, duration),
+                set(NodeProperty::ExecutionEnded, ended),
+                set(NodeProperty::ExecutionCount, count),
+            ],
         );
 
         WalkControl::Break
     }
 }
+
+/// Parse the value of a parameter from a string
+///
+/// Uses the validator of the parameter, if any, to determine the type of the value.
+/// Otherwise, parses the string as JSON (so that numbers, booleans etc have the
+/// expected type) falling back to a string.
+fn parse_value(value: &str, validator: Option<&Validator>) -> Result<Node> {
+    let trimmed = value.trim();
+    Ok(match validator {
+        Some(Validator::BooleanValidator(..)) => match trimmed.to_lowercase().as_str() {
+            "true" | "yes" | "1" => Node::Boolean(true),
+            "false" | "no" | "0" => Node::Boolean(false),
+            _ => bail!("`{value}` is not a boolean"),
+        },
+        Some(Validator::IntegerValidator(..)) => match trimmed.parse() {
+            Ok(integer) => Node::Integer(integer),
+            Err(..) => bail!("`{value}` is not an integer"),
+        },
+        Some(Validator::NumberValidator(..)) => match trimmed.parse() {
+            Ok(number) => Node::Number(number),
+            Err(..) => bail!("`{value}` is not a number"),
+        },
+        Some(Validator::StringValidator(..)) => Node::String(value.to_string()),
+        _ => serde_json::from_str(trimmed).unwrap_or_else(|_| Node::String(value.to_string())),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use common_dev::pretty_assertions::assert_eq;
+    use schema::{BooleanValidator, IntegerValidator, Null, NumberValidator, StringValidator};
+
+    use super::*;
+
+    #[test]
+    fn untyped_values() -> Result<()> {
+        assert_eq!(parse_value("1", None)?, Node::Integer(1));
+        assert_eq!(parse_value(" -1.5 ", None)?, Node::Number(-1.5));
+        assert_eq!(parse_value("true", None)?, Node::Boolean(true));
+        assert_eq!(parse_value("null", None)?, Node::Null(Null));
+        assert_eq!(parse_value(r#""1""#, None)?, Node::String("1".to_string()));
+        assert_eq!(parse_value("A12", None)?, Node::String("A12".to_string()));
+        assert_eq!(parse_value("", None)?, Node::String(String::new()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn typed_values() -> Result<()> {
+        let boolean = Validator::BooleanValidator(BooleanValidator::new());
+        for value in ["true", "Yes", "1"] {
+            assert_eq!(parse_value(value, Some(&boolean))?, Node::Boolean(true));
+        }
+        for value in ["FALSE", "no", " 0 "] {
+            assert_eq!(parse_value(value, Some(&boolean))?, Node::Boolean(false));
+        }
+        assert!(parse_value("maybe", Some(&boolean)).is_err());
+
+        let integer = Validator::IntegerValidator(IntegerValidator::new());
+        assert_eq!(parse_value(" 42 ", Some(&integer))?, Node::Integer(42));
+        assert!(parse_value("4.2", Some(&integer)).is_err());
+        assert!(parse_value("", Some(&integer)).is_err());
+
+        let number = Validator::NumberValidator(NumberValidator::new());
+        assert_eq!(parse_value("4.2", Some(&number))?, Node::Number(4.2));
+        assert_eq!(parse_value("4", Some(&number))?, Node::Number(4.0));
+        assert!(parse_value("four", Some(&number)).is_err());
+
+        // Strings are not trimmed or parsed as JSON
+        let string = Validator::StringValidator(StringValidator::new());
+        assert_eq!(
+            parse_value(" 42", Some(&string))?,
+            Node::String(" 42".to_string())
+        );
+        assert_eq!(
+            parse_value("true", Some(&string))?,
+            Node::String("true".to_string())
+        );
+
+        Ok(())
+    }
+}
@@ -17,12 +17,12 @@ pub fn node_map<T: WalkNode>(node: &T) -> IndexMap<NodeId, NodePath> {
 }
 
 /// The path to a node within another node
-#[derive(Default, Clone, Serialize, Deref, DerefMut)]
+#[derive(Debug, Default, Clone, Serialize, Deref, DerefMut)]
 #[serde(crate = "common::serde")]
 pub struct NodePath(Vec<NodePathSegment>);
 
 /// A segment in a node path
-#[derive(Clone, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(untagged, crate = "common::serde")]
 pub enum NodePathSegment {
     Property(SmolStr),
@@ -56,6 +56,7 @@ pub enum NodeProperty {
     ContentUrl,
     Contributors,
     CostWeight,
+    CpuLimit,
     Css,
     DateAccepted,
     DateCreated,
@@ -159,7 +160,9 @@ pub enum NodeProperty {
     MediaType,
     MemberOf,
     Members,
+    MemoryLimit,
     Message,
+    Metadata,
     MinItems,
     MinLength,
     Minimum,
@@ -202,11 +205,13 @@ pub enum NodeProperty {
     Publisher,
     QualityWeight,
     RandomSeed,
+    Ratings,
     Recursion,
     References,
     Rel,
     Replacement,
     Replicates,
+    Restricted,
     Returns,
     ReviewAspect,
     Reviews,
@@ -216,6 +221,7 @@ pub enum NodeProperty {
     RowType,
     Rows,
     RuntimePlatform,
+    Secrets,
     SectionType,
     Select,
     SemanticDigest,
@@ -244,6 +250,7 @@ pub enum NodeProperty {
     Thumbnail,
     TimeUnit,
     TimeUnits,
+    Timeout,
     Title,
     Transcript,
     Type,
@@ -1,9 +1,12 @@
 use std::hash::{Hash, Hasher};
 
-use common::{once_cell::sync::Lazy, regex::Regex, seahash::SeaHasher};
+use common::{itertools::Itertools, once_cell::sync::Lazy, regex::Regex, seahash::SeaHasher};
 use format::Format;
 use schema::{CompilationDigest, ExecutionTag};
 
+mod syntax;
+pub use syntax::{is_identifier, is_identifier_char, Identifier, Syntax};
+
 // Re-exports for the convenience of internal crates implementing
 // the `Parser` trait
 pub use common;
@@ -17,6 +20,12 @@ pub struct ParseInfo {
 
     /// Tags parsed from comments in the code
     pub execution_tags: Option<Vec<ExecutionTag>>,
+
+    /// The names of the variables that the code may use
+    pub uses: Vec<String>,
+
+    /// The names of the variables that the code may assign
+    pub assigns: Vec<String>,
 }
 
 /// A parser of code in a programming language
@@ -46,7 +55,7 @@ pub trait Parser: Sync + Send {
     fn execution_tags(&self, code: &str) -> Option<Vec<ExecutionTag>> {
         static REGEX: Lazy<Regex> = Lazy::new(|| {
             Regex::new(
-                r"@(pure|impure|assigns|changes|uses|imports|reads|writes|watches)\s*([^\n]*)",
+                r"@(pure|impure|assigns|changes|uses|imports|reads|writes|watches|timeout)\s*([^\n]*)",
             )
             .expect("Invalid regex")
         });
@@ -71,6 +80,47 @@ pub trait Parser: Sync + Send {
         }
     }
 
+    /// Get the names of the variables that some code may use
+    ///
+    /// This default implementation uses the lexical [`Syntax`] of the language to find the
+    /// identifiers in the code which are not keywords, attributes, or within strings or
+    /// comments, as well as the names declared using `@uses` tags. As such, it will include
+    /// names which are not variables (e.g. functions) but should not miss those that are.
+    fn uses(&self, code: &str, format: &Format) -> Vec<String> {
+        let identifiers = Syntax::new(format)
+            .identifiers(code)
+            .into_iter()
+            .map(|identifier| identifier.name);
+
+        let tagged = self
+            .execution_tags(code)
+            .into_iter()
+            .flatten()
+            .filter_map(|tag| (tag.name == "uses").then_some(tag.value));
+
+        identifiers.chain(tagged).unique().collect()
+    }
+
+    /// Get the names of the variables that some code may assign
+    ///
+    /// This default implementation uses the lexical [`Syntax`] of the language to find
+    /// the identifiers which are assigned to, or declared, (e.g. `x = 1`, `let x`, `def x(`)
+    /// as well as the names declared using `@assigns` tags.
+    fn assigns(&self, code: &str, format: &Format) -> Vec<String> {
+        let assigned = Syntax::new(format)
+            .identifiers(code)
+            .into_iter()
+            .filter_map(|identifier| identifier.is_assignment.then_some(identifier.name));
+
+        let tagged = self
+            .execution_tags(code)
+            .into_iter()
+            .flatten()
+            .filter_map(|tag| (tag.name == "assigns").then_some(tag.value));
+
+        assigned.chain(tagged).unique().collect()
+    }
+
     /// Parse code in a language
     fn parse(&self, code: &str, format: &Format) -> ParseInfo;
 }
@@ -94,6 +144,76 @@ impl Parser for DefaultParser {
                 ..Default::default()
             },
             execution_tags: self.execution_tags(code),
+            uses: self.uses(code, format),
+            assigns: self.assigns(code, format),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uses() {
+        let parser = DefaultParser::default();
+        let uses = |code: &str, format: Format| parser.uses(code, &format);
+
+        assert_eq!(uses("a + b_1 * 2", Format::Unknown), vec!["a", "b_1"]);
+        assert_eq!(uses("f(a, a)", Format::Unknown), vec!["f", "a"]);
+        assert_eq!(
+            uses("'a' + \"b \\\" c\" + `d`", Format::Unknown),
+            Vec::<String>::new()
+        );
+        assert_eq!(uses("# @uses x y\nz", Format::Python), vec!["z", "x", "y"]);
+
+        // Comments are language specific: in Python `//` is integer division
+        // and in JavaScript `#` is used for private fields
+        assert_eq!(
+            uses("a # b\nc // d\ne / f", Format::Unknown),
+            vec!["a", "c", "e", "f"]
+        );
+        assert_eq!(
+            uses("a # b\nc // d\ne / f", Format::Python),
+            vec!["a", "c", "d", "e", "f"]
+        );
+        assert_eq!(
+            uses("a // b\nc /* d */ + e", Format::JavaScript),
+            vec!["a", "c", "e"]
+        );
+
+        // Attributes and keywords are excluded
+        assert_eq!(uses("df.head()", Format::Python), vec!["df"]);
+        assert_eq!(uses("dfs.head()", Format::Python), vec!["dfs"]);
+        assert_eq!(uses("if x is None: pass", Format::Python), vec!["x"]);
+
+        // R identifiers can include dots
+        assert_eq!(
+            uses("summary(my.data$col)", Format::R),
+            vec!["summary", "my.data"]
+        );
+        assert_eq!(uses("my_data", Format::R), vec!["my_data"]);
+    }
+
+    #[test]
+    fn assigns() {
+        let parser = DefaultParser::default();
+
+        assert_eq!(
+            parser.assigns("a = 1\nb <- a\nf(c = 2)", &Format::R),
+            vec!["a", "b"]
+        );
+        assert_eq!(
+            parser.assigns("def f(x):\n  y = x\n  return y", &Format::Python),
+            vec!["f", "y"]
+        );
+        assert_eq!(
+            parser.assigns("let a = 1; a += 1; // b = 2", &Format::JavaScript),
+            vec!["a"]
+        );
+        assert_eq!(
+            parser.assigns("# @assigns x\nload()", &Format::Python),
+            vec!["x"]
+        );
+    }
+}
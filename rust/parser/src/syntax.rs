@@ -0,0 +1,515 @@
+use format::Format;
+
+/// The lexical syntax of a programming language
+///
+/// Describes the strings, comments and keywords of a language in enough detail to
+/// find the identifiers in code, and whether they are assigned to, without a
+/// language specific parser.
+pub struct Syntax {
+    /// Markers for the start of line comments
+    line_comments: &'static [&'static str],
+
+    /// Markers for the start and end of block comments
+    block_comment: Option<(&'static str, &'static str)>,
+
+    /// Characters which delimit strings
+    ///
+    /// Strings delimited by three of these characters (e.g. `"""` in Python)
+    /// are also recognized and, like those delimited by backticks, may span
+    /// several lines.
+    quotes: &'static [char],
+
+    /// Characters, in addition to alphanumerics and underscores, which
+    /// may be part of identifiers (e.g. `.` in R)
+    identifier_chars: &'static [char],
+
+    /// Characters which, when before an identifier, make it an attribute
+    /// rather than a variable (e.g. `obj.name`, `df$name`)
+    attribute_prefixes: &'static [char],
+
+    /// The reserved words of the language
+    keywords: &'static [&'static [&'static str]],
+
+    /// Whether keywords are case insensitive
+    keywords_case_insensitive: bool,
+}
+
+/// An identifier in some code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identifier {
+    /// The name of the identifier
+    pub name: String,
+
+    /// The byte index of the identifier in the code
+    pub index: usize,
+
+    /// Whether the identifier is assigned to
+    pub is_assignment: bool,
+}
+
+const PYTHON_KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+const R_KEYWORDS: &[&str] = &[
+    "FALSE",
+    "Inf",
+    "NA",
+    "NA_character_",
+    "NA_complex_",
+    "NA_integer_",
+    "NA_real_",
+    "NaN",
+    "NULL",
+    "TRUE",
+    "break",
+    "else",
+    "for",
+    "function",
+    "if",
+    "in",
+    "next",
+    "repeat",
+    "while",
+];
+
+const JAVASCRIPT_KEYWORDS: &[&str] = &[
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "import",
+    "in",
+    "instanceof",
+    "let",
+    "new",
+    "null",
+    "return",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+const RHAI_KEYWORDS: &[&str] = &[
+    "as", "break", "catch", "const", "continue", "do", "else", "export", "false", "fn", "for",
+    "if", "import", "in", "let", "loop", "private", "return", "switch", "this", "throw", "true",
+    "try", "until", "while",
+];
+
+const BASH_KEYWORDS: &[&str] = &[
+    "case", "do", "done", "elif", "else", "esac", "fi", "for", "function", "if", "in", "select",
+    "then", "time", "until", "while",
+];
+
+const SQL_KEYWORDS: &[&str] = &[
+    "and", "as", "by", "create", "delete", "from", "group", "having", "insert", "into", "join",
+    "not", "null", "on", "or", "order", "select", "set", "table", "update", "values", "where",
+];
+
+impl Syntax {
+    /// Get the syntax of a programming language
+    ///
+    /// For languages which are not known, a combination of common syntaxes
+    /// is used so that, for example, both `#` and `//` comments are ignored.
+    pub fn new(format: &Format) -> Self {
+        match format {
+            Format::Python => Self {
+                line_comments: &["#"],
+                block_comment: None,
+                quotes: &['"', '\''],
+                identifier_chars: &[],
+                attribute_prefixes: &['.'],
+                keywords: &[PYTHON_KEYWORDS],
+                keywords_case_insensitive: false,
+            },
+            Format::R => Self {
+                line_comments: &["#"],
+                block_comment: None,
+                quotes: &['"', '\'', '`'],
+                identifier_chars: &['.'],
+                attribute_prefixes: &['$', '@'],
+                keywords: &[R_KEYWORDS],
+                keywords_case_insensitive: false,
+            },
+            Format::Bash | Format::Shell => Self {
+                line_comments: &["#"],
+                block_comment: None,
+                quotes: &['"', '\'', '`'],
+                identifier_chars: &[],
+                attribute_prefixes: &[],
+                keywords: &[BASH_KEYWORDS],
+                keywords_case_insensitive: false,
+            },
+            Format::JavaScript => Self {
+                line_comments: &["//"],
+                block_comment: Some(("/*", "*/")),
+                quotes: &['"', '\'', '`'],
+                identifier_chars: &[],
+                attribute_prefixes: &['.'],
+                keywords: &[JAVASCRIPT_KEYWORDS],
+                keywords_case_insensitive: false,
+            },
+            Format::Rhai => Self {
+                line_comments: &["//"],
+                block_comment: Some(("/*", "*/")),
+                quotes: &['"', '\'', '`'],
+                identifier_chars: &[],
+                attribute_prefixes: &['.'],
+                keywords: &[RHAI_KEYWORDS],
+                keywords_case_insensitive: false,
+            },
+            Format::Other(name) if name.eq_ignore_ascii_case("sql") => Self {
+                line_comments: &["--"],
+                block_comment: Some(("/*", "*/")),
+                quotes: &['"', '\''],
+                identifier_chars: &[],
+                attribute_prefixes: &['.'],
+                keywords: &[SQL_KEYWORDS],
+                keywords_case_insensitive: true,
+            },
+            _ => Self {
+                line_comments: &["#", "//"],
+                block_comment: Some(("/*", "*/")),
+                quotes: &['"', '\'', '`'],
+                identifier_chars: &[],
+                attribute_prefixes: &['.', '$'],
+                keywords: &[PYTHON_KEYWORDS, R_KEYWORDS, JAVASCRIPT_KEYWORDS],
+                keywords_case_insensitive: false,
+            },
+        }
+    }
+
+    /// Whether a name is a keyword of the language
+    pub fn is_keyword(&self, name: &str) -> bool {
+        let name = if self.keywords_case_insensitive {
+            name.to_lowercase()
+        } else {
+            name.to_string()
+        };
+
+        self.keywords
+            .iter()
+            .any(|keywords| keywords.contains(&name.as_str()))
+    }
+
+    /// Replace the strings and comments in code with spaces
+    ///
+    /// The delimiters of strings are retained. Newlines, and the byte
+    /// length of the code, are preserved so that positions are unchanged.
+    pub fn blank(&self, code: &str) -> String {
+        fn spaces(text: &str) -> String {
+            text.chars()
+                .map(|char| {
+                    if char == '\n' {
+                        "\n".to_string()
+                    } else {
+                        " ".repeat(char.len_utf8())
+                    }
+                })
+                .collect()
+        }
+
+        let mut blanked = String::with_capacity(code.len());
+        let mut rest = code;
+        while let Some(char) = rest.chars().next() {
+            if let Some((open, close)) = self.block_comment {
+                if rest.starts_with(open) {
+                    let end = rest[open.len()..]
+                        .find(close)
+                        .map_or(rest.len(), |index| open.len() + index + close.len());
+                    blanked.push_str(&spaces(&rest[..end]));
+                    rest = &rest[end..];
+                    continue;
+                }
+            }
+
+            if self
+                .line_comments
+                .iter()
+                .any(|marker| rest.starts_with(marker))
+            {
+                let end = rest.find('\n').unwrap_or(rest.len());
+                blanked.push_str(&spaces(&rest[..end]));
+                rest = &rest[end..];
+                continue;
+            }
+
+            if self.quotes.contains(&char) {
+                let triple = char.to_string().repeat(3);
+                let delimiter = if rest.starts_with(&triple) {
+                    triple
+                } else {
+                    char.to_string()
+                };
+                let multiline = delimiter.len() > 1 || char == '`';
+
+                let body = &rest[delimiter.len()..];
+                let mut end = None;
+                let mut escaped = false;
+                for (index, next) in body.char_indices() {
+                    if escaped {
+                        escaped = false;
+                    } else if next == '\\' {
+                        escaped = true;
+                    } else if body[index..].starts_with(&delimiter) {
+                        end = Some(index);
+                        break;
+                    } else if next == '\n' && !multiline {
+                        // Unterminated string: end it at the end of the line
+                        end = Some(index);
+                        break;
+                    }
+                }
+
+                let end = end.unwrap_or(body.len());
+                blanked.push_str(&delimiter);
+                blanked.push_str(&spaces(&body[..end]));
+                rest = &body[end..];
+                if rest.starts_with(&delimiter) {
+                    blanked.push_str(&delimiter);
+                    rest = &rest[delimiter.len()..];
+                }
+                continue;
+            }
+
+            blanked.push(char);
+            rest = &rest[char.len_utf8()..];
+        }
+
+        blanked
+    }
+
+    /// Get the identifiers in code, other than keywords and those in strings and comments
+    ///
+    /// Attributes (e.g. `obj.name`) are excluded and each identifier
+    /// is flagged according to whether it is assigned to.
+    pub fn identifiers(&self, code: &str) -> Vec<Identifier> {
+        let blanked = self.blank(code);
+
+        let mut identifiers = Vec::new();
+        let mut depth = 0;
+        let mut start = None;
+        for (index, char) in blanked
+            .char_indices()
+            .chain(std::iter::once((blanked.len(), ' ')))
+        {
+            if is_identifier_char(char) || self.identifier_chars.contains(&char) {
+                start.get_or_insert(index);
+                continue;
+            }
+
+            if let Some(start) = start.take() {
+                let name = &blanked[start..index];
+                let before = &blanked[..start];
+                if !name.starts_with(|char: char| char.is_numeric())
+                    && !self.is_keyword(name)
+                    && !before.ends_with(self.attribute_prefixes)
+                {
+                    identifiers.push(Identifier {
+                        name: name.to_string(),
+                        index: start,
+                        is_assignment: is_assignment(before, &blanked[index..], depth > 0),
+                    });
+                }
+            }
+
+            match char {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+        }
+
+        identifiers
+    }
+}
+
+/// Whether a char is part of an identifier
+pub fn is_identifier_char(char: char) -> bool {
+    char.is_alphanumeric() || char == '_'
+}
+
+/// Whether a string is a valid identifier
+pub fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().all(is_identifier_char)
+        && !name.starts_with(|char: char| char.is_numeric())
+}
+
+/// Whether an occurrence of an identifier is an assignment to it
+///
+/// Detects assignment operators (e.g. `x = 1`, `x <- 1`, `x += 1`) and keywords
+/// which declare variables and functions (e.g. `let x`, `def x(`, `for x in`).
+/// Within parentheses, `=` is not considered an assignment because it is
+/// usually a keyword argument (e.g. `f(x = 1)`) or default value of a parameter.
+fn is_assignment(before: &str, after: &str, in_parens: bool) -> bool {
+    let after = after.trim_start();
+    let assigns = after.starts_with("<-")
+        || after.starts_with(":=")
+        || (!in_parens && after.starts_with('=') && !after.starts_with("=="))
+        || ["+=", "-=", "*=", "/=", "|=", "&="]
+            .iter()
+            .any(|op| after.starts_with(op));
+
+    let before = before.trim_end();
+    let declares = [
+        "let", "const", "var", "def", "function", "for", "local", "fn",
+    ]
+    .iter()
+    .any(|keyword| {
+        before
+            .strip_suffix(keyword)
+            .is_some_and(|rest| !rest.ends_with(is_identifier_char))
+    });
+
+    assigns || declares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn syntax(lang: &str) -> Syntax {
+        Syntax::new(&Format::from_name(lang))
+    }
+
+    /// Get the names, and whether they are assignments, of the identifiers in code
+    fn identifiers(code: &str, lang: &str) -> Vec<(String, usize, bool)> {
+        syntax(lang)
+            .identifiers(code)
+            .into_iter()
+            .map(|id| (id.name, id.index, id.is_assignment))
+            .collect()
+    }
+
+    fn id(name: &str, index: usize, is_assignment: bool) -> (String, usize, bool) {
+        (name.to_string(), index, is_assignment)
+    }
+
+    #[test]
+    fn blank() {
+        let python = syntax("python");
+        assert_eq!(python.blank("x = 'a x' # x"), "x = '   '    ");
+        assert_eq!(python.blank("x // 2"), "x // 2");
+        assert_eq!(
+            python.blank("\"\"\"\nx\n\"\"\" + x"),
+            "\"\"\"\n \n\"\"\" + x"
+        );
+        assert_eq!(python.blank(r#""a \" x" + x"#), r#""      " + x"#);
+
+        let js = syntax("js");
+        assert_eq!(js.blank("x / 2 // x"), "x / 2     ");
+        assert_eq!(js.blank("x /* x\nx */ x"), "x     \n     x");
+        assert_eq!(js.blank("`${x}\nx` + x"), "`    \n ` + x");
+
+        let sql = syntax("sql");
+        assert_eq!(sql.blank("x - 1 -- x"), "x - 1     ");
+
+        // Byte lengths are preserved
+        assert_eq!(python.blank("'é' + x").len(), "'é' + x".len());
+    }
+
+    #[test]
+    fn keywords() {
+        assert!(syntax("python").is_keyword("lambda"));
+        assert!(!syntax("python").is_keyword("function"));
+        assert!(syntax("r").is_keyword("function"));
+        assert!(syntax("js").is_keyword("let"));
+        assert!(syntax("sql").is_keyword("SELECT"));
+        assert!(syntax("").is_keyword("def"));
+        assert!(syntax("").is_keyword("NULL"));
+        assert!(!syntax("").is_keyword("x"));
+    }
+
+    #[test]
+    fn skips_strings_comments_and_attributes() {
+        assert_eq!(
+            identifiers("x = 1\nprint('x', x.y) # x", "python"),
+            vec![id("x", 0, true), id("print", 6, false), id("x", 17, false)]
+        );
+
+        // In Python `//` is integer division, not a comment
+        assert_eq!(
+            identifiers("y = x // 2", "python"),
+            vec![id("y", 0, true), id("x", 4, false)]
+        );
+
+        // In R `$` accesses a list element but in Bash it expands a variable
+        assert_eq!(identifiers("a$b", "r"), vec![id("a", 0, false)]);
+        assert_eq!(
+            identifiers("echo $a", "bash"),
+            vec![id("echo", 0, false), id("a", 6, false)]
+        );
+
+        // Keywords and numbers are not identifiers
+        assert_eq!(identifiers("for i in 1:10", "r"), vec![id("i", 4, true)]);
+    }
+
+    #[test]
+    fn assignments() {
+        assert!(is_assignment("", " = 1", false));
+        assert!(is_assignment("", " <- 1", false));
+        assert!(is_assignment("", " += 1", false));
+        assert!(is_assignment("let ", " = 1", false));
+        assert!(is_assignment("def ", "(a):", false));
+        assert!(!is_assignment("", " == 1", false));
+        assert!(!is_assignment("print(", ")", true));
+
+        // Keyword arguments are not assignments
+        assert!(!is_assignment("f(", " = 1)", true));
+        assert_eq!(
+            identifiers("f(x = 1)\nx = 2", "r"),
+            vec![id("f", 0, false), id("x", 2, false), id("x", 9, true)]
+        );
+        assert_eq!(
+            identifiers("f(a,\n  x=1)\nx=2", "python"),
+            vec![
+                id("f", 0, false),
+                id("a", 2, false),
+                id("x", 7, false),
+                id("x", 12, true)
+            ]
+        );
+
+        // Assignments within parentheses using other operators are
+        assert_eq!(
+            identifiers("if (x := f()):", "python"),
+            vec![id("x", 4, true), id("f", 9, false)]
+        );
+    }
+
+    #[test]
+    fn identifier_names() {
+        assert!(is_identifier("x_1"));
+        assert!(!is_identifier("1x"));
+        assert!(!is_identifier("x-1"));
+        assert!(!is_identifier(""));
+    }
+}
@@ -1,17 +1,25 @@
 use parser::{common::once_cell::sync::Lazy, format::Format, DefaultParser};
 
-pub use parser::{ParseInfo, Parser};
+pub use parser::{is_identifier, is_identifier_char, Identifier, ParseInfo, Parser, Syntax};
 
-/// Parse some code in a language
-pub fn parse(code: &str, language: &str) -> ParseInfo {
+/// Get the parser for a language
+fn parser_for(format: &Format) -> &'static dyn Parser {
     static PARSERS: Lazy<Vec<Box<dyn Parser>>> = Lazy::new(Vec::new);
+    static DEFAULT: DefaultParser = DefaultParser {};
+
+    PARSERS
+        .iter()
+        .find(|parser| parser.supports_language(format))
+        .map_or(&DEFAULT as &dyn Parser, |parser| parser.as_ref())
+}
 
+/// Parse some code in a language
+pub fn parse(code: &str, language: &str) -> ParseInfo {
     let format = Format::from_name(language);
-    for parser in PARSERS.iter() {
-        if parser.supports_language(&format) {
-            return parser.parse(code, &format);
-        }
-    }
+    parser_for(&format).parse(code, &format)
+}
 
-    DefaultParser::default().parse(code, &format)
+/// Get the names of the variables that some code in a language may use
+pub fn uses(code: &str, format: &Format) -> Vec<String> {
+    parser_for(format).uses(code, format)
 }
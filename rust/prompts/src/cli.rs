@@ -5,21 +5,18 @@ use cli_utils::{
 use codecs::{EncodeOptions, Format};
 use model::{
     common::{
-        clap::{self, Args, Parser, Subcommand},
+        clap::{self, Args, Subcommand},
         eyre::Result,
     },
     schema::{InstructionMessage, InstructionType, Node, Prompt, StringOrNumber},
 };
 
-/// Manage prompts
-#[derive(Debug, Parser)]
-pub struct Cli {
-    #[command(subcommand)]
-    command: Option<Command>,
-}
-
+/// Subcommands for managing prompts
+///
+/// Public so that subcommands requiring other crates (e.g. `eval` which
+/// requires documents) can be added alongside these.
 #[derive(Debug, Subcommand)]
-enum Command {
+pub enum Command {
     List(List),
     Show(Show),
     Select(Select),
@@ -27,14 +24,9 @@ enum Command {
     Reset(Reset),
 }
 
-impl Cli {
+impl Command {
     pub async fn run(self) -> Result<()> {
-        let Some(command) = self.command else {
-            List {}.run().await?;
-            return Ok(());
-        };
-
-        match command {
+        match self {
             Command::List(list) => list.run().await?,
             Command::Show(show) => show.run().await?,
             Command::Select(select) => select.run().await?,
@@ -48,7 +40,7 @@ impl Cli {
 
 /// List the prompts available
 #[derive(Debug, Args)]
-struct List;
+pub struct List;
 
 impl List {
     async fn run(self) -> Result<()> {
@@ -94,7 +86,7 @@ impl List {
 
 /// Show a prompt
 #[derive(Debug, Args)]
-struct Show {
+pub struct Show {
     /// The id of the prompt to show
     id: String,
 
@@ -126,7 +118,7 @@ impl Show {
 ///
 /// Useful for checking which prompt will be matched to a given instruction
 #[derive(Debug, Args)]
-struct Select {
+pub struct Select {
     /// The type of instruction
     r#type: InstructionType,
 
@@ -152,7 +144,7 @@ impl Select {
 
 /// Update builtin prompts
 #[derive(Debug, Args)]
-struct Update {}
+pub struct Update {}
 
 impl Update {
     async fn run(self) -> Result<()> {
@@ -165,7 +157,7 @@ impl Update {
 /// Re-initializes the builtin prompts directory to those prompts
 /// embedded in this version of Stencila
 #[derive(Debug, Args)]
-struct Reset {}
+pub struct Reset {}
 
 impl Reset {
     async fn run(self) -> Result<()> {
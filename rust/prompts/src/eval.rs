@@ -0,0 +1,320 @@
+//! Evaluation of prompts across models
+//!
+//! An evaluation test case is a YAML file describing an instruction, the document
+//! context in which it is executed, and assertions about the suggestions generated
+//! for it. For example,
+//!
+//! ```yaml
+//! type: create
+//! message: a figure with a plot of sin(x) for x from 0 to 2π
+//! prompt: stencila/create/figure-code
+//! context: |
+//!   ```python exec
+//!   import numpy as np
+//!   ```
+//! assert:
+//!   - node-type: Figure
+//!   - executes: true
+//!   - regex: np\.sin
+//! ```
+//!
+//! This module is responsible for reading test cases and checking suggestions against
+//! their assertions. Executing the instructions, and any code in the suggestions,
+//! requires a document and is done by the caller.
+
+use std::path::Path;
+
+use codec_markdown_trait::to_markdown;
+use codecs::{DecodeOptions, Format};
+use common::{
+    eyre::{bail, Result},
+    glob::glob,
+    itertools::Itertools,
+    regex::Regex,
+    serde::{Deserialize, Serialize},
+    serde_with::{serde_as, DisplayFromStr},
+    serde_yaml,
+    tokio::fs::read_to_string,
+};
+use model::schema::{
+    Article, Block, InstructionBlock, InstructionMessage, InstructionModel, InstructionType, Node,
+};
+
+/// An evaluation test case
+#[serde_as(crate = "common::serde_with")]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", crate = "common::serde")]
+pub struct EvalCase {
+    /// The name of the test case
+    ///
+    /// Defaults to the stem of the file name.
+    #[serde(default)]
+    pub name: String,
+
+    /// The type of the instruction
+    #[serde(rename = "type")]
+    #[serde_as(as = "DisplayFromStr")]
+    pub instruction_type: InstructionType,
+
+    /// The instruction message
+    pub message: String,
+
+    /// The id of the prompt to use
+    ///
+    /// If not specified, the prompt will be selected as for any other instruction.
+    pub prompt: Option<String>,
+
+    /// The document context of the instruction, as Markdown
+    ///
+    /// Placed before the instruction in the document that is executed.
+    pub context: Option<String>,
+
+    /// The content of the instruction, as Markdown
+    ///
+    /// Usually only used for `edit`, `fix` and `describe` instructions.
+    pub content: Option<String>,
+
+    /// The number of suggestions to generate for each model
+    pub replicates: Option<u64>,
+
+    /// The assertions to check against each suggestion
+    #[serde(default, rename = "assert")]
+    pub assertions: Vec<EvalAssertion>,
+}
+
+/// An assertion about a suggestion
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", crate = "common::serde")]
+pub enum EvalAssertion {
+    /// The suggestion has a block of the node type
+    NodeType(String),
+
+    /// Code in the suggestion executes without errors (or with errors if `false`)
+    Executes(bool),
+
+    /// The Markdown of the suggestion matches the regular expression
+    Regex(String),
+}
+
+impl EvalAssertion {
+    /// Check the assertion against the content of a suggestion
+    ///
+    /// The `errors` are those arising from executing the suggestion in
+    /// the context of the document and are only required for `executes` assertions.
+    pub fn check(&self, blocks: &[Block], errors: Option<&[String]>) -> Result<(), String> {
+        match self {
+            EvalAssertion::NodeType(node_type) => {
+                let types = blocks
+                    .iter()
+                    .map(|block| block.node_type().to_string())
+                    .collect_vec();
+                if types.iter().any(|type_| type_ == node_type) {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "expected a `{node_type}`, got [{}]",
+                        types.join(", ")
+                    ))
+                }
+            }
+            EvalAssertion::Executes(expected) => {
+                let Some(errors) = errors else {
+                    return Err("suggestion was not executed".to_string());
+                };
+                match (expected, errors.is_empty()) {
+                    (true, true) | (false, false) => Ok(()),
+                    (true, false) => Err(errors.join("; ")),
+                    (false, true) => Err("expected errors, got none".to_string()),
+                }
+            }
+            EvalAssertion::Regex(pattern) => {
+                let regex = Regex::new(pattern).map_err(|error| error.to_string())?;
+                let md = to_markdown(&blocks.to_vec());
+                if regex.is_match(&md) {
+                    Ok(())
+                } else {
+                    Err(format!("no match for `{pattern}`"))
+                }
+            }
+        }
+    }
+
+    /// Whether the assertion requires the suggestion to be executed
+    pub fn requires_execution(&self) -> bool {
+        matches!(self, EvalAssertion::Executes(..))
+    }
+}
+
+impl EvalCase {
+    /// Read a test case from a YAML file
+    pub async fn read(path: &Path) -> Result<Self> {
+        let yaml = read_to_string(path).await?;
+        let mut case: EvalCase = serde_yaml::from_str(&yaml)?;
+
+        if case.name.is_empty() {
+            case.name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+        }
+
+        Ok(case)
+    }
+
+    /// Read all the test cases in a directory (including in subdirectories)
+    pub async fn read_dir(dir: &Path) -> Result<Vec<Self>> {
+        if !dir.is_dir() {
+            bail!("Not a directory: {}", dir.display())
+        }
+
+        let mut cases = Vec::new();
+        for path in glob(&format!("{}/**/*.y*ml", dir.display()))?
+            .flatten()
+            .sorted()
+        {
+            cases.push(Self::read(&path).await?);
+        }
+
+        Ok(cases)
+    }
+
+    /// Create an article containing the context of the test case followed by its instruction
+    ///
+    /// If a model id pattern is supplied it is used for the instruction.
+    pub async fn to_article(&self, model: Option<&str>) -> Result<Article> {
+        let mut content = decode_blocks(self.context.as_deref()).await?;
+
+        let mut instruction = InstructionBlock::new(self.instruction_type.clone());
+        instruction.message = Some(InstructionMessage::from(self.message.as_str()));
+        instruction.prompt.clone_from(&self.prompt);
+        instruction.replicates = self.replicates;
+        instruction.model = model.map(|id_pattern| {
+            Box::new(InstructionModel {
+                id_pattern: Some(id_pattern.to_string()),
+                ..Default::default()
+            })
+        });
+        if self.content.is_some() {
+            instruction.content = Some(decode_blocks(self.content.as_deref()).await?);
+        }
+
+        content.push(Block::InstructionBlock(instruction));
+
+        Ok(Article::new(content))
+    }
+
+    /// Create an article containing the context of the test case followed by some content
+    ///
+    /// Used to execute the content of a suggestion in the context of the test case.
+    pub async fn with_context(&self, blocks: Vec<Block>) -> Result<Article> {
+        let mut content = decode_blocks(self.context.as_deref()).await?;
+        content.extend(blocks);
+
+        Ok(Article::new(content))
+    }
+}
+
+/// Decode Markdown into blocks
+async fn decode_blocks(md: Option<&str>) -> Result<Vec<Block>> {
+    let Some(md) = md else {
+        return Ok(Vec::new());
+    };
+
+    let node = codecs::from_str(
+        md,
+        Some(DecodeOptions {
+            format: Some(Format::Markdown),
+            ..Default::default()
+        }),
+    )
+    .await?;
+
+    let Node::Article(Article { content, .. }) = node else {
+        bail!("Expected Markdown to be decoded to an article")
+    };
+
+    Ok(content)
+}
+
+/// The result of evaluating a suggestion for a test case
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase", crate = "common::serde")]
+pub struct EvalResult {
+    /// The name of the test case
+    pub case: String,
+
+    /// The id of the model that generated the suggestion
+    pub model: String,
+
+    /// The index of the suggestion (when there are replicates)
+    pub replicate: usize,
+
+    /// The assertions that failed, and why
+    pub failures: Vec<(EvalAssertion, String)>,
+
+    /// Any error that occurred while executing the instruction
+    pub error: Option<String>,
+}
+
+impl EvalResult {
+    /// Whether the suggestion passed all assertions
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.failures.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use model::schema::shortcuts::{cc, p, t};
+
+    use super::*;
+
+    #[test]
+    fn case() -> Result<()> {
+        let case: EvalCase = serde_yaml::from_str(
+            r#"
+type: create
+message: a plot
+assert:
+  - node-type: CodeChunk
+  - executes: true
+  - regex: plot\(
+"#,
+        )?;
+
+        assert_eq!(case.instruction_type, InstructionType::Create);
+        assert_eq!(case.assertions.len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn assertions() {
+        let blocks = vec![p([t("A plot")]), cc("plot(x)", Some("r"))];
+
+        assert!(EvalAssertion::NodeType("CodeChunk".into())
+            .check(&blocks, None)
+            .is_ok());
+        assert!(EvalAssertion::NodeType("Table".into())
+            .check(&blocks, None)
+            .is_err());
+
+        assert!(EvalAssertion::Regex(r"plot\(".into())
+            .check(&blocks, None)
+            .is_ok());
+        assert!(EvalAssertion::Regex(r"^lines\(".into())
+            .check(&blocks, None)
+            .is_err());
+
+        assert!(EvalAssertion::Executes(true).check(&blocks, None).is_err());
+        assert!(EvalAssertion::Executes(true)
+            .check(&blocks, Some(&[]))
+            .is_ok());
+        assert!(EvalAssertion::Executes(true)
+            .check(&blocks, Some(&["Error: object 'x' not found".into()]))
+            .is_err());
+        assert!(EvalAssertion::Executes(false)
+            .check(&blocks, Some(&["Error".into()]))
+            .is_ok());
+    }
+}
@@ -30,15 +30,16 @@ use rust_embed::RustEmbed;
 
 use model::{
     schema::{
-        authorship, shortcuts::p, Article, AudioObject, Author, AuthorRole, Block,
-        CompilationMessage, ExecutionMessage, ImageObject, Inline, InstructionBlock,
+        authorship, shortcuts::p, Article, AudioObject, Author, AuthorRole, AuthorRoleAuthor,
+        Block, CompilationMessage, ExecutionMessage, ImageObject, Inline, InstructionBlock,
         InstructionMessage, InstructionType, Link, MessageLevel, MessagePart, Node, Prompt,
         SuggestionBlock, SuggestionStatus, Timestamp, VideoObject,
     },
-    ModelOutput, ModelOutputKind, ModelTask,
+    ModelOutput, ModelOutputKind, ModelTask, ModelTaskCache,
 };
 
 pub mod cli;
+pub mod eval;
 
 // Re-export
 pub use prompt;
@@ -415,7 +416,9 @@ pub async fn execute_instruction_block(
     prompter: AuthorRole,
     system_prompt: &str,
     instruction: &InstructionBlock,
+    replicate: u32,
     dry_run: bool,
+    cache: ModelTaskCache,
 ) -> Result<SuggestionBlock> {
     // Create a vector of messages beginning with the system message
     let mut messages = vec![InstructionMessage::system(
@@ -527,7 +530,12 @@ pub async fn execute_instruction_block(
         instruction.model.as_deref().cloned(),
         messages,
     );
+    task.replicate = (replicate > 0).then_some(replicate);
     task.dry_run = dry_run;
+    task.cache = cache;
+    if let AuthorRoleAuthor::SoftwareApplication(prompt) = &prompter.author {
+        task.prompt = prompt.id.clone();
+    }
 
     // Perform the task
     let started = Timestamp::now();
@@ -483,6 +483,8 @@ export const provenanceCategories: {{ [Property in ProvenanceCategory]: string }
             .join("\n    ");
         let super_args = super_args.join(", ");
 
+        // Use `globalThis.Object` in constructors because an imported schema type
+        // (e.g. `Object` itself) may shadow the global
         let class = if let Some(base) = base {
             format!(
                 r#"export class {title} extends {base} {{
@@ -491,7 +493,7 @@ export const provenanceCategories: {{ [Property in ProvenanceCategory]: string }
   constructor({required_args}options?: Partial<{title}>) {{
     super({super_args});
     this.type = "{title}";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     {required_assignments}
   }}
 }}"#
@@ -502,7 +504,7 @@ export const provenanceCategories: {{ [Property in ProvenanceCategory]: string }
 {props}
 
   constructor({required_args}options?: Partial<{title}>) {{
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     {required_assignments}
   }}
 }}"#
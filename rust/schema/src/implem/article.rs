@@ -2,7 +2,7 @@ use codec_markdown_trait::to_markdown;
 use common::serde_yaml;
 use node_strip::{StripNode, StripTargets};
 
-use crate::{prelude::*, Article, Author};
+use crate::{prelude::*, Article, Author, Comment, CreativeWorkType};
 
 impl Article {
     pub fn to_jats_special(&self) -> (String, Losses) {
@@ -18,6 +18,16 @@ impl Article {
 
         let back = String::new(); // TODO elem_no_attrs("back", "");
 
+        // Reviews of the article are encoded as review report sub-articles
+        let mut sub_articles = String::new();
+        for part in self.options.parts.iter().flatten() {
+            if let CreativeWorkType::Review(review) = part {
+                let (review_jats, review_losses) = review.to_jats_special();
+                sub_articles += &review_jats;
+                losses.merge(review_losses);
+            }
+        }
+
         (
             elem(
                 "article",
@@ -26,7 +36,7 @@ impl Article {
                     ("xmlns:xlink", "http://www.w3.org/1999/xlink"),
                     ("xmlns:mml", "http://www.w3.org/1998/Math/MathML"),
                 ],
-                [front, body, back].concat(),
+                [front, body, back, sub_articles].concat(),
             ),
             losses,
         )
@@ -59,6 +69,22 @@ impl MarkdownCodec for Article {
         });
         header.headings = None;
 
+        // Comments are encoded as comment blocks (below) rather than in the header
+        header.comments = None;
+
+        // Reviews are not part of the authored content of the article so are not
+        // encoded in the header (they are persisted in the document's sidecar file)
+        if let Some(parts) = &mut header.options.parts {
+            let count = parts.len();
+            parts.retain(|part| !matches!(part, CreativeWorkType::Review(..)));
+            if parts.len() < count {
+                context.add_loss("Article.parts");
+            }
+            if parts.is_empty() {
+                header.options.parts = None;
+            }
+        }
+
         // If there is a title, represent it as Markdown
         let mut title_string: Option<String> = None;
         if let Some(title) = &header.title {
@@ -138,6 +164,67 @@ impl MarkdownCodec for Article {
 
         context.append_footnotes();
 
+        if let Some(comments) = &self.comments {
+            if matches!(context.format, Format::Smd) {
+                comments_to_markdown(comments, None, context);
+            } else {
+                context.add_loss("Article.comments");
+            }
+        }
+
         context.exit_node_final();
     }
 }
+
+/// Encode comments, and their replies, as footnote-style comment blocks
+///
+/// Each comment is encoded as a footnote definition, with the id of the comment
+/// as its label, and any anchor (`on`) or parent comment (`reply`) as attributes
+/// e.g. `[^comment-1]: {on="pgh_abc#10-25"} The comment`. Replies are encoded
+/// after the comment they reply to, rather than nested within it.
+fn comments_to_markdown(
+    comments: &[Comment],
+    reply: Option<&str>,
+    context: &mut MarkdownEncodeContext,
+) {
+    for comment in comments {
+        if comment.authors.is_some() {
+            context.add_loss("Comment.authors");
+        }
+
+        let id = comment
+            .id
+            .clone()
+            .unwrap_or_else(|| ["comment-", &comment.node_id().to_string()].concat());
+
+        context
+            .enter_node(comment.node_type(), comment.node_id())
+            .push_str("[^")
+            .push_str(&id)
+            .push_str("]: ");
+
+        let mut attrs = Vec::new();
+        if let Some(aspect) = &comment.options.comment_aspect {
+            attrs.push(format!("on=\"{aspect}\""));
+        }
+        if let Some(reply) = reply {
+            attrs.push(format!("reply=\"{reply}\""));
+        }
+        if !attrs.is_empty() {
+            context
+                .push_str("{")
+                .push_str(&attrs.join(" "))
+                .push_str("} ");
+        }
+
+        context.push_line_prefix("    ");
+        comment.content.to_markdown(context);
+        context.pop_line_prefix();
+
+        context.exit_node();
+
+        if let Some(replies) = &comment.options.comments {
+            comments_to_markdown(replies, Some(&id), context);
+        }
+    }
+}
@@ -61,4 +61,46 @@ impl Author {
             Author::AuthorRole(role) => role,
         }
     }
+
+    /**
+     * Get the name of an author
+     *
+     * For a [`Person`], this is their given and family names (falling back to
+     * their `name`). Returns "Anonymous" if the author has no name.
+     */
+    pub fn name(&self) -> String {
+        let name = match self {
+            Author::Person(person) => person_name(person),
+            Author::Organization(org) => org.name.clone().unwrap_or_default(),
+            Author::SoftwareApplication(software) => software.name.clone(),
+            Author::AuthorRole(role) => match &role.author {
+                AuthorRoleAuthor::Person(person) => person_name(person),
+                AuthorRoleAuthor::Organization(org) => org.name.clone().unwrap_or_default(),
+                AuthorRoleAuthor::SoftwareApplication(software) => software.name.clone(),
+                AuthorRoleAuthor::Thing(thing) => thing.options.name.clone().unwrap_or_default(),
+            },
+        };
+
+        if name.is_empty() {
+            "Anonymous".to_string()
+        } else {
+            name
+        }
+    }
+}
+
+/// Get the name of a person from their given and family names, or their `name`
+fn person_name(person: &Person) -> String {
+    let name = person
+        .given_names
+        .iter()
+        .flatten()
+        .chain(person.family_names.iter().flatten())
+        .join(" ");
+
+    if name.is_empty() {
+        person.options.name.clone().unwrap_or_default()
+    } else {
+        name
+    }
 }
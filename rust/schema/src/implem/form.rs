@@ -0,0 +1,99 @@
+use codec_info::{lost_exec_options, lost_options};
+
+use crate::{prelude::*, Form, FormDeriveAction, IntegerOrString};
+
+impl MarkdownCodec for Form {
+    fn to_markdown(&self, context: &mut MarkdownEncodeContext) {
+        if context.render || matches!(context.format, Format::Llmd) {
+            // Record any execution messages
+            if let Some(messages) = &self.options.execution_messages {
+                for message in messages {
+                    context.add_message(
+                        self.node_type(),
+                        self.node_id(),
+                        message.level.clone().into(),
+                        message.message.to_string(),
+                    );
+                }
+            }
+
+            // Encode content only
+            self.content.to_markdown(context);
+
+            return;
+        }
+
+        context
+            .enter_node(self.node_type(), self.node_id())
+            .merge_losses(lost_options!(self, id))
+            .merge_losses(lost_exec_options!(self));
+
+        context.push_colons().push_str(" form");
+
+        if self.execution_mode.is_some()
+            || self.options.derive_from.is_some()
+            || self.options.derive_action.is_some()
+            || self.options.derive_item.is_some()
+        {
+            context.push_str(" {");
+
+            let mut prefix = "";
+            if let Some(mode) = &self.execution_mode {
+                context.push_prop_str(
+                    NodeProperty::ExecutionMode,
+                    &mode.to_string().to_lowercase(),
+                );
+                prefix = " ";
+            }
+
+            if let Some(from) = &self.options.derive_from {
+                context
+                    .push_str(prefix)
+                    .push_str("from=")
+                    .push_prop_str(NodeProperty::DeriveFrom, from);
+                prefix = " ";
+            }
+
+            if let Some(action) = &self.options.derive_action {
+                let action = match action {
+                    FormDeriveAction::Create => "create",
+                    FormDeriveAction::Update => "update",
+                    FormDeriveAction::Delete => "delete",
+                    FormDeriveAction::UpdateOrDelete => "update-or-delete",
+                };
+                context
+                    .push_str(prefix)
+                    .push_str("action=")
+                    .push_prop_str(NodeProperty::DeriveAction, action);
+                prefix = " ";
+            }
+
+            if let Some(item) = &self.options.derive_item {
+                // String items are always quoted (and quotes within them escaped)
+                // so that they are not decoded as integers and can contain spaces and braces
+                let item = match item {
+                    IntegerOrString::Integer(index) => index.to_string(),
+                    IntegerOrString::String(key) => {
+                        format!("\"{}\"", key.replace('"', "\\\""))
+                    }
+                };
+                context
+                    .push_str(prefix)
+                    .push_str("item=")
+                    .push_prop_str(NodeProperty::DeriveItem, &item);
+            }
+
+            context.push_str("}");
+        }
+
+        context
+            .push_str("\n\n")
+            .increase_depth()
+            .push_prop_fn(NodeProperty::Content, |context| {
+                self.content.to_markdown(context)
+            })
+            .decrease_depth();
+
+        context.push_colons().newline().exit_node().newline();
+    }
+}
@@ -28,6 +28,7 @@ mod execution_mode;
 mod execution_status;
 mod figure;
 mod for_block;
+mod form;
 mod heading;
 mod if_block;
 mod if_block_clause;
@@ -65,6 +66,7 @@ mod provenance_count;
 mod quote_block;
 mod raw_block;
 mod replace_block;
+mod review;
 mod section;
 mod string_or_number;
 mod string_patch;
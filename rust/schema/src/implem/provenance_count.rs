@@ -17,6 +17,38 @@ impl ProvenanceCount {
         (human as f64 / all as f64 * 100.).round().min(100.) as u64
     }
 
+    /// Calculate the aggregate percentage of machine written, and not human edited, content
+    ///
+    /// This is the complement of [`ProvenanceCount::human_percent`].
+    pub fn machine_percent(counts: &[Self]) -> u64 {
+        let all: u64 = counts.iter().map(|count| count.character_count).sum();
+        if all == 0 {
+            return 0;
+        }
+
+        100 - Self::human_percent(counts)
+    }
+
+    /// Calculate the aggregate percentage of machine written content which has not been verified
+    pub fn machine_unverified_percent(counts: &[Self]) -> u64 {
+        let mut unverified = 0;
+        let mut all = 0;
+        for count in counts {
+            if count.provenance_category.is_machine_written()
+                && !count.provenance_category.is_verified()
+            {
+                unverified += count.character_count;
+            }
+            all += count.character_count;
+        }
+
+        if all == 0 {
+            return 0;
+        }
+
+        (unverified as f64 / all as f64 * 100.).round().min(100.) as u64
+    }
+
     /// Calculate the aggregate percentage of human written or machine verified content
     pub fn verified_percent(counts: &[Self]) -> u64 {
         let mut verified = 0;
@@ -0,0 +1,123 @@
+use codec_jats_trait::encode::{elem, elem_no_attrs, escape};
+
+use crate::{prelude::*, Comment, Review};
+
+impl Review {
+    /// Encode a review as a JATS `<sub-article>` review report
+    ///
+    /// The title, authors, date and ratings of the review are encoded in the
+    /// `<front-stub>`, and its content, followed by its comments, in the `<body>`.
+    /// Comments are encoded as sections with a cross reference to the claim, or
+    /// other node, that they are anchored to.
+    pub fn to_jats_special(&self) -> (String, Losses) {
+        let mut losses = Losses::none();
+
+        let mut front = String::new();
+
+        if let Some(title) = &self.options.title {
+            let (title_jats, title_losses) = title.to_jats();
+            front += &elem_no_attrs("title-group", elem_no_attrs("article-title", title_jats));
+            losses.merge(title_losses);
+        }
+
+        if let Some(authors) = &self.options.authors {
+            let (authors_jats, authors_losses) = authors.to_jats();
+            front += &elem(
+                "contrib-group",
+                [("content-type", "reviewer")],
+                authors_jats,
+            );
+            losses.merge(authors_losses);
+        }
+
+        if let Some(date) = &self.options.date_published {
+            front += &elem(
+                "pub-date",
+                [("date-type", "pub"), ("iso-8601-date", date.value.as_str())],
+                String::new(),
+            );
+        }
+
+        if let Some(ratings) = &self.options.ratings {
+            let ratings = ratings
+                .iter()
+                .map(|rating| {
+                    let name = rating.options.name.as_deref().unwrap_or("rating");
+                    elem_no_attrs(
+                        "custom-meta",
+                        [
+                            elem_no_attrs("meta-name", escape(name)),
+                            elem_no_attrs("meta-value", escape(rating.value.to_text().0)),
+                        ]
+                        .concat(),
+                    )
+                })
+                .join("");
+            front += &elem_no_attrs("custom-meta-group", ratings);
+        }
+
+        let mut body = String::new();
+
+        if let Some(content) = &self.options.content {
+            let (content_jats, content_losses) = content.to_jats();
+            body += &content_jats;
+            losses.merge(content_losses);
+        }
+
+        for comment in self.options.comments.iter().flatten() {
+            let (comment_jats, comment_losses) = comment_to_jats(comment);
+            body += &comment_jats;
+            losses.merge(comment_losses);
+        }
+
+        let mut attrs = vec![("article-type", "referee-report")];
+        if let Some(id) = &self.id {
+            attrs.push(("id", id.as_str()));
+        }
+
+        (
+            elem(
+                "sub-article",
+                attrs,
+                [
+                    elem_no_attrs("front-stub", front),
+                    elem_no_attrs("body", body),
+                ]
+                .concat(),
+            ),
+            losses,
+        )
+    }
+}
+
+/// Encode a review comment, and its replies, as a JATS `<sec>`
+///
+/// If the comment is anchored to a node (e.g. a claim or section) then a
+/// `<xref>` to the `id` of that node is prepended to the comment's content.
+fn comment_to_jats(comment: &Comment) -> (String, Losses) {
+    let mut losses = Losses::none();
+
+    let mut sec = String::new();
+
+    if let Some(aspect) = &comment.options.comment_aspect {
+        let rid = aspect.split('#').next().unwrap_or(aspect);
+        sec += &elem_no_attrs("p", elem("xref", [("rid", rid)], escape(aspect)));
+    }
+
+    let (content_jats, content_losses) = comment.content.to_jats();
+    sec += &content_jats;
+    losses.merge(content_losses);
+
+    for reply in comment.options.comments.iter().flatten() {
+        let (reply_jats, reply_losses) = comment_to_jats(reply);
+        sec += &reply_jats;
+        losses.merge(reply_losses);
+    }
+
+    let mut attrs = vec![("sec-type", "comment")];
+    if let Some(id) = &comment.id {
+        attrs.push(("id", id.as_str()));
+    }
+
+    (elem("sec", attrs, sec), losses)
+}
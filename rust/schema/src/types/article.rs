@@ -87,6 +87,15 @@ pub struct Article {
     #[dom(elem = "div")]
     pub provenance: Option<Vec<ProvenanceCount>>,
 
+    /// Comments about this creative work.
+    #[serde(alias = "comment")]
+    #[serde(default, deserialize_with = "option_one_or_many")]
+    #[strip(metadata)]
+    #[patch(format = "smd")]
+    #[cfg_attr(feature = "proptest", proptest(value = "None"))]
+    #[dom(elem = "section")]
+    pub comments: Option<Vec<Comment>>,
+
     /// Date/time of creation.
     #[serde(alias = "date-created", alias = "date_created")]
     #[serde(default, deserialize_with = "option_string_or_object")]
@@ -272,14 +281,6 @@ pub struct ArticleOptions {
     #[dom(elem = "section")]
     pub maintainers: Option<Vec<PersonOrOrganization>>,
 
-    /// Comments about this creative work.
-    #[serde(alias = "comment")]
-    #[serde(default, deserialize_with = "option_one_or_many")]
-    #[strip(metadata)]
-    #[cfg_attr(feature = "proptest", proptest(value = "None"))]
-    #[dom(elem = "section")]
-    pub comments: Option<Vec<Comment>>,
-
     /// People or organizations that funded the `CreativeWork`.
     #[serde(alias = "funder")]
     #[serde(default, deserialize_with = "option_one_or_many_string_or_object")]
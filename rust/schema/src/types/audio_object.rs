@@ -185,6 +185,7 @@ pub struct AudioObjectOptions {
     #[serde(alias = "comment")]
     #[serde(default, deserialize_with = "option_one_or_many")]
     #[strip(metadata)]
+    #[patch(format = "smd")]
     #[cfg_attr(feature = "proptest", proptest(value = "None"))]
     #[dom(elem = "section")]
     pub comments: Option<Vec<Comment>>,
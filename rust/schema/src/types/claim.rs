@@ -177,6 +177,7 @@ pub struct ClaimOptions {
     #[serde(alias = "comment")]
     #[serde(default, deserialize_with = "option_one_or_many")]
     #[strip(metadata)]
+    #[patch(format = "smd")]
     #[cfg_attr(feature = "proptest", proptest(value = "None"))]
     #[dom(elem = "section")]
     pub comments: Option<Vec<Comment>>,
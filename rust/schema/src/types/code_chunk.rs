@@ -20,6 +20,7 @@ use super::execution_tag::ExecutionTag;
 use super::integer::Integer;
 use super::label_type::LabelType;
 use super::node::Node;
+use super::object::Object;
 use super::provenance_count::ProvenanceCount;
 use super::string::String;
 use super::timestamp::Timestamp;
@@ -244,6 +245,11 @@ pub struct CodeChunkOptions {
     #[strip(execution)]
     #[cfg_attr(feature = "proptest", proptest(value = "None"))]
     pub execution_pure: Option<Boolean>,
+
+    /// Metadata associated with the chunk that is not otherwise represented.
+    #[strip(metadata)]
+    #[cfg_attr(feature = "proptest", proptest(value = "None"))]
+    pub metadata: Option<Object>,
 }
 
 impl CodeChunk {
@@ -141,6 +141,7 @@ pub struct CollectionOptions {
     #[serde(alias = "comment")]
     #[serde(default, deserialize_with = "option_one_or_many")]
     #[strip(metadata)]
+    #[patch(format = "smd")]
     #[dom(elem = "section")]
     pub comments: Option<Vec<Comment>>,
 
@@ -2,7 +2,9 @@
 
 use crate::prelude::*;
 
+use super::boolean::Boolean;
 use super::string::String;
+use super::unsigned_integer::UnsignedInteger;
 
 /// Stencila document configuration options.
 #[skip_serializing_none]
@@ -24,6 +26,25 @@ pub struct Config {
     #[patch(format = "all")]
     pub theme: Option<String>,
 
+    /// The names of the secrets required by the document
+    #[serde(alias = "secret")]
+    #[serde(default, deserialize_with = "option_one_or_many")]
+    pub secrets: Option<Vec<String>>,
+
+    /// The maximum memory, in megabytes, that each kernel for the document may use
+    #[serde(alias = "memory-limit", alias = "memory_limit")]
+    pub memory_limit: Option<UnsignedInteger>,
+
+    /// The maximum CPU time, in seconds, that each kernel for the document may use
+    #[serde(alias = "cpu-limit", alias = "cpu_limit")]
+    pub cpu_limit: Option<UnsignedInteger>,
+
+    /// The maximum time, in seconds, for each execution of code in the document
+    pub timeout: Option<UnsignedInteger>,
+
+    /// Whether kernels for the document should be run in restricted mode
+    pub restricted: Option<Boolean>,
+
     /// A unique identifier for a node within a document
     
     #[serde(skip)]
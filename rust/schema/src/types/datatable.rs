@@ -134,6 +134,7 @@ pub struct DatatableOptions {
     #[serde(alias = "comment")]
     #[serde(default, deserialize_with = "option_one_or_many")]
     #[strip(metadata)]
+    #[patch(format = "smd")]
     pub comments: Option<Vec<Comment>>,
 
     /// Date/time of creation.
@@ -177,6 +177,7 @@ pub struct ImageObjectOptions {
     #[serde(alias = "comment")]
     #[serde(default, deserialize_with = "option_one_or_many")]
     #[strip(metadata)]
+    #[patch(format = "smd")]
     #[cfg_attr(feature = "proptest", proptest(value = "None"))]
     pub comments: Option<Vec<Comment>>,
 
@@ -146,6 +146,7 @@ pub struct MediaObjectOptions {
     #[serde(alias = "comment")]
     #[serde(default, deserialize_with = "option_one_or_many")]
     #[strip(metadata)]
+    #[patch(format = "smd")]
     #[dom(elem = "section")]
     pub comments: Option<Vec<Comment>>,
 
@@ -6,6 +6,7 @@ use super::author::Author;
 use super::compilation_digest::CompilationDigest;
 use super::compilation_message::CompilationMessage;
 use super::cord::Cord;
+use super::object::Object;
 use super::provenance_count::ProvenanceCount;
 use super::string::String;
 
@@ -74,6 +75,11 @@ pub struct RawBlock {
     #[cfg_attr(feature = "proptest", proptest(value = "None"))]
     pub provenance: Option<Vec<ProvenanceCount>>,
 
+    /// Metadata associated with the block that is not otherwise represented.
+    #[strip(metadata)]
+    #[cfg_attr(feature = "proptest", proptest(value = "None"))]
+    pub metadata: Option<Object>,
+
     /// A unique identifier for a node within a document
     #[cfg_attr(feature = "proptest", proptest(value = "Default::default()"))]
     #[serde(skip)]
@@ -13,6 +13,7 @@ use super::image_object::ImageObject;
 use super::inline::Inline;
 use super::person::Person;
 use super::person_or_organization::PersonOrOrganization;
+use super::property_value::PropertyValue;
 use super::property_value_or_string::PropertyValueOrString;
 use super::provenance_count::ProvenanceCount;
 use super::string::String;
@@ -29,6 +30,7 @@ use super::thing_type::ThingType;
 #[derive(derive_more::Display)]
 #[display(fmt = "Review")]
 #[patch(authors_on = "options")]
+#[jats(elem = "sub-article", special)]
 pub struct Review {
     /// The type of this item.
     pub r#type: MustBe!("Review"),
@@ -135,6 +137,7 @@ pub struct ReviewOptions {
     #[serde(alias = "comment")]
     #[serde(default, deserialize_with = "option_one_or_many")]
     #[strip(metadata)]
+    #[patch(format = "smd")]
     #[dom(elem = "section")]
     pub comments: Option<Vec<Comment>>,
 
@@ -250,6 +253,19 @@ pub struct ReviewOptions {
     /// The part or facet of the item that is being reviewed.
     #[serde(alias = "review-aspect", alias = "review_aspect")]
     pub review_aspect: Option<String>,
+
+    /// The content of the review, e.g. a summary and major and minor issues.
+    #[serde(default, deserialize_with = "option_one_or_many")]
+    #[walk]
+    #[patch(format = "all")]
+    #[dom(elem = "section")]
+    pub content: Option<Vec<Block>>,
+
+    /// Ratings of the item on various criteria.
+    #[serde(alias = "rating")]
+    #[serde(default, deserialize_with = "option_one_or_many")]
+    #[dom(elem = "section")]
+    pub ratings: Option<Vec<PropertyValue>>,
 }
 
 impl Review {
@@ -18,13 +18,18 @@ pub struct Cli {
 /// A command to perform with secrets
 #[derive(Debug, Subcommand)]
 enum Command {
-    /// List the secrets used by Stencila
+    /// List the secrets used by Stencila and any user-defined secrets
     List,
 
-    /// Set a secret used by Stencila
+    /// Set a secret used by Stencila, or a user-defined secret
     ///
     /// You will be prompted for the secret. Alternatively, you can echo the
     /// password into this command i.e. `echo <TOKEN> | stencila secrets set STENCILA_API_TOKEN`
+    ///
+    /// User-defined secrets (e.g. `DATABASE_PASSWORD`) can be made available to
+    /// kernels as environment variables by listing them in the `secrets` of a
+    /// document's config and allowing them using the `--allow-secret` option
+    /// when executing or rendering the document.
     #[command(alias = "add")]
     Set(Set),
 
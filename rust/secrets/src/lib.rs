@@ -1,5 +1,6 @@
-use std::env;
+use std::{env, fs, path::PathBuf};
 
+use app::{get_app_dir, DirType};
 use cli_utils::{
     table::{self, Attribute, Cell, CellAlignment, Color},
     ToStdout,
@@ -9,7 +10,9 @@ use common::{
     eyre::{bail, Result},
     itertools::Itertools,
     once_cell::sync::Lazy,
+    regex::Regex,
     serde::Serialize,
+    serde_json,
     serde_with::skip_serializing_none,
     tracing,
 };
@@ -21,6 +24,7 @@ pub mod cli;
 #[serde(crate = "common::serde")]
 enum SecretCategory {
     AiApiKey,
+    UserDefined,
 }
 
 #[skip_serializing_none]
@@ -112,38 +116,87 @@ static SECRETS: Lazy<Vec<Secret>> = Lazy::new(|| {
             "Mistral API Key",
             "Used to access the Mistral API",
         ),
+        Secret::new(
+            SecretCategory::AiApiKey,
+            "OPENAI_COMPATIBLE_API_KEY",
+            "OpenAI-compatible API Key",
+            "Used to access OpenAI-compatible endpoints configured in `models/endpoints.yaml`",
+        ),
     ]
 });
 
-/// Validate a name is a known secret
+/// Validate a secret name
+///
+/// Secrets used by Stencila are always valid. Other, user-defined, secrets
+/// must have names that are valid environment variable names in upper snake
+/// case (e.g. `DATABASE_PASSWORD`) because that is how they are exposed to kernels.
 pub fn name_validator(name: &str) -> Result<String, String> {
-    let possible_values = SECRETS
-        .iter()
-        .map(|secret| secret.name.as_str())
-        .collect_vec();
-    if possible_values.contains(&name) {
+    static REGEX: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"^[A-Z][A-Z0-9_]*$").expect("invalid regex"));
+
+    if SECRETS.iter().any(|secret| secret.name == name) || REGEX.is_match(name) {
         Ok(name.to_string())
     } else {
         Err(format!(
-            "not a known secret name [{}]",
-            possible_values.join(", ")
+            "not a known secret name [{}], or a valid name for a user-defined secret (e.g. DATABASE_PASSWORD)",
+            SECRETS.iter().map(|secret| secret.name.as_str()).join(", ")
         ))
     }
 }
 
+/// Get the path of the file listing the names of user-defined secrets
+///
+/// Keyrings do not provide a way to list entries so the names (but not the values!)
+/// of user-defined secrets are stored in this file.
+fn user_defined_path() -> Result<PathBuf> {
+    Ok(get_app_dir(DirType::Config, true)?.join("secrets.json"))
+}
+
+/// Read the names of user-defined secrets
+fn user_defined_read() -> Result<Vec<String>> {
+    let path = user_defined_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let json = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// Write the names of user-defined secrets
+fn user_defined_write(names: Vec<String>) -> Result<()> {
+    let path = user_defined_path()?;
+    fs::write(path, serde_json::to_string_pretty(&names)?)?;
+
+    Ok(())
+}
+
 /// Create a keyring entry for the secret
 fn entry(name: &str) -> Result<keyring::Entry> {
     Ok(keyring::Entry::new(name, "stencila")?)
 }
 
 /// List secrets
+///
+/// Lists the secrets used by Stencila followed by any user-defined secrets.
 pub fn list() -> Result<SecretList> {
+    let user_defined = user_defined_read()?.into_iter().map(|name| {
+        Secret::new(
+            SecretCategory::UserDefined,
+            &name,
+            &name,
+            "User-defined secret",
+        )
+    });
+
     SECRETS
         .iter()
+        .cloned()
+        .chain(user_defined)
         .map(|secret| {
             Ok(Secret {
                 redacted: entry(&secret.name)?.get_password().ok().map(redact),
-                ..secret.clone()
+                ..secret
             })
         })
         .collect::<Result<Vec<Secret>>>()
@@ -182,15 +235,24 @@ fn redact(value: String) -> String {
 ///
 /// If the value is a blank string then delete the entry
 pub fn set(name: &str, value: &str) -> Result<()> {
-    if !SECRETS.iter().any(|secret| secret.name == name) {
-        bail!("Only secrets used by Stencila can be set by Stencila")
+    if let Err(error) = name_validator(name) {
+        bail!("Invalid secret name `{name}`: {error}")
     }
 
-    let secret_entry = entry(name)?;
     if value.trim().is_empty() {
-        secret_entry.delete_password()?;
-    } else {
-        secret_entry.set_password(value)?;
+        return delete(name);
+    }
+
+    entry(name)?.set_password(value)?;
+
+    // Record the name of the secret if it is user-defined
+    if !SECRETS.iter().any(|secret| secret.name == name) {
+        let mut names = user_defined_read()?;
+        if !names.iter().any(|existing| existing == name) {
+            names.push(name.to_string());
+            names.sort();
+            user_defined_write(names)?;
+        }
     }
 
     Ok(())
@@ -201,6 +263,23 @@ pub fn get(name: &str) -> Result<String> {
     Ok(entry(name)?.get_password()?)
 }
 
+/// Get a user-defined secret
+///
+/// Used to get the values of the secrets provided to kernels. Errors if the secret is
+/// one used by Stencila (e.g. an API key) or has not been set by the user, so that a
+/// document can not obtain the value of any other secret or environment variable.
+pub fn get_user_defined(name: &str) -> Result<String> {
+    if SECRETS.iter().any(|secret| secret.name == name) {
+        bail!("Secret `{name}` is used by Stencila and can not be provided to documents")
+    }
+
+    if !user_defined_read()?.iter().any(|existing| existing == name) {
+        bail!("Secret `{name}` is not a user-defined secret")
+    }
+
+    get(name)
+}
+
 /// Get an environment variable or secret
 pub fn env_or_get(name: &str) -> Result<String> {
     env::var(name).or_else(|_| get(name))
@@ -208,8 +287,15 @@ pub fn env_or_get(name: &str) -> Result<String> {
 
 /// Delete a secret
 pub fn delete(name: &str) -> Result<()> {
-    if !SECRETS.iter().any(|secret| secret.name == name) {
-        bail!("Only secrets used by Stencila can be deleted by Stencila")
+    if let Err(error) = name_validator(name) {
+        bail!("Invalid secret name `{name}`: {error}")
+    }
+
+    // Remove the name of the secret if it is user-defined
+    let mut names = user_defined_read()?;
+    if let Some(index) = names.iter().position(|existing| existing == name) {
+        names.remove(index);
+        user_defined_write(names)?;
     }
 
     match entry(name)?.delete_password() {
@@ -221,3 +307,20 @@ pub fn delete(name: &str) -> Result<()> {
         Ok(..) => Ok(()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names() {
+        assert!(name_validator("OPENAI_API_KEY").is_ok());
+        assert!(name_validator("DATABASE_PASSWORD").is_ok());
+        assert!(name_validator("S3_TOKEN_2").is_ok());
+
+        assert!(name_validator("database_password").is_err());
+        assert!(name_validator("2FA_CODE").is_err());
+        assert!(name_validator("API-KEY").is_err());
+        assert!(name_validator("").is_err());
+    }
+}
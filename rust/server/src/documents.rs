@@ -4,7 +4,7 @@ use std::{
     path::{Component, PathBuf},
     str::FromStr,
     sync::Arc,
-    time::UNIX_EPOCH,
+    time::{Duration, UNIX_EPOCH},
 };
 
 use axum::{
@@ -34,21 +34,32 @@ use common::{
         self,
         fs::read,
         sync::{
+            broadcast::error::RecvError,
             mpsc::{channel, Receiver, Sender},
             RwLock,
         },
+        time::{sleep, timeout},
     },
     tracing,
     uuid::Uuid,
 };
-use document::{Command, CommandWait, Document, DocumentId, SyncDirection};
+use document::{Command, CommandStatus, CommandWait, Document, DocumentId, SyncDirection};
 use format::Format;
+use schema::{NodeId, NodeType, Patch};
 
 use crate::{
     errors::InternalError,
+    search::{self, SearchIndex, SearchQuery},
     server::{ServerState, STENCILA_VERSION},
 };
 
+/// How long the final status of a command is kept after it has finished
+const COMMAND_STATUS_TTL: Duration = Duration::from_secs(300);
+
+/// How long the status of a command is recorded for before it is evicted
+/// regardless of whether it has finished
+const COMMAND_STATUS_MAX_AGE: Duration = Duration::from_secs(3600);
+
 /// A store of documents
 #[derive(Debug, Default)]
 pub(crate) struct Documents {
@@ -58,9 +69,41 @@ pub(crate) struct Documents {
 
     /// A mapping of document ids to [`Document`]s
     docs: RwLock<HashMap<Uuid, Arc<Document>>>,
+
+    /// The latest status of commands sent to documents via the REST API
+    ///
+    /// Keyed by the document's [`Uuid`] and the command id. Entries are evicted
+    /// [`COMMAND_STATUS_TTL`] after the command has finished, when the document
+    /// is closed, or after [`COMMAND_STATUS_MAX_AGE`].
+    commands: Arc<RwLock<HashMap<(Uuid, u64), CommandStatus>>>,
+
+    /// The search index to update when documents are opened and changed
+    search: Option<Arc<SearchIndex>>,
+
+    /// The version of each document, incremented each time its root node changes
+    ///
+    /// A counter, updated by a task watching the document's root node, is used rather
+    /// than a digest of the content of the root node because calculating a digest would
+    /// require walking the entire document on every request. The trade-off is that the
+    /// version changes, and so the HTML is regenerated, even if a change is reverted.
+    versions: Arc<RwLock<HashMap<Uuid, u64>>>,
+
+    /// A cache of the HTML of the root node of documents
+    ///
+    /// Keyed by the document's [`Uuid`] with the version of the document
+    /// the HTML was generated from.
+    html: Arc<RwLock<HashMap<Uuid, (u64, String)>>>,
 }
 
 impl Documents {
+    /// Create a new document store which updates a search index
+    pub fn with_search(search: Arc<SearchIndex>) -> Self {
+        Self {
+            search: Some(search),
+            ..Default::default()
+        }
+    }
+
     /// Get a document by path
     ///
     /// At present this always returns the trunk document for the path.
@@ -91,6 +134,23 @@ impl Documents {
 
         let uuid = doc.id().uuid();
 
+        if let Some(search) = &self.search {
+            search.clone().track(path.to_path_buf(), &doc);
+        }
+
+        // Invalidate the cached HTML of the document whenever its root node changes,
+        // and evict it once the document has been dropped
+        let versions = self.versions.clone();
+        let html = self.html.clone();
+        let mut root = doc.watch();
+        tokio::spawn(async move {
+            while root.changed().await.is_ok() {
+                *versions.write().await.entry(uuid).or_default() += 1;
+            }
+            versions.write().await.remove(&uuid);
+            html.write().await.remove(&uuid);
+        });
+
         self.paths.write().await.insert(path.to_path_buf(), uuid);
         self.docs.write().await.insert(uuid, Arc::new(doc));
 
@@ -115,11 +175,117 @@ impl Documents {
         Ok(doc)
     }
 
+    /// Send a command to a document and record its status as it changes
+    ///
+    /// Returns the id of the command which can be used to get its status
+    /// using [`Documents::command_status`].
+    pub async fn command(&self, doc: &Document, command: Command) -> Result<u64> {
+        let uuid = doc.id().uuid();
+        let (command_id, mut status_receiver) = doc.command_subscribe(command).await?;
+
+        let commands = self.commands.clone();
+        commands
+            .write()
+            .await
+            .insert((uuid, command_id), CommandStatus::Waiting);
+
+        tokio::spawn(async move {
+            // Record the status of the command until it finishes, returning
+            // `false` if the document is closed before then
+            let record = async {
+                loop {
+                    let (id, status) = match status_receiver.recv().await {
+                        Ok(message) => message,
+                        Err(RecvError::Lagged(count)) => {
+                            // The final status may have been missed, in which case the
+                            // status is evicted after the max age
+                            tracing::debug!("Command status receiver lagged by {count} messages");
+                            continue;
+                        }
+                        Err(RecvError::Closed) => return false,
+                    };
+                    if id != command_id {
+                        continue;
+                    }
+
+                    let finished = status.finished();
+                    commands.write().await.insert((uuid, command_id), status);
+                    if finished {
+                        return true;
+                    }
+                }
+            };
+
+            if let Ok(true) = timeout(COMMAND_STATUS_MAX_AGE, record).await {
+                // Keep the final status for long enough for clients to poll it
+                sleep(COMMAND_STATUS_TTL).await;
+            }
+            commands.write().await.remove(&(uuid, command_id));
+        });
+
+        Ok(command_id)
+    }
+
+    /// Get the status of a command previously sent using [`Documents::command`]
+    pub async fn command_status(&self, id: &DocumentId, command_id: u64) -> Option<CommandStatus> {
+        self.commands
+            .read()
+            .await
+            .get(&(id.uuid(), command_id))
+            .cloned()
+    }
+
+    /// Get the HTML of the root node of a document
+    ///
+    /// The HTML is cached and only regenerated if the document has
+    /// changed since it was last generated.
+    pub async fn html(&self, doc: &Document) -> Result<String> {
+        let uuid = doc.id().uuid();
+
+        let version = self
+            .versions
+            .read()
+            .await
+            .get(&uuid)
+            .copied()
+            .unwrap_or_default();
+
+        if let Some((cached_version, html)) = self.html.read().await.get(&uuid) {
+            if *cached_version == version {
+                return Ok(html.clone());
+            }
+        }
+
+        let html = doc
+            .export(
+                None,
+                Some(EncodeOptions {
+                    format: Some(Format::Dom),
+                    ..Default::default()
+                }),
+            )
+            .await?;
+
+        self.html
+            .write()
+            .await
+            .insert(uuid, (version, html.clone()));
+
+        Ok(html)
+    }
+
     /// Close a document by [`DocumentId`]
     pub async fn close(&self, id: &DocumentId) -> Result<()> {
         let uuid = id.uuid();
 
         self.docs.write().await.remove(&uuid);
+        self.versions.write().await.remove(&uuid);
+        self.html.write().await.remove(&uuid);
+
+        self.commands
+            .write()
+            .await
+            .retain(|(doc_uuid, ..), _| doc_uuid != &uuid);
 
         // TODO: When there are multiple docs for a path this will need to be revised.
         self.paths
@@ -219,8 +385,16 @@ fn resolve_path(path: PathBuf) -> Result<Option<PathBuf>, InternalError> {
 pub fn router() -> Router<ServerState> {
     Router::new()
         .route("/open/*path", get(open_document))
+        .route("/search", get(search_documents))
+        .route("/:id", get(get_document).patch(patch_document))
+        .route("/:id/nodes/:node_id", get(get_node))
+        .route("/:id/executions", get(executions_document))
         .route("/:id/close", post(close_document))
         .route("/:id/command", post(command_document))
+        .route("/:id/commands", post(commands_document))
+        .route("/:id/commands/:command_id", get(command_status))
+        .route("/:id/comments", get(comments_document))
+        .route("/:id/reviews", get(reviews_document))
         .route("/:id/export", get(export_document))
         .route("/:id/websocket", get(websocket_for_document))
 }
@@ -335,16 +509,7 @@ pub async fn serve_path(
     // HTML as the basis for patching. We could skip including the HTML here (we used to) but then that is unsafe
     // if there are Websocket issues (the page would be blank).
     let root_type = doc.root_type().await;
-    let root_html = doc
-        .export(
-            None,
-            Some(EncodeOptions {
-                format: Some(Format::Dom),
-                ..Default::default()
-            }),
-        )
-        .await
-        .map_err(InternalError::new)?;
+    let root_html = docs.html(&doc).await.map_err(InternalError::new)?;
     let body = format!("<stencila-{view}-view doc={doc_id} type={root_type} view={view} access={access} theme={theme} format={format}>{root_html}</stencila-{view}-view>");
 
     // The version path segment for static assets (JS & CSS)
@@ -474,6 +639,39 @@ async fn open_document(
     .into_response())
 }
 
+/// Search the documents in the served directory
+///
+/// Results are answered from the search index without opening any documents.
+async fn search_documents(
+    State(ServerState {
+        dir, search: index, ..
+    }): State<ServerState>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Response, InternalError> {
+    let terms = search::words(&query.q).collect_vec();
+
+    let node_type = match query.r#type.as_deref().map(NodeType::from_str) {
+        Some(Ok(node_type)) => Some(node_type),
+        Some(Err(..)) => {
+            return Ok((StatusCode::BAD_REQUEST, "Invalid node type").into_response());
+        }
+        None => None,
+    };
+
+    if terms.is_empty() && node_type.is_none() {
+        return Ok((StatusCode::BAD_REQUEST, "No query or node type").into_response());
+    }
+
+    let limit = query
+        .limit
+        .unwrap_or(search::DEFAULT_LIMIT)
+        .min(search::MAX_LIMIT);
+
+    let results = index.search(&dir, &terms, node_type, limit).await;
+
+    Ok(Json(results).into_response())
+}
+
 /// Parse a string as a `DocumentId` and return the corresponding document
 async fn doc_by_id(docs: &Arc<Documents>, id: &str) -> Result<Arc<Document>> {
     let id = DocumentId::from_str(id)?;
@@ -511,6 +709,142 @@ async fn command_document(
     Ok(StatusCode::OK.into_response())
 }
 
+/// Handle a request for the root node of a document
+async fn get_document(
+    State(ServerState { docs, .. }): State<ServerState>,
+    Path(id): Path<String>,
+) -> Result<Response, InternalError> {
+    let Ok(doc) = doc_by_id(&docs, &id).await else {
+        return Ok((StatusCode::BAD_REQUEST, "Invalid document id").into_response());
+    };
+
+    let root = doc.root_read().await;
+    Ok(Json(&*root).into_response())
+}
+
+/// Handle a request to patch a document
+///
+/// Waits for the patch to be applied before responding.
+async fn patch_document(
+    State(ServerState { docs, .. }): State<ServerState>,
+    Path(id): Path<String>,
+    Json(patch): Json<Patch>,
+) -> Result<Response, InternalError> {
+    let Ok(doc) = doc_by_id(&docs, &id).await else {
+        return Ok((StatusCode::BAD_REQUEST, "Invalid document id").into_response());
+    };
+
+    if let Err(error) = doc
+        .command(Command::PatchNode(patch), CommandWait::Yes)
+        .await
+    {
+        return Ok((StatusCode::BAD_REQUEST, error.to_string()).into_response());
+    }
+
+    Ok(StatusCode::OK.into_response())
+}
+
+/// Handle a request for a node within a document
+async fn get_node(
+    State(ServerState { docs, .. }): State<ServerState>,
+    Path((id, node_id)): Path<(String, String)>,
+) -> Result<Response, InternalError> {
+    let Ok(doc) = doc_by_id(&docs, &id).await else {
+        return Ok((StatusCode::BAD_REQUEST, "Invalid document id").into_response());
+    };
+
+    let Ok(node_id) = NodeId::from_str(&node_id) else {
+        return Ok((StatusCode::BAD_REQUEST, "Invalid node id").into_response());
+    };
+
+    match doc.find_node(node_id).await {
+        Some(node) => Ok(Json(node).into_response()),
+        None => Ok(not_found()),
+    }
+}
+
+/// Handle a request for the execution status of the executable nodes in a document
+async fn executions_document(
+    State(ServerState { docs, .. }): State<ServerState>,
+    Path(id): Path<String>,
+) -> Result<Response, InternalError> {
+    let Ok(doc) = doc_by_id(&docs, &id).await else {
+        return Ok((StatusCode::BAD_REQUEST, "Invalid document id").into_response());
+    };
+
+    Ok(Json(doc.executions().await).into_response())
+}
+
+/// Handle a request to perform a document command and return its id
+///
+/// Unlike `command_document`, the status of the command is recorded
+/// so that it can be polled using `command_status`.
+async fn commands_document(
+    State(ServerState { docs, .. }): State<ServerState>,
+    Path(id): Path<String>,
+    Json(command): Json<Command>,
+) -> Result<Response, InternalError> {
+    let Ok(doc) = doc_by_id(&docs, &id).await else {
+        return Ok((StatusCode::BAD_REQUEST, "Invalid document id").into_response());
+    };
+
+    let command_id = docs
+        .command(&doc, command)
+        .await
+        .map_err(InternalError::new)?;
+
+    #[derive(Serialize)]
+    #[serde(crate = "common::serde")]
+    struct CommandResponse {
+        id: u64,
+    }
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(CommandResponse { id: command_id }),
+    )
+        .into_response())
+}
+
+/// Handle a request for the status of a document command
+async fn command_status(
+    State(ServerState { docs, .. }): State<ServerState>,
+    Path((id, command_id)): Path<(String, u64)>,
+) -> Result<Response, InternalError> {
+    let Ok(id) = DocumentId::from_str(&id) else {
+        return Ok((StatusCode::BAD_REQUEST, "Invalid document id").into_response());
+    };
+
+    match docs.command_status(&id, command_id).await {
+        Some(status) => Ok(Json(status).into_response()),
+        None => Ok(not_found()),
+    }
+}
+
+/// Handle a request for the comments on a document
+async fn comments_document(
+    State(ServerState { docs, .. }): State<ServerState>,
+    Path(id): Path<String>,
+) -> Result<Response, InternalError> {
+    let Ok(doc) = doc_by_id(&docs, &id).await else {
+        return Ok((StatusCode::BAD_REQUEST, "Invalid document id").into_response());
+    };
+
+    Ok(Json(doc.comments().await).into_response())
+}
+
+/// Handle a request for the reviews of a document
+async fn reviews_document(
+    State(ServerState { docs, .. }): State<ServerState>,
+    Path(id): Path<String>,
+) -> Result<Response, InternalError> {
+    let Ok(doc) = doc_by_id(&docs, &id).await else {
+        return Ok((StatusCode::BAD_REQUEST, "Invalid document id").into_response());
+    };
+
+    Ok(Json(doc.reviews().await).into_response())
+}
+
 /// Handle a request to export a document
 ///
 /// TODO: This should add correct MIME type to response
@@ -829,12 +1163,203 @@ fn not_found() -> Response {
 
 #[cfg(test)]
 mod tests {
-    use axum::http::HeaderValue;
+    use axum::{body::to_bytes, http::HeaderValue};
 
-    use common::{eyre::Result, tokio};
+    use common::{
+        eyre::Result,
+        serde_json::{json, Value},
+        tempfile::{tempdir, TempDir},
+        tokio::{self, fs::write},
+    };
+    use schema::{Block, Node, NodeProperty, PatchOp, PatchPath, PatchValue};
 
     use super::*;
 
+    /// Open a document in a temporary directory and return the server state,
+    /// the id of the document, and the node id of its first paragraph
+    async fn open() -> Result<(TempDir, ServerState, String, NodeId)> {
+        let dir = tempdir()?;
+        let path = dir.path().join("doc.smd");
+        write(&path, "A paragraph.\n\n```python exec\nx = 1\n```\n").await?;
+
+        let docs = Arc::new(Documents::default());
+        let doc = docs.by_path(&path, None).await?;
+
+        let paragraph = match &*doc.root_read().await {
+            Node::Article(article) => match article.content.first() {
+                Some(Block::Paragraph(paragraph)) => paragraph.node_id(),
+                _ => unreachable!("first block should be a paragraph"),
+            },
+            _ => unreachable!("root should be an article"),
+        };
+
+        let state = ServerState {
+            dir: dir.path().to_path_buf(),
+            docs,
+            ..Default::default()
+        };
+
+        Ok((dir, state, doc.id().to_string(), paragraph))
+    }
+
+    /// Get the status and JSON body of a response
+    async fn json(response: Response) -> Result<(StatusCode, Value)> {
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await?;
+        let value = serde_json::from_slice(&bytes).unwrap_or_default();
+        Ok((status, value))
+    }
+
+    /// Poll the status of a command until it has finished
+    async fn finished(docs: &Documents, id: &str, command_id: u64) -> Result<CommandStatus> {
+        let id = DocumentId::from_str(id)?;
+        for _ in 0..100 {
+            if let Some(status) = docs.command_status(&id, command_id).await {
+                if status.finished() {
+                    return Ok(status);
+                }
+            }
+            sleep(Duration::from_millis(50)).await;
+        }
+        eyre::bail!("Command did not finish")
+    }
+
+    /// Test getting and patching a document, and getting a node within it
+    #[tokio::test]
+    async fn document_and_node() -> Result<()> {
+        let (_dir, state, id, paragraph) = open().await?;
+
+        let response = get_document(State(state.clone()), Path(id.clone())).await?;
+        let (status, value) = json(response).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(value["type"], json!("Article"));
+        assert_eq!(value["content"][0]["type"], json!("Paragraph"));
+
+        let response = get_document(State(state.clone()), Path("invalid".to_string())).await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let patch = Patch {
+            node_id: Some(paragraph.clone()),
+            ops: vec![(
+                PatchPath::from(NodeProperty::Id),
+                PatchOp::Set(PatchValue::String("intro".to_string())),
+            )],
+            ..Default::default()
+        };
+        let response = patch_document(State(state.clone()), Path(id.clone()), Json(patch)).await?;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = get_node(
+            State(state.clone()),
+            Path((id.clone(), paragraph.to_string())),
+        )
+        .await?;
+        let (status, value) = json(response).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(value["type"], json!("Paragraph"));
+        assert_eq!(value["id"], json!("intro"));
+
+        let response = get_node(
+            State(state.clone()),
+            Path((id.clone(), "invalid".to_string())),
+        )
+        .await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let unknown = NodeId::new(b"pgh", b"unknown").to_string();
+        let response = get_node(State(state.clone()), Path((id.clone(), unknown))).await?;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+
+    /// Test getting the execution status of the executable nodes in a document
+    #[tokio::test]
+    async fn executions() -> Result<()> {
+        let (_dir, state, id, ..) = open().await?;
+
+        let response = executions_document(State(state.clone()), Path(id)).await?;
+        let (status, value) = json(response).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(value.as_array().map(|array| array.len()), Some(1));
+        assert_eq!(value[0]["nodeType"], json!("CodeChunk"));
+
+        let response =
+            executions_document(State(state.clone()), Path("invalid".to_string())).await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }
+
+    /// Test sending a command to a document and polling for its status
+    #[tokio::test]
+    async fn commands() -> Result<()> {
+        let (_dir, state, id, ..) = open().await?;
+
+        let response = commands_document(
+            State(state.clone()),
+            Path(id.clone()),
+            Json(Command::CompileDocument),
+        )
+        .await?;
+        let (status, value) = json(response).await?;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let command_id = value["id"].as_u64().expect("should have an id");
+
+        assert!(finished(&state.docs, &id, command_id).await?.succeeded());
+
+        let response = command_status(State(state.clone()), Path((id.clone(), command_id))).await?;
+        let (status, value) = json(response).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(value["status"], json!("succeeded"));
+
+        let response = command_status(State(state.clone()), Path((id.clone(), u64::MAX))).await?;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response =
+            command_status(State(state.clone()), Path(("invalid".to_string(), 0))).await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = commands_document(
+            State(state.clone()),
+            Path("invalid".to_string()),
+            Json(Command::CompileDocument),
+        )
+        .await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }
+
+    /// Test that [`Documents::command`] records the status of commands, including failures,
+    /// and that statuses are evicted when the document is closed
+    #[tokio::test]
+    async fn documents_command() -> Result<()> {
+        let (_dir, state, id, ..) = open().await?;
+        let docs = &state.docs;
+        let doc = doc_by_id(docs, &id).await?;
+
+        let compile = docs.command(&doc, Command::CompileDocument).await?;
+        let failing = docs
+            .command(
+                &doc,
+                Command::RemoveComment {
+                    id: "comment-1".to_string(),
+                },
+            )
+            .await?;
+        assert_ne!(compile, failing);
+
+        assert!(finished(docs, &id, compile).await?.succeeded());
+        assert!(finished(docs, &id, failing).await?.failed());
+
+        let doc_id = DocumentId::from_str(&id)?;
+        docs.close(&doc_id).await?;
+        assert!(docs.command_status(&doc_id, compile).await.is_none());
+
+        Ok(())
+    }
+
     /// Test the `resolve_path` method using the `routing` example
     #[tokio::test]
     async fn test_resolve_path() -> Result<()> {
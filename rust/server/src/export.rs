@@ -0,0 +1,290 @@
+//! Export of the served documents as a static site
+//!
+//! Rather than re-implementing rendering, each document is requested from the
+//! server's router (in-process, without binding to a port) so that the exported
+//! site is the same as the one that is served.
+
+use std::{
+    collections::HashMap,
+    fs::{copy, create_dir_all, write},
+    path::{Path, PathBuf},
+};
+
+use axum::{
+    body::{to_bytes, Body},
+    http::Request,
+    Router,
+};
+use tower::ServiceExt;
+
+use common::{
+    eyre::Result,
+    glob::{glob_with, MatchOptions},
+    once_cell::sync::Lazy,
+    regex::{Captures, Regex},
+    tracing,
+};
+use format::Format;
+use web_dist::Web;
+
+use crate::{
+    search::{is_document, is_public},
+    server::STENCILA_VERSION,
+};
+
+/// Export the documents in `dir` as a static site in `out`
+pub(crate) async fn export(router: Router, dir: &Path, out: &Path) -> Result<()> {
+    create_dir_all(out)?;
+
+    // Write the static assets (e.g. themes) at the same path as they are served
+    let version = if cfg!(debug_assertions) {
+        "dev"
+    } else {
+        STENCILA_VERSION
+    };
+    Web::to_path(&out.join("~static").join(version), false)?;
+
+    let pattern = format!("{}/**/*", dir.display());
+    let options = MatchOptions {
+        require_literal_leading_dot: true,
+        ..Default::default()
+    };
+
+    let mut dests: HashMap<PathBuf, (String, PathBuf)> = HashMap::new();
+    let mut count = 0;
+    for path in glob_with(&pattern, options)?.flatten() {
+        if !path.is_file() || !is_public(dir, &path) {
+            continue;
+        }
+        let relative = path.strip_prefix(dir)?;
+
+        if is_document(dir, &path) {
+            // Only export each destination once. There may be more than one file for a
+            // route (e.g. `report.md` and `report.ipynb`) in which case the one that is
+            // served is exported. But different routes can also have the same destination
+            // (e.g. `report.md` and `report/index.md`) in which case one is not exported.
+            let (route, dest) = route_and_dest(relative);
+            if let Some((existing_route, existing)) = dests.get(&dest) {
+                if existing_route != &route {
+                    tracing::warn!(
+                        "Not exporting `{}` because `{}` is already exported to `{}`",
+                        relative.display(),
+                        existing.display(),
+                        dest.display()
+                    );
+                }
+                continue;
+            }
+            dests.insert(dest.clone(), (route.clone(), relative.to_path_buf()));
+
+            let uri = format!("/{}?view=static", route.replace(' ', "%20"));
+            let request = Request::get(uri).body(Body::empty())?;
+            let response = router.clone().oneshot(request).await?;
+            if !response.status().is_success() {
+                tracing::warn!(
+                    "Unable to export `{}`: {}",
+                    relative.display(),
+                    response.status()
+                );
+                continue;
+            }
+            let bytes = to_bytes(response.into_body(), usize::MAX).await?;
+
+            // Documents that are not index files are written one directory deeper than
+            // their source so relative URLs (e.g. of media and links) need to be adjusted
+            let bytes = if route.ends_with('/') || route.is_empty() {
+                bytes.to_vec()
+            } else {
+                rewrite_relative_urls(&String::from_utf8_lossy(&bytes)).into_bytes()
+            };
+
+            let dest = out.join(dest);
+            if let Some(parent) = dest.parent() {
+                create_dir_all(parent)?;
+            }
+            write(&dest, bytes)?;
+
+            tracing::debug!("Exported `{}` to `{}`", relative.display(), dest.display());
+            count += 1;
+        } else {
+            // Copy any images and other media used in documents
+            let format = Format::from_path(&path);
+            if format.is_image() || format.is_audio() || format.is_video() {
+                let dest = out.join(relative);
+                if let Some(parent) = dest.parent() {
+                    create_dir_all(parent)?;
+                }
+                copy(&path, dest)?;
+            }
+        }
+    }
+
+    tracing::info!("Exported {count} documents to `{}`", out.display());
+
+    Ok(())
+}
+
+/// Get the route for a document and the path of the HTML file to write it to
+///
+/// Index, main and readme files are served at the route for their directory
+/// and so are written to `index.html` in that directory. Other files are written
+/// to `index.html` in a directory with the same stem so that their route (e.g. `/report`)
+/// works on static hosts without needing to add a `.html` extension.
+fn route_and_dest(relative: &Path) -> (String, PathBuf) {
+    let parent = relative.parent().unwrap_or(Path::new(""));
+    let stem = relative
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let parent_route = parent
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+
+    if matches!(stem.to_lowercase().as_str(), "index" | "main" | "readme") {
+        let route = if parent_route.is_empty() {
+            String::new()
+        } else {
+            [&parent_route, "/"].concat()
+        };
+        (route, parent.join("index.html"))
+    } else {
+        let route = if parent_route.is_empty() {
+            stem.clone()
+        } else {
+            [&parent_route, "/", &stem].concat()
+        };
+        (route, parent.join(&stem).join("index.html"))
+    }
+}
+
+/// Rewrite relative URLs in HTML so that they resolve from one directory deeper
+///
+/// The `src` and `content-url` attributes, used for images, audio and video, and the
+/// `href` attribute, used for links, are rewritten. Absolute paths, URLs with a scheme
+/// (including `data:` and `mailto:` URLs), and fragments are left unchanged.
+fn rewrite_relative_urls(html: &str) -> String {
+    static URL_ATTR: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"\b(src|href|content-url)="([^"]*)""#).expect("Unable to create regex")
+    });
+
+    URL_ATTR
+        .replace_all(html, |captures: &Captures| {
+            let url = &captures[2];
+            if url.is_empty() || url.starts_with('/') || url.starts_with('#') || url.contains(':') {
+                captures[0].to_string()
+            } else {
+                let url = url.strip_prefix("./").unwrap_or(url);
+                format!(r#"{}="../{url}""#, &captures[1])
+            }
+        })
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use common_dev::pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn routes_and_dests() {
+        let test = |path: &str| route_and_dest(Path::new(path));
+
+        assert_eq!(
+            test("index.md"),
+            (String::new(), PathBuf::from("index.html"))
+        );
+        assert_eq!(
+            test("README.md"),
+            (String::new(), PathBuf::from("index.html"))
+        );
+        assert_eq!(
+            test("sub/main.ipynb"),
+            ("sub/".to_string(), PathBuf::from("sub/index.html"))
+        );
+        assert_eq!(
+            test("report.md"),
+            ("report".to_string(), PathBuf::from("report/index.html"))
+        );
+        assert_eq!(
+            test("sub/dir/report two.smd"),
+            (
+                "sub/dir/report two".to_string(),
+                PathBuf::from("sub/dir/report two/index.html")
+            )
+        );
+    }
+
+    #[test]
+    fn relative_urls() {
+        assert_eq!(
+            rewrite_relative_urls(r#"<img src="images/a.png"><img src="./b.png">"#),
+            r#"<img src="../images/a.png"><img src="../b.png">"#
+        );
+        assert_eq!(
+            rewrite_relative_urls(r#"<stencila-video-object content-url="c.mp4">"#),
+            r#"<stencila-video-object content-url="../c.mp4">"#
+        );
+        assert_eq!(
+            rewrite_relative_urls(r#"<a href="other">Other</a><a href="sub/">Sub</a>"#),
+            r#"<a href="../other">Other</a><a href="../sub/">Sub</a>"#
+        );
+
+        let unchanged = r##"<img src="/~static/a.png"><img src="https://example.org/a.png"><img src="data:image/png;base64,AA=="><a href="#intro"><a href="mailto:a@example.org">"##;
+        assert_eq!(rewrite_relative_urls(unchanged), unchanged);
+    }
+
+    /// Test exporting a directory using a router which responds with the path requested
+    #[tokio::test]
+    async fn export_dir() -> Result<()> {
+        use std::fs::{read_to_string, write};
+
+        use axum::http::Uri;
+        use common::{tempfile::tempdir, tokio};
+
+        let dir = tempdir()?;
+        let out = tempdir()?;
+        let (dir, out) = (dir.path(), out.path());
+
+        create_dir_all(dir.join("report"))?;
+        create_dir_all(dir.join("_private"))?;
+        for (path, content) in [
+            ("index.md", "Index"),
+            ("report.md", "Report"),
+            ("report/index.md", "Report index"),
+            ("a.png", ""),
+            ("notes.txt", "Not exported"),
+            ("_private/secret.md", "Secret"),
+        ] {
+            write(dir.join(path), content)?;
+        }
+
+        let router = Router::new().fallback(|uri: Uri| async move {
+            format!(r#"<a href="other">{}</a><img src="a.png">"#, uri.path())
+        });
+        export(router, dir, out).await?;
+
+        assert_eq!(
+            read_to_string(out.join("index.html"))?,
+            r#"<a href="other">/</a><img src="a.png">"#
+        );
+
+        // Only one of `report.md` and `report/index.md` is exported and, because
+        // it is one directory deeper than its source, relative URLs are rewritten
+        let report = read_to_string(out.join("report").join("index.html"))?;
+        assert!(
+            report == r#"<a href="../other">/report</a><img src="../a.png">"#
+                || report == r#"<a href="other">/report/</a><img src="a.png">"#
+        );
+
+        assert!(out.join("a.png").exists());
+        assert!(out.join("~static").exists());
+        assert!(!out.join("notes.txt").exists());
+        assert!(!out.join("_private").exists());
+
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 mod documents;
 mod errors;
+mod export;
 mod login;
+mod search;
 mod server;
 mod statics;
 
@@ -0,0 +1,723 @@
+//! A full-text and node-type index of the documents in the served directory
+//!
+//! The index is built when the server starts and is kept up to date by watching
+//! the served directory for changes. Only the nodes that users are likely to
+//! search for (e.g. paragraphs, headings, and code) are indexed. Each node's text is
+//! split into words which are stored in an inverted index so that a search only needs
+//! to look up the nodes containing words starting with each term of the query.
+//!
+//! Documents that are opened by the server are instead re-indexed whenever they are
+//! updated so that, for those documents, the ids of the document and nodes in search
+//! results can be used with the other `/~documents` routes. For other documents, the
+//! path to the node is returned so that the node can be located after the document is opened.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use codec_text_trait::TextCodec;
+use common::{
+    glob::{glob_with, MatchOptions},
+    itertools::Itertools,
+    serde::{Deserialize, Serialize},
+    tokio::{
+        self,
+        sync::{mpsc::unbounded_channel, RwLock},
+    },
+    tracing,
+};
+use document::Document;
+use format::Format;
+use node_map::{node_map, NodePath};
+use schema::{Block, Inline, Node, NodeId, NodeType, Visitor, WalkControl};
+
+/// The maximum number of characters of the text of a node to include in a search result
+const SNIPPET_LENGTH: usize = 200;
+
+/// The default maximum number of search results
+pub(crate) const DEFAULT_LIMIT: usize = 100;
+
+/// The maximum number of search results that can be requested
+pub(crate) const MAX_LIMIT: usize = 1000;
+
+/// A search of the documents in the served directory
+#[derive(Debug, Deserialize)]
+#[serde(crate = "common::serde")]
+pub(crate) struct SearchQuery {
+    /// The text to search for
+    ///
+    /// Split into words, all of which must be the start of a word in the
+    /// text of a node (case insensitively) for it to match.
+    #[serde(default)]
+    pub q: String,
+
+    /// The type of node to restrict the search to (e.g. `CodeChunk`)
+    pub r#type: Option<String>,
+
+    /// The maximum number of results to return
+    pub limit: Option<usize>,
+}
+
+/// A node in a document which matches a [`SearchQuery`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase", crate = "common::serde")]
+pub(crate) struct SearchResult {
+    /// The path of the document, relative to the served directory
+    pub path: PathBuf,
+
+    /// The id of the document, if it is open
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc_id: Option<String>,
+
+    /// The id of the node, if the document is open
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<NodeId>,
+
+    /// The path to the node within the document
+    pub node_path: NodePath,
+
+    /// The type of the node
+    pub node_type: NodeType,
+
+    /// The text of the node, truncated if necessary
+    pub text: String,
+}
+
+/// A node in the index
+#[derive(Debug, Clone)]
+struct IndexEntry {
+    /// The id of the node
+    node_id: NodeId,
+
+    /// The path to the node within the document
+    node_path: NodePath,
+
+    /// The type of the node
+    node_type: NodeType,
+
+    /// The text of the node
+    text: String,
+}
+
+impl IndexEntry {
+    /// Get a snippet of the text of the entry
+    fn snippet(&self) -> String {
+        if self.text.chars().count() > SNIPPET_LENGTH {
+            let text: String = self.text.chars().take(SNIPPET_LENGTH).collect();
+            [&text, "…"].concat()
+        } else {
+            self.text.clone()
+        }
+    }
+}
+
+/// The indexed nodes of a document
+#[derive(Debug)]
+struct IndexedDocument {
+    /// The id of the document, if the nodes were indexed from an open document
+    doc_id: Option<String>,
+
+    /// The indexed nodes
+    entries: Vec<IndexEntry>,
+}
+
+/// The location of an entry in the index: the path of the document and the
+/// index of the entry within it
+type Posting = (PathBuf, usize);
+
+/// An inverted index of nodes
+#[derive(Debug, Default)]
+struct Index {
+    /// The indexed documents, by path
+    docs: HashMap<PathBuf, IndexedDocument>,
+
+    /// The entries containing each word
+    words: BTreeMap<String, BTreeSet<Posting>>,
+
+    /// The entries of each node type
+    types: HashMap<NodeType, BTreeSet<Posting>>,
+}
+
+impl Index {
+    /// Insert, or replace, the entries for a document
+    fn insert(&mut self, path: &Path, doc_id: Option<String>, entries: Vec<IndexEntry>) {
+        self.remove(path);
+
+        for (index, entry) in entries.iter().enumerate() {
+            let posting = (path.to_path_buf(), index);
+            for word in words(&entry.text).unique() {
+                self.words.entry(word).or_default().insert(posting.clone());
+            }
+            self.types
+                .entry(entry.node_type)
+                .or_default()
+                .insert(posting);
+        }
+
+        self.docs
+            .insert(path.to_path_buf(), IndexedDocument { doc_id, entries });
+    }
+
+    /// Remove the entries for a document
+    fn remove(&mut self, path: &Path) {
+        let Some(doc) = self.docs.remove(path) else {
+            return;
+        };
+
+        for (index, entry) in doc.entries.iter().enumerate() {
+            let posting = (path.to_path_buf(), index);
+            for word in words(&entry.text).unique() {
+                if let Some(postings) = self.words.get_mut(&word) {
+                    postings.remove(&posting);
+                    if postings.is_empty() {
+                        self.words.remove(&word);
+                    }
+                }
+            }
+            if let Some(postings) = self.types.get_mut(&entry.node_type) {
+                postings.remove(&posting);
+            }
+        }
+    }
+
+    /// Remove the entries for all documents within a path
+    ///
+    /// The path may be a file or a directory.
+    fn remove_within(&mut self, path: &Path) {
+        let paths = self
+            .docs
+            .keys()
+            .filter(|doc_path| doc_path.starts_with(path))
+            .cloned()
+            .collect_vec();
+        for path in paths {
+            self.remove(&path);
+        }
+    }
+
+    /// Is a document indexed from one that is open?
+    fn is_open(&self, path: &Path) -> bool {
+        self.docs
+            .get(path)
+            .map_or(false, |doc| doc.doc_id.is_some())
+    }
+
+    /// Get the entries containing a word starting with a term
+    fn prefixed(&self, term: &str) -> BTreeSet<Posting> {
+        self.words
+            .range(term.to_string()..)
+            .take_while(|(word, ..)| word.starts_with(term))
+            .flat_map(|(.., postings)| postings.iter().cloned())
+            .collect()
+    }
+
+    /// Search for entries matching the terms and node type
+    fn search(
+        &self,
+        terms: &[String],
+        node_type: Option<NodeType>,
+        limit: usize,
+    ) -> Vec<(&Path, &IndexedDocument, &IndexEntry)> {
+        let mut matches: Option<BTreeSet<Posting>> =
+            node_type.map(|node_type| self.types.get(&node_type).cloned().unwrap_or_default());
+        for term in terms {
+            let postings = self.prefixed(term);
+            matches = Some(match matches {
+                Some(matches) => matches.intersection(&postings).cloned().collect(),
+                None => postings,
+            });
+        }
+
+        matches
+            .into_iter()
+            .flatten()
+            .filter_map(|(path, index)| {
+                let (path, doc) = self.docs.get_key_value(&path)?;
+                Some((path.as_path(), doc, doc.entries.get(index)?))
+            })
+            .take(limit)
+            .collect()
+    }
+}
+
+/// An index of the documents in a directory
+#[derive(Debug, Default)]
+pub(crate) struct SearchIndex {
+    index: RwLock<Index>,
+}
+
+impl SearchIndex {
+    /// Index all the documents in a directory and then watch it for changes
+    pub fn start(self: Arc<Self>, dir: PathBuf) {
+        tokio::spawn(async move {
+            self.index_dir(&dir).await;
+            self.watch(dir);
+        });
+    }
+
+    /// Index all the documents in a directory
+    async fn index_dir(&self, dir: &Path) {
+        let pattern = format!("{}/**/*", dir.display());
+        let options = MatchOptions {
+            require_literal_leading_dot: true,
+            ..Default::default()
+        };
+        let paths = match glob_with(&pattern, options) {
+            Ok(paths) => paths.flatten(),
+            Err(error) => {
+                tracing::error!("While listing files to index: {error}");
+                return;
+            }
+        };
+
+        let mut count = 0;
+        for path in paths {
+            if is_document(dir, &path) {
+                self.index_path(&path).await;
+                count += 1;
+            }
+        }
+
+        tracing::info!("Indexed {count} documents for search");
+    }
+
+    /// Watch a directory and re-index documents when they change
+    ///
+    /// As for file syncing, `notify` watchers are not async, so the watcher is
+    /// run in a separate thread and changed paths are forwarded to an async task.
+    /// Documents that are open are skipped because they are re-indexed when
+    /// they are updated (see [`SearchIndex::track`]).
+    fn watch(self: Arc<Self>, dir: PathBuf) {
+        let (sender, mut receiver) = unbounded_channel::<PathBuf>();
+
+        let watched = dir.clone();
+        std::thread::spawn(move || {
+            let (watch_sender, watch_receiver) = std::sync::mpsc::channel();
+            let mut watcher = match notify::recommended_watcher(watch_sender) {
+                Ok(watcher) => watcher,
+                Err(error) => {
+                    tracing::error!("While instantiating watcher: {error}");
+                    return;
+                }
+            };
+
+            if let Err(error) = watcher.watch(&watched, RecursiveMode::Recursive) {
+                tracing::error!("While watching directory `{}`: {error}", watched.display());
+                return;
+            }
+
+            loop {
+                match watch_receiver.recv() {
+                    Ok(Ok(event)) => {
+                        if !matches!(
+                            event.kind,
+                            EventKind::Create(..) | EventKind::Modify(..) | EventKind::Remove(..)
+                        ) {
+                            continue;
+                        }
+                        for path in event.paths {
+                            if sender.send(path).is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Ok(Err(error)) => {
+                        tracing::error!("While watching directory: {error}");
+                    }
+                    Err(error) => {
+                        tracing::error!("While receiving file watching events: {error}");
+                        break;
+                    }
+                }
+            }
+        });
+
+        tokio::spawn(async move {
+            while let Some(path) = receiver.recv().await {
+                self.changed(&dir, &path).await;
+            }
+        });
+    }
+
+    /// Update the index for a path that has been created, modified or removed
+    async fn changed(&self, dir: &Path, path: &Path) {
+        if path.exists() {
+            if is_document(dir, path) && !self.index.read().await.is_open(path) {
+                self.index_path(path).await;
+            }
+        } else {
+            // The path may be a file or a directory so remove all entries within it
+            self.index.write().await.remove_within(path);
+        }
+    }
+
+    /// Index a document at a path
+    async fn index_path(&self, path: &Path) {
+        match codecs::from_path(path, None).await {
+            Ok(node) => self.index_node(path, None, &node).await,
+            Err(error) => {
+                tracing::debug!("While indexing `{}`: {error}", path.display());
+                self.index.write().await.remove(path);
+            }
+        }
+    }
+
+    /// Index the root node of a document at a path
+    async fn index_node(&self, path: &Path, doc_id: Option<String>, node: &Node) {
+        let entries = entries(node);
+        self.index.write().await.insert(path, doc_id, entries);
+    }
+
+    /// Index a document that has been opened and re-index it each time it is updated
+    ///
+    /// Uses the document's own update notifications (which, when the document is synced,
+    /// include changes to its source file) rather than separately watching the file system.
+    /// When the document is dropped, it is re-indexed from its file so that the index does
+    /// not refer to the ids of the closed document.
+    pub fn track(self: Arc<Self>, path: PathBuf, doc: &Document) {
+        if !is_indexed_format(&path) {
+            return;
+        }
+
+        let doc_id = doc.id().to_string();
+        let mut updates = doc.watch_updates();
+        let root = doc.watch();
+        tokio::spawn(async move {
+            loop {
+                let entries = entries(&root.borrow());
+                self.index
+                    .write()
+                    .await
+                    .insert(&path, Some(doc_id.clone()), entries);
+
+                if updates.changed().await.is_err() {
+                    break;
+                }
+            }
+
+            self.index_path(&path).await;
+        });
+    }
+
+    /// Search the index
+    ///
+    /// Returns at most `limit` results with the paths of documents relative to `dir`.
+    pub async fn search(
+        &self,
+        dir: &Path,
+        terms: &[String],
+        node_type: Option<NodeType>,
+        limit: usize,
+    ) -> Vec<SearchResult> {
+        self.index
+            .read()
+            .await
+            .search(terms, node_type, limit)
+            .into_iter()
+            .map(|(path, doc, entry)| SearchResult {
+                path: path.strip_prefix(dir).unwrap_or(path).to_path_buf(),
+                doc_id: doc.doc_id.clone(),
+                node_id: doc.doc_id.as_ref().map(|..| entry.node_id.clone()),
+                node_path: entry.node_path.clone(),
+                node_type: entry.node_type,
+                text: entry.snippet(),
+            })
+            .collect()
+    }
+}
+
+/// Split text into lowercase words
+///
+/// Used for both the text of nodes and search queries. Words are sequences
+/// of alphanumeric characters and underscores.
+pub(crate) fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+}
+
+/// Is a file a document that should be indexed and exported?
+///
+/// Only files in formats that are decoded without external tools are included.
+pub(crate) fn is_document(dir: &Path, path: &Path) -> bool {
+    path.is_file() && is_public(dir, path) && is_indexed_format(path)
+}
+
+/// Is a file in a format that is indexed?
+fn is_indexed_format(path: &Path) -> bool {
+    let format = Format::from_path(path);
+    format.is_markdown_flavor() || matches!(format, Format::Ipynb | Format::Jats)
+}
+
+/// Is a path within `dir` public?
+///
+/// Hidden and private (prefixed with `_`) files and directories within `dir` are
+/// not public, as is the case when serving documents.
+pub(crate) fn is_public(dir: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(dir) else {
+        return false;
+    };
+
+    !relative.components().any(|component| {
+        matches!(component, Component::Normal(name) if {
+            let name = name.to_string_lossy();
+            name.starts_with('.') || name.starts_with('_')
+        })
+    })
+}
+
+/// Collect the index entries for the root node of a document
+fn entries(node: &Node) -> Vec<IndexEntry> {
+    let mut walker = IndexWalker::default();
+    walker.visit(node);
+
+    let mut paths = node_map(node);
+    walker
+        .entries
+        .into_iter()
+        .filter_map(|(node_id, node_type, text)| {
+            Some(IndexEntry {
+                node_path: paths.swap_remove(&node_id)?,
+                node_id,
+                node_type,
+                text,
+            })
+        })
+        .collect()
+}
+
+/// A visitor which collects the id, type and text of nodes to index
+#[derive(Default)]
+struct IndexWalker {
+    entries: Vec<(NodeId, NodeType, String)>,
+}
+
+impl IndexWalker {
+    /// Add an entry to the index
+    fn entry(&mut self, node_id: NodeId, node_type: NodeType, text: String) {
+        if text.trim().is_empty() {
+            return;
+        }
+
+        self.entries.push((node_id, node_type, text))
+    }
+}
+
+impl Visitor for IndexWalker {
+    fn visit_block(&mut self, block: &Block) -> WalkControl {
+        match block {
+            Block::CodeBlock(node) => {
+                self.entry(node.node_id(), NodeType::CodeBlock, node.code.to_string())
+            }
+            Block::CodeChunk(node) => {
+                self.entry(node.node_id(), NodeType::CodeChunk, node.code.to_string())
+            }
+            Block::Heading(node) => {
+                self.entry(node.node_id(), NodeType::Heading, node.content.to_text().0)
+            }
+            Block::MathBlock(node) => {
+                self.entry(node.node_id(), NodeType::MathBlock, node.code.to_string())
+            }
+            Block::Paragraph(node) => self.entry(
+                node.node_id(),
+                NodeType::Paragraph,
+                node.content.to_text().0,
+            ),
+            Block::RawBlock(node) => {
+                self.entry(node.node_id(), NodeType::RawBlock, node.content.to_string())
+            }
+            _ => {}
+        }
+
+        WalkControl::Continue
+    }
+
+    fn visit_inline(&mut self, inline: &Inline) -> WalkControl {
+        match inline {
+            Inline::CodeExpression(node) => self.entry(
+                node.node_id(),
+                NodeType::CodeExpression,
+                node.code.to_string(),
+            ),
+            Inline::CodeInline(node) => {
+                self.entry(node.node_id(), NodeType::CodeInline, node.code.to_string())
+            }
+            Inline::MathInline(node) => {
+                self.entry(node.node_id(), NodeType::MathInline, node.code.to_string())
+            }
+            _ => {}
+        }
+
+        WalkControl::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_dev::pretty_assertions::assert_eq;
+    use schema::shortcuts::{art, cc, ci, h1, p, t};
+
+    use super::*;
+
+    /// Search an index and return the types and texts of the results
+    fn search(index: &Index, query: &str, node_type: Option<NodeType>) -> Vec<(NodeType, String)> {
+        let terms = words(query).collect_vec();
+        index
+            .search(&terms, node_type, MAX_LIMIT)
+            .into_iter()
+            .map(|(.., entry)| (entry.node_type, entry.text.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn matching() {
+        let mut index = Index::default();
+        index.insert(
+            Path::new("a.md"),
+            None,
+            entries(&art([
+                h1([t("Growth of Plants")]),
+                p([t("Biomass was measured "), ci("pd.read_csv")]),
+                cc("import pandas as pd", None::<String>),
+            ])),
+        );
+        index.insert(
+            Path::new("b.md"),
+            None,
+            entries(&art([p([t("Plants grow.")])])),
+        );
+
+        // Terms are case insensitive prefixes of words
+        assert_eq!(
+            search(&index, "GROW", None),
+            vec![
+                (NodeType::Heading, "Growth of Plants".to_string()),
+                (NodeType::Paragraph, "Plants grow.".to_string())
+            ]
+        );
+
+        // All terms must match
+        assert_eq!(
+            search(&index, "plants growth", None),
+            vec![(NodeType::Heading, "Growth of Plants".to_string())]
+        );
+        assert_eq!(search(&index, "plants biomass", None), vec![]);
+
+        // Restricted to node type
+        assert_eq!(
+            search(&index, "pd", Some(NodeType::CodeChunk)),
+            vec![(NodeType::CodeChunk, "import pandas as pd".to_string())]
+        );
+        assert_eq!(
+            search(&index, "", Some(NodeType::CodeInline)),
+            vec![(NodeType::CodeInline, "pd.read_csv".to_string())]
+        );
+
+        // Substrings that are not the start of a word do not match
+        assert_eq!(search(&index, "rowth", None), vec![]);
+
+        // Limits are applied
+        let terms = words("plants").collect_vec();
+        assert_eq!(index.search(&terms, None, 1).len(), 1);
+    }
+
+    #[test]
+    fn indexing() {
+        let mut index = Index::default();
+
+        let root = art([p([t("Hello world")]), p([t("  ")])]);
+        let path = Path::new("a.md");
+        index.insert(path, Some("doc".to_string()), entries(&root));
+
+        // Empty nodes are not indexed and node paths are recorded
+        let doc = &index.docs[path];
+        assert_eq!(doc.entries.len(), 1);
+        assert_eq!(
+            common::serde_json::to_string(&doc.entries[0].node_path).unwrap(),
+            r#"["content",0]"#
+        );
+
+        // Re-indexing replaces previous entries, including their words
+        index.insert(path, None, entries(&art([p([t("Goodbye world")])])));
+        assert_eq!(search(&index, "hello", None), vec![]);
+        assert_eq!(search(&index, "world", None).len(), 1);
+        assert!(!index.words.contains_key("hello"));
+
+        // Removing removes all entries
+        index.remove(path);
+        assert_eq!(search(&index, "world", None), vec![]);
+        assert!(index.words.is_empty());
+        assert!(index.types.values().all(|postings| postings.is_empty()));
+    }
+
+    /// Count the results of a search of a [`SearchIndex`]
+    async fn count(index: &SearchIndex, query: &str) -> usize {
+        search(&*index.index.read().await, query, None).len()
+    }
+
+    /// Test that the index is updated when files are created, modified and removed
+    #[tokio::test]
+    async fn changes() -> common::eyre::Result<()> {
+        use std::fs::{create_dir, remove_dir_all, write};
+
+        let dir = common::tempfile::tempdir()?;
+        let dir = dir.path();
+        let index = SearchIndex::default();
+
+        create_dir(dir.join("sub"))?;
+        let a = dir.join("sub").join("a.md");
+        let b = dir.join("b.md");
+
+        write(&a, "Hello world")?;
+        index.changed(dir, &a).await;
+        assert_eq!(count(&index, "hello").await, 1);
+
+        write(&a, "Goodbye world")?;
+        index.changed(dir, &a).await;
+        assert_eq!(count(&index, "hello").await, 0);
+        assert_eq!(count(&index, "goodbye").await, 1);
+
+        // Private files are not indexed
+        let private = dir.join("_private.md");
+        write(&private, "Hello")?;
+        index.changed(dir, &private).await;
+        assert_eq!(count(&index, "hello").await, 0);
+
+        // Documents indexed from open documents are not re-indexed from their files
+        write(&b, "Hello again")?;
+        index.index.write().await.insert(
+            &b,
+            Some("doc".to_string()),
+            entries(&art([p([t("Open")])])),
+        );
+        index.changed(dir, &b).await;
+        assert_eq!(count(&index, "hello").await, 0);
+        assert_eq!(count(&index, "open").await, 1);
+
+        // Removing a directory removes the documents within it
+        remove_dir_all(dir.join("sub"))?;
+        index.changed(dir, &dir.join("sub")).await;
+        assert_eq!(count(&index, "world").await, 0);
+        assert_eq!(count(&index, "open").await, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn public() {
+        let dir = Path::new("/served");
+
+        assert!(is_public(dir, Path::new("/served/a.md")));
+        assert!(is_public(dir, Path::new("/served/sub/a.md")));
+
+        assert!(!is_public(dir, Path::new("/served/.hidden.md")));
+        assert!(!is_public(dir, Path::new("/served/.git/a.md")));
+        assert!(!is_public(dir, Path::new("/served/_private/a.md")));
+        assert!(!is_public(dir, Path::new("/served/sub/_a.md")));
+        assert!(!is_public(dir, Path::new("/other/a.md")));
+    }
+}
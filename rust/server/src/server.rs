@@ -1,7 +1,8 @@
 use std::{
     env,
+    fs::{create_dir_all, write},
     net::{IpAddr, Ipv4Addr, SocketAddr},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 
@@ -14,12 +15,14 @@ use axum::{
     routing::get,
     Router,
 };
+use axum_server::tls_rustls::RustlsConfig;
 use tower_cookies::{Cookie, CookieManagerLayer, Cookies};
 use tower_http::trace::TraceLayer;
 
+use app::{get_app_dir, DirType};
 use common::{
     clap::{self, Args},
-    eyre::{self},
+    eyre,
     rand::{self, Rng},
     serde::Deserialize,
     smart_default::SmartDefault,
@@ -31,7 +34,9 @@ pub(crate) use version::STENCILA_VERSION;
 
 use crate::{
     documents::{self, Documents},
-    login, statics,
+    export, login,
+    search::SearchIndex,
+    statics,
 };
 
 /// Server state available from all routes
@@ -55,6 +60,9 @@ pub(crate) struct ServerState {
 
     /// The cache of documents
     pub docs: Arc<Documents>,
+
+    /// The search index of documents in `dir`
+    pub search: Arc<SearchIndex>,
 }
 
 /// Run the HTTP/Websocket server
@@ -109,6 +117,51 @@ pub struct ServeOptions {
     #[arg(long)]
     pub sync: Option<SyncDirection>,
 
+    /// Do not index the documents in `dir` for searching
+    ///
+    /// By default, all Markdown, Jupyter Notebook and JATS files in `dir`
+    /// are indexed when the server starts, and re-indexed when they change.
+    #[arg(long)]
+    pub no_search: bool,
+
+    /// Serve over HTTPS
+    ///
+    /// If `--tls-cert` and `--tls-key` are not provided, a self-signed certificate
+    /// is generated and stored in the Stencila config directory (and reused
+    /// on subsequent runs).
+    #[arg(long)]
+    pub tls: bool,
+
+    /// The path to the TLS certificate (PEM) file
+    ///
+    /// Implies `--tls`.
+    #[arg(long, requires = "tls_key")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// The path to the TLS private key (PEM) file
+    ///
+    /// Implies `--tls`.
+    #[arg(long, requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
+
+    /// Listen on a Unix domain socket at this path
+    ///
+    /// Useful when serving behind a reverse proxy, or to a local editor, without
+    /// exposing a TCP port. The socket is only accessible by the current user.
+    /// Requests made on it still require the access token unless `--no-auth` is used.
+    #[arg(long, conflicts_with_all = ["address", "port", "tls", "tls_cert", "tls_key"])]
+    pub socket: Option<PathBuf>,
+
+    /// Export the served documents as a static site to this directory
+    ///
+    /// Rather than starting the server, each document in `dir` is requested
+    /// (using the static view) and written to an HTML file at the same URL
+    /// path, along with any images and other media, and the static assets
+    /// (e.g. themes) that they use. The exported site can be hosted on any
+    /// static file server.
+    #[arg(long, conflicts_with_all = ["socket", "tls", "tls_cert", "tls_key"])]
+    pub export: Option<PathBuf>,
+
     /// The access token to use
     ///
     /// This is not a CLI argument. It is only passed to the `serve()` function
@@ -127,32 +180,58 @@ pub async fn serve(
         raw,
         source,
         sync,
+        no_search,
+        tls,
+        tls_cert,
+        tls_key,
+        socket,
+        export,
         access_token,
     }: ServeOptions,
 ) -> eyre::Result<()> {
     let dir = dir.canonicalize()?;
 
     let address = SocketAddr::new(address, port);
-    let access_token = if no_auth {
+    let access_token = if export.is_some() {
+        // Requests are made in-process when exporting so no access token is needed
+        None
+    } else if no_auth {
         tracing::warn!("Using `--no-auth` flag; no routes are protected by authentication/authorization checks");
         None
     } else {
         Some(access_token.unwrap_or_else(get_access_token))
     };
 
-    let mut url = format!("http://{address}");
+    // Resolve the TLS certificate and key, if any
+    let tls = match (tls_cert, tls_key) {
+        (Some(cert), Some(key)) => Some((cert, key)),
+        _ if tls => Some(self_signed_cert(&address)?),
+        _ => None,
+    };
+
+    let scheme = if tls.is_some() { "https" } else { "http" };
+    let mut url = format!("{scheme}://{address}");
     if let Some(access_token) = &access_token {
         url.push_str("/~login?access_token=");
         url.push_str(access_token);
     }
 
+    let search = Arc::new(SearchIndex::default());
+    let docs = if !no_search && export.is_none() {
+        search.clone().start(dir.clone());
+        Documents::with_search(search.clone())
+    } else {
+        Documents::default()
+    };
+
     let state = ServerState {
         dir,
         access_token,
         raw,
         source,
         sync,
-        ..Default::default()
+        docs: Arc::new(docs),
+        search,
     };
 
     let router = Router::new()
@@ -172,7 +251,31 @@ pub async fn serve(
         )
         .layer(TraceLayer::new_for_http())
         .layer(CookieManagerLayer::new())
-        .with_state(state);
+        .with_state(state.clone());
+
+    if let Some(export) = export {
+        return export::export(router, &state.dir, &export).await;
+    }
+
+    if let Some(socket) = socket {
+        match &state.access_token {
+            Some(access_token) => tracing::info!(
+                "Starting server on Unix socket {} with access token {access_token}",
+                socket.display()
+            ),
+            None => tracing::info!("Starting server on Unix socket {}", socket.display()),
+        }
+        return serve_unix(&socket, router).await;
+    }
+
+    if let Some((cert, key)) = tls {
+        let config = RustlsConfig::from_pem_file(cert, key).await?;
+        tracing::info!("Starting server at {url}");
+        axum_server::bind_rustls(address, config)
+            .serve(router.into_make_service())
+            .await?;
+        return Ok(());
+    }
 
     let listener = TcpListener::bind(&address).await?;
     tracing::info!("Starting server at {url}");
@@ -182,6 +285,120 @@ pub async fn serve(
     Ok(())
 }
 
+/// Get, or generate, a self-signed TLS certificate and private key
+///
+/// The certificate and key are stored in the `tls` subdirectory of the Stencila
+/// config directory so that browsers only need to trust the certificate once.
+/// Returns the paths of the certificate and key files.
+fn self_signed_cert(address: &SocketAddr) -> eyre::Result<(PathBuf, PathBuf)> {
+    let dir = get_app_dir(DirType::Config, true)?.join("tls");
+    let cert_path = dir.join("cert.pem");
+    let key_path = dir.join("key.pem");
+
+    if cert_path.exists() && key_path.exists() {
+        return Ok((cert_path, key_path));
+    }
+
+    tracing::info!("Generating self-signed TLS certificate");
+
+    let mut names = vec!["localhost".to_string(), "127.0.0.1".to_string()];
+    let ip = address.ip().to_string();
+    if !names.contains(&ip) {
+        names.push(ip);
+    }
+
+    let rcgen::CertifiedKey { cert, key_pair } = rcgen::generate_simple_self_signed(names)?;
+
+    create_dir_all(&dir)?;
+    write(&cert_path, cert.pem())?;
+    write(&key_path, key_pair.serialize_pem())?;
+
+    #[cfg(unix)]
+    {
+        use std::{fs::set_permissions, os::unix::fs::PermissionsExt};
+        set_permissions(&key_path, PermissionsExt::from_mode(0o600))?;
+    }
+
+    Ok((cert_path, key_path))
+}
+
+/// Serve on a Unix domain socket
+///
+/// Axum's `serve` function only supports TCP listeners so this accepts connections
+/// on the socket and serves each using `hyper` (with support for upgrades so that
+/// WebSockets work).
+#[cfg(unix)]
+async fn serve_unix(path: &Path, router: Router) -> eyre::Result<()> {
+    use common::tokio;
+    use hyper_util::{
+        rt::{TokioExecutor, TokioIo},
+        server::conn::auto,
+        service::TowerToHyperService,
+    };
+
+    let listener = bind_unix(path)?;
+
+    loop {
+        let (stream, ..) = listener.accept().await?;
+        let service = TowerToHyperService::new(router.clone());
+        tokio::spawn(async move {
+            if let Err(error) = auto::Builder::new(TokioExecutor::new())
+                .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                .await
+            {
+                tracing::debug!("While serving connection on Unix socket: {error}");
+            }
+        });
+    }
+}
+
+/// Bind a listener to a Unix domain socket that is only accessible by the current user
+///
+/// Any stale socket from a previous run is removed, but any other type of file at the
+/// path is an error. The socket is bound within a private (`0700`) directory, and its
+/// permissions set to `0600`, before it is moved to the path so that no other user
+/// can connect to it in the meantime.
+#[cfg(unix)]
+fn bind_unix(path: &Path) -> eyre::Result<common::tokio::net::UnixListener> {
+    use std::{
+        fs::{remove_file, rename, set_permissions, symlink_metadata},
+        os::unix::fs::{FileTypeExt, PermissionsExt},
+    };
+
+    use common::{tempfile, tokio::net::UnixListener};
+
+    if let Ok(metadata) = symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            eyre::bail!("Path `{}` exists and is not a socket", path.display())
+        }
+        remove_file(path)?;
+    }
+
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let private = tempfile::Builder::new()
+        .prefix(".stencila-")
+        .permissions(PermissionsExt::from_mode(0o700))
+        .tempdir_in(parent)?;
+
+    let temp = private.path().join("socket");
+    let listener = UnixListener::bind(&temp)?;
+    set_permissions(&temp, PermissionsExt::from_mode(0o600))?;
+    rename(&temp, path)?;
+
+    Ok(listener)
+}
+
+/// Serve on a Unix domain socket
+///
+/// Unix domain sockets are not supported on this platform.
+#[cfg(not(unix))]
+async fn serve_unix(_path: &Path, _router: Router) -> eyre::Result<()> {
+    eyre::bail!("Serving on a Unix domain socket is not supported on this platform")
+}
+
 /// Get or generate an access token
 ///
 /// If the `STENCILA_ACCESS_TOKEN` environment variable is present
@@ -265,3 +482,48 @@ async fn auth_middleware(
 
     Err((StatusCode::UNAUTHORIZED, "Unauthorized").into_response())
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::{
+        fs::{metadata, read_dir, read_to_string, write},
+        os::unix::fs::{FileTypeExt, PermissionsExt},
+    };
+
+    use common::{tempfile::tempdir, tokio};
+
+    use super::*;
+
+    /// Test that a stale socket is replaced with one only accessible by the
+    /// current user, and that no private directory is left behind
+    #[tokio::test]
+    async fn bind_unix_stale() -> eyre::Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("stencila.sock");
+
+        drop(bind_unix(&path)?);
+        assert!(metadata(&path)?.file_type().is_socket());
+
+        let _listener = bind_unix(&path)?;
+        let metadata = metadata(&path)?;
+        assert!(metadata.file_type().is_socket());
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+
+        assert_eq!(read_dir(dir.path())?.count(), 1);
+
+        Ok(())
+    }
+
+    /// Test that an existing file which is not a socket is not removed
+    #[tokio::test]
+    async fn bind_unix_not_socket() -> eyre::Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("stencila.sock");
+        write(&path, "not a socket")?;
+
+        assert!(bind_unix(&path).is_err());
+        assert_eq!(read_to_string(&path)?, "not a socket");
+
+        Ok(())
+    }
+}
@@ -47,7 +47,7 @@ export class Admonition extends Entity {
   constructor(admonitionType: AdmonitionType, content: Block[], options?: Partial<Admonition>) {
     super();
     this.type = "Admonition";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.admonitionType = admonitionType;
     this.content = content;
   }
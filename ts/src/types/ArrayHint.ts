@@ -39,7 +39,7 @@ export class ArrayHint extends Entity {
   constructor(length: Integer, options?: Partial<ArrayHint>) {
     super();
     this.type = "ArrayHint";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.length = length;
   }
 }
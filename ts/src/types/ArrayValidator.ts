@@ -44,7 +44,7 @@ export class ArrayValidator extends Entity {
   constructor(options?: Partial<ArrayValidator>) {
     super();
     this.type = "ArrayValidator";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
@@ -52,7 +52,7 @@ export class Article extends CreativeWork {
   constructor(content: Block[], options?: Partial<Article>) {
     super();
     this.type = "Article";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
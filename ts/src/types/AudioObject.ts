@@ -23,7 +23,7 @@ export class AudioObject extends MediaObject {
   constructor(contentUrl: string, options?: Partial<AudioObject>) {
     super(contentUrl);
     this.type = "AudioObject";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.contentUrl = contentUrl;
   }
 }
@@ -35,7 +35,7 @@ export class AuthorRole extends Role {
   constructor(author: AuthorRoleAuthor, roleName: AuthorRoleName, options?: Partial<AuthorRole>) {
     super();
     this.type = "AuthorRole";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.author = author;
     this.roleName = roleName;
   }
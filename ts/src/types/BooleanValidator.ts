@@ -12,7 +12,7 @@ export class BooleanValidator extends Entity {
   constructor(options?: Partial<BooleanValidator>) {
     super();
     this.type = "BooleanValidator";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
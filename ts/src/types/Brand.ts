@@ -28,7 +28,7 @@ export class Brand extends Thing {
   constructor(name: string, options?: Partial<Brand>) {
     super();
     this.type = "Brand";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.name = name;
   }
 }
@@ -28,7 +28,7 @@ export class Button extends CodeExecutable {
   constructor(code: Cord, name: string, options?: Partial<Button>) {
     super(code);
     this.type = "Button";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.code = code;
     this.name = name;
   }
@@ -29,7 +29,7 @@ export class CallArgument extends Parameter {
   constructor(name: string, code: Cord, options?: Partial<CallArgument>) {
     super(name);
     this.type = "CallArgument";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.name = name;
     this.code = code;
   }
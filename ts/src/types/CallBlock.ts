@@ -18,7 +18,7 @@ export class CallBlock extends IncludeBlock {
   constructor(source: string, args: CallArgument[], options?: Partial<CallBlock>) {
     super(source);
     this.type = "CallBlock";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.source = source;
     this.arguments = args;
   }
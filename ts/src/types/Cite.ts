@@ -61,7 +61,7 @@ export class Cite extends Entity {
   constructor(target: string, citationMode: CitationMode, options?: Partial<Cite>) {
     super();
     this.type = "Cite";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.target = target;
     this.citationMode = citationMode;
   }
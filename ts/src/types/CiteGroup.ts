@@ -18,7 +18,7 @@ export class CiteGroup extends Entity {
   constructor(items: Cite[], options?: Partial<CiteGroup>) {
     super();
     this.type = "CiteGroup";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.items = items;
   }
 }
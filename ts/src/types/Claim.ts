@@ -29,7 +29,7 @@ export class Claim extends CreativeWork {
   constructor(claimType: ClaimType, content: Block[], options?: Partial<Claim>) {
     super();
     this.type = "Claim";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.claimType = claimType;
     this.content = content;
   }
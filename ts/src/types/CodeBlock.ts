@@ -13,7 +13,7 @@ export class CodeBlock extends CodeStatic {
   constructor(code: Cord, options?: Partial<CodeBlock>) {
     super(code);
     this.type = "CodeBlock";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.code = code;
   }
 }
@@ -5,6 +5,7 @@ import { CodeExecutable } from "./CodeExecutable.js";
 import { Cord } from "./Cord.js";
 import { LabelType } from "./LabelType.js";
 import { Node } from "./Node.js";
+import { Object } from "./Object.js";
 
 /**
  * A executable chunk of code.
@@ -48,10 +49,15 @@ export class CodeChunk extends CodeExecutable {
    */
   executionPure?: boolean;
 
+  /**
+   * Metadata associated with the chunk that is not otherwise represented.
+   */
+  metadata?: Object;
+
   constructor(code: Cord, options?: Partial<CodeChunk>) {
     super(code);
     this.type = "CodeChunk";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.code = code;
   }
 }
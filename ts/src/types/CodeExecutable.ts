@@ -35,7 +35,7 @@ export class CodeExecutable extends Executable {
   constructor(code: Cord, options?: Partial<CodeExecutable>) {
     super();
     this.type = "CodeExecutable";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.code = code;
   }
 }
@@ -30,7 +30,7 @@ export class CodeExpression extends CodeExecutable {
   constructor(code: Cord, options?: Partial<CodeExpression>) {
     super(code);
     this.type = "CodeExpression";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.code = code;
   }
 }
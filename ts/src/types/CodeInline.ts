@@ -13,7 +13,7 @@ export class CodeInline extends CodeStatic {
   constructor(code: Cord, options?: Partial<CodeInline>) {
     super(code);
     this.type = "CodeInline";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.code = code;
   }
 }
@@ -38,7 +38,7 @@ export class CodeLocation extends Entity {
   constructor(options?: Partial<CodeLocation>) {
     super();
     this.type = "CodeLocation";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
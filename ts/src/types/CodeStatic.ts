@@ -35,7 +35,7 @@ export class CodeStatic extends Entity {
   constructor(code: Cord, options?: Partial<CodeStatic>) {
     super();
     this.type = "CodeStatic";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.code = code;
   }
 }
@@ -18,7 +18,7 @@ export class Collection extends CreativeWork {
   constructor(parts: CreativeWorkType[], options?: Partial<Collection>) {
     super();
     this.type = "Collection";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.parts = parts;
   }
 }
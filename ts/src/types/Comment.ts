@@ -28,7 +28,7 @@ export class Comment extends CreativeWork {
   constructor(content: Block[], options?: Partial<Comment>) {
     super();
     this.type = "Comment";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
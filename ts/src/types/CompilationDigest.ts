@@ -38,7 +38,7 @@ export class CompilationDigest extends Entity {
   constructor(stateDigest: UnsignedInteger, options?: Partial<CompilationDigest>) {
     super();
     this.type = "CompilationDigest";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.stateDigest = stateDigest;
   }
 }
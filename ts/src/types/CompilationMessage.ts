@@ -34,7 +34,7 @@ export class CompilationMessage extends Entity {
   constructor(level: MessageLevel, message: string, options?: Partial<CompilationMessage>) {
     super();
     this.type = "CompilationMessage";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.level = level;
     this.message = message;
   }
@@ -1,6 +1,7 @@
 // Generated file; do not edit. See https://github.com/stencila/stencila/tree/main/rust/schema-gen
 
 import { Entity } from "./Entity.js";
+import { UnsignedInteger } from "./UnsignedInteger.js";
 
 /**
  * Stencila document configuration options.
@@ -14,10 +15,35 @@ export class Config extends Entity {
    */
   theme?: string;
 
+  /**
+   * The names of the secrets required by the document
+   */
+  secrets?: string[];
+
+  /**
+   * The maximum memory, in megabytes, that each kernel for the document may use
+   */
+  memoryLimit?: UnsignedInteger;
+
+  /**
+   * The maximum CPU time, in seconds, that each kernel for the document may use
+   */
+  cpuLimit?: UnsignedInteger;
+
+  /**
+   * The maximum time, in seconds, for each execution of code in the document
+   */
+  timeout?: UnsignedInteger;
+
+  /**
+   * Whether kernels for the document should be run in restricted mode
+   */
+  restricted?: boolean;
+
   constructor(options?: Partial<Config>) {
     super();
     this.type = "Config";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
@@ -18,7 +18,7 @@ export class ConstantValidator extends Entity {
   constructor(value: Node, options?: Partial<ConstantValidator>) {
     super();
     this.type = "ConstantValidator";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.value = value;
   }
 }
@@ -27,7 +27,7 @@ export class ContactPoint extends Thing {
   constructor(options?: Partial<ContactPoint>) {
     super();
     this.type = "ContactPoint";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
@@ -151,7 +151,7 @@ export class CreativeWork extends Thing {
   constructor(options?: Partial<CreativeWork>) {
     super();
     this.type = "CreativeWork";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
@@ -18,7 +18,7 @@ export class Datatable extends CreativeWork {
   constructor(columns: DatatableColumn[], options?: Partial<Datatable>) {
     super();
     this.type = "Datatable";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.columns = columns;
   }
 }
@@ -29,7 +29,7 @@ export class DatatableColumn extends Entity {
   constructor(name: string, values: Primitive[], options?: Partial<DatatableColumn>) {
     super();
     this.type = "DatatableColumn";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.name = name;
     this.values = values;
   }
@@ -39,7 +39,7 @@ export class DatatableColumnHint extends Entity {
   constructor(name: string, itemType: string, options?: Partial<DatatableColumnHint>) {
     super();
     this.type = "DatatableColumnHint";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.name = name;
     this.itemType = itemType;
   }
@@ -24,7 +24,7 @@ export class DatatableHint extends Entity {
   constructor(rows: Integer, columns: DatatableColumnHint[], options?: Partial<DatatableHint>) {
     super();
     this.type = "DatatableHint";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.rows = rows;
     this.columns = columns;
   }
@@ -17,7 +17,7 @@ export class Date extends Entity {
   constructor(value: string, options?: Partial<Date>) {
     super();
     this.type = "Date";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.value = value;
   }
 }
@@ -17,7 +17,7 @@ export class DateTime extends Entity {
   constructor(value: string, options?: Partial<DateTime>) {
     super();
     this.type = "DateTime";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.value = value;
   }
 }
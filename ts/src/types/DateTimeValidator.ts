@@ -23,7 +23,7 @@ export class DateTimeValidator extends Entity {
   constructor(options?: Partial<DateTimeValidator>) {
     super();
     this.type = "DateTimeValidator";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
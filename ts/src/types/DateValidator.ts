@@ -23,7 +23,7 @@ export class DateValidator extends Entity {
   constructor(options?: Partial<DateValidator>) {
     super();
     this.type = "DateValidator";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
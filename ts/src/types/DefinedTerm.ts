@@ -22,7 +22,7 @@ export class DefinedTerm extends Thing {
   constructor(name: string, options?: Partial<DefinedTerm>) {
     super();
     this.type = "DefinedTerm";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.name = name;
   }
 }
@@ -13,7 +13,7 @@ export class DeleteBlock extends SuggestionBlock {
   constructor(content: Block[], options?: Partial<DeleteBlock>) {
     super(content);
     this.type = "DeleteBlock";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
@@ -13,7 +13,7 @@ export class DeleteInline extends SuggestionInline {
   constructor(content: Inline[], options?: Partial<DeleteInline>) {
     super(content);
     this.type = "DeleteInline";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
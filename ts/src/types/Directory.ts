@@ -28,7 +28,7 @@ export class Directory extends Entity {
   constructor(name: string, path: string, parts: FileOrDirectory[], options?: Partial<Directory>) {
     super();
     this.type = "Directory";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.name = name;
     this.path = path;
     this.parts = parts;
@@ -24,7 +24,7 @@ export class Duration extends Entity {
   constructor(value: Integer, timeUnit: TimeUnit, options?: Partial<Duration>) {
     super();
     this.type = "Duration";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.value = value;
     this.timeUnit = timeUnit;
   }
@@ -29,7 +29,7 @@ export class DurationValidator extends Entity {
   constructor(options?: Partial<DurationValidator>) {
     super();
     this.type = "DurationValidator";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
@@ -13,7 +13,7 @@ export class Emphasis extends Mark {
   constructor(content: Inline[], options?: Partial<Emphasis>) {
     super(content);
     this.type = "Emphasis";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
@@ -13,7 +13,7 @@ export class Entity {
   id?: string;
 
   constructor(options?: Partial<Entity>) {
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
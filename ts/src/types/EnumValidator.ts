@@ -18,7 +18,7 @@ export class EnumValidator extends Entity {
   constructor(values: Node[], options?: Partial<EnumValidator>) {
     super();
     this.type = "EnumValidator";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.values = values;
   }
 }
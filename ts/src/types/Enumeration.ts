@@ -12,7 +12,7 @@ export class Enumeration extends Thing {
   constructor(options?: Partial<Enumeration>) {
     super();
     this.type = "Enumeration";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
@@ -100,7 +100,7 @@ export class Executable extends Entity {
   constructor(options?: Partial<Executable>) {
     super();
     this.type = "Executable";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
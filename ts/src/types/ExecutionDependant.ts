@@ -30,7 +30,7 @@ export class ExecutionDependant extends Entity {
   constructor(dependantRelation: ExecutionDependantRelation, dependantNode: ExecutionDependantNode, options?: Partial<ExecutionDependant>) {
     super();
     this.type = "ExecutionDependant";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.dependantRelation = dependantRelation;
     this.dependantNode = dependantNode;
   }
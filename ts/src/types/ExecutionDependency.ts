@@ -30,7 +30,7 @@ export class ExecutionDependency extends Entity {
   constructor(dependencyRelation: ExecutionDependencyRelation, dependencyNode: ExecutionDependencyNode, options?: Partial<ExecutionDependency>) {
     super();
     this.type = "ExecutionDependency";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.dependencyRelation = dependencyRelation;
     this.dependencyNode = dependencyNode;
   }
@@ -39,7 +39,7 @@ export class ExecutionMessage extends Entity {
   constructor(level: MessageLevel, message: string, options?: Partial<ExecutionMessage>) {
     super();
     this.type = "ExecutionMessage";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.level = level;
     this.message = message;
   }
@@ -27,7 +27,7 @@ export class ExecutionTag extends Entity {
   constructor(name: string, value: string, isGlobal: boolean, options?: Partial<ExecutionTag>) {
     super();
     this.type = "ExecutionTag";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.name = name;
     this.value = value;
     this.isGlobal = isGlobal;
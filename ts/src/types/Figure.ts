@@ -33,7 +33,7 @@ export class Figure extends CreativeWork {
   constructor(content: Block[], options?: Partial<Figure>) {
     super();
     this.type = "Figure";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
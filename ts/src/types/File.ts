@@ -27,7 +27,7 @@ export class File extends Entity {
   constructor(name: string, path: string, options?: Partial<File>) {
     super();
     this.type = "File";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.name = name;
     this.path = path;
   }
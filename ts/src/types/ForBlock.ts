@@ -34,7 +34,7 @@ export class ForBlock extends CodeExecutable {
   constructor(code: Cord, variable: string, content: Block[], options?: Partial<ForBlock>) {
     super(code);
     this.type = "ForBlock";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.code = code;
     this.variable = variable;
     this.content = content;
@@ -35,7 +35,7 @@ export class Form extends Executable {
   constructor(content: Block[], options?: Partial<Form>) {
     super();
     this.type = "Form";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
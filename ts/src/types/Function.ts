@@ -29,7 +29,7 @@ export class Function extends Entity {
   constructor(name: string, parameters: Parameter[], options?: Partial<Function>) {
     super();
     this.type = "Function";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.name = name;
     this.parameters = parameters;
   }
@@ -23,7 +23,7 @@ export class Grant extends Thing {
   constructor(options?: Partial<Grant>) {
     super();
     this.type = "Grant";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
@@ -36,7 +36,7 @@ export class Heading extends Entity {
   constructor(level: Integer, content: Inline[], options?: Partial<Heading>) {
     super();
     this.type = "Heading";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.level = level;
     this.content = content;
   }
@@ -18,7 +18,7 @@ export class IfBlock extends Executable {
   constructor(clauses: IfBlockClause[], options?: Partial<IfBlock>) {
     super();
     this.type = "IfBlock";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.clauses = clauses;
   }
 }
@@ -24,7 +24,7 @@ export class IfBlockClause extends CodeExecutable {
   constructor(code: Cord, content: Block[], options?: Partial<IfBlockClause>) {
     super(code);
     this.type = "IfBlockClause";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.code = code;
     this.content = content;
   }
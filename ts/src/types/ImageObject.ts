@@ -23,7 +23,7 @@ export class ImageObject extends MediaObject {
   constructor(contentUrl: string, options?: Partial<ImageObject>) {
     super(contentUrl);
     this.type = "ImageObject";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.contentUrl = contentUrl;
   }
 }
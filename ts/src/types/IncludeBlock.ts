@@ -33,7 +33,7 @@ export class IncludeBlock extends Executable {
   constructor(source: string, options?: Partial<IncludeBlock>) {
     super();
     this.type = "IncludeBlock";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.source = source;
   }
 }
@@ -13,7 +13,7 @@ export class InsertBlock extends SuggestionBlock {
   constructor(content: Block[], options?: Partial<InsertBlock>) {
     super(content);
     this.type = "InsertBlock";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
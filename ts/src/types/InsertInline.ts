@@ -13,7 +13,7 @@ export class InsertInline extends SuggestionInline {
   constructor(content: Inline[], options?: Partial<InsertInline>) {
     super(content);
     this.type = "InsertInline";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
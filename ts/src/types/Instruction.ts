@@ -57,7 +57,7 @@ export class Instruction extends Executable {
   constructor(instructionType: InstructionType, options?: Partial<Instruction>) {
     super();
     this.type = "Instruction";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.instructionType = instructionType;
   }
 }
@@ -25,7 +25,7 @@ export class InstructionBlock extends Instruction {
   constructor(instructionType: InstructionType, options?: Partial<InstructionBlock>) {
     super(instructionType);
     this.type = "InstructionBlock";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.instructionType = instructionType;
   }
 }
@@ -25,7 +25,7 @@ export class InstructionInline extends Instruction {
   constructor(instructionType: InstructionType, options?: Partial<InstructionInline>) {
     super(instructionType);
     this.type = "InstructionInline";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.instructionType = instructionType;
   }
 }
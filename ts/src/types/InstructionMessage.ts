@@ -36,7 +36,7 @@ export class InstructionMessage extends Entity {
   constructor(parts: MessagePart[], options?: Partial<InstructionMessage>) {
     super();
     this.type = "InstructionMessage";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.parts = parts;
   }
 }
@@ -49,7 +49,7 @@ export class InstructionModel extends Entity {
   constructor(options?: Partial<InstructionModel>) {
     super();
     this.type = "InstructionModel";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
@@ -12,7 +12,7 @@ export class IntegerValidator extends NumberValidator {
   constructor(options?: Partial<IntegerValidator>) {
     super();
     this.type = "IntegerValidator";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
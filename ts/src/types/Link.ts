@@ -33,7 +33,7 @@ export class Link extends Entity {
   constructor(content: Inline[], target: string, options?: Partial<Link>) {
     super();
     this.type = "Link";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
     this.target = target;
   }
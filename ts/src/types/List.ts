@@ -36,7 +36,7 @@ export class List extends Entity {
   constructor(items: ListItem[], order: ListOrder, options?: Partial<List>) {
     super();
     this.type = "List";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.items = items;
     this.order = order;
   }
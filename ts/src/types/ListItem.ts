@@ -35,7 +35,7 @@ export class ListItem extends Thing {
   constructor(content: Block[], options?: Partial<ListItem>) {
     super();
     this.type = "ListItem";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
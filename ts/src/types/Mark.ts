@@ -18,7 +18,7 @@ export class Mark extends Entity {
   constructor(content: Inline[], options?: Partial<Mark>) {
     super();
     this.type = "Mark";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
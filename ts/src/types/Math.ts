@@ -52,7 +52,7 @@ export class Math extends Entity {
   constructor(code: Cord, options?: Partial<Math>) {
     super();
     this.type = "Math";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.code = code;
   }
 }
@@ -23,7 +23,7 @@ export class MathBlock extends Math {
   constructor(code: Cord, options?: Partial<MathBlock>) {
     super(code);
     this.type = "MathBlock";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.code = code;
   }
 }
@@ -13,7 +13,7 @@ export class MathInline extends Math {
   constructor(code: Cord, options?: Partial<MathInline>) {
     super(code);
     this.type = "MathInline";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.code = code;
   }
 }
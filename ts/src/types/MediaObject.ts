@@ -37,7 +37,7 @@ export class MediaObject extends CreativeWork {
   constructor(contentUrl: string, options?: Partial<MediaObject>) {
     super();
     this.type = "MediaObject";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.contentUrl = contentUrl;
   }
 }
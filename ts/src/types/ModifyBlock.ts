@@ -19,7 +19,7 @@ export class ModifyBlock extends SuggestionBlock {
   constructor(content: Block[], operations: ModifyOperation[], options?: Partial<ModifyBlock>) {
     super(content);
     this.type = "ModifyBlock";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
     this.operations = operations;
   }
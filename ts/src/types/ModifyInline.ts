@@ -19,7 +19,7 @@ export class ModifyInline extends SuggestionInline {
   constructor(content: Inline[], operations: ModifyOperation[], options?: Partial<ModifyInline>) {
     super(content);
     this.type = "ModifyInline";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
     this.operations = operations;
   }
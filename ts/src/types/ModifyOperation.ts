@@ -23,7 +23,7 @@ export class ModifyOperation extends Entity {
   constructor(target: string, value: StringPatchOrPrimitive, options?: Partial<ModifyOperation>) {
     super();
     this.type = "ModifyOperation";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.target = target;
     this.value = value;
   }
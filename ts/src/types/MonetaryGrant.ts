@@ -23,7 +23,7 @@ export class MonetaryGrant extends Grant {
   constructor(options?: Partial<MonetaryGrant>) {
     super();
     this.type = "MonetaryGrant";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
@@ -24,7 +24,7 @@ export class Note extends Entity {
   constructor(noteType: NoteType, content: Block[], options?: Partial<Note>) {
     super();
     this.type = "Note";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.noteType = noteType;
     this.content = content;
   }
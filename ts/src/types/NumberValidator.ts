@@ -37,7 +37,7 @@ export class NumberValidator extends Entity {
   constructor(options?: Partial<NumberValidator>) {
     super();
     this.type = "NumberValidator";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
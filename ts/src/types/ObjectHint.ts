@@ -29,7 +29,7 @@ export class ObjectHint extends Entity {
   constructor(length: Integer, keys: string[], values: Hint[], options?: Partial<ObjectHint>) {
     super();
     this.type = "ObjectHint";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.length = length;
     this.keys = keys;
     this.values = values;
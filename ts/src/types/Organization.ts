@@ -62,7 +62,7 @@ export class Organization extends Thing {
   constructor(options?: Partial<Organization>) {
     super();
     this.type = "Organization";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
@@ -30,7 +30,7 @@ export class Paragraph extends Entity {
   constructor(content: Inline[], options?: Partial<Paragraph>) {
     super();
     this.type = "Paragraph";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
@@ -44,7 +44,7 @@ export class Parameter extends Executable {
   constructor(name: string, options?: Partial<Parameter>) {
     super();
     this.type = "Parameter";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.name = name;
   }
 }
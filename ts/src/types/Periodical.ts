@@ -28,7 +28,7 @@ export class Periodical extends CreativeWork {
   constructor(options?: Partial<Periodical>) {
     super();
     this.type = "Periodical";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
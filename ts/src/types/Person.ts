@@ -70,7 +70,7 @@ export class Person extends Thing {
   constructor(options?: Partial<Person>) {
     super();
     this.type = "Person";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
@@ -42,7 +42,7 @@ export class PostalAddress extends ContactPoint {
   constructor(options?: Partial<PostalAddress>) {
     super();
     this.type = "PostalAddress";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
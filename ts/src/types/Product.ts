@@ -29,7 +29,7 @@ export class Product extends Thing {
   constructor(options?: Partial<Product>) {
     super();
     this.type = "Product";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
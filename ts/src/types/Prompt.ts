@@ -50,7 +50,7 @@ export class Prompt extends CreativeWork {
   constructor(description: string, name: string, version: StringOrNumber, instructionTypes: InstructionType[], nodeTypes: string[], content: Block[], options?: Partial<Prompt>) {
     super();
     this.type = "Prompt";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.description = description;
     this.name = name;
     this.version = version;
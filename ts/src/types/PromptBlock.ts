@@ -23,7 +23,7 @@ export class PromptBlock extends Executable {
   constructor(prompt: string, options?: Partial<PromptBlock>) {
     super();
     this.type = "PromptBlock";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.prompt = prompt;
   }
 }
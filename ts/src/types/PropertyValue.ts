@@ -23,7 +23,7 @@ export class PropertyValue extends Thing {
   constructor(value: Primitive, options?: Partial<PropertyValue>) {
     super();
     this.type = "PropertyValue";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.value = value;
   }
 }
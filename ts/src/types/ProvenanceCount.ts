@@ -29,7 +29,7 @@ export class ProvenanceCount extends Entity {
   constructor(provenanceCategory: ProvenanceCategory, characterCount: UnsignedInteger, options?: Partial<ProvenanceCount>) {
     super();
     this.type = "ProvenanceCount";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.provenanceCategory = provenanceCategory;
     this.characterCount = characterCount;
   }
@@ -33,7 +33,7 @@ export class PublicationIssue extends CreativeWork {
   constructor(options?: Partial<PublicationIssue>) {
     super();
     this.type = "PublicationIssue";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
@@ -33,7 +33,7 @@ export class PublicationVolume extends CreativeWork {
   constructor(options?: Partial<PublicationVolume>) {
     super();
     this.type = "PublicationVolume";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
@@ -36,7 +36,7 @@ export class QuoteBlock extends Entity {
   constructor(content: Block[], options?: Partial<QuoteBlock>) {
     super();
     this.type = "QuoteBlock";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
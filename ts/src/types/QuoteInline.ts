@@ -19,7 +19,7 @@ export class QuoteInline extends Mark {
   constructor(content: Inline[], options?: Partial<QuoteInline>) {
     super(content);
     this.type = "QuoteInline";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
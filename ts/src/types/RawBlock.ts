@@ -5,6 +5,7 @@ import { CompilationDigest } from "./CompilationDigest.js";
 import { CompilationMessage } from "./CompilationMessage.js";
 import { Cord } from "./Cord.js";
 import { Entity } from "./Entity.js";
+import { Object } from "./Object.js";
 import { ProvenanceCount } from "./ProvenanceCount.js";
 
 /**
@@ -49,10 +50,15 @@ export class RawBlock extends Entity {
    */
   provenance?: ProvenanceCount[];
 
+  /**
+   * Metadata associated with the block that is not otherwise represented.
+   */
+  metadata?: Object;
+
   constructor(format: string, content: Cord, options?: Partial<RawBlock>) {
     super();
     this.type = "RawBlock";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.format = format;
     this.content = content;
   }
@@ -18,7 +18,7 @@ export class ReplaceBlock extends SuggestionBlock {
   constructor(content: Block[], replacement: Block[], options?: Partial<ReplaceBlock>) {
     super(content);
     this.type = "ReplaceBlock";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
     this.replacement = replacement;
   }
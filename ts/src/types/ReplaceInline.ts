@@ -18,7 +18,7 @@ export class ReplaceInline extends SuggestionInline {
   constructor(content: Inline[], replacement: Inline[], options?: Partial<ReplaceInline>) {
     super(content);
     this.type = "ReplaceInline";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
     this.replacement = replacement;
   }
@@ -1,6 +1,8 @@
 // Generated file; do not edit. See https://github.com/stencila/stencila/tree/main/rust/schema-gen
 
+import { Block } from "./Block.js";
 import { CreativeWork } from "./CreativeWork.js";
+import { PropertyValue } from "./PropertyValue.js";
 import { Thing } from "./Thing.js";
 
 /**
@@ -20,10 +22,20 @@ export class Review extends CreativeWork {
    */
   reviewAspect?: string;
 
+  /**
+   * The content of the review, e.g. a summary and major and minor issues.
+   */
+  content?: Block[];
+
+  /**
+   * Ratings of the item on various criteria.
+   */
+  ratings?: PropertyValue[];
+
   constructor(options?: Partial<Review>) {
     super();
     this.type = "Review";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
@@ -12,7 +12,7 @@ export class Role extends Entity {
   constructor(options?: Partial<Role>) {
     super();
     this.type = "Role";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
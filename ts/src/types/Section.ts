@@ -36,7 +36,7 @@ export class Section extends Entity {
   constructor(content: Block[], options?: Partial<Section>) {
     super();
     this.type = "Section";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
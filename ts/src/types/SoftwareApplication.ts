@@ -32,7 +32,7 @@ export class SoftwareApplication extends CreativeWork {
   constructor(name: string, options?: Partial<SoftwareApplication>) {
     super();
     this.type = "SoftwareApplication";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.name = name;
   }
 }
@@ -49,7 +49,7 @@ export class SoftwareSourceCode extends CreativeWork {
   constructor(name: string, programmingLanguage: string, options?: Partial<SoftwareSourceCode>) {
     super();
     this.type = "SoftwareSourceCode";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.name = name;
     this.programmingLanguage = programmingLanguage;
   }
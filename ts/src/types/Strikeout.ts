@@ -13,7 +13,7 @@ export class Strikeout extends Mark {
   constructor(content: Inline[], options?: Partial<Strikeout>) {
     super(content);
     this.type = "Strikeout";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
@@ -18,7 +18,7 @@ export class StringHint extends Entity {
   constructor(chars: Integer, options?: Partial<StringHint>) {
     super();
     this.type = "StringHint";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.chars = chars;
   }
 }
@@ -28,7 +28,7 @@ export class StringOperation extends Entity {
   constructor(startPosition: UnsignedInteger, options?: Partial<StringOperation>) {
     super();
     this.type = "StringOperation";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.startPosition = startPosition;
   }
 }
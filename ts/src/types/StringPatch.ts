@@ -18,7 +18,7 @@ export class StringPatch extends Entity {
   constructor(operations: StringOperation[], options?: Partial<StringPatch>) {
     super();
     this.type = "StringPatch";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.operations = operations;
   }
 }
@@ -28,7 +28,7 @@ export class StringValidator extends Entity {
   constructor(options?: Partial<StringValidator>) {
     super();
     this.type = "StringValidator";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
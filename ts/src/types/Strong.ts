@@ -13,7 +13,7 @@ export class Strong extends Mark {
   constructor(content: Inline[], options?: Partial<Strong>) {
     super(content);
     this.type = "Strong";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
@@ -57,7 +57,7 @@ export class Styled extends Entity {
   constructor(code: Cord, options?: Partial<Styled>) {
     super();
     this.type = "Styled";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.code = code;
   }
 }
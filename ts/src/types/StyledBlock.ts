@@ -19,7 +19,7 @@ export class StyledBlock extends Styled {
   constructor(code: Cord, content: Block[], options?: Partial<StyledBlock>) {
     super(code);
     this.type = "StyledBlock";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.code = code;
     this.content = content;
   }
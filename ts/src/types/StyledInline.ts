@@ -19,7 +19,7 @@ export class StyledInline extends Styled {
   constructor(code: Cord, content: Inline[], options?: Partial<StyledInline>) {
     super(code);
     this.type = "StyledInline";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.code = code;
     this.content = content;
   }
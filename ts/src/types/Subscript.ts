@@ -13,7 +13,7 @@ export class Subscript extends Mark {
   constructor(content: Inline[], options?: Partial<Subscript>) {
     super(content);
     this.type = "Subscript";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
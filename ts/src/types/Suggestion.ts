@@ -47,7 +47,7 @@ export class Suggestion extends Entity {
   constructor(options?: Partial<Suggestion>) {
     super();
     this.type = "Suggestion";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
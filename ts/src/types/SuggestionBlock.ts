@@ -18,7 +18,7 @@ export class SuggestionBlock extends Suggestion {
   constructor(content: Block[], options?: Partial<SuggestionBlock>) {
     super();
     this.type = "SuggestionBlock";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
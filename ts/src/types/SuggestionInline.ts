@@ -18,7 +18,7 @@ export class SuggestionInline extends Suggestion {
   constructor(content: Inline[], options?: Partial<SuggestionInline>) {
     super();
     this.type = "SuggestionInline";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
@@ -13,7 +13,7 @@ export class Superscript extends Mark {
   constructor(content: Inline[], options?: Partial<Superscript>) {
     super(content);
     this.type = "Superscript";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
@@ -39,7 +39,7 @@ export class Table extends CreativeWork {
   constructor(rows: TableRow[], options?: Partial<Table>) {
     super();
     this.type = "Table";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.rows = rows;
   }
 }
@@ -40,7 +40,7 @@ export class TableCell extends Entity {
   constructor(content: Block[], options?: Partial<TableCell>) {
     super();
     this.type = "TableCell";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
@@ -24,7 +24,7 @@ export class TableRow extends Entity {
   constructor(cells: TableCell[], options?: Partial<TableRow>) {
     super();
     this.type = "TableRow";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.cells = cells;
   }
 }
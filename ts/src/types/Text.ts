@@ -18,7 +18,7 @@ export class Text extends Entity {
   constructor(value: Cord, options?: Partial<Text>) {
     super();
     this.type = "Text";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.value = value;
   }
 }
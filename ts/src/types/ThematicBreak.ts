@@ -12,7 +12,7 @@ export class ThematicBreak extends Entity {
   constructor(options?: Partial<ThematicBreak>) {
     super();
     this.type = "ThematicBreak";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
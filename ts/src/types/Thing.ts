@@ -44,7 +44,7 @@ export class Thing extends Entity {
   constructor(options?: Partial<Thing>) {
     super();
     this.type = "Thing";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
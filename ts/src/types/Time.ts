@@ -17,7 +17,7 @@ export class Time extends Entity {
   constructor(value: string, options?: Partial<Time>) {
     super();
     this.type = "Time";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.value = value;
   }
 }
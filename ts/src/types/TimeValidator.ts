@@ -23,7 +23,7 @@ export class TimeValidator extends Entity {
   constructor(options?: Partial<TimeValidator>) {
     super();
     this.type = "TimeValidator";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
@@ -24,7 +24,7 @@ export class Timestamp extends Entity {
   constructor(value: Integer, timeUnit: TimeUnit, options?: Partial<Timestamp>) {
     super();
     this.type = "Timestamp";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.value = value;
     this.timeUnit = timeUnit;
   }
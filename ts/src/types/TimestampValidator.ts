@@ -29,7 +29,7 @@ export class TimestampValidator extends Entity {
   constructor(options?: Partial<TimestampValidator>) {
     super();
     this.type = "TimestampValidator";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
@@ -18,7 +18,7 @@ export class TupleValidator extends Entity {
   constructor(options?: Partial<TupleValidator>) {
     super();
     this.type = "TupleValidator";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
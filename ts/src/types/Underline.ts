@@ -13,7 +13,7 @@ export class Underline extends Mark {
   constructor(content: Inline[], options?: Partial<Underline>) {
     super(content);
     this.type = "Underline";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
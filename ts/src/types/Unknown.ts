@@ -12,7 +12,7 @@ export class Unknown extends Entity {
   constructor(options?: Partial<Unknown>) {
     super();
     this.type = "Unknown";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     
   }
 }
@@ -49,7 +49,7 @@ export class Variable extends Entity {
   constructor(name: string, options?: Partial<Variable>) {
     super();
     this.type = "Variable";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.name = name;
   }
 }
@@ -29,7 +29,7 @@ export class VideoObject extends MediaObject {
   constructor(contentUrl: string, options?: Partial<VideoObject>) {
     super(contentUrl);
     this.type = "VideoObject";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.contentUrl = contentUrl;
   }
 }
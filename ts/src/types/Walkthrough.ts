@@ -23,7 +23,7 @@ export class Walkthrough extends Entity {
   constructor(steps: WalkthroughStep[], options?: Partial<Walkthrough>) {
     super();
     this.type = "Walkthrough";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.steps = steps;
   }
 }
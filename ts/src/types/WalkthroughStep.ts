@@ -23,7 +23,7 @@ export class WalkthroughStep extends Entity {
   constructor(content: Block[], options?: Partial<WalkthroughStep>) {
     super();
     this.type = "WalkthroughStep";
-    if (options) Object.assign(this, options);
+    if (options) globalThis.Object.assign(this, options);
     this.content = content;
   }
 }
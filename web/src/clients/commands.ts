@@ -25,6 +25,10 @@ export interface DocumentCommand {
     | 'reject-node'
     | 'revise-node'
     | 'archive-node'
+    | 'add-comment'
+    | 'remove-comment'
+    | 'add-review'
+    | 'remove-review'
 
   /**
    * The type of the node that the command is being executed on.
@@ -49,6 +53,41 @@ export interface DocumentCommand {
    * The scope for the command
    */
   scope?: 'only' | 'plus-before' | 'plus-after' | 'plus-upstream-downstream'
+
+  /**
+   * The node that a comment is anchored to (for `add-comment`)
+   */
+  nodeId?: NodeId
+
+  /**
+   * The range of characters within the node that a comment is on (for `add-comment`)
+   */
+  range?: [number, number]
+
+  /**
+   * The id of the comment being replied to (for `add-comment`)
+   */
+  replyTo?: string
+
+  /**
+   * The id of the review that a comment is part of (for `add-comment`)
+   */
+  review?: string
+
+  /**
+   * The content of a comment or review (for `add-comment` and `add-review`)
+   */
+  content?: unknown[]
+
+  /**
+   * The ratings of a review (for `add-review`)
+   */
+  ratings?: unknown[]
+
+  /**
+   * The id of a comment or review (for `remove-comment` and `remove-review`)
+   */
+  id?: string
 }
 
 /**
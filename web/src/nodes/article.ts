@@ -104,6 +104,8 @@ export class StencilaArticle extends LitElement {
       </stencila-ui-article-headings>
 
       <slot name="content"></slot>
+
+      <slot name="comments"></slot>
     `
   }
 }
@@ -0,0 +1,70 @@
+import { apply } from '@twind/core'
+import { html } from 'lit'
+import { customElement, property } from 'lit/decorators.js'
+
+import { documentCommandEvent } from '../clients/commands'
+import { withTwind } from '../twind'
+
+import { CodeExecutable } from './code-executable'
+
+/**
+ * Web component representing a Stencila Schema `Button` node
+ *
+ * Clicking the button executes it, and then its dependants, on the server.
+ *
+ * @see https://github.com/stencila/stencila/blob/main/docs/reference/schema/flow/button.md
+ */
+@customElement('stencila-button')
+@withTwind()
+export class Button extends CodeExecutable {
+  @property()
+  name: string
+
+  @property()
+  label?: string
+
+  @property({
+    attribute: 'is-disabled',
+    type: Boolean,
+    // Converter needed because encoded not a boolean attribute (present or absent)
+    // but as a stringified boolean
+    converter: (attr) => attr == 'true',
+  })
+  isDisabled: boolean = false
+
+  /**
+   * Emit a custom event to execute the button
+   */
+  private onClick(event: Event) {
+    event.stopImmediatePropagation()
+
+    this.dispatchEvent(
+      documentCommandEvent({
+        command: 'execute-nodes',
+        nodeType: 'Button',
+        nodeIds: [this.id],
+        scope: 'only',
+      })
+    )
+  }
+
+  override render() {
+    const isRunning =
+      this.executionStatus === 'Pending' || this.executionStatus === 'Running'
+
+    const classes = apply([
+      'px-3 py-1 rounded',
+      'bg-blue-500 text-white font-sans text-sm',
+      'hover:bg-blue-600',
+      'disabled:opacity-50 disabled:cursor-not-allowed',
+    ])
+
+    return html`<button
+      class=${classes}
+      ?disabled=${this.isDisabled || isRunning}
+      @click=${(event: Event) => this.onClick(event)}
+    >
+      ${this.label ?? this.name}
+    </button>`
+  }
+}
@@ -20,6 +20,9 @@ export class Claim extends Entity {
   @property({ attribute: 'claim-type' })
   claimType: string
 
+  @property()
+  label?: string
+
   override render() {
     if (this.ancestors.includes('StyledBlock')) {
       return html`<slot name="content"></slot>`
@@ -28,7 +31,9 @@ export class Claim extends Entity {
     return html`
       <stencila-ui-block-on-demand
         type="Claim"
-        header-title=${this.claimType}
+        header-title=${this.label
+          ? `${this.claimType} ${this.label}`
+          : this.claimType}
         depth=${this.depth}
         ancestors=${this.ancestors}
       >
@@ -0,0 +1,88 @@
+import { apply } from '@twind/core'
+import { html } from 'lit'
+import { customElement, property } from 'lit/decorators.js'
+
+import { documentCommandEvent } from '../clients/commands'
+import { withTwind } from '../twind'
+
+import { Entity } from './entity'
+
+/**
+ * Web component representing a Stencila Schema `Comment` node
+ *
+ * Comments are anchored to a node by their `commentAspect` which is the `id`
+ * of the node, optionally followed by a character range (e.g. `intro#10-25`).
+ * Hovering over a comment highlights the node that it is anchored to.
+ *
+ * @see https://github.com/stencila/stencila/blob/main/docs/reference/schema/works/comment.md
+ */
+@customElement('stencila-comment')
+@withTwind()
+export class Comment extends Entity {
+  @property({ attribute: 'comment-aspect' })
+  commentAspect?: string
+
+  /**
+   * Get the element of the node that the comment is anchored to, if any
+   */
+  private anchor(): HTMLElement | null {
+    const id = this.commentAspect?.split('#')[0]
+    if (!id) {
+      return null
+    }
+
+    return (
+      document.querySelector(`[_id="${id}"]`) ?? document.getElementById(id)
+    )
+  }
+
+  /**
+   * Highlight, or unhighlight, the node that the comment is anchored to
+   */
+  private highlight(on: boolean) {
+    this.anchor()?.style.setProperty('outline', on ? '2px solid #fbbf24' : '')
+  }
+
+  /**
+   * Emit a custom event to remove the comment
+   */
+  private onRemove(event: Event) {
+    event.stopImmediatePropagation()
+
+    this.dispatchEvent(
+      documentCommandEvent({
+        command: 'remove-comment',
+        id: this.$id,
+      })
+    )
+  }
+
+  override render() {
+    const classes = apply([
+      'my-2 p-3 rounded',
+      'border border-amber-300 bg-amber-50',
+      'font-sans text-sm',
+    ])
+
+    return html`<div
+      class=${classes}
+      @mouseenter=${() => this.highlight(true)}
+      @mouseleave=${() => this.highlight(false)}
+    >
+      <div class="flex justify-between text-xs text-gray-500">
+        <slot name="authors"></slot>
+        <button
+          class="hover:text-gray-800"
+          title="Remove comment"
+          @click=${(event: Event) => this.onRemove(event)}
+        >
+          ✕
+        </button>
+      </div>
+      <slot name="content"></slot>
+      <div class="ml-4">
+        <slot name="comments"></slot>
+      </div>
+    </div>`
+  }
+}
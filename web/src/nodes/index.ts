@@ -3,6 +3,7 @@ import './array'
 import './article'
 import './author-role'
 import './boolean'
+import './button'
 import './call-argument'
 import './call-block'
 import './claim'
@@ -10,6 +11,7 @@ import './code-block'
 import './code-chunk'
 import './code-expression'
 import './code-inline'
+import './comment'
 import './compilation-message'
 import './config'
 import './datatable'
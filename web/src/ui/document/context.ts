@@ -26,6 +26,12 @@ export type DocumentContext = {
    */
   showAllAuthorshipHighlight: boolean
 
+  /**
+   * Toggles the highlighting of machine written content which has
+   * not been verified on the entire document
+   */
+  showUnverifiedMachineHighlight: boolean
+
   /**
    * Toggles the display of the `<stencila-article>` level author and provenance info
    */
@@ -180,6 +180,13 @@ export class DocumentMenu extends LitElement {
           <stencila-ui-icon name="highlights" slot="prefix"></stencila-ui-icon>
           <span class="text-sm">Show authorship highlighting</span>
         </sl-menu-item>
+        <sl-menu-item
+          type="checkbox"
+          data-event="toggle-unverified-machine-highlight"
+        >
+          <stencila-ui-icon name="highlights" slot="prefix"></stencila-ui-icon>
+          <span class="text-sm">Highlight unverified machine content</span>
+        </sl-menu-item>
         <sl-divider></sl-divider>
         <sl-menu-label>
           <div class="flex items-center gap-2">Node Chips</div>
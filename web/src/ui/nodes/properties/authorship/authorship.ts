@@ -106,6 +106,17 @@ export class StencilaAuthorship extends LitElement {
     }
   }
 
+  /**
+   * Is the content machine written and not yet verified?
+   *
+   * Provenance strings for machine written content start with `Mw`,
+   * and those for verified content include `Hv` or `Mv`.
+   */
+  private isUnverifiedMachine(): boolean {
+    const provenance = this.provenance as string
+    return provenance?.startsWith('Mw') && !provenance.includes('v')
+  }
+
   override render() {
     const showHighlights =
       this.documentContext?.showAllAuthorshipHighlight ||
@@ -113,11 +124,21 @@ export class StencilaAuthorship extends LitElement {
 
     if (showHighlights) {
       return this.renderHighlights()
+    } else if (
+      this.documentContext?.showUnverifiedMachineHighlight &&
+      this.isUnverifiedMachine()
+    ) {
+      return this.renderUnverifiedMachine()
     } else {
       return html`<slot></slot>`
     }
   }
 
+  renderUnverifiedMachine() {
+    // prettier-ignore
+    return html`<span class="bg-amber-100"><slot></slot></span>`
+  }
+
   renderHighlights() {
     const textOpacity = getProvenanceOpacity(this.mi as ProvenanceOpacityLevel)
 
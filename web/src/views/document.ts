@@ -16,6 +16,7 @@ export abstract class DocumentView extends LitElement {
   @state()
   protected context: DocumentContext = {
     showAllAuthorshipHighlight: false,
+    showUnverifiedMachineHighlight: false,
     nodeChipState: 'hover-only',
     showAuthorProvenance: false,
   }
@@ -35,6 +36,14 @@ export abstract class DocumentView extends LitElement {
       }
     })
 
+    this.addEventListener('toggle-unverified-machine-highlight', () => {
+      this.context = {
+        ...this.context,
+        showUnverifiedMachineHighlight:
+          !this.context.showUnverifiedMachineHighlight,
+      }
+    })
+
     this.addEventListener('toggle-author-provenance', () => {
       this.context = {
         ...this.context,